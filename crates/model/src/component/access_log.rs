@@ -0,0 +1,19 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(AccessLogProperties, (PORT, "port", 0), (PATH_PREFIX, "path_prefix", ""), (ENTRY, "entry", {}));
+
+component_ty!(COMPONENT_ACCESS_LOG, NAMESPACE_HTTP, COMPONENT_NAME_ACCESS_LOG, "access_log");
+behaviour_ty!(BEHAVIOUR_ACCESS_LOG, NAMESPACE_HTTP, BEHAVIOUR_NAME_ACCESS_LOG, "access_log");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_ACCESS_LOG, COMPONENT_ACCESS_LOG, BEHAVIOUR_ACCESS_LOG);
+
+component_model!(
+    ComponentAccessLog,
+    set port u64,
+    set path_prefix string,
+    get entry object
+);
@@ -0,0 +1,28 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    BatchProperties,
+    (REQUESTS, "requests", []),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (MAX_CONCURRENCY, "max_concurrency", 4),
+    (RESULTS, "results", []),
+    (ENV_IN_PAYLOAD, "env_in_payload", false)
+);
+
+component_ty!(COMPONENT_BATCH, NAMESPACE_HTTP, COMPONENT_NAME_BATCH, "batch");
+behaviour_ty!(BEHAVIOUR_BATCH, NAMESPACE_HTTP, BEHAVIOUR_NAME_BATCH, "batch");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_BATCH, COMPONENT_BATCH, BEHAVIOUR_BATCH);
+
+component_model!(
+    ComponentBatch,
+    set requests array,
+    set request_headers object,
+    set max_concurrency u64,
+    get results array,
+    set env_in_payload bool
+);
@@ -0,0 +1,28 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    ConnectivityCheckProperties,
+    (ENDPOINTS, "endpoints", ["http://connectivitycheck.gstatic.com/generate_204"]),
+    (INTERVAL, "interval", 30000),
+    (TIMEOUT, "timeout", 5000),
+    (ONLINE, "online", false),
+    (CAPTIVE_PORTAL, "captive_portal", false)
+);
+
+component_ty!(COMPONENT_CONNECTIVITY_CHECK, NAMESPACE_HTTP, COMPONENT_NAME_CONNECTIVITY_CHECK, "connectivity_check");
+behaviour_ty!(BEHAVIOUR_CONNECTIVITY_CHECK, NAMESPACE_HTTP, BEHAVIOUR_NAME_CONNECTIVITY_CHECK, "connectivity_check");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_CONNECTIVITY_CHECK, COMPONENT_CONNECTIVITY_CHECK, BEHAVIOUR_CONNECTIVITY_CHECK);
+
+component_model!(
+    ComponentConnectivityCheck,
+    set endpoints array,
+    set interval u64,
+    set timeout u64,
+    get online bool,
+    get captive_portal bool
+);
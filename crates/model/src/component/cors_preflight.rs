@@ -0,0 +1,36 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    CorsPreflightProperties,
+    (URL, "url", ""),
+    (ORIGIN, "origin", ""),
+    (REQUEST_METHOD, "request_method", "GET"),
+    (REQUEST_HEADER_NAMES, "request_header_names", []),
+    (CREDENTIALS, "credentials", false),
+    (TIMEOUT, "timeout", 10000),
+    (ALLOWED, "allowed", false),
+    (REASONS, "reasons", []),
+    (PREFLIGHT_HEADERS, "preflight_headers", {})
+);
+
+component_ty!(COMPONENT_CORS_PREFLIGHT, NAMESPACE_HTTP, COMPONENT_NAME_CORS_PREFLIGHT, "cors_preflight");
+behaviour_ty!(BEHAVIOUR_CORS_PREFLIGHT, NAMESPACE_HTTP, BEHAVIOUR_NAME_CORS_PREFLIGHT, "cors_preflight");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_CORS_PREFLIGHT, COMPONENT_CORS_PREFLIGHT, BEHAVIOUR_CORS_PREFLIGHT);
+
+component_model!(
+    ComponentCorsPreflight,
+    set url string,
+    set origin string,
+    set request_method string,
+    set request_header_names array,
+    set credentials bool,
+    set timeout u64,
+    get allowed bool,
+    get reasons array,
+    get preflight_headers object
+);
@@ -0,0 +1,14 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(DnsCacheProperties, (FLUSH, "flush", false), (FLUSHED_HOSTS, "flushed_hosts", 0));
+
+component_ty!(COMPONENT_DNS_CACHE, NAMESPACE_HTTP, COMPONENT_NAME_DNS_CACHE, "dns_cache");
+behaviour_ty!(BEHAVIOUR_DNS_CACHE, NAMESPACE_HTTP, BEHAVIOUR_NAME_DNS_CACHE, "dns_cache");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_DNS_CACHE, COMPONENT_DNS_CACHE, BEHAVIOUR_DNS_CACHE);
+
+component_model!(ComponentDnsCache, set flush bool, get flushed_hosts u64);
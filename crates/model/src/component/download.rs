@@ -0,0 +1,46 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    DownloadProperties,
+    (URL, "url", ""),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (PATH, "path", ""),
+    (CHECKSUM_ALGORITHM, "checksum_algorithm", "sha256"),
+    (EXPECTED_CHECKSUM, "expected_checksum", ""),
+    (DELETE_ON_MISMATCH, "delete_on_mismatch", false),
+    (CHECKSUM, "checksum", ""),
+    (CHECKSUM_VALID, "checksum_valid", false),
+    (ERROR, "error", ""),
+    (BYTES_DOWNLOADED, "bytes_downloaded", 0),
+    (TOTAL_BYTES, "total_bytes", 0),
+    (PROGRESS_PERCENT, "progress_percent", 0),
+    (MAX_DOWNLOAD_KBPS, "max_download_kbps", 0),
+    (SEGMENTS, "segments", 1)
+);
+
+component_ty!(COMPONENT_DOWNLOAD, NAMESPACE_HTTP, COMPONENT_NAME_DOWNLOAD, "download");
+behaviour_ty!(BEHAVIOUR_DOWNLOAD, NAMESPACE_HTTP, BEHAVIOUR_NAME_DOWNLOAD, "download");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_DOWNLOAD, COMPONENT_DOWNLOAD, BEHAVIOUR_DOWNLOAD);
+
+component_model!(
+    ComponentDownload,
+    set url string,
+    set request_headers object,
+    set path string,
+    set checksum_algorithm string,
+    set expected_checksum string,
+    set delete_on_mismatch bool,
+    get checksum string,
+    get checksum_valid bool,
+    get error string,
+    get bytes_downloaded u64,
+    get total_bytes u64,
+    get progress_percent u64,
+    set max_download_kbps u64,
+    set segments u64
+);
@@ -0,0 +1,30 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    FanOutProperties,
+    (METHOD, "method", "GET"),
+    (URLS, "urls", []),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (MAX_CONCURRENCY, "max_concurrency", 4),
+    (RESULTS, "results", []),
+    (ERRORS, "errors", [])
+);
+
+component_ty!(COMPONENT_FAN_OUT, NAMESPACE_HTTP, COMPONENT_NAME_FAN_OUT, "fan_out");
+behaviour_ty!(BEHAVIOUR_FAN_OUT, NAMESPACE_HTTP, BEHAVIOUR_NAME_FAN_OUT, "fan_out");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_FAN_OUT, COMPONENT_FAN_OUT, BEHAVIOUR_FAN_OUT);
+
+component_model!(
+    ComponentFanOut,
+    set method string,
+    set urls array,
+    set request_headers object,
+    set max_concurrency u64,
+    get results array,
+    get errors array
+);
@@ -0,0 +1,32 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    GraphQlProperties,
+    (QUERY, "query", ""),
+    (VARIABLES, "variables", {}),
+    (OPERATION_NAME, "operation_name", ""),
+    (PERSISTED_QUERIES, "persisted_queries", false),
+    (DATA, "data", {}),
+    (ERRORS, "errors", []),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_GRAPHQL, NAMESPACE_HTTP, COMPONENT_NAME_GRAPHQL, "graphql");
+behaviour_ty!(BEHAVIOUR_GRAPHQL, NAMESPACE_HTTP, BEHAVIOUR_NAME_GRAPHQL, "graphql");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_GRAPHQL, COMPONENT_GRAPHQL, BEHAVIOUR_GRAPHQL);
+
+component_model!(
+    ComponentGraphQl,
+    set query string,
+    set variables object,
+    set operation_name string,
+    set persisted_queries bool,
+    get data value,
+    get errors array,
+    get error string
+);
@@ -13,7 +13,69 @@ properties!(
     (PAYLOAD, "payload", {}),
     (RESPONSE_HEADERS, "response_headers", {}),
     (RESULT, "result", {}),
-    (STATUS, "status", 200)
+    (STATUS, "status", 200),
+    (CONCURRENCY_MODE, "concurrency_mode", "queue"),
+    (DISTINCT, "distinct", false),
+    (RESULT_DIFF, "result_diff", []),
+    (RESPONSE_SCHEMA, "response_schema", {}),
+    (SCHEMA_VALID, "schema_valid", true),
+    (SCHEMA_ERRORS, "schema_errors", []),
+    (BASE_URLS, "base_urls", []),
+    (LOAD_BALANCING, "load_balancing", "round_robin"),
+    (UPSTREAM, "upstream", ""),
+    (FALLBACK_URLS, "fallback_urls", []),
+    (SERVICE, "service", ""),
+    (SERVICE_SCHEME, "service_scheme", "http"),
+    (PROXY, "proxy", ""),
+    (SSRF_PROTECTION, "ssrf_protection", false),
+    (ALLOWED_HOSTS, "allowed_hosts", []),
+    (DENIED_HOSTS, "denied_hosts", []),
+    (HASH_ALGORITHM, "hash_algorithm", ""),
+    (BODY_HASH, "body_hash", ""),
+    (PRIORITY, "priority", 0),
+    (DELAY_MS, "delay_ms", 0),
+    (MAX_RETRIES, "max_retries", 3),
+    (MAX_RETRY_AFTER, "max_retry_after", 60000),
+    (IDEMPOTENCY, "idempotency", false),
+    (SEND_CONDITION, "send_condition", ""),
+    (SKIPPED_REASON, "skipped_reason", ""),
+    (API_KEYS, "api_keys", []),
+    (API_KEY_HEADER, "api_key_header", "Authorization"),
+    (ACTIVE_API_KEY, "active_api_key", 0),
+    (CACHE, "cache", false),
+    (FROM_CACHE, "from_cache", false),
+    (STALE_WHILE_REVALIDATE, "stale_while_revalidate", 0),
+    (OFFLINE_FALLBACK, "offline_fallback", false),
+    (STALE, "stale", false),
+    (REQUIRE_ONLINE, "require_online", false),
+    (REQUEST_STARTED, "request_started", {}),
+    (REQUEST_SUCCEEDED, "request_succeeded", {}),
+    (REQUEST_FAILED, "request_failed", {}),
+    (MAX_REDIRECTS, "max_redirects", 5),
+    (REDIRECT_CHAIN, "redirect_chain", []),
+    (DNS_MS, "dns_ms", 0),
+    (CONNECT_MS, "connect_ms", 0),
+    (TLS_HANDSHAKE_MS, "tls_handshake_ms", 0),
+    (TTFB_MS, "ttfb_ms", 0),
+    (RESPONSE_TIME_MS, "response_time_ms", 0),
+    (ACCEPT_LANGUAGE, "accept_language", ""),
+    (TLS_MIN_VERSION, "tls_min_version", ""),
+    (TLS_MAX_VERSION, "tls_max_version", ""),
+    (PINNED_SPKI_SHA256, "pinned_spki_sha256", []),
+    (RESPECT_ROBOTS, "respect_robots", false),
+    (BLOCKED_BY_ROBOTS, "blocked_by_robots", false),
+    (HAWK_ID, "hawk_id", ""),
+    (HAWK_KEY, "hawk_key", ""),
+    (RESPECT_RATE_LIMITS, "respect_rate_limits", false),
+    (RATE_LIMIT_REMAINING, "rate_limit_remaining", -1),
+    (RATE_LIMIT_RESET, "rate_limit_reset", 0),
+    (ON_SUCCESS, "on_success", false),
+    (ON_ERROR, "on_error", false),
+    (PARSE_MODE, "parse_mode", "auto"),
+    (DRY_RUN, "dry_run", false),
+    (REQUEST_PREVIEW, "request_preview", {}),
+    (MIRROR_URL, "mirror_url", ""),
+    (ENV_IN_PAYLOAD, "env_in_payload", false)
 );
 
 component_ty!(COMPONENT_HTTP, NAMESPACE_HTTP, COMPONENT_NAME_HTTP, "http");
@@ -27,5 +89,67 @@ component_model!(
     set request_headers object,
     set payload value,
     get response_headers object,
-    get status u64
+    get status u64,
+    set concurrency_mode string,
+    set distinct bool,
+    get result_diff array,
+    set response_schema object,
+    get schema_valid bool,
+    get schema_errors array,
+    set base_urls array,
+    set load_balancing string,
+    get upstream string,
+    set fallback_urls array,
+    set service string,
+    set service_scheme string,
+    set proxy string,
+    set ssrf_protection bool,
+    set allowed_hosts array,
+    set denied_hosts array,
+    set hash_algorithm string,
+    get body_hash string,
+    set priority i64,
+    set delay_ms u64,
+    set max_retries u64,
+    set max_retry_after u64,
+    set idempotency bool,
+    set send_condition string,
+    get skipped_reason string,
+    set api_keys array,
+    set api_key_header string,
+    get active_api_key u64,
+    set cache bool,
+    get from_cache bool,
+    set stale_while_revalidate u64,
+    set offline_fallback bool,
+    get stale bool,
+    set require_online bool,
+    get request_started object,
+    get request_succeeded object,
+    get request_failed object,
+    set max_redirects u64,
+    get redirect_chain array,
+    get dns_ms u64,
+    get connect_ms u64,
+    get tls_handshake_ms u64,
+    get ttfb_ms u64,
+    get response_time_ms u64,
+    set accept_language string,
+    set tls_min_version string,
+    set tls_max_version string,
+    set pinned_spki_sha256 array,
+    set respect_robots bool,
+    get blocked_by_robots bool,
+    set hawk_id string,
+    set hawk_key string,
+    set respect_rate_limits bool,
+    get rate_limit_remaining i64,
+    get rate_limit_reset u64,
+    get on_success bool,
+    get on_error bool,
+    set parse_mode string,
+    set dry_run bool,
+    get request_preview object,
+    set mirror_url string,
+    set env_in_payload bool
 );
@@ -0,0 +1,28 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    HttpResponseProperties,
+    (BODY_TEMPLATE, "body_template", ""),
+    (CONTENT_TYPES, "content_types", ["application/json"]),
+    (ASYNC, "async", false),
+    (TIMEOUT, "timeout", 30000),
+    (COMPLETE, "complete", false),
+    (CONTENT_TYPE, "content_type", "")
+);
+
+component_ty!(COMPONENT_HTTP_RESPONSE, NAMESPACE_HTTP, COMPONENT_NAME_HTTP_RESPONSE, "http_response");
+behaviour_ty!(BEHAVIOUR_HTTP_RESPONSE, NAMESPACE_HTTP, BEHAVIOUR_NAME_HTTP_RESPONSE, "http_response");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_HTTP_RESPONSE, COMPONENT_HTTP_RESPONSE, BEHAVIOUR_HTTP_RESPONSE);
+
+component_model!(
+    ComponentHttpResponse,
+    set content_types array,
+    set timeout u64,
+    set complete bool,
+    get content_type string
+);
@@ -0,0 +1,48 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    HttpRouteProperties,
+    (PORT, "port", 8090),
+    (METHOD, "method", "GET"),
+    (PATH, "path", "/"),
+    (DISPATCH_NAMESPACE, "dispatch_namespace", ""),
+    (DISPATCH_TYPE, "dispatch_type", ""),
+    (REQUEST_METHOD, "request_method", ""),
+    (REQUEST_PATH, "request_path", ""),
+    (PATH_PARAMS, "path_params", {}),
+    (QUERY, "query", {}),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (REQUEST_BODY, "request_body", {}),
+    (REMOTE_ADDR, "remote_addr", ""),
+    (REQUEST, "request", {}),
+    (RESPONSE_STATUS, "response_status", 200),
+    (RESPONSE_HEADERS, "response_headers", {}),
+    (RESPONSE_BODY, "response_body", {})
+);
+
+component_ty!(COMPONENT_HTTP_ROUTE, NAMESPACE_HTTP, COMPONENT_NAME_HTTP_ROUTE, "http_route");
+behaviour_ty!(BEHAVIOUR_HTTP_ROUTE, NAMESPACE_HTTP, BEHAVIOUR_NAME_HTTP_ROUTE, "http_route");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_HTTP_ROUTE, COMPONENT_HTTP_ROUTE, BEHAVIOUR_HTTP_ROUTE);
+
+component_model!(
+    ComponentHttpRoute,
+    set port u64,
+    set method string,
+    set path string,
+    set dispatch_namespace string,
+    set dispatch_type string,
+    get request_method string,
+    get request_path string,
+    get path_params object,
+    get query object,
+    get request_headers object,
+    get remote_addr string,
+    get request object,
+    set response_status u64,
+    set response_headers object
+);
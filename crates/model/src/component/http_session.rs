@@ -0,0 +1,20 @@
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    HttpSessionProperties,
+    (REQUEST_HEADERS, "request_headers", {}),
+    (PROXY, "proxy", ""),
+    (COOKIES, "cookies", {})
+);
+
+component_ty!(COMPONENT_HTTP_SESSION, NAMESPACE_HTTP, COMPONENT_NAME_HTTP_SESSION, "http_session");
+
+component_model!(
+    ComponentHttpSession,
+    set request_headers object,
+    set proxy string,
+    set cookies object
+);
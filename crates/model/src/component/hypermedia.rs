@@ -0,0 +1,33 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    HypermediaProperties,
+    (DOCUMENT, "document", {}),
+    (RESPONSE_HEADERS, "response_headers", {}),
+    (BASE_URL, "base_url", ""),
+    (RELATION, "relation", "next"),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (LINKS, "links", {}),
+    (STATUS, "status", 0),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_HYPERMEDIA, NAMESPACE_HTTP, COMPONENT_NAME_HYPERMEDIA, "hypermedia");
+behaviour_ty!(BEHAVIOUR_HYPERMEDIA, NAMESPACE_HTTP, BEHAVIOUR_NAME_HYPERMEDIA, "hypermedia");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_HYPERMEDIA, COMPONENT_HYPERMEDIA, BEHAVIOUR_HYPERMEDIA);
+
+component_model!(
+    ComponentHypermedia,
+    set response_headers object,
+    set base_url string,
+    set relation string,
+    set request_headers object,
+    get links object,
+    get status u64,
+    get error string
+);
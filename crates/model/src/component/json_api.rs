@@ -0,0 +1,29 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    JsonApiProperties,
+    (DOCUMENT, "document", {}),
+    (MAX_DEPTH, "max_depth", 2),
+    (DATA, "data", []),
+    (INCLUDED, "included", []),
+    (META, "meta", {}),
+    (ERRORS, "errors", [])
+);
+
+component_ty!(COMPONENT_JSON_API, NAMESPACE_HTTP, COMPONENT_NAME_JSON_API, "json_api");
+behaviour_ty!(BEHAVIOUR_JSON_API, NAMESPACE_HTTP, BEHAVIOUR_NAME_JSON_API, "json_api");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_JSON_API, COMPONENT_JSON_API, BEHAVIOUR_JSON_API);
+
+component_model!(
+    ComponentJsonApi,
+    set document object,
+    set max_depth u64,
+    get included array,
+    get meta object,
+    get errors array
+);
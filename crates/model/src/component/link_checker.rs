@@ -0,0 +1,32 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    LinkCheckerProperties,
+    (URLS, "urls", []),
+    (PAGE_URL, "page_url", ""),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (MAX_CONCURRENCY, "max_concurrency", 4),
+    (TIMEOUT, "timeout", 10000),
+    (REPORT, "report", []),
+    (BROKEN_LINKS, "broken_links", [])
+);
+
+component_ty!(COMPONENT_LINK_CHECKER, NAMESPACE_HTTP, COMPONENT_NAME_LINK_CHECKER, "link_checker");
+behaviour_ty!(BEHAVIOUR_LINK_CHECKER, NAMESPACE_HTTP, BEHAVIOUR_NAME_LINK_CHECKER, "link_checker");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_LINK_CHECKER, COMPONENT_LINK_CHECKER, BEHAVIOUR_LINK_CHECKER);
+
+component_model!(
+    ComponentLinkChecker,
+    set urls array,
+    set page_url string,
+    set request_headers object,
+    set max_concurrency u64,
+    set timeout u64,
+    get report array,
+    get broken_links array
+);
@@ -0,0 +1,14 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(LongPollProperties, (IDLE_DELAY, "idle_delay", 0), (TIMEOUT, "timeout", 60000), (CRON, "cron", ""));
+
+component_ty!(COMPONENT_LONG_POLL, NAMESPACE_HTTP, COMPONENT_NAME_LONG_POLL, "long_poll");
+behaviour_ty!(BEHAVIOUR_LONG_POLL, NAMESPACE_HTTP, BEHAVIOUR_NAME_LONG_POLL, "long_poll");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_LONG_POLL, COMPONENT_LONG_POLL, BEHAVIOUR_LONG_POLL);
+
+component_model!(ComponentLongPoll, set idle_delay u64, set timeout u64, set cron string);
@@ -1,8 +1,86 @@
+pub use access_log::*;
 pub use action::*;
+pub use batch::*;
+pub use connectivity_check::*;
+pub use cors_preflight::*;
+pub use dns_cache::*;
+pub use download::*;
+pub use fan_out::*;
+pub use graphql::*;
 pub use http::*;
+pub use http_response::*;
+pub use http_route::*;
+pub use http_session::*;
+pub use hypermedia::*;
+pub use json_api::*;
 pub use json_rpc::*;
+pub use link_checker::*;
+pub use long_poll::*;
+pub use odata::*;
+pub use openapi_import::*;
+pub use openapi_operation::*;
+pub use pausable::*;
+pub use postman_import::*;
+pub use presigned_url::*;
+pub use reconnect::*;
+pub use route_auth::*;
+pub use route_cors::*;
+pub use route_rate_limit::*;
+pub use security_headers::*;
+pub use signalr::*;
+pub use sitemap::*;
+pub use socket_io::*;
+pub use sse::*;
+pub use stomp::*;
+pub use tls_certificate_monitor::*;
+pub use tunnel::*;
+pub use upload::*;
+pub use upload_endpoint::*;
+pub use webdav::*;
+pub use webhook_relay::*;
+pub use webhook_sender::*;
+pub use websocket_bridge::*;
 
 // TODO: remove action
+pub mod access_log;
 pub mod action;
+pub mod batch;
+pub mod connectivity_check;
+pub mod cors_preflight;
+pub mod dns_cache;
+pub mod download;
+pub mod fan_out;
+pub mod graphql;
 pub mod http;
+pub mod http_response;
+pub mod http_route;
+pub mod http_session;
+pub mod hypermedia;
+pub mod json_api;
 pub mod json_rpc;
+pub mod link_checker;
+pub mod long_poll;
+pub mod odata;
+pub mod openapi_import;
+pub mod openapi_operation;
+pub mod pausable;
+pub mod postman_import;
+pub mod presigned_url;
+pub mod reconnect;
+pub mod route_auth;
+pub mod route_cors;
+pub mod route_rate_limit;
+pub mod security_headers;
+pub mod signalr;
+pub mod sitemap;
+pub mod socket_io;
+pub mod sse;
+pub mod stomp;
+pub mod tls_certificate_monitor;
+pub mod tunnel;
+pub mod upload;
+pub mod upload_endpoint;
+pub mod webdav;
+pub mod webhook_relay;
+pub mod webhook_sender;
+pub mod websocket_bridge;
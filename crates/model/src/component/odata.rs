@@ -0,0 +1,51 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    ODataProperties,
+    (SERVICE_URL, "service_url", ""),
+    (ENTITY_SET, "entity_set", ""),
+    (ODATA_VERSION, "odata_version", "4"),
+    (FILTER, "filter", ""),
+    (SELECT, "select", []),
+    (EXPAND, "expand", []),
+    (ORDER_BY, "order_by", []),
+    (TOP, "top", 0),
+    (SKIP, "skip", 0),
+    (COUNT, "count", false),
+    (FOLLOW_NEXT_LINKS, "follow_next_links", true),
+    (MAX_PAGES, "max_pages", 100),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (QUERY_URL, "query_url", ""),
+    (ENTITIES, "entities", []),
+    (TOTAL_COUNT, "total_count", -1),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_ODATA, NAMESPACE_HTTP, COMPONENT_NAME_ODATA, "odata");
+behaviour_ty!(BEHAVIOUR_ODATA, NAMESPACE_HTTP, BEHAVIOUR_NAME_ODATA, "odata");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_ODATA, COMPONENT_ODATA, BEHAVIOUR_ODATA);
+
+component_model!(
+    ComponentOData,
+    set service_url string,
+    set entity_set string,
+    set odata_version string,
+    set select array,
+    set expand array,
+    set order_by array,
+    set top u64,
+    set skip u64,
+    set count bool,
+    set follow_next_links bool,
+    set max_pages u64,
+    set request_headers object,
+    get query_url string,
+    get entities array,
+    get total_count i64,
+    get error string
+);
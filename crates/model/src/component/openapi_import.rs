@@ -0,0 +1,30 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    OpenApiImportProperties,
+    (SPEC, "spec", {}),
+    (SPEC_URL, "spec_url", ""),
+    (NAMESPACE, "namespace", "openapi"),
+    (ENTITY_TYPES, "entity_types", []),
+    (STARTER_FLOW, "starter_flow", {}),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_OPENAPI_IMPORT, NAMESPACE_HTTP, COMPONENT_NAME_OPENAPI_IMPORT, "openapi_import");
+behaviour_ty!(BEHAVIOUR_OPENAPI_IMPORT, NAMESPACE_HTTP, BEHAVIOUR_NAME_OPENAPI_IMPORT, "openapi_import");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_OPENAPI_IMPORT, COMPONENT_OPENAPI_IMPORT, BEHAVIOUR_OPENAPI_IMPORT);
+
+component_model!(
+    ComponentOpenApiImport,
+    set spec object,
+    set spec_url string,
+    set namespace string,
+    get entity_types array,
+    get starter_flow object,
+    get error string
+);
@@ -0,0 +1,11 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_ty;
+use crate::NAMESPACE_HTTP;
+
+component_ty!(COMPONENT_OPENAPI_OPERATION, NAMESPACE_HTTP, COMPONENT_NAME_OPENAPI_OPERATION, "openapi_operation");
+behaviour_ty!(BEHAVIOUR_OPENAPI_OPERATION, NAMESPACE_HTTP, BEHAVIOUR_NAME_OPENAPI_OPERATION, "openapi_operation");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_OPENAPI_OPERATION, COMPONENT_OPENAPI_OPERATION, BEHAVIOUR_OPENAPI_OPERATION);
+
+/// The name of the entity type extension which describes the imported OpenAPI operation.
+pub const EXTENSION_OPENAPI_OPERATION: &str = "openapi_operation";
@@ -0,0 +1,10 @@
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(PausableProperties, (ENABLED, "enabled", true));
+
+component_ty!(COMPONENT_PAUSABLE, NAMESPACE_HTTP, COMPONENT_NAME_PAUSABLE, "pausable");
+
+component_model!(ComponentPausable, set enabled bool);
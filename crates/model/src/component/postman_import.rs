@@ -0,0 +1,26 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    PostmanImportProperties,
+    (COLLECTION, "collection", {}),
+    (COLLECTION_URL, "collection_url", ""),
+    (ENTITY_INSTANCES, "entity_instances", []),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_POSTMAN_IMPORT, NAMESPACE_HTTP, COMPONENT_NAME_POSTMAN_IMPORT, "postman_import");
+behaviour_ty!(BEHAVIOUR_POSTMAN_IMPORT, NAMESPACE_HTTP, BEHAVIOUR_NAME_POSTMAN_IMPORT, "postman_import");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_POSTMAN_IMPORT, COMPONENT_POSTMAN_IMPORT, BEHAVIOUR_POSTMAN_IMPORT);
+
+component_model!(
+    ComponentPostmanImport,
+    set collection object,
+    set collection_url string,
+    get entity_instances array,
+    get error string
+);
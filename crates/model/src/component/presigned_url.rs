@@ -0,0 +1,40 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    PresignedUrlProperties,
+    (METHOD, "method", "GET"),
+    (ENDPOINT, "endpoint", "https://s3.amazonaws.com"),
+    (BUCKET, "bucket", ""),
+    (OBJECT_KEY, "object_key", ""),
+    (REGION, "region", "us-east-1"),
+    (ACCESS_KEY_ID, "access_key_id", ""),
+    (SECRET_ACCESS_KEY, "secret_access_key", ""),
+    (EXPIRES, "expires", 3600),
+    (PRESIGNED_URL, "presigned_url", ""),
+    (EXPIRES_AT, "expires_at", ""),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_PRESIGNED_URL, NAMESPACE_HTTP, COMPONENT_NAME_PRESIGNED_URL, "presigned_url");
+behaviour_ty!(BEHAVIOUR_PRESIGNED_URL, NAMESPACE_HTTP, BEHAVIOUR_NAME_PRESIGNED_URL, "presigned_url");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_PRESIGNED_URL, COMPONENT_PRESIGNED_URL, BEHAVIOUR_PRESIGNED_URL);
+
+component_model!(
+    ComponentPresignedUrl,
+    set method string,
+    set endpoint string,
+    set bucket string,
+    set object_key string,
+    set region string,
+    set access_key_id string,
+    set secret_access_key string,
+    set expires u64,
+    get presigned_url string,
+    get expires_at string,
+    get error string
+);
@@ -0,0 +1,22 @@
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    ReconnectProperties,
+    (RECONNECT_INITIAL_DELAY, "reconnect_initial_delay", 1000),
+    (RECONNECT_MAX_DELAY, "reconnect_max_delay", 30000),
+    (RECONNECT_MAX_ATTEMPTS, "reconnect_max_attempts", 0),
+    (CONNECTED, "connected", false)
+);
+
+component_ty!(COMPONENT_RECONNECT, NAMESPACE_HTTP, COMPONENT_NAME_RECONNECT, "reconnect");
+
+component_model!(
+    ComponentReconnect,
+    set reconnect_initial_delay u64,
+    set reconnect_max_delay u64,
+    set reconnect_max_attempts u64,
+    get connected bool
+);
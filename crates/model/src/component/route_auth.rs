@@ -0,0 +1,34 @@
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    RouteAuthProperties,
+    (AUTH_TYPE, "auth_type", "none"),
+    (API_KEY_HEADER, "api_key_header", "X-API-Key"),
+    (API_KEYS, "api_keys", []),
+    (USERS, "users", {}),
+    (BEARER_TOKENS, "bearer_tokens", []),
+    (JWKS_URL, "jwks_url", ""),
+    (JWT_SECRET, "jwt_secret", ""),
+    (JWT_ISSUER, "jwt_issuer", ""),
+    (JWT_AUDIENCE, "jwt_audience", ""),
+    (REALM, "realm", "Inexor")
+);
+
+component_ty!(COMPONENT_ROUTE_AUTH, NAMESPACE_HTTP, COMPONENT_NAME_ROUTE_AUTH, "route_auth");
+
+component_model!(
+    ComponentRouteAuth,
+    set auth_type string,
+    set api_key_header string,
+    set api_keys array,
+    set users object,
+    set bearer_tokens array,
+    set jwks_url string,
+    set jwt_secret string,
+    set jwt_issuer string,
+    set jwt_audience string,
+    set realm string
+);
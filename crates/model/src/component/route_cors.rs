@@ -0,0 +1,26 @@
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    RouteCorsProperties,
+    (CORS_ORIGINS, "cors_origins", []),
+    (CORS_METHODS, "cors_methods", []),
+    (CORS_HEADERS, "cors_headers", []),
+    (CORS_EXPOSE_HEADERS, "cors_expose_headers", []),
+    (CORS_CREDENTIALS, "cors_credentials", false),
+    (CORS_MAX_AGE, "cors_max_age", 600)
+);
+
+component_ty!(COMPONENT_ROUTE_CORS, NAMESPACE_HTTP, COMPONENT_NAME_ROUTE_CORS, "route_cors");
+
+component_model!(
+    ComponentRouteCors,
+    set cors_origins array,
+    set cors_methods array,
+    set cors_headers array,
+    set cors_expose_headers array,
+    set cors_credentials bool,
+    set cors_max_age u64
+);
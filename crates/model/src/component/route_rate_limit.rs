@@ -0,0 +1,22 @@
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    RouteRateLimitProperties,
+    (RATE_LIMIT, "rate_limit", 0),
+    (RATE_LIMIT_WINDOW, "rate_limit_window", 60000),
+    (RATE_LIMIT_BURST, "rate_limit_burst", 0),
+    (TRUST_FORWARDED_FOR, "trust_forwarded_for", false)
+);
+
+component_ty!(COMPONENT_ROUTE_RATE_LIMIT, NAMESPACE_HTTP, COMPONENT_NAME_ROUTE_RATE_LIMIT, "route_rate_limit");
+
+component_model!(
+    ComponentRouteRateLimit,
+    set rate_limit u64,
+    set rate_limit_window u64,
+    set rate_limit_burst u64,
+    set trust_forwarded_for bool
+);
@@ -0,0 +1,32 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    SecurityHeadersProperties,
+    (URL, "url", ""),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (TIMEOUT, "timeout", 10000),
+    (REPORT, "report", []),
+    (SCORE, "score", 0),
+    (GRADE, "grade", ""),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_SECURITY_HEADERS, NAMESPACE_HTTP, COMPONENT_NAME_SECURITY_HEADERS, "security_headers");
+behaviour_ty!(BEHAVIOUR_SECURITY_HEADERS, NAMESPACE_HTTP, BEHAVIOUR_NAME_SECURITY_HEADERS, "security_headers");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_SECURITY_HEADERS, COMPONENT_SECURITY_HEADERS, BEHAVIOUR_SECURITY_HEADERS);
+
+component_model!(
+    ComponentSecurityHeaders,
+    set url string,
+    set request_headers object,
+    set timeout u64,
+    get report array,
+    get score u64,
+    get grade string,
+    get error string
+);
@@ -0,0 +1,37 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    SignalrProperties,
+    (URL, "url", ""),
+    (ACCESS_TOKEN, "access_token", ""),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (INVOKE_TARGET, "invoke_target", ""),
+    (INVOKE_ARGUMENTS, "invoke_arguments", []),
+    (TARGET, "target", ""),
+    (ARGUMENTS, "arguments", []),
+    (RESULT, "result", {}),
+    (CONNECTION_ID, "connection_id", ""),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_SIGNALR, NAMESPACE_HTTP, COMPONENT_NAME_SIGNALR, "signalr");
+behaviour_ty!(BEHAVIOUR_SIGNALR, NAMESPACE_HTTP, BEHAVIOUR_NAME_SIGNALR, "signalr");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_SIGNALR, COMPONENT_SIGNALR, BEHAVIOUR_SIGNALR);
+
+component_model!(
+    ComponentSignalr,
+    set url string,
+    set access_token string,
+    set request_headers object,
+    set invoke_target string,
+    set invoke_arguments array,
+    get target string,
+    get arguments array,
+    get connection_id string,
+    get error string
+);
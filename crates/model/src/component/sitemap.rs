@@ -0,0 +1,34 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    SitemapProperties,
+    (URL, "url", ""),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (MODIFIED_SINCE, "modified_since", ""),
+    (MAX_SITEMAPS, "max_sitemaps", 100),
+    (URLS, "urls", []),
+    (ENTRIES, "entries", []),
+    (DISCOVERED_URL, "discovered_url", ""),
+    (ERRORS, "errors", [])
+);
+
+component_ty!(COMPONENT_SITEMAP, NAMESPACE_HTTP, COMPONENT_NAME_SITEMAP, "sitemap");
+behaviour_ty!(BEHAVIOUR_SITEMAP, NAMESPACE_HTTP, BEHAVIOUR_NAME_SITEMAP, "sitemap");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_SITEMAP, COMPONENT_SITEMAP, BEHAVIOUR_SITEMAP);
+
+component_model!(
+    ComponentSitemap,
+    set url string,
+    set request_headers object,
+    set modified_since string,
+    set max_sitemaps u64,
+    get urls array,
+    get entries array,
+    get discovered_url string,
+    get errors array
+);
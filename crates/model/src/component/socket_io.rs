@@ -0,0 +1,41 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    SocketIoProperties,
+    (URL, "url", ""),
+    (PATH, "path", "/socket.io/"),
+    (NAMESPACE, "namespace", "/"),
+    (AUTH, "auth", {}),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (EMIT_EVENT, "emit_event", ""),
+    (EMIT_PAYLOAD, "emit_payload", {}),
+    (EVENT, "event", ""),
+    (PAYLOAD, "payload", {}),
+    (ACK, "ack", {}),
+    (ACK_EVENT, "ack_event", {}),
+    (SID, "sid", ""),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_SOCKET_IO, NAMESPACE_HTTP, COMPONENT_NAME_SOCKET_IO, "socket_io");
+behaviour_ty!(BEHAVIOUR_SOCKET_IO, NAMESPACE_HTTP, BEHAVIOUR_NAME_SOCKET_IO, "socket_io");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_SOCKET_IO, COMPONENT_SOCKET_IO, BEHAVIOUR_SOCKET_IO);
+
+component_model!(
+    ComponentSocketIo,
+    set url string,
+    set path string,
+    set namespace string,
+    set auth object,
+    set request_headers object,
+    set emit_event string,
+    get event string,
+    get ack_event object,
+    get sid string,
+    get error string
+);
@@ -0,0 +1,32 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    SseProperties,
+    (URL, "url", ""),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (IDLE_TIMEOUT, "idle_timeout", 60000),
+    (LAST_EVENT_ID, "last_event_id", ""),
+    (EVENT, "event", ""),
+    (DATA, "data", {}),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_SSE, NAMESPACE_HTTP, COMPONENT_NAME_SSE, "sse");
+behaviour_ty!(BEHAVIOUR_SSE, NAMESPACE_HTTP, BEHAVIOUR_NAME_SSE, "sse");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_SSE, COMPONENT_SSE, BEHAVIOUR_SSE);
+
+component_model!(
+    ComponentSse,
+    set url string,
+    set request_headers object,
+    set idle_timeout u64,
+    get last_event_id string,
+    get event string,
+    get data value,
+    get error string
+);
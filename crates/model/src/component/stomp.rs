@@ -0,0 +1,44 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    StompProperties,
+    (URL, "url", ""),
+    (LOGIN, "login", ""),
+    (PASSCODE, "passcode", ""),
+    (VIRTUAL_HOST, "virtual_host", ""),
+    (HEARTBEAT, "heartbeat", 10000),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (SUBSCRIPTIONS, "subscriptions", []),
+    (SEND_DESTINATION, "send_destination", ""),
+    (SEND_HEADERS, "send_headers", {}),
+    (SEND_BODY, "send_body", {}),
+    (MESSAGE_DESTINATION, "message_destination", ""),
+    (MESSAGE_HEADERS, "message_headers", {}),
+    (MESSAGE_BODY, "message_body", {}),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_STOMP, NAMESPACE_HTTP, COMPONENT_NAME_STOMP, "stomp");
+behaviour_ty!(BEHAVIOUR_STOMP, NAMESPACE_HTTP, BEHAVIOUR_NAME_STOMP, "stomp");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_STOMP, COMPONENT_STOMP, BEHAVIOUR_STOMP);
+
+component_model!(
+    ComponentStomp,
+    set url string,
+    set login string,
+    set passcode string,
+    set virtual_host string,
+    set heartbeat u64,
+    set request_headers object,
+    set subscriptions array,
+    set send_destination string,
+    set send_headers object,
+    get message_destination string,
+    get message_headers object,
+    get error string
+);
@@ -0,0 +1,50 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    TlsCertificateMonitorProperties,
+    (HOSTNAME, "hostname", ""),
+    (PORT, "port", 443),
+    (INTERVAL, "interval", 3600000),
+    (TIMEOUT, "timeout", 5000),
+    (EXPIRES_AT, "expires_at", ""),
+    (DAYS_REMAINING, "days_remaining", 0),
+    (ISSUER, "issuer", ""),
+    (CERTIFICATE_CHAIN, "certificate_chain", []),
+    (ERROR, "error", "")
+);
+
+component_ty!(
+    COMPONENT_TLS_CERTIFICATE_MONITOR,
+    NAMESPACE_HTTP,
+    COMPONENT_NAME_TLS_CERTIFICATE_MONITOR,
+    "tls_certificate_monitor"
+);
+behaviour_ty!(
+    BEHAVIOUR_TLS_CERTIFICATE_MONITOR,
+    NAMESPACE_HTTP,
+    BEHAVIOUR_NAME_TLS_CERTIFICATE_MONITOR,
+    "tls_certificate_monitor"
+);
+component_behaviour_ty!(
+    COMPONENT_BEHAVIOUR_TLS_CERTIFICATE_MONITOR,
+    COMPONENT_TLS_CERTIFICATE_MONITOR,
+    BEHAVIOUR_TLS_CERTIFICATE_MONITOR
+);
+
+component_model!(
+    ComponentTlsCertificateMonitor,
+    set hostname string,
+    set port u64,
+    set interval u64,
+    set timeout u64,
+    get expires_at string,
+    get days_remaining i64,
+    get issuer string,
+    get certificate_chain array,
+    get error string
+);
@@ -0,0 +1,30 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    TunnelProperties,
+    (PROVIDER, "provider", "ngrok"),
+    (PORT, "port", 8090),
+    (AUTH_TOKEN, "auth_token", ""),
+    (DOMAIN, "domain", ""),
+    (PUBLIC_URL, "public_url", ""),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_TUNNEL, NAMESPACE_HTTP, COMPONENT_NAME_TUNNEL, "tunnel");
+behaviour_ty!(BEHAVIOUR_TUNNEL, NAMESPACE_HTTP, BEHAVIOUR_NAME_TUNNEL, "tunnel");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_TUNNEL, COMPONENT_TUNNEL, BEHAVIOUR_TUNNEL);
+
+component_model!(
+    ComponentTunnel,
+    set provider string,
+    set port u64,
+    set auth_token string,
+    set domain string,
+    get public_url string,
+    get error string
+);
@@ -0,0 +1,50 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    UploadProperties,
+    (METHOD, "method", "PUT"),
+    (URL, "url", ""),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (PATH, "path", ""),
+    (FIELD_NAME, "field_name", ""),
+    (PROGRESS_GRANULARITY, "progress_granularity", 1),
+    (BYTES_UPLOADED, "bytes_uploaded", 0),
+    (TOTAL_BYTES, "total_bytes", 0),
+    (PROGRESS_PERCENT, "progress_percent", 0),
+    (STATUS, "status", 0),
+    (RESULT, "result", {}),
+    (ERROR, "error", ""),
+    (MAX_UPLOAD_KBPS, "max_upload_kbps", 0),
+    (PROTOCOL, "protocol", "plain"),
+    (CHUNK_SIZE, "chunk_size", 5242880),
+    (UPLOAD_URL, "upload_url", "")
+);
+
+component_ty!(COMPONENT_UPLOAD, NAMESPACE_HTTP, COMPONENT_NAME_UPLOAD, "upload");
+behaviour_ty!(BEHAVIOUR_UPLOAD, NAMESPACE_HTTP, BEHAVIOUR_NAME_UPLOAD, "upload");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_UPLOAD, COMPONENT_UPLOAD, BEHAVIOUR_UPLOAD);
+
+component_model!(
+    ComponentUpload,
+    set method string,
+    set url string,
+    set request_headers object,
+    set path string,
+    set field_name string,
+    set progress_granularity u64,
+    get bytes_uploaded u64,
+    get total_bytes u64,
+    get progress_percent u64,
+    get status u64,
+    get result object,
+    get error string,
+    set max_upload_kbps u64,
+    set protocol string,
+    set chunk_size u64,
+    get upload_url string
+);
@@ -0,0 +1,40 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    UploadEndpointProperties,
+    (PORT, "port", 8090),
+    (PATH, "path", "/upload"),
+    (DIRECTORY, "directory", "uploads"),
+    (MAX_FILE_SIZE, "max_file_size", 104857600),
+    (MAX_TOTAL_SIZE, "max_total_size", 1073741824),
+    (MAX_PARTS, "max_parts", 100),
+    (OVERWRITE, "overwrite", false),
+    (FILE, "file", {}),
+    (FILES, "files", []),
+    (FIELDS, "fields", {}),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_UPLOAD_ENDPOINT, NAMESPACE_HTTP, COMPONENT_NAME_UPLOAD_ENDPOINT, "upload_endpoint");
+behaviour_ty!(BEHAVIOUR_UPLOAD_ENDPOINT, NAMESPACE_HTTP, BEHAVIOUR_NAME_UPLOAD_ENDPOINT, "upload_endpoint");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_UPLOAD_ENDPOINT, COMPONENT_UPLOAD_ENDPOINT, BEHAVIOUR_UPLOAD_ENDPOINT);
+
+component_model!(
+    ComponentUploadEndpoint,
+    set port u64,
+    set path string,
+    set directory string,
+    set max_file_size u64,
+    set max_total_size u64,
+    set max_parts u64,
+    set overwrite bool,
+    get file object,
+    get files array,
+    get fields object,
+    get error string
+);
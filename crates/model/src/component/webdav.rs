@@ -0,0 +1,36 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    WebDavProperties,
+    (METHOD, "method", "PROPFIND"),
+    (URL, "url", ""),
+    (DESTINATION, "destination", ""),
+    (DEPTH, "depth", "1"),
+    (OVERWRITE, "overwrite", true),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (STATUS, "status", 0),
+    (RESOURCES, "resources", []),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_WEBDAV, NAMESPACE_HTTP, COMPONENT_NAME_WEBDAV, "webdav");
+behaviour_ty!(BEHAVIOUR_WEBDAV, NAMESPACE_HTTP, BEHAVIOUR_NAME_WEBDAV, "webdav");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_WEBDAV, COMPONENT_WEBDAV, BEHAVIOUR_WEBDAV);
+
+component_model!(
+    ComponentWebDav,
+    set method string,
+    set url string,
+    set destination string,
+    set depth string,
+    set overwrite bool,
+    set request_headers object,
+    get status u64,
+    get resources array,
+    get error string
+);
@@ -0,0 +1,42 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    WebhookRelayProperties,
+    (PORT, "port", 8090),
+    (PATH, "path", "/webhook"),
+    (TARGET_URL, "target_url", ""),
+    (METHOD, "method", "POST"),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (TRANSFORM, "transform", ""),
+    (INBOUND_METHOD, "inbound_method", ""),
+    (INBOUND_HEADERS, "inbound_headers", {}),
+    (INBOUND_BODY, "inbound_body", {}),
+    (OUTBOUND_STATUS, "outbound_status", 0),
+    (OUTBOUND_RESULT, "outbound_result", {}),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_WEBHOOK_RELAY, NAMESPACE_HTTP, COMPONENT_NAME_WEBHOOK_RELAY, "webhook_relay");
+behaviour_ty!(BEHAVIOUR_WEBHOOK_RELAY, NAMESPACE_HTTP, BEHAVIOUR_NAME_WEBHOOK_RELAY, "webhook_relay");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_WEBHOOK_RELAY, COMPONENT_WEBHOOK_RELAY, BEHAVIOUR_WEBHOOK_RELAY);
+
+component_model!(
+    ComponentWebhookRelay,
+    set port u64,
+    set path string,
+    set target_url string,
+    set method string,
+    set request_headers object,
+    set transform string,
+    get inbound_method string,
+    get inbound_headers object,
+    get inbound_body object,
+    get outbound_status u64,
+    get outbound_result object,
+    get error string
+);
@@ -0,0 +1,40 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    WebhookSenderProperties,
+    (METHOD, "method", "POST"),
+    (URL, "url", ""),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (PAYLOAD_TEMPLATE, "payload_template", {}),
+    (DATA, "data", {}),
+    (MAX_RETRIES, "max_retries", 3),
+    (RETRY_DELAY, "retry_delay", 1000),
+    (QUEUE_SIZE, "queue_size", 10),
+    (DELIVERY_STATUS, "delivery_status", ""),
+    (PERSISTENT, "persistent", false),
+    (ENV_IN_PAYLOAD, "env_in_payload", false)
+);
+
+component_ty!(COMPONENT_WEBHOOK_SENDER, NAMESPACE_HTTP, COMPONENT_NAME_WEBHOOK_SENDER, "webhook_sender");
+behaviour_ty!(BEHAVIOUR_WEBHOOK_SENDER, NAMESPACE_HTTP, BEHAVIOUR_NAME_WEBHOOK_SENDER, "webhook_sender");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_WEBHOOK_SENDER, COMPONENT_WEBHOOK_SENDER, BEHAVIOUR_WEBHOOK_SENDER);
+
+component_model!(
+    ComponentWebhookSender,
+    set method string,
+    set url string,
+    set request_headers object,
+    set payload_template value,
+    set data object,
+    set max_retries u64,
+    set retry_delay u64,
+    set queue_size u64,
+    get delivery_status string,
+    set persistent bool,
+    set env_in_payload bool
+);
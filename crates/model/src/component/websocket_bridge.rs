@@ -0,0 +1,36 @@
+use crate::model::behaviour_ty;
+use crate::model::component_behaviour_ty;
+use crate::model::component_model;
+use crate::model::component_ty;
+use crate::model::properties;
+use crate::NAMESPACE_HTTP;
+
+properties!(
+    WebSocketBridgeProperties,
+    (URL, "url", ""),
+    (REQUEST_HEADERS, "request_headers", {}),
+    (SUBPROTOCOL, "subprotocol", ""),
+    (PORT, "port", 8091),
+    (PATH, "path", "/"),
+    (UPSTREAM_TRANSFORM, "upstream_transform", ""),
+    (CLIENT_TRANSFORM, "client_transform", ""),
+    (CLIENTS, "clients", 0),
+    (ERROR, "error", "")
+);
+
+component_ty!(COMPONENT_WEBSOCKET_BRIDGE, NAMESPACE_HTTP, COMPONENT_NAME_WEBSOCKET_BRIDGE, "websocket_bridge");
+behaviour_ty!(BEHAVIOUR_WEBSOCKET_BRIDGE, NAMESPACE_HTTP, BEHAVIOUR_NAME_WEBSOCKET_BRIDGE, "websocket_bridge");
+component_behaviour_ty!(COMPONENT_BEHAVIOUR_WEBSOCKET_BRIDGE, COMPONENT_WEBSOCKET_BRIDGE, BEHAVIOUR_WEBSOCKET_BRIDGE);
+
+component_model!(
+    ComponentWebSocketBridge,
+    set url string,
+    set request_headers object,
+    set subprotocol string,
+    set port u64,
+    set path string,
+    set upstream_transform string,
+    set client_transform string,
+    get clients u64,
+    get error string
+);
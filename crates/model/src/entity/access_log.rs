@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentAccessLog;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_ACCESS_LOG, NAMESPACE_HTTP, ENTITY_TYPE_NAME_ACCESS_LOG, "access_log");
+
+entity_model!(AccessLog);
+impl ComponentAccessLog for AccessLog {}
+impl ComponentPausable for AccessLog {}
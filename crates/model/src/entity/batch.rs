@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentBatch;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_BATCH, NAMESPACE_HTTP, ENTITY_TYPE_NAME_BATCH, "batch");
+
+entity_model!(Batch);
+impl ComponentBatch for Batch {}
+impl ComponentPausable for Batch {}
@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentConnectivityCheck;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_CONNECTIVITY_CHECK, NAMESPACE_HTTP, ENTITY_TYPE_NAME_CONNECTIVITY_CHECK, "connectivity_check");
+
+entity_model!(ConnectivityCheck);
+impl ComponentConnectivityCheck for ConnectivityCheck {}
+impl ComponentPausable for ConnectivityCheck {}
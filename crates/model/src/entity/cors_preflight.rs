@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentCorsPreflight;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_CORS_PREFLIGHT, NAMESPACE_HTTP, ENTITY_TYPE_NAME_CORS_PREFLIGHT, "cors_preflight");
+
+entity_model!(CorsPreflight);
+impl ComponentCorsPreflight for CorsPreflight {}
+impl ComponentPausable for CorsPreflight {}
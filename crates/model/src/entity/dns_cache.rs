@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentDnsCache;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_DNS_CACHE, NAMESPACE_HTTP, ENTITY_TYPE_NAME_DNS_CACHE, "dns_cache");
+
+entity_model!(DnsCache);
+impl ComponentDnsCache for DnsCache {}
+impl ComponentPausable for DnsCache {}
@@ -0,0 +1,13 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::Action;
+use crate::ComponentDownload;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_DOWNLOAD, NAMESPACE_HTTP, ENTITY_TYPE_NAME_DOWNLOAD, "download");
+
+entity_model!(Download);
+impl ComponentDownload for Download {}
+impl Action for Download {}
+impl ComponentPausable for Download {}
@@ -0,0 +1,13 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::Action;
+use crate::ComponentFanOut;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_FAN_OUT, NAMESPACE_HTTP, ENTITY_TYPE_NAME_FAN_OUT, "fan_out");
+
+entity_model!(FanOut);
+impl ComponentFanOut for FanOut {}
+impl Action for FanOut {}
+impl ComponentPausable for FanOut {}
@@ -0,0 +1,15 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::Action;
+use crate::ComponentGraphQl;
+use crate::ComponentHttp;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_GRAPHQL, NAMESPACE_HTTP, ENTITY_TYPE_NAME_GRAPHQL, "graphql");
+
+entity_model!(GraphQl);
+impl ComponentGraphQl for GraphQl {}
+impl ComponentHttp for GraphQl {}
+impl Action for GraphQl {}
+impl ComponentPausable for GraphQl {}
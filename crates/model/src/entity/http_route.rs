@@ -0,0 +1,19 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentHttpResponse;
+use crate::ComponentHttpRoute;
+use crate::ComponentPausable;
+use crate::ComponentRouteAuth;
+use crate::ComponentRouteCors;
+use crate::ComponentRouteRateLimit;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_HTTP_ROUTE, NAMESPACE_HTTP, ENTITY_TYPE_NAME_HTTP_ROUTE, "http_route");
+
+entity_model!(HttpRoute);
+impl ComponentHttpRoute for HttpRoute {}
+impl ComponentHttpResponse for HttpRoute {}
+impl ComponentRouteAuth for HttpRoute {}
+impl ComponentRouteRateLimit for HttpRoute {}
+impl ComponentRouteCors for HttpRoute {}
+impl ComponentPausable for HttpRoute {}
@@ -0,0 +1,9 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentHttpSession;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_HTTP_SESSION, NAMESPACE_HTTP, ENTITY_TYPE_NAME_HTTP_SESSION, "http_session");
+
+entity_model!(HttpSession);
+impl ComponentHttpSession for HttpSession {}
@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentHypermedia;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_HYPERMEDIA, NAMESPACE_HTTP, ENTITY_TYPE_NAME_HYPERMEDIA, "hypermedia");
+
+entity_model!(Hypermedia);
+impl ComponentHypermedia for Hypermedia {}
+impl ComponentPausable for Hypermedia {}
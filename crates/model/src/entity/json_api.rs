@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentJsonApi;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_JSON_API, NAMESPACE_HTTP, ENTITY_TYPE_NAME_JSON_API, "json_api");
+
+entity_model!(JsonApi);
+impl ComponentJsonApi for JsonApi {}
+impl ComponentPausable for JsonApi {}
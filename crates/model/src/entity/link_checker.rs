@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentLinkChecker;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_LINK_CHECKER, NAMESPACE_HTTP, ENTITY_TYPE_NAME_LINK_CHECKER, "link_checker");
+
+entity_model!(LinkChecker);
+impl ComponentLinkChecker for LinkChecker {}
+impl ComponentPausable for LinkChecker {}
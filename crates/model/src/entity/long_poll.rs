@@ -0,0 +1,15 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentHttp;
+use crate::ComponentLongPoll;
+use crate::ComponentPausable;
+use crate::ComponentReconnect;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_LONG_POLL, NAMESPACE_HTTP, ENTITY_TYPE_NAME_LONG_POLL, "long_poll");
+
+entity_model!(LongPoll);
+impl ComponentHttp for LongPoll {}
+impl ComponentLongPoll for LongPoll {}
+impl ComponentReconnect for LongPoll {}
+impl ComponentPausable for LongPoll {}
@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentOData;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_ODATA, NAMESPACE_HTTP, ENTITY_TYPE_NAME_ODATA, "odata");
+
+entity_model!(OData);
+impl ComponentOData for OData {}
+impl ComponentPausable for OData {}
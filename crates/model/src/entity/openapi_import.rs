@@ -0,0 +1,13 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::Action;
+use crate::ComponentOpenApiImport;
+use crate::ComponentPausable;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_OPENAPI_IMPORT, NAMESPACE_HTTP, ENTITY_TYPE_NAME_OPENAPI_IMPORT, "openapi_import");
+
+entity_model!(OpenApiImport);
+impl ComponentOpenApiImport for OpenApiImport {}
+impl Action for OpenApiImport {}
+impl ComponentPausable for OpenApiImport {}
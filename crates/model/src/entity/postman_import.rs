@@ -0,0 +1,13 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::Action;
+use crate::ComponentPausable;
+use crate::ComponentPostmanImport;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_POSTMAN_IMPORT, NAMESPACE_HTTP, ENTITY_TYPE_NAME_POSTMAN_IMPORT, "postman_import");
+
+entity_model!(PostmanImport);
+impl ComponentPostmanImport for PostmanImport {}
+impl Action for PostmanImport {}
+impl ComponentPausable for PostmanImport {}
@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentPresignedUrl;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_PRESIGNED_URL, NAMESPACE_HTTP, ENTITY_TYPE_NAME_PRESIGNED_URL, "presigned_url");
+
+entity_model!(PresignedUrl);
+impl ComponentPresignedUrl for PresignedUrl {}
+impl ComponentPausable for PresignedUrl {}
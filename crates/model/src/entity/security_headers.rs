@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentSecurityHeaders;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_SECURITY_HEADERS, NAMESPACE_HTTP, ENTITY_TYPE_NAME_SECURITY_HEADERS, "security_headers");
+
+entity_model!(SecurityHeaders);
+impl ComponentSecurityHeaders for SecurityHeaders {}
+impl ComponentPausable for SecurityHeaders {}
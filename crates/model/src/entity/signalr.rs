@@ -0,0 +1,13 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentReconnect;
+use crate::ComponentSignalr;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_SIGNALR, NAMESPACE_HTTP, ENTITY_TYPE_NAME_SIGNALR, "signalr");
+
+entity_model!(Signalr);
+impl ComponentSignalr for Signalr {}
+impl ComponentReconnect for Signalr {}
+impl ComponentPausable for Signalr {}
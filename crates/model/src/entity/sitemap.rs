@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentSitemap;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_SITEMAP, NAMESPACE_HTTP, ENTITY_TYPE_NAME_SITEMAP, "sitemap");
+
+entity_model!(Sitemap);
+impl ComponentSitemap for Sitemap {}
+impl ComponentPausable for Sitemap {}
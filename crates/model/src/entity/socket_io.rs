@@ -0,0 +1,13 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentReconnect;
+use crate::ComponentSocketIo;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_SOCKET_IO, NAMESPACE_HTTP, ENTITY_TYPE_NAME_SOCKET_IO, "socket_io");
+
+entity_model!(SocketIo);
+impl ComponentSocketIo for SocketIo {}
+impl ComponentReconnect for SocketIo {}
+impl ComponentPausable for SocketIo {}
@@ -0,0 +1,13 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentReconnect;
+use crate::ComponentSse;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_SSE, NAMESPACE_HTTP, ENTITY_TYPE_NAME_SSE, "sse");
+
+entity_model!(Sse);
+impl ComponentSse for Sse {}
+impl ComponentReconnect for Sse {}
+impl ComponentPausable for Sse {}
@@ -0,0 +1,13 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentReconnect;
+use crate::ComponentStomp;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_STOMP, NAMESPACE_HTTP, ENTITY_TYPE_NAME_STOMP, "stomp");
+
+entity_model!(Stomp);
+impl ComponentStomp for Stomp {}
+impl ComponentReconnect for Stomp {}
+impl ComponentPausable for Stomp {}
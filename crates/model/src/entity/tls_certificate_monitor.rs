@@ -0,0 +1,16 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentTlsCertificateMonitor;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(
+    ENTITY_TYPE_TLS_CERTIFICATE_MONITOR,
+    NAMESPACE_HTTP,
+    ENTITY_TYPE_NAME_TLS_CERTIFICATE_MONITOR,
+    "tls_certificate_monitor"
+);
+
+entity_model!(TlsCertificateMonitor);
+impl ComponentTlsCertificateMonitor for TlsCertificateMonitor {}
+impl ComponentPausable for TlsCertificateMonitor {}
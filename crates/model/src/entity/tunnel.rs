@@ -0,0 +1,13 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentReconnect;
+use crate::ComponentTunnel;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_TUNNEL, NAMESPACE_HTTP, ENTITY_TYPE_NAME_TUNNEL, "tunnel");
+
+entity_model!(Tunnel);
+impl ComponentTunnel for Tunnel {}
+impl ComponentReconnect for Tunnel {}
+impl ComponentPausable for Tunnel {}
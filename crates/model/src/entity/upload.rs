@@ -0,0 +1,13 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::Action;
+use crate::ComponentPausable;
+use crate::ComponentUpload;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_UPLOAD, NAMESPACE_HTTP, ENTITY_TYPE_NAME_UPLOAD, "upload");
+
+entity_model!(Upload);
+impl ComponentUpload for Upload {}
+impl Action for Upload {}
+impl ComponentPausable for Upload {}
@@ -0,0 +1,17 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentRouteAuth;
+use crate::ComponentRouteCors;
+use crate::ComponentRouteRateLimit;
+use crate::ComponentUploadEndpoint;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_UPLOAD_ENDPOINT, NAMESPACE_HTTP, ENTITY_TYPE_NAME_UPLOAD_ENDPOINT, "upload_endpoint");
+
+entity_model!(UploadEndpoint);
+impl ComponentUploadEndpoint for UploadEndpoint {}
+impl ComponentRouteAuth for UploadEndpoint {}
+impl ComponentRouteRateLimit for UploadEndpoint {}
+impl ComponentRouteCors for UploadEndpoint {}
+impl ComponentPausable for UploadEndpoint {}
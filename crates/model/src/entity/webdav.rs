@@ -0,0 +1,11 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentWebDav;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_WEBDAV, NAMESPACE_HTTP, ENTITY_TYPE_NAME_WEBDAV, "webdav");
+
+entity_model!(WebDav);
+impl ComponentWebDav for WebDav {}
+impl ComponentPausable for WebDav {}
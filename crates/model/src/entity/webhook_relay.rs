@@ -0,0 +1,17 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentRouteAuth;
+use crate::ComponentRouteCors;
+use crate::ComponentRouteRateLimit;
+use crate::ComponentWebhookRelay;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_WEBHOOK_RELAY, NAMESPACE_HTTP, ENTITY_TYPE_NAME_WEBHOOK_RELAY, "webhook_relay");
+
+entity_model!(WebhookRelay);
+impl ComponentWebhookRelay for WebhookRelay {}
+impl ComponentRouteAuth for WebhookRelay {}
+impl ComponentRouteRateLimit for WebhookRelay {}
+impl ComponentRouteCors for WebhookRelay {}
+impl ComponentPausable for WebhookRelay {}
@@ -0,0 +1,13 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::Action;
+use crate::ComponentPausable;
+use crate::ComponentWebhookSender;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_WEBHOOK_SENDER, NAMESPACE_HTTP, ENTITY_TYPE_NAME_WEBHOOK_SENDER, "webhook_sender");
+
+entity_model!(WebhookSender);
+impl ComponentWebhookSender for WebhookSender {}
+impl Action for WebhookSender {}
+impl ComponentPausable for WebhookSender {}
@@ -0,0 +1,17 @@
+use crate::model::entity_model;
+use crate::model::entity_ty;
+use crate::ComponentPausable;
+use crate::ComponentReconnect;
+use crate::ComponentRouteAuth;
+use crate::ComponentRouteRateLimit;
+use crate::ComponentWebSocketBridge;
+use crate::NAMESPACE_HTTP;
+
+entity_ty!(ENTITY_TYPE_WEBSOCKET_BRIDGE, NAMESPACE_HTTP, ENTITY_TYPE_NAME_WEBSOCKET_BRIDGE, "websocket_bridge");
+
+entity_model!(WebSocketBridge);
+impl ComponentWebSocketBridge for WebSocketBridge {}
+impl ComponentReconnect for WebSocketBridge {}
+impl ComponentRouteAuth for WebSocketBridge {}
+impl ComponentRouteRateLimit for WebSocketBridge {}
+impl ComponentPausable for WebSocketBridge {}
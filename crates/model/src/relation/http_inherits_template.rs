@@ -0,0 +1,22 @@
+use crate::model::behaviour_ty;
+use crate::model::relation_behaviour_ty;
+use crate::model::relation_ty;
+use crate::NAMESPACE_HTTP;
+
+relation_ty!(
+    RELATION_TYPE_HTTP_INHERITS_TEMPLATE,
+    NAMESPACE_HTTP,
+    RELATION_TYPE_NAME_HTTP_INHERITS_TEMPLATE,
+    "http_inherits_template"
+);
+behaviour_ty!(
+    BEHAVIOUR_HTTP_INHERITS_TEMPLATE,
+    NAMESPACE_HTTP,
+    BEHAVIOUR_NAME_HTTP_INHERITS_TEMPLATE,
+    "http_inherits_template"
+);
+relation_behaviour_ty!(
+    RELATION_BEHAVIOUR_HTTP_INHERITS_TEMPLATE,
+    RELATION_TYPE_HTTP_INHERITS_TEMPLATE,
+    BEHAVIOUR_HTTP_INHERITS_TEMPLATE
+);
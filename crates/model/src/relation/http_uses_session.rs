@@ -0,0 +1,8 @@
+use crate::model::behaviour_ty;
+use crate::model::relation_behaviour_ty;
+use crate::model::relation_ty;
+use crate::NAMESPACE_HTTP;
+
+relation_ty!(RELATION_TYPE_HTTP_USES_SESSION, NAMESPACE_HTTP, RELATION_TYPE_NAME_HTTP_USES_SESSION, "http_uses_session");
+behaviour_ty!(BEHAVIOUR_HTTP_USES_SESSION, NAMESPACE_HTTP, BEHAVIOUR_NAME_HTTP_USES_SESSION, "http_uses_session");
+relation_behaviour_ty!(RELATION_BEHAVIOUR_HTTP_USES_SESSION, RELATION_TYPE_HTTP_USES_SESSION, BEHAVIOUR_HTTP_USES_SESSION);
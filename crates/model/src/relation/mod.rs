@@ -0,0 +1,5 @@
+pub use http_inherits_template::*;
+pub use http_uses_session::*;
+
+pub mod http_inherits_template;
+pub mod http_uses_session;
@@ -0,0 +1,68 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+
+use chrono::DateTime;
+use chrono::Utc;
+use lazy_static::lazy_static;
+use log::error;
+use log::info;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::config::get_config;
+
+/// A request which has been served by an embedded server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessLogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub port: u16,
+    /// The id of the route or none, if no route matched.
+    pub route: Option<Uuid>,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    /// The time until the response has been sent completely. Streamed responses are logged after the stream has ended.
+    pub duration_ms: u64,
+    pub remote_addr: String,
+}
+
+lazy_static! {
+    /// The subscribers which watch the served requests.
+    static ref SUBSCRIBERS: Mutex<Vec<Sender<AccessLogEntry>>> = Mutex::new(Vec::new());
+}
+
+/// Logs the served request and passes it to the subscribers.
+pub fn record_access(entry: AccessLogEntry) {
+    let config = &get_config().access_log;
+    if config.log {
+        info!(
+            "{} \"{} {}\" {} {}ms on port {}",
+            entry.remote_addr, entry.method, entry.path, entry.status, entry.duration_ms, entry.port
+        );
+    }
+    if !config.path.is_empty() {
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&config.path)
+            .and_then(|mut file| writeln!(file, "{}", json!(entry)));
+        if let Err(e) = result {
+            error!("Failed to write the access log {}: {}", config.path, e);
+        }
+    }
+    // Subscribers which have gone away are removed
+    SUBSCRIBERS.lock().unwrap().retain(|subscriber| subscriber.send(entry.clone()).is_ok());
+}
+
+/// Returns a receiver of the requests which are served from now on. Dropping the receiver ends the subscription.
+pub fn subscribe_access_log() -> Receiver<AccessLogEntry> {
+    let (sender, receiver) = channel();
+    SUBSCRIBERS.lock().unwrap().push(sender);
+    receiver
+}
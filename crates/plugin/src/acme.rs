@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use chrono::Utc;
+use lazy_static::lazy_static;
+use log::error;
+use log::info;
+use rcgen::CertificateParams;
+use rcgen::DistinguishedName;
+use ring::rand::SystemRandom;
+use ring::signature::EcdsaKeyPair;
+use ring::signature::KeyPair;
+use ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING;
+use serde_json::json;
+use serde_json::Value;
+use sha2::Digest;
+use sha2::Sha256;
+use uuid::Uuid;
+use x509_parser::pem::parse_x509_pem;
+
+use crate::config::get_config;
+use crate::config::AcmeConfig;
+use crate::config::ServerConfig;
+use crate::server::register_route;
+use crate::server::restart_server;
+use crate::server::unregister_route;
+use crate::server::OutgoingResponse;
+use crate::server::Route;
+use crate::server::RouteHandler;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+use crate::worker::WorkerSignal;
+
+/// The interval in which the certificates are checked for renewal.
+const CHECK_INTERVAL: Duration = Duration::from_secs(12 * 3600);
+
+/// The interval in which the state of an authorization or an order is polled.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+const MAX_POLLS: u32 = 60;
+
+const WORKER_NAME_ACME: &str = "acme";
+
+lazy_static! {
+    static ref ACME_WORKER_ID: Uuid = Uuid::new_v4();
+
+    /// The key authorizations of the pending HTTP-01 challenges by token.
+    static ref CHALLENGES: RwLock<HashMap<String, String>> = RwLock::new(HashMap::new());
+}
+
+/// Orders and renews the certificates of the embedded servers with ACME settings.
+///
+/// Until the first certificate has been issued, a self-signed certificate is used, so that the server can be started.
+/// Only HTTP-01 challenges are supported, because the TLS connections are terminated by the embedded server, which can't
+/// negotiate the ALPN protocol acme-tls/1 of TLS-ALPN-01 challenges.
+pub fn start_acme() {
+    let servers: Vec<&'static ServerConfig> = get_config().servers.iter().filter(|server| server.acme.is_some()).collect();
+    if servers.is_empty() {
+        return;
+    }
+    for server in servers.iter() {
+        if let Err(e) = create_placeholder_certificate(server) {
+            error!("Failed to create the certificate of the embedded server on port {}: {}", server.port, e);
+        }
+    }
+    start_worker(*ACME_WORKER_ID, WORKER_NAME_ACME, move |signal| loop {
+        for server in servers.iter() {
+            if let Err(e) = renew_certificate(server, &signal) {
+                error!("Failed to renew the certificate of the embedded server on port {}: {}", server.port, e);
+            }
+        }
+        if !signal.sleep(CHECK_INTERVAL) {
+            return;
+        }
+    });
+}
+
+pub fn stop_acme() {
+    stop_worker(*ACME_WORKER_ID, WORKER_NAME_ACME);
+}
+
+fn create_placeholder_certificate(server: &ServerConfig) -> Result<(), String> {
+    let acme = server.acme.as_ref().unwrap();
+    if !server.has_tls() {
+        return Err(String::from("The certificate and the private key have to be configured"));
+    }
+    if Path::new(&server.certificate).exists() {
+        return Ok(());
+    }
+    let certificate = rcgen::generate_simple_self_signed(acme.domains.clone()).map_err(|e| e.to_string())?;
+    let certificate_pem = certificate.serialize_pem().map_err(|e| e.to_string())?;
+    write_certificate(server, certificate.serialize_private_key_pem().as_bytes(), certificate_pem.as_bytes())
+}
+
+/// Orders a new certificate, if the certificate is self-signed or expires soon, and restarts the server with it.
+fn renew_certificate(server: &ServerConfig, signal: &WorkerSignal) -> Result<(), String> {
+    let acme = server.acme.as_ref().unwrap();
+    if !server.has_tls() || acme.domains.is_empty() {
+        return Err(String::from("The certificate, the private key and the domains have to be configured"));
+    }
+    if let Ok(pem) = fs::read(&server.certificate) {
+        let (_, pem) = parse_x509_pem(&pem).map_err(|e| format!("Invalid certificate: {}", e))?;
+        let certificate = pem.parse_x509().map_err(|e| format!("Invalid certificate: {}", e))?;
+        let renew_at = certificate.validity().not_after.timestamp() - (acme.renew_before_days * 86400) as i64;
+        let self_signed = certificate.issuer().to_string() == certificate.subject().to_string();
+        if !self_signed && Utc::now().timestamp() < renew_at {
+            return Ok(());
+        }
+    }
+    info!("Ordering the certificate of {} for the embedded server on port {}", acme.domains.join(", "), server.port);
+    let mut client = AcmeClient::new(acme)?;
+    let (certificate_chain, private_key) = client.order_certificate(acme, signal)?;
+    write_certificate(server, private_key.as_bytes(), certificate_chain.as_bytes())?;
+    info!("Issued the certificate of {} for the embedded server on port {}", acme.domains.join(", "), server.port);
+    restart_server(server.port)
+}
+
+/// A client of the ACME protocol (RFC 8555), which signs its requests with the account key.
+struct AcmeClient {
+    key_pair: EcdsaKeyPair,
+    directory: Value,
+    /// The url of the account, which identifies the key after the account has been registered.
+    kid: Option<String>,
+    nonce: Option<String>,
+}
+
+impl AcmeClient {
+    fn new(acme: &AcmeConfig) -> Result<Self, String> {
+        let directory = ureq::get(&acme.directory)
+            .call()
+            .map_err(|e| format!("Failed to fetch the directory {}: {}", acme.directory, e))?
+            .into_json()
+            .map_err(|e| format!("Invalid directory {}: {}", acme.directory, e))?;
+        Ok(AcmeClient {
+            key_pair: load_account_key(&acme.account_key)?,
+            directory,
+            kid: None,
+            nonce: None,
+        })
+    }
+
+    /// Registers the account or looks up the existing account of the key, validates the domains and returns the
+    /// certificate chain and the private key.
+    fn order_certificate(&mut self, acme: &AcmeConfig, signal: &WorkerSignal) -> Result<(String, String), String> {
+        let new_account = self.resource("newAccount")?;
+        let response = self.post(&new_account, Some(&json!({ "termsOfServiceAgreed": true, "contact": acme.contact })))?;
+        self.kid = Some(location(&response)?);
+        let identifiers: Vec<Value> = acme.domains.iter().map(|domain| json!({ "type": "dns", "value": domain })).collect();
+        let new_order = self.resource("newOrder")?;
+        let response = self.post(&new_order, Some(&json!({ "identifiers": identifiers })))?;
+        let order_url = location(&response)?;
+        let order: Value = response.into_json().map_err(|e| e.to_string())?;
+        let route_id = Uuid::new_v4();
+        let route = Route {
+            id: route_id,
+            method: Some(String::from("GET")),
+            path: String::from("/.well-known/acme-challenge/:token"),
+            handler: RouteHandler::Buffered(Arc::new(|request| {
+                let token = request.params.get("token").and_then(Value::as_str).unwrap_or_default();
+                match CHALLENGES.read().unwrap().get(token) {
+                    Some(key_authorization) => OutgoingResponse {
+                        status: 200,
+                        headers: vec![(String::from("Content-Type"), String::from("text/plain"))],
+                        body: key_authorization.clone().into_bytes(),
+                        stream: None,
+                    },
+                    None => OutgoingResponse::status(404),
+                }
+            })),
+            cors: None,
+        };
+        register_route(acme.challenge_port, route)?;
+        let result = self.authorize(&order, signal).and_then(|_| self.finalize(acme, &order, &order_url, signal));
+        unregister_route(route_id);
+        result
+    }
+
+    /// Answers the HTTP-01 challenges of the authorizations of the order.
+    fn authorize(&mut self, order: &Value, signal: &WorkerSignal) -> Result<(), String> {
+        for authorization_url in order["authorizations"].as_array().cloned().unwrap_or_default() {
+            let authorization_url = authorization_url.as_str().unwrap_or_default();
+            let authorization: Value = self.post(authorization_url, None)?.into_json().map_err(|e| e.to_string())?;
+            let domain = authorization["identifier"]["value"].as_str().unwrap_or_default().to_string();
+            if authorization["status"] == json!("valid") {
+                continue;
+            }
+            let challenge = authorization["challenges"]
+                .as_array()
+                .and_then(|challenges| challenges.iter().find(|challenge| challenge["type"] == json!("http-01")))
+                .ok_or_else(|| format!("The certificate authority doesn't offer an HTTP-01 challenge for {}", domain))?;
+            let token = challenge["token"].as_str().unwrap_or_default().to_string();
+            let key_authorization = format!("{}.{}", token, self.thumbprint());
+            CHALLENGES.write().unwrap().insert(token.clone(), key_authorization);
+            let result = self
+                .post(challenge["url"].as_str().unwrap_or_default(), Some(&json!({})))
+                .and_then(|_| self.poll(authorization_url, signal));
+            CHALLENGES.write().unwrap().remove(&token);
+            let authorization = result?;
+            if authorization["status"] != json!("valid") {
+                let e = authorization["challenges"]
+                    .as_array()
+                    .and_then(|challenges| challenges.iter().find_map(|challenge| challenge["error"]["detail"].as_str()))
+                    .unwrap_or("The challenge failed");
+                return Err(format!("The validation of {} failed: {}", domain, e));
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends the certificate signing request and downloads the issued certificate chain.
+    fn finalize(&mut self, acme: &AcmeConfig, order: &Value, order_url: &str, signal: &WorkerSignal) -> Result<(String, String), String> {
+        let mut params = CertificateParams::new(acme.domains.clone());
+        params.distinguished_name = DistinguishedName::new();
+        let certificate = rcgen::Certificate::from_params(params).map_err(|e| e.to_string())?;
+        let csr = certificate.serialize_request_der().map_err(|e| e.to_string())?;
+        self.post(order["finalize"].as_str().unwrap_or_default(), Some(&json!({ "csr": encode(&csr) })))?;
+        let order = self.poll(order_url, signal)?;
+        if order["status"] != json!("valid") {
+            return Err(format!("The order is {}", order["status"].as_str().unwrap_or("invalid")));
+        }
+        let certificate_chain = self
+            .post(order["certificate"].as_str().unwrap_or_default(), None)?
+            .into_string()
+            .map_err(|e| e.to_string())?;
+        Ok((certificate_chain, certificate.serialize_private_key_pem()))
+    }
+
+    /// Fetches the resource until it is neither pending nor processing.
+    fn poll(&mut self, url: &str, signal: &WorkerSignal) -> Result<Value, String> {
+        for _ in 0..MAX_POLLS {
+            let resource: Value = self.post(url, None)?.into_json().map_err(|e| e.to_string())?;
+            if resource["status"] != json!("pending") && resource["status"] != json!("processing") {
+                return Ok(resource);
+            }
+            if !signal.sleep(POLL_INTERVAL) {
+                return Err(String::from("The order has been cancelled"));
+            }
+        }
+        Err(format!("{} is still pending", url))
+    }
+
+    fn resource(&self, name: &str) -> Result<String, String> {
+        self.directory[name]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| format!("The directory doesn't contain {}", name))
+    }
+
+    fn jwk(&self) -> Value {
+        // The uncompressed point consists of the tag and the coordinates
+        let public_key = self.key_pair.public_key().as_ref();
+        json!({ "crv": "P-256", "kty": "EC", "x": encode(&public_key[1..33]), "y": encode(&public_key[33..65]) })
+    }
+
+    /// Returns the thumbprint of the account key (RFC 7638), which is part of the key authorizations.
+    fn thumbprint(&self) -> String {
+        let jwk = self.jwk();
+        // The members are hashed without whitespace in lexicographic order
+        let canonical = format!(
+            r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#,
+            jwk["x"].as_str().unwrap_or_default(),
+            jwk["y"].as_str().unwrap_or_default()
+        );
+        encode(&Sha256::digest(canonical.as_bytes()))
+    }
+
+    fn next_nonce(&mut self) -> Result<String, String> {
+        if let Some(nonce) = self.nonce.take() {
+            return Ok(nonce);
+        }
+        let new_nonce = self.resource("newNonce")?;
+        let response = ureq::head(&new_nonce).call().map_err(|e| format!("Failed to fetch a nonce: {}", e))?;
+        response
+            .header("Replay-Nonce")
+            .map(String::from)
+            .ok_or_else(|| String::from("The certificate authority didn't send a nonce"))
+    }
+
+    /// Sends the payload as JWS. Without payload a POST-as-GET request is sent. A rejected nonce is retried once.
+    fn post(&mut self, url: &str, payload: Option<&Value>) -> Result<ureq::Response, String> {
+        for attempt in 0..2 {
+            let mut protected = json!({ "alg": "ES256", "nonce": self.next_nonce()?, "url": url });
+            match &self.kid {
+                Some(kid) => protected["kid"] = json!(kid),
+                None => protected["jwk"] = self.jwk(),
+            }
+            let protected = encode(protected.to_string().as_bytes());
+            let payload = payload.map(|payload| encode(payload.to_string().as_bytes())).unwrap_or_default();
+            let signature = self
+                .key_pair
+                .sign(&SystemRandom::new(), format!("{}.{}", protected, payload).as_bytes())
+                .map_err(|_| String::from("Failed to sign the request"))?;
+            let body = json!({ "protected": protected, "payload": payload, "signature": encode(signature.as_ref()) });
+            match ureq::post(url).set("Content-Type", "application/jose+json").send_string(&body.to_string()) {
+                Ok(response) => {
+                    self.nonce = response.header("Replay-Nonce").map(String::from);
+                    return Ok(response);
+                }
+                Err(ureq::Error::Status(status, response)) => {
+                    self.nonce = response.header("Replay-Nonce").map(String::from);
+                    let problem: Value = response.into_json().unwrap_or(Value::Null);
+                    if problem["type"] == json!("urn:ietf:params:acme:error:badNonce") && attempt == 0 {
+                        continue;
+                    }
+                    return Err(format!("{} answered with {}: {}", url, status, problem["detail"].as_str().unwrap_or_default()));
+                }
+                Err(e) => return Err(format!("Failed to send the request to {}: {}", url, e)),
+            }
+        }
+        Err(format!("{} rejected the nonce", url))
+    }
+}
+
+fn location(response: &ureq::Response) -> Result<String, String> {
+    response
+        .header("Location")
+        .map(String::from)
+        .ok_or_else(|| format!("The response of {} doesn't contain a location", response.get_url()))
+}
+
+/// Loads the PKCS#8 account key or creates it, if the file doesn't exist.
+fn load_account_key(path: &str) -> Result<EcdsaKeyPair, String> {
+    let pkcs8 = match fs::read(path) {
+        Ok(pkcs8) => pkcs8,
+        Err(_) => {
+            let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &SystemRandom::new())
+                .map_err(|_| String::from("Failed to create the account key"))?;
+            let temp_path = write_temp_file(path, pkcs8.as_ref(), true)?;
+            rename(&temp_path, path)?;
+            pkcs8.as_ref().to_vec()
+        }
+    };
+    EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &pkcs8).map_err(|e| format!("Invalid account key {}: {}", path, e))
+}
+
+/// Replaces the private key and the certificate of the server. Both are renamed into place only after both have been
+/// written, so that a failure doesn't leave a private key which doesn't match the certificate.
+fn write_certificate(server: &ServerConfig, private_key: &[u8], certificate: &[u8]) -> Result<(), String> {
+    let private_key_path = write_temp_file(&server.private_key, private_key, true)?;
+    let certificate_path = match write_temp_file(&server.certificate, certificate, false) {
+        Ok(certificate_path) => certificate_path,
+        Err(e) => {
+            let _ = fs::remove_file(&private_key_path);
+            return Err(e);
+        }
+    };
+    rename(&private_key_path, &server.private_key)?;
+    rename(&certificate_path, &server.certificate)
+}
+
+/// Writes the contents to a new temporary file next to the path. Private files are only readable by the owner.
+fn write_temp_file(path: &str, contents: &[u8], private: bool) -> Result<PathBuf, String> {
+    if let Some(directory) = Path::new(path).parent().filter(|directory| !directory.as_os_str().is_empty()) {
+        fs::create_dir_all(directory).map_err(|e| format!("Failed to create the directory of {}: {}", path, e))?;
+    }
+    let temp_path = PathBuf::from(format!("{}.{}.tmp", path, Uuid::new_v4().simple()));
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    if private {
+        restrict_permissions(&mut options);
+    }
+    let result = options.open(&temp_path).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("Failed to write {}: {}", path, e));
+    }
+    Ok(temp_path)
+}
+
+#[cfg(unix)]
+fn restrict_permissions(options: &mut OpenOptions) {
+    options.mode(0o600);
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_: &mut OpenOptions) {}
+
+fn rename(temp_path: &Path, path: &str) -> Result<(), String> {
+    fs::rename(temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(temp_path);
+        format!("Failed to write {}: {}", path, e)
+    })
+}
+
+fn encode(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_the_private_key_and_the_certificate() {
+        let directory = std::env::temp_dir().join(format!("acme-{}", Uuid::new_v4().simple()));
+        let server = ServerConfig {
+            port: 8443,
+            certificate: directory.join("certificate.pem").to_string_lossy().to_string(),
+            private_key: directory.join("keys").join("private_key.pem").to_string_lossy().to_string(),
+            acme: None,
+        };
+        write_certificate(&server, b"key", b"certificate").unwrap();
+        assert_eq!(fs::read(&server.private_key).unwrap(), b"key");
+        assert_eq!(fs::read(&server.certificate).unwrap(), b"certificate");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&server.private_key).unwrap().permissions().mode();
+            assert_eq!(mode & 0o077, 0);
+        }
+        let leftovers = fs::read_dir(&directory)
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
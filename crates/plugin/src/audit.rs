@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+use std::sync::RwLock;
+
+use chrono::DateTime;
+use chrono::Utc;
+use lazy_static::lazy_static;
+use log::error;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::config::get_config;
+use crate::headers::is_credential_header;
+
+/// A request which has been sent by an instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    /// The name of the behaviour which has sent the request.
+    #[serde(default)]
+    pub behaviour: String,
+    pub timestamp: DateTime<Utc>,
+    pub method: String,
+    pub url: String,
+    pub request_headers: Map<String, Value>,
+    pub payload: Value,
+    /// The status or none, if the server was not reachable.
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Filters the audit log. Empty criteria match all entries.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditQuery {
+    pub id: Option<Uuid>,
+    pub since: Option<DateTime<Utc>>,
+    /// Only failed requests: requests without status or with a status of 400 or above.
+    pub failed: bool,
+    /// The maximum number of entries, the most recent first. Zero means unlimited.
+    pub limit: usize,
+}
+
+impl AuditQuery {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.id.map(|id| id == entry.id).unwrap_or(true)
+            && self.since.map(|since| entry.timestamp >= since).unwrap_or(true)
+            && (!self.failed || entry.status.map(|status| status >= 400).unwrap_or(true))
+    }
+}
+
+lazy_static! {
+    static ref AUDIT_LOG: RwLock<VecDeque<AuditEntry>> = RwLock::new(VecDeque::new());
+    /// The subscribers which watch the requests as they are sent.
+    static ref SUBSCRIBERS: Mutex<Vec<Sender<AuditEntry>>> = Mutex::new(Vec::new());
+}
+
+/// Records the request. The oldest entries are dropped if the capacity is exceeded.
+///
+/// The credentials in the request headers are redacted.
+pub fn record_request(mut entry: AuditEntry) {
+    for (name, value) in entry.request_headers.iter_mut() {
+        if is_credential_header(name) {
+            *value = json!("***");
+        }
+    }
+    // Subscribers which have gone away are removed
+    SUBSCRIBERS.lock().unwrap().retain(|subscriber| subscriber.send(entry.clone()).is_ok());
+    let config = &get_config().audit;
+    if config.capacity == 0 {
+        return;
+    }
+    if !config.path.is_empty() {
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&config.path)
+            .and_then(|mut file| writeln!(file, "{}", json!(entry)));
+        if let Err(e) = result {
+            error!("Failed to write the audit log {}: {}", config.path, e);
+        }
+    }
+    let mut audit_log = AUDIT_LOG.write().unwrap();
+    while audit_log.len() >= config.capacity {
+        audit_log.pop_front();
+    }
+    audit_log.push_back(entry);
+}
+
+/// Returns the matching entries, the most recent first.
+pub fn query_audit_log(query: &AuditQuery) -> Vec<AuditEntry> {
+    let audit_log = AUDIT_LOG.read().unwrap();
+    let entries = audit_log.iter().rev().filter(|entry| query.matches(entry)).cloned();
+    match query.limit {
+        0 => entries.collect(),
+        limit => entries.take(limit).collect(),
+    }
+}
+
+/// Returns a receiver of the requests which are recorded from now on. Dropping the receiver ends the subscription.
+pub fn subscribe_requests() -> Receiver<AuditEntry> {
+    let (sender, receiver) = channel();
+    SUBSCRIBERS.lock().unwrap().push(sender);
+    receiver
+}
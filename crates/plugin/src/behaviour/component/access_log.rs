@@ -0,0 +1,58 @@
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::access_log::subscribe_access_log;
+use crate::model::*;
+use crate::model_http::AccessLogProperties::ENTRY;
+use crate::model_http::AccessLogProperties::PATH_PREFIX;
+use crate::model_http::AccessLogProperties::PORT;
+use crate::model_http::BEHAVIOUR_NAME_ACCESS_LOG;
+use crate::pausable::is_enabled;
+use crate::reactive::*;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+
+/// The interval in which the worker looks whether it has been stopped while no requests are served.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+entity_behaviour!(AccessLog, AccessLogFactory, AccessLogFsm, AccessLogBehaviourTransitions, AccessLogValidator);
+
+behaviour_validator!(AccessLogValidator, ReactiveEntityInstance, PORT.as_ref(), ENTRY.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for AccessLogBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for AccessLogBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        start_worker(self.reactive_instance.id, BEHAVIOUR_NAME_ACCESS_LOG, move |signal| {
+            let receiver = subscribe_access_log();
+            while !signal.is_stopped() {
+                let entry = match receiver.recv_timeout(POLL_INTERVAL) {
+                    Ok(entry) => entry,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                };
+                // Requests are dropped while the instance is paused
+                if !is_enabled(&reactive_instance) {
+                    continue;
+                }
+                let port = reactive_instance.as_u64(PORT).unwrap_or(0);
+                let path_prefix = reactive_instance.as_string(PATH_PREFIX).unwrap_or_default();
+                if (port == 0 || port == entry.port as u64) && entry.path.starts_with(&path_prefix) {
+                    reactive_instance.set(ENTRY, json!(entry));
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        stop_worker(self.reactive_instance.id, BEHAVIOUR_NAME_ACCESS_LOG);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for AccessLogBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for AccessLogBehaviourTransitions {}
@@ -0,0 +1,88 @@
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::concurrency::map_concurrently;
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::BatchProperties::ENV_IN_PAYLOAD;
+use crate::model_http::BatchProperties::MAX_CONCURRENCY;
+use crate::model_http::BatchProperties::REQUESTS;
+use crate::model_http::BatchProperties::REQUEST_HEADERS;
+use crate::model_http::BatchProperties::RESULTS;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::placeholder::resolve_env_in_value;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+
+entity_behaviour!(Batch, BatchFactory, BatchFsm, BatchBehaviourTransitions, BatchValidator);
+
+behaviour_validator!(BatchValidator, ReactiveEntityInstance, REQUESTS.as_ref(), MAX_CONCURRENCY.as_ref(), RESULTS.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for BatchBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for BatchBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            batch(&reactive_instance);
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for BatchBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for BatchBehaviourTransitions {}
+
+fn batch(reactive_instance: &ReactiveEntityInstance) {
+    let requests = reactive_instance.as_array(REQUESTS).unwrap_or_default();
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let max_concurrency = reactive_instance.as_u64(MAX_CONCURRENCY).unwrap_or(4) as usize;
+    let agent = UrlPolicy::from(reactive_instance).agent();
+    let env_in_payload = reactive_instance.as_bool(ENV_IN_PAYLOAD).unwrap_or(false);
+    let results = map_concurrently(&requests, max_concurrency, |_, request| execute(&agent, request, &request_headers, env_in_payload));
+    reactive_instance.set(RESULTS, json!(results));
+}
+
+/// Sends the request described by the spec and returns the status, the result and the error.
+///
+/// The spec consists of the url, the method (default GET), the payload and headers which are merged into the headers of
+/// the batch.
+fn execute(agent: &ureq::Agent, spec: &Value, request_headers: &Map<String, Value>, env_in_payload: bool) -> Value {
+    let Some(url) = spec["url"].as_str().map(resolve_env) else {
+        return json!({ "status": null, "result": null, "error": "The request has no url" });
+    };
+    let method = spec["method"].as_str().unwrap_or("GET");
+    let mut request_headers = request_headers.clone();
+    if let Some(headers) = spec["headers"].as_object() {
+        request_headers.extend(headers.clone());
+    }
+    let request = match set_request_headers(agent.request(method, &url), &url, &request_headers) {
+        Ok(request) => request,
+        Err(e) => return json!({ "status": null, "result": null, "error": e }),
+    };
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    let response = match spec.get("payload").filter(|payload| !payload.is_null()) {
+        Some(payload) if env_in_payload => request.send_json(resolve_env_in_value(payload)),
+        Some(payload) => request.send_json(payload.clone()),
+        None => request.call(),
+    };
+    match response {
+        Ok(response) => {
+            let status = response.status();
+            match response.into_json::<Value>() {
+                Ok(result) => json!({ "status": status, "result": result, "error": null }),
+                Err(e) => json!({ "status": status, "result": null, "error": format!("Failed to parse response as JSON: {}", e) }),
+            }
+        }
+        Err(ureq::Error::Status(status, response)) => json!({ "status": status, "result": null, "error": response.status_text() }),
+        Err(e) => json!({ "status": null, "result": null, "error": e.to_string() }),
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::info;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::connectivity::remove_connectivity_check;
+use crate::connectivity::set_online;
+use crate::model::*;
+use crate::model_http::ConnectivityCheckProperties::CAPTIVE_PORTAL;
+use crate::model_http::ConnectivityCheckProperties::ENDPOINTS;
+use crate::model_http::ConnectivityCheckProperties::INTERVAL;
+use crate::model_http::ConnectivityCheckProperties::ONLINE;
+use crate::model_http::ConnectivityCheckProperties::TIMEOUT;
+use crate::model_http::BEHAVIOUR_NAME_CONNECTIVITY_CHECK;
+use crate::pausable::is_enabled;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+use crate::worker::WorkerSignal;
+
+entity_behaviour!(
+    ConnectivityCheck,
+    ConnectivityCheckFactory,
+    ConnectivityCheckFsm,
+    ConnectivityCheckBehaviourTransitions,
+    ConnectivityCheckValidator
+);
+
+behaviour_validator!(ConnectivityCheckValidator, ReactiveEntityInstance, ENDPOINTS.as_ref(), ONLINE.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for ConnectivityCheckBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for ConnectivityCheckBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        start_worker(self.reactive_instance.id, BEHAVIOUR_NAME_CONNECTIVITY_CHECK, move |signal| check(reactive_instance, signal));
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        stop_worker(self.reactive_instance.id, BEHAVIOUR_NAME_CONNECTIVITY_CHECK);
+        remove_connectivity_check(self.reactive_instance.id);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for ConnectivityCheckBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for ConnectivityCheckBehaviourTransitions {}
+
+/// The result of probing an endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Probe {
+    /// The endpoint responded with 204 No Content.
+    Online,
+    /// The response has been replaced, for example by the login page of a captive portal.
+    CaptivePortal,
+    Unreachable,
+}
+
+fn check(reactive_instance: Arc<ReactiveEntityInstance>, signal: WorkerSignal) {
+    loop {
+        if is_enabled(&reactive_instance) {
+            probe_endpoints(&reactive_instance);
+        }
+        if !signal.sleep(Duration::from_millis(reactive_instance.as_u64(INTERVAL).unwrap_or(30000))) {
+            break;
+        }
+    }
+}
+
+fn probe_endpoints(reactive_instance: &ReactiveEntityInstance) {
+    let timeout = Duration::from_millis(reactive_instance.as_u64(TIMEOUT).unwrap_or(5000));
+    let endpoints = reactive_instance.as_array(ENDPOINTS).unwrap_or_default();
+    // Redirects are not followed because captive portals redirect to their login page
+    let agent = UrlPolicy::from(reactive_instance).agent_builder().redirects(0).timeout(timeout).build();
+    let probes: Vec<Probe> = endpoints.iter().filter_map(Value::as_str).map(|endpoint| probe(&agent, endpoint)).collect();
+    // A single endpoint which is reachable suffices
+    let online = probes.contains(&Probe::Online);
+    let captive_portal = !online && probes.contains(&Probe::CaptivePortal);
+    if reactive_instance.as_bool(ONLINE) != Some(online) {
+        info!("The network is {}", if online { "online" } else { "offline" });
+        reactive_instance.set(ONLINE, json!(online));
+    }
+    if reactive_instance.as_bool(CAPTIVE_PORTAL) != Some(captive_portal) {
+        reactive_instance.set(CAPTIVE_PORTAL, json!(captive_portal));
+    }
+    set_online(reactive_instance.id, online);
+}
+
+fn probe(agent: &ureq::Agent, endpoint: &str) -> Probe {
+    match agent.get(endpoint).call() {
+        Ok(response) if response.status() == 204 => Probe::Online,
+        Ok(_) | Err(ureq::Error::Status(_, _)) => Probe::CaptivePortal,
+        Err(_) => Probe::Unreachable,
+    }
+}
@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use log::error;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::CorsPreflightProperties::ALLOWED;
+use crate::model_http::CorsPreflightProperties::CREDENTIALS;
+use crate::model_http::CorsPreflightProperties::ORIGIN;
+use crate::model_http::CorsPreflightProperties::PREFLIGHT_HEADERS;
+use crate::model_http::CorsPreflightProperties::REASONS;
+use crate::model_http::CorsPreflightProperties::REQUEST_HEADER_NAMES;
+use crate::model_http::CorsPreflightProperties::REQUEST_METHOD;
+use crate::model_http::CorsPreflightProperties::TIMEOUT;
+use crate::model_http::CorsPreflightProperties::URL;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+
+/// Methods which don't have to be allowed by Access-Control-Allow-Methods.
+const SAFELISTED_METHODS: [&str; 3] = ["GET", "HEAD", "POST"];
+
+/// Headers which don't have to be allowed by Access-Control-Allow-Headers.
+const SAFELISTED_HEADERS: [&str; 3] = ["accept", "accept-language", "content-language"];
+
+entity_behaviour!(
+    CorsPreflight,
+    CorsPreflightFactory,
+    CorsPreflightFsm,
+    CorsPreflightBehaviourTransitions,
+    CorsPreflightValidator
+);
+
+behaviour_validator!(CorsPreflightValidator, ReactiveEntityInstance, URL.as_ref(), ORIGIN.as_ref(), ALLOWED.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for CorsPreflightBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for CorsPreflightBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            simulate_preflight(&reactive_instance);
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for CorsPreflightBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for CorsPreflightBehaviourTransitions {}
+
+/// Sends the OPTIONS request a browser would send and checks the response like the CORS check of the fetch standard.
+fn simulate_preflight(reactive_instance: &ReactiveEntityInstance) {
+    let Some(url) = reactive_instance.as_string(URL).map(|url| resolve_env(&url)).filter(|url| !url.is_empty()) else {
+        return;
+    };
+    let origin = reactive_instance.as_string(ORIGIN).unwrap_or_default();
+    let method = reactive_instance.as_string(REQUEST_METHOD).unwrap_or_else(|| String::from("GET"));
+    let credentials = reactive_instance.as_bool(CREDENTIALS).unwrap_or(false);
+    // Browsers send the header names lower case and sorted
+    let mut header_names: Vec<String> = reactive_instance
+        .as_array(REQUEST_HEADER_NAMES)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|header_name| header_name.as_str().map(|header_name| header_name.trim().to_ascii_lowercase()))
+        .filter(|header_name| !header_name.is_empty())
+        .collect();
+    header_names.sort();
+    header_names.dedup();
+    let timeout = Duration::from_millis(reactive_instance.as_u64(TIMEOUT).unwrap_or(10000));
+    // Browsers treat a redirected preflight as network error
+    let agent = UrlPolicy::from(reactive_instance).agent_builder().timeout(timeout).redirects(0).build();
+    let mut request = agent
+        .request("OPTIONS", &url)
+        .set("Origin", &origin)
+        .set("Access-Control-Request-Method", &method);
+    if !header_names.is_empty() {
+        request = request.set("Access-Control-Request-Headers", &header_names.join(","));
+    }
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    let response = match request.call() {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+        Err(e) => {
+            error!("Failed to send the preflight request to {}: {}", url, e);
+            reactive_instance.set(PREFLIGHT_HEADERS, json!({}));
+            reactive_instance.set(REASONS, json!([e.to_string()]));
+            reactive_instance.set(ALLOWED, json!(false));
+            return;
+        }
+    };
+    let mut preflight_headers = Map::new();
+    for name in response.headers_names() {
+        if name.to_ascii_lowercase().starts_with("access-control-") {
+            preflight_headers.insert(name.to_ascii_lowercase(), json!(response.header(&name)));
+        }
+    }
+    let reasons = check_preflight(&response, &origin, &method, &header_names, credentials);
+    reactive_instance.set(PREFLIGHT_HEADERS, json!(preflight_headers));
+    reactive_instance.set(REASONS, json!(reasons));
+    reactive_instance.set(ALLOWED, json!(reasons.is_empty()));
+}
+
+/// Returns the reasons why the browser would block the request. The wildcard isn't accepted for requests with credentials.
+fn check_preflight(response: &ureq::Response, origin: &str, method: &str, header_names: &[String], credentials: bool) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if !(200..300).contains(&response.status()) {
+        reasons.push(format!("The preflight response has status {} instead of an ok status", response.status()));
+    }
+    match response.header("access-control-allow-origin").map(str::trim) {
+        None => reasons.push(String::from("Access-Control-Allow-Origin is missing")),
+        Some("*") if credentials => reasons.push(String::from("Access-Control-Allow-Origin must not be * for requests with credentials")),
+        Some("*") => {}
+        Some(allowed_origin) if allowed_origin == origin => {}
+        Some(allowed_origin) => reasons.push(format!("Access-Control-Allow-Origin {} doesn't match the origin {}", allowed_origin, origin)),
+    }
+    if credentials && response.header("access-control-allow-credentials").map(str::trim) != Some("true") {
+        reasons.push(String::from("Access-Control-Allow-Credentials must be true for requests with credentials"));
+    }
+    let allowed_methods = list(response.header("access-control-allow-methods"));
+    let method_allowed = SAFELISTED_METHODS.contains(&method)
+        || allowed_methods.iter().any(|allowed_method| allowed_method == method)
+        || (!credentials && allowed_methods.iter().any(|allowed_method| allowed_method == "*"));
+    if !method_allowed {
+        reasons.push(format!("The method {} isn't allowed by Access-Control-Allow-Methods", method));
+    }
+    let allowed_headers: Vec<String> = list(response.header("access-control-allow-headers"))
+        .iter()
+        .map(|allowed_header| allowed_header.to_ascii_lowercase())
+        .collect();
+    let wildcard = !credentials && allowed_headers.iter().any(|allowed_header| allowed_header == "*");
+    for header_name in header_names {
+        if SAFELISTED_HEADERS.contains(&header_name.as_str()) || allowed_headers.contains(header_name) {
+            continue;
+        }
+        // The wildcard doesn't cover the authorization header
+        if wildcard && header_name != "authorization" {
+            continue;
+        }
+        reasons.push(format!("The header {} isn't allowed by Access-Control-Allow-Headers", header_name));
+    }
+    reasons
+}
+
+fn list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
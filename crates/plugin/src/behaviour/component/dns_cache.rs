@@ -0,0 +1,34 @@
+use log::info;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::dns::flush_dns_cache;
+use crate::model::*;
+use crate::model_http::DnsCacheProperties::FLUSH;
+use crate::model_http::DnsCacheProperties::FLUSHED_HOSTS;
+use crate::pausable::is_enabled;
+use crate::reactive::*;
+
+entity_behaviour!(DnsCache, DnsCacheFactory, DnsCacheFsm, DnsCacheBehaviourTransitions, DnsCacheValidator);
+
+behaviour_validator!(DnsCacheValidator, ReactiveEntityInstance, FLUSH.as_ref(), FLUSHED_HOSTS.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for DnsCacheBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for DnsCacheBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(FLUSH.as_ref(), move |flush: &Value| {
+            if !flush.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            let flushed_hosts = flush_dns_cache();
+            info!("Flushed the DNS cache: removed {} host names", flushed_hosts);
+            reactive_instance.set(FLUSHED_HOSTS, json!(flushed_hosts));
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for DnsCacheBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for DnsCacheBehaviourTransitions {}
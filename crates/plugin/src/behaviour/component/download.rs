@@ -0,0 +1,301 @@
+use std::fs;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use log::error;
+use log::warn;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::hashing::BodyHasher;
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::DownloadProperties::BYTES_DOWNLOADED;
+use crate::model_http::DownloadProperties::CHECKSUM;
+use crate::model_http::DownloadProperties::CHECKSUM_ALGORITHM;
+use crate::model_http::DownloadProperties::CHECKSUM_VALID;
+use crate::model_http::DownloadProperties::DELETE_ON_MISMATCH;
+use crate::model_http::DownloadProperties::ERROR;
+use crate::model_http::DownloadProperties::EXPECTED_CHECKSUM;
+use crate::model_http::DownloadProperties::MAX_DOWNLOAD_KBPS;
+use crate::model_http::DownloadProperties::PATH;
+use crate::model_http::DownloadProperties::PROGRESS_PERCENT;
+use crate::model_http::DownloadProperties::REQUEST_HEADERS;
+use crate::model_http::DownloadProperties::SEGMENTS;
+use crate::model_http::DownloadProperties::TOTAL_BYTES;
+use crate::model_http::DownloadProperties::URL;
+use crate::model_http::BEHAVIOUR_NAME_DOWNLOAD;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::progress::Progress;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+use crate::throttle::Throttle;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+use crate::worker::WorkerSignal;
+
+entity_behaviour!(Download, DownloadFactory, DownloadFsm, DownloadBehaviourTransitions, DownloadValidator);
+
+behaviour_validator!(DownloadValidator, ReactiveEntityInstance, URL.as_ref(), PATH.as_ref(), CHECKSUM.as_ref(), ERROR.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for DownloadBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for DownloadBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            // A new download cancels the running download
+            let reactive_instance = reactive_instance.clone();
+            start_worker(reactive_instance.id, BEHAVIOUR_NAME_DOWNLOAD, move |signal| download(reactive_instance, signal));
+        });
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        stop_worker(self.reactive_instance.id, BEHAVIOUR_NAME_DOWNLOAD);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for DownloadBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for DownloadBehaviourTransitions {}
+
+/// Downloads into a partial file which is renamed to the target path after the checksum has been verified.
+fn download(reactive_instance: Arc<ReactiveEntityInstance>, signal: WorkerSignal) {
+    let path = PathBuf::from(reactive_instance.as_string(PATH).unwrap_or_default());
+    let part_path = PathBuf::from(format!("{}.part", path.display()));
+    let algorithm = reactive_instance.as_string(CHECKSUM_ALGORITHM).unwrap_or_else(|| String::from("sha256"));
+    let Some(hasher) = BodyHasher::new(&algorithm) else {
+        fail(&reactive_instance, format!("Unknown checksum algorithm {}", algorithm));
+        return;
+    };
+    let checksum = match download_to(&reactive_instance, &signal, &part_path, hasher) {
+        Ok(Some(checksum)) => checksum,
+        // Cancelled
+        Ok(None) => return,
+        Err(e) => {
+            fail(&reactive_instance, e);
+            return;
+        }
+    };
+    reactive_instance.set(CHECKSUM, json!(checksum));
+    let expected_checksum = reactive_instance.as_string(EXPECTED_CHECKSUM).unwrap_or_default();
+    if !expected_checksum.is_empty() && !expected_checksum.eq_ignore_ascii_case(&checksum) {
+        warn!("The checksum of {} does not match: expected {}, got {}", path.display(), expected_checksum, checksum);
+        if reactive_instance.as_bool(DELETE_ON_MISMATCH).unwrap_or(false) {
+            let _ = fs::remove_file(&part_path);
+        }
+        reactive_instance.set(CHECKSUM_VALID, json!(false));
+        reactive_instance.set(ERROR, json!("Checksum mismatch"));
+        return;
+    }
+    if let Err(e) = fs::rename(&part_path, &path) {
+        fail(&reactive_instance, format!("Failed to move {} to {}: {}", part_path.display(), path.display(), e));
+        return;
+    }
+    reactive_instance.set(CHECKSUM_VALID, json!(true));
+    reactive_instance.set(ERROR, json!(""));
+}
+
+/// Returns the checksum or none, if the download has been cancelled.
+fn download_to(reactive_instance: &ReactiveEntityInstance, signal: &WorkerSignal, part_path: &Path, mut hasher: BodyHasher) -> Result<Option<String>, String> {
+    let url = resolve_env(&reactive_instance.as_string(URL).unwrap_or_default());
+    let url_policy = UrlPolicy::from(reactive_instance);
+    url_policy.check(&url)?;
+    let agent = url_policy.agent();
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let segments = reactive_instance.as_u64(SEGMENTS).unwrap_or(1);
+    if segments > 1 {
+        if let Some(total_bytes) = get_ranged_length(&agent, &url, &request_headers) {
+            let download = SegmentedDownload {
+                reactive_instance,
+                agent: &agent,
+                url: &url,
+                request_headers: &request_headers,
+                part_path,
+                progress: Mutex::new(Progress::new(Some(total_bytes), 1)),
+                throttle: Mutex::new(Throttle::new(reactive_instance.as_u64(MAX_DOWNLOAD_KBPS).unwrap_or(0))),
+                cancelled: AtomicBool::new(false),
+            };
+            if !download.download(signal, total_bytes, segments)? {
+                return Ok(None);
+            }
+            return hash_file(part_path, hasher).map(Some);
+        }
+    }
+    // The permit is held until the body has been read
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    let response = set_request_headers(agent.get(&url), &url, &request_headers)?
+        .call()
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+    let total_bytes = response.header("content-length").and_then(|content_length| content_length.parse::<u64>().ok());
+    let mut progress = Progress::new(total_bytes, 1);
+    report_progress(reactive_instance, &progress);
+    let mut file = File::create(part_path).map_err(|e| format!("Failed to create {}: {}", part_path.display(), e))?;
+    let mut throttle = Throttle::new(reactive_instance.as_u64(MAX_DOWNLOAD_KBPS).unwrap_or(0));
+    let mut reader = response.into_reader();
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        if signal.is_stopped() {
+            return Ok(None);
+        }
+        let length = reader.read(&mut buffer).map_err(|e| format!("Failed to download {}: {}", url, e))?;
+        if length == 0 {
+            break;
+        }
+        throttle.consume(length as u64);
+        hasher.update(&buffer[..length]);
+        file.write_all(&buffer[..length])
+            .map_err(|e| format!("Failed to write {}: {}", part_path.display(), e))?;
+        if progress.advance(length as u64) {
+            report_progress(reactive_instance, &progress);
+        }
+    }
+    // If the total size was unknown, it is known now
+    progress.total_bytes = Some(progress.transferred_bytes);
+    report_progress(reactive_instance, &progress);
+    Ok(Some(hasher.finalize()))
+}
+
+/// Returns the size of the file, if the server supports range requests.
+fn get_ranged_length(agent: &ureq::Agent, url: &str, request_headers: &Map<String, Value>) -> Option<u64> {
+    let response = set_request_headers(agent.head(url), url, request_headers).ok()?.call().ok()?;
+    if !response
+        .header("accept-ranges")
+        .map(|accept_ranges| accept_ranges.eq_ignore_ascii_case("bytes"))
+        .unwrap_or(false)
+    {
+        return None;
+    }
+    response.header("content-length")?.parse().ok()
+}
+
+/// Downloads the ranges of a file in parallel into the partial file.
+struct SegmentedDownload<'a> {
+    reactive_instance: &'a ReactiveEntityInstance,
+    agent: &'a ureq::Agent,
+    url: &'a str,
+    request_headers: &'a Map<String, Value>,
+    part_path: &'a Path,
+    /// The progress and the bandwidth limit are shared by all segments.
+    progress: Mutex<Progress>,
+    throttle: Mutex<Throttle>,
+    cancelled: AtomicBool,
+}
+
+impl SegmentedDownload<'_> {
+    /// Returns false, if the download has been cancelled.
+    fn download(&self, signal: &WorkerSignal, total_bytes: u64, segments: u64) -> Result<bool, String> {
+        let file = File::create(self.part_path).map_err(|e| format!("Failed to create {}: {}", self.part_path.display(), e))?;
+        file.set_len(total_bytes)
+            .map_err(|e| format!("Failed to allocate {}: {}", self.part_path.display(), e))?;
+        report_progress(self.reactive_instance, &self.progress.lock().unwrap());
+        let segment_size = (total_bytes + segments - 1) / segments;
+        let results: Vec<Result<(), String>> = thread::scope(|scope| {
+            let segments: Vec<_> = (0..segments)
+                .map(|segment| segment * segment_size)
+                .filter(|start| *start < total_bytes)
+                .map(|start| scope.spawn(move || self.download_segment(start, (start + segment_size).min(total_bytes) - 1)))
+                .collect();
+            // The signal can't be shared with the segments, so it is watched here
+            while !segments.iter().all(|segment| segment.is_finished()) {
+                if signal.is_stopped() {
+                    self.cancelled.store(true, Ordering::SeqCst);
+                }
+                thread::sleep(Duration::from_millis(100));
+            }
+            segments
+                .into_iter()
+                .map(|segment| segment.join().unwrap_or_else(|_| Err(String::from("A segment of the download panicked"))))
+                .collect()
+        });
+        if self.cancelled.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+        results.into_iter().collect::<Result<(), String>>()?;
+        Ok(true)
+    }
+
+    /// Downloads the bytes from start to end, both inclusive.
+    fn download_segment(&self, start: u64, end: u64) -> Result<(), String> {
+        let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+        let response = set_request_headers(self.agent.get(self.url), self.url, self.request_headers)?
+            .set("Range", &format!("bytes={}-{}", start, end))
+            .call()
+            .map_err(|e| format!("Failed to download {}: {}", self.url, e))?;
+        if response.status() != 206 {
+            return Err(format!("Failed to download {}: the server ignored the range request", self.url));
+        }
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(self.part_path)
+            .map_err(|e| format!("Failed to open {}: {}", self.part_path.display(), e))?;
+        file.seek(SeekFrom::Start(start))
+            .map_err(|e| format!("Failed to write {}: {}", self.part_path.display(), e))?;
+        let mut reader = response.into_reader().take(end - start + 1);
+        let mut buffer = [0; 64 * 1024];
+        loop {
+            if self.cancelled.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+            let length = reader.read(&mut buffer).map_err(|e| format!("Failed to download {}: {}", self.url, e))?;
+            if length == 0 {
+                break;
+            }
+            self.throttle.lock().unwrap().consume(length as u64);
+            file.write_all(&buffer[..length])
+                .map_err(|e| format!("Failed to write {}: {}", self.part_path.display(), e))?;
+            let mut progress = self.progress.lock().unwrap();
+            if progress.advance(length as u64) {
+                report_progress(self.reactive_instance, &progress);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Computes the checksum of a file which has been downloaded in segments.
+fn hash_file(path: &Path, mut hasher: BodyHasher) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let length = file.read(&mut buffer).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if length == 0 {
+            break;
+        }
+        hasher.update(&buffer[..length]);
+    }
+    Ok(hasher.finalize())
+}
+
+fn report_progress(reactive_instance: &ReactiveEntityInstance, progress: &Progress) {
+    reactive_instance.set(BYTES_DOWNLOADED, json!(progress.transferred_bytes));
+    reactive_instance.set(TOTAL_BYTES, json!(progress.total_bytes.unwrap_or(0)));
+    reactive_instance.set(PROGRESS_PERCENT, json!(progress.percent()));
+}
+
+fn fail(reactive_instance: &ReactiveEntityInstance, e: String) {
+    error!("{}", e);
+    reactive_instance.set(CHECKSUM_VALID, json!(false));
+    reactive_instance.set(ERROR, json!(e));
+}
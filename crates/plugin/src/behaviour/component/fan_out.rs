@@ -0,0 +1,95 @@
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::concurrency::map_concurrently;
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::FanOutProperties::ERRORS;
+use crate::model_http::FanOutProperties::MAX_CONCURRENCY;
+use crate::model_http::FanOutProperties::METHOD;
+use crate::model_http::FanOutProperties::REQUEST_HEADERS;
+use crate::model_http::FanOutProperties::RESULTS;
+use crate::model_http::FanOutProperties::URLS;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+
+entity_behaviour!(FanOut, FanOutFactory, FanOutFsm, FanOutBehaviourTransitions, FanOutValidator);
+
+behaviour_validator!(
+    FanOutValidator,
+    ReactiveEntityInstance,
+    METHOD.as_ref(),
+    URLS.as_ref(),
+    REQUEST_HEADERS.as_ref(),
+    MAX_CONCURRENCY.as_ref(),
+    RESULTS.as_ref(),
+    ERRORS.as_ref()
+);
+
+impl BehaviourInit<ReactiveEntityInstance> for FanOutBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for FanOutBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            fan_out(&reactive_instance);
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for FanOutBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for FanOutBehaviourTransitions {}
+
+fn fan_out(reactive_instance: &ReactiveEntityInstance) {
+    let method = reactive_instance.as_string(METHOD).unwrap_or_else(|| String::from("GET"));
+    let urls = reactive_instance.as_array(URLS).unwrap_or_default();
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let max_concurrency = reactive_instance.as_u64(MAX_CONCURRENCY).unwrap_or(4) as usize;
+    let agent = UrlPolicy::from(reactive_instance).agent();
+    let responses = map_concurrently(&urls, max_concurrency, |_, url| fetch(&agent, &method, url.as_str().unwrap_or_default(), &request_headers));
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    for (index, (url, response)) in urls.iter().zip(responses.into_iter()).enumerate() {
+        match response {
+            Ok(result) => results.push(result),
+            Err((status, error)) => {
+                results.push(Value::Null);
+                errors.push(json!({
+                    "index": index,
+                    "url": url,
+                    "status": status,
+                    "error": error
+                }));
+            }
+        }
+    }
+    reactive_instance.set(ERRORS, json!(errors));
+    reactive_instance.set(RESULTS, json!(results));
+}
+
+/// Fetches the url and returns the response body or the status and the error.
+fn fetch(agent: &ureq::Agent, method: &str, url: &str, request_headers: &Map<String, Value>) -> Result<Value, (Option<u16>, String)> {
+    let url = resolve_env(url);
+    let request = set_request_headers(agent.request(method, &url), &url, request_headers).map_err(|e| (None, e))?;
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    match request.call() {
+        Ok(response) => {
+            let status = response.status();
+            response
+                .into_json()
+                .map_err(|e| (Some(status), format!("Failed to parse response as JSON: {}", e)))
+        }
+        Err(ureq::Error::Status(status, response)) => Err((Some(status), response.status_text().to_string())),
+        Err(e) => Err((None, e.to_string())),
+    }
+}
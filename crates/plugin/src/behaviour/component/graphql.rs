@@ -0,0 +1,208 @@
+use std::sync::Arc;
+use std::thread;
+
+use log::debug;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::behaviour::component::http::send_request;
+use crate::hashing::hash_body;
+use crate::model::*;
+use crate::model_http::GraphQlProperties::DATA;
+use crate::model_http::GraphQlProperties::ERROR;
+use crate::model_http::GraphQlProperties::ERRORS;
+use crate::model_http::GraphQlProperties::OPERATION_NAME;
+use crate::model_http::GraphQlProperties::PERSISTED_QUERIES;
+use crate::model_http::GraphQlProperties::QUERY;
+use crate::model_http::GraphQlProperties::VARIABLES;
+use crate::model_http::HttpProperties::METHOD;
+use crate::model_http::HttpProperties::PAYLOAD;
+use crate::model_http::HttpProperties::RESULT;
+use crate::pausable::is_enabled;
+use crate::reactive::*;
+
+/// The message of the error of servers which don't know the hash of a persisted query.
+const PERSISTED_QUERY_NOT_FOUND: &str = "PersistedQueryNotFound";
+
+/// The code of the error of servers which don't know the hash of a persisted query.
+const PERSISTED_QUERY_NOT_FOUND_CODE: &str = "PERSISTED_QUERY_NOT_FOUND";
+
+entity_behaviour!(GraphQl, GraphQlFactory, GraphQlFsm, GraphQlBehaviourTransitions, GraphQlValidator);
+
+behaviour_validator!(
+    GraphQlValidator,
+    ReactiveEntityInstance,
+    QUERY.as_ref(),
+    VARIABLES.as_ref(),
+    PAYLOAD.as_ref(),
+    RESULT.as_ref(),
+    DATA.as_ref(),
+    ERRORS.as_ref(),
+    ERROR.as_ref()
+);
+
+impl BehaviourInit<ReactiveEntityInstance> for GraphQlBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for GraphQlBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        update_payload(&self.reactive_instance);
+        for property in [QUERY, VARIABLES, OPERATION_NAME, PERSISTED_QUERIES] {
+            let reactive_instance = self.reactive_instance.clone();
+            self.property_observers
+                .observe_with_handle(property.as_ref(), move |_: &Value| update_payload(&reactive_instance));
+        }
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(RESULT.as_ref(), move |result: &Value| {
+            // The request which sends the query is sent outside of the propagation of the result
+            if is_persisted_query_not_found(result) && !has_query(&reactive_instance) {
+                if is_enabled(&reactive_instance) {
+                    let reactive_instance = reactive_instance.clone();
+                    thread::spawn(move || register_query(&reactive_instance));
+                }
+                return;
+            }
+            apply_result(&reactive_instance, result);
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for GraphQlBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for GraphQlBehaviourTransitions {}
+
+/// Builds the payload of the HTTP request from the query, the variables and the operation name.
+fn update_payload(reactive_instance: &ReactiveEntityInstance) {
+    let query = reactive_instance.as_string(QUERY).unwrap_or_default();
+    let variables = reactive_instance.get(VARIABLES).unwrap_or(Value::Null);
+    let operation_name = reactive_instance.as_string(OPERATION_NAME).unwrap_or_default();
+    let persisted_queries = reactive_instance.as_bool(PERSISTED_QUERIES).unwrap_or(false);
+    reactive_instance.set(METHOD, json!("POST"));
+    reactive_instance.set(PAYLOAD, build_payload(&query, variables, &operation_name, persisted_queries));
+}
+
+/// Builds the request of a GraphQL operation. Persisted queries are sent as SHA-256 hash without query.
+fn build_payload(query: &str, variables: Value, operation_name: &str, persisted_queries: bool) -> Value {
+    let mut payload = Map::new();
+    if !persisted_queries {
+        payload.insert(String::from("query"), json!(query));
+    }
+    if !variables.is_null() {
+        payload.insert(String::from("variables"), variables);
+    }
+    if !operation_name.is_empty() {
+        payload.insert(String::from("operationName"), json!(operation_name));
+    }
+    if persisted_queries {
+        let sha256_hash = hash_body("sha256", query.as_bytes()).unwrap_or_default();
+        payload.insert(
+            String::from("extensions"),
+            json!({
+                "persistedQuery": {
+                    "version": 1,
+                    "sha256Hash": sha256_hash
+                }
+            }),
+        );
+    }
+    json!(payload)
+}
+
+fn has_query(reactive_instance: &ReactiveEntityInstance) -> bool {
+    reactive_instance.get(PAYLOAD).map(|payload| payload.get("query").is_some()).unwrap_or(false)
+}
+
+/// Sends the query together with its hash, so that the server persists the query. The following requests send the hash
+/// only.
+fn register_query(reactive_instance: &Arc<ReactiveEntityInstance>) {
+    let Some(Value::Object(mut payload)) = reactive_instance.get(PAYLOAD) else {
+        return;
+    };
+    debug!("The server doesn't know the persisted query of {}: Sending the query", reactive_instance.id);
+    payload.insert(String::from("query"), json!(reactive_instance.as_string(QUERY).unwrap_or_default()));
+    reactive_instance.set(PAYLOAD, json!(payload));
+    send_request(reactive_instance, None);
+    update_payload(reactive_instance);
+}
+
+/// Returns true, if the response asks for the query of a persisted query.
+fn is_persisted_query_not_found(result: &Value) -> bool {
+    result
+        .get("errors")
+        .and_then(Value::as_array)
+        .map(|errors| {
+            errors.iter().any(|error| {
+                error.get("message").and_then(Value::as_str) == Some(PERSISTED_QUERY_NOT_FOUND)
+                    || error.pointer("/extensions/code").and_then(Value::as_str) == Some(PERSISTED_QUERY_NOT_FOUND_CODE)
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Writes the data and the errors of the response into the output properties.
+fn apply_result(reactive_instance: &ReactiveEntityInstance, result: &Value) {
+    let errors = result.get("errors").and_then(Value::as_array).cloned().unwrap_or_default();
+    reactive_instance.set(ERROR, json!(error_messages(&errors)));
+    reactive_instance.set(ERRORS, json!(errors));
+    reactive_instance.set(DATA, result.get("data").cloned().unwrap_or(Value::Null));
+}
+
+/// Joins the messages of the errors.
+fn error_messages(errors: &[Value]) -> String {
+    errors
+        .iter()
+        .filter_map(|error| error.get("message").and_then(Value::as_str))
+        .collect::<Vec<&str>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_the_payload_of_a_query() {
+        let payload = build_payload("{ hero { name } }", json!({"episode": "JEDI"}), "Hero", false);
+        assert_eq!(
+            payload,
+            json!({
+                "query": "{ hero { name } }",
+                "variables": {"episode": "JEDI"},
+                "operationName": "Hero"
+            })
+        );
+    }
+
+    #[test]
+    fn builds_the_payload_of_a_persisted_query() {
+        let payload = build_payload("{ hero { name } }", Value::Null, "", true);
+        assert_eq!(
+            payload,
+            json!({
+                "extensions": {
+                    "persistedQuery": {
+                        "version": 1,
+                        "sha256Hash": "aae585680c3470e4947255eafbd1eafe87d1c3f129259cf15e404d1bb7f1e8f4"
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn detects_unknown_persisted_queries() {
+        assert!(is_persisted_query_not_found(&json!({"errors": [{"message": "PersistedQueryNotFound"}]})));
+        assert!(is_persisted_query_not_found(&json!({
+            "errors": [{"message": "Unknown query", "extensions": {"code": "PERSISTED_QUERY_NOT_FOUND"}}]
+        })));
+        assert!(!is_persisted_query_not_found(&json!({"errors": [{"message": "PersistedQueryNotSupported"}]})));
+        assert!(!is_persisted_query_not_found(&json!({"data": {"hero": null}})));
+    }
+
+    #[test]
+    fn joins_the_error_messages() {
+        let errors = vec![json!({"message": "First"}), json!({"locations": []}), json!({"message": "Second"})];
+        assert_eq!(error_messages(&errors), "First; Second");
+        assert_eq!(error_messages(&[]), "");
+    }
+}
@@ -1,18 +1,140 @@
+use chrono::DateTime;
+use chrono::Utc;
+use json_patch::diff;
+use jsonschema::JSONSchema;
+use log::debug;
 use log::error;
+use log::warn;
 use serde_json::json;
+use serde_json::Map;
 use serde_json::Value;
+use std::io;
+use std::io::Read;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use url::Url;
+use uuid::Uuid;
 
+use crate::audit::record_request;
+use crate::audit::AuditEntry;
+use crate::cache::begin_revalidation;
+use crate::cache::cache_key;
+use crate::cache::end_revalidation;
+use crate::cache::get_cached_response;
+use crate::cache::store_response;
+use crate::cache::vary;
+use crate::cache::CacheControl;
+use crate::cache::CachedResponse;
+use crate::concurrency::Cancellation;
+use crate::condition::check_condition;
+use crate::connectivity::is_online;
+use crate::dns::TimedResolver;
+use crate::hashing::hash_body;
+use crate::hawk::HawkCredentials;
+use crate::headers::is_credential_header;
+use crate::headers::set_request_headers;
+use crate::hsts::record_hsts;
+use crate::hsts::upgrade_to_https;
+use crate::inheritance::inherit_array;
+use crate::inheritance::inherit_object;
+use crate::inheritance::inherit_string;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::load_balancer::join_url;
+use crate::load_balancer::report_failure;
+use crate::load_balancer::report_success;
+use crate::load_balancer::select_target;
+use crate::load_balancer::LoadBalancing;
 use crate::model::*;
 use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::HttpProperties;
+use crate::model_http::HttpProperties::ACCEPT_LANGUAGE;
+use crate::model_http::HttpProperties::ACTIVE_API_KEY;
+use crate::model_http::HttpProperties::API_KEYS;
+use crate::model_http::HttpProperties::API_KEY_HEADER;
+use crate::model_http::HttpProperties::BASE_URLS;
+use crate::model_http::HttpProperties::BLOCKED_BY_ROBOTS;
+use crate::model_http::HttpProperties::BODY_HASH;
+use crate::model_http::HttpProperties::CACHE;
+use crate::model_http::HttpProperties::CONCURRENCY_MODE;
+use crate::model_http::HttpProperties::CONNECT_MS;
+use crate::model_http::HttpProperties::DELAY_MS;
+use crate::model_http::HttpProperties::DISTINCT;
+use crate::model_http::HttpProperties::DNS_MS;
+use crate::model_http::HttpProperties::DRY_RUN;
+use crate::model_http::HttpProperties::ENV_IN_PAYLOAD;
+use crate::model_http::HttpProperties::FALLBACK_URLS;
+use crate::model_http::HttpProperties::FROM_CACHE;
+use crate::model_http::HttpProperties::HASH_ALGORITHM;
+use crate::model_http::HttpProperties::IDEMPOTENCY;
+use crate::model_http::HttpProperties::LOAD_BALANCING;
+use crate::model_http::HttpProperties::MAX_REDIRECTS;
+use crate::model_http::HttpProperties::MAX_RETRIES;
+use crate::model_http::HttpProperties::MAX_RETRY_AFTER;
 use crate::model_http::HttpProperties::METHOD;
+use crate::model_http::HttpProperties::MIRROR_URL;
+use crate::model_http::HttpProperties::OFFLINE_FALLBACK;
+use crate::model_http::HttpProperties::ON_ERROR;
+use crate::model_http::HttpProperties::ON_SUCCESS;
+use crate::model_http::HttpProperties::PARSE_MODE;
 use crate::model_http::HttpProperties::PAYLOAD;
+use crate::model_http::HttpProperties::PRIORITY;
+use crate::model_http::HttpProperties::PROXY;
+use crate::model_http::HttpProperties::RATE_LIMIT_REMAINING;
+use crate::model_http::HttpProperties::RATE_LIMIT_RESET;
+use crate::model_http::HttpProperties::REDIRECT_CHAIN;
+use crate::model_http::HttpProperties::REQUEST_FAILED;
 use crate::model_http::HttpProperties::REQUEST_HEADERS;
+use crate::model_http::HttpProperties::REQUEST_PREVIEW;
+use crate::model_http::HttpProperties::REQUEST_STARTED;
+use crate::model_http::HttpProperties::REQUEST_SUCCEEDED;
+use crate::model_http::HttpProperties::REQUIRE_ONLINE;
+use crate::model_http::HttpProperties::RESPECT_RATE_LIMITS;
+use crate::model_http::HttpProperties::RESPECT_ROBOTS;
 use crate::model_http::HttpProperties::RESPONSE_HEADERS;
+use crate::model_http::HttpProperties::RESPONSE_SCHEMA;
+use crate::model_http::HttpProperties::RESPONSE_TIME_MS;
 use crate::model_http::HttpProperties::RESULT;
+use crate::model_http::HttpProperties::RESULT_DIFF;
+use crate::model_http::HttpProperties::SCHEMA_ERRORS;
+use crate::model_http::HttpProperties::SCHEMA_VALID;
+use crate::model_http::HttpProperties::SEND_CONDITION;
+use crate::model_http::HttpProperties::SERVICE;
+use crate::model_http::HttpProperties::SERVICE_SCHEME;
+use crate::model_http::HttpProperties::SKIPPED_REASON;
+use crate::model_http::HttpProperties::STALE;
+use crate::model_http::HttpProperties::STALE_WHILE_REVALIDATE;
 use crate::model_http::HttpProperties::STATUS;
+use crate::model_http::HttpProperties::TLS_HANDSHAKE_MS;
+use crate::model_http::HttpProperties::TTFB_MS;
+use crate::model_http::HttpProperties::UPSTREAM;
 use crate::model_http::HttpProperties::URL;
+use crate::model_http::BEHAVIOUR_NAME_HTTP;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::placeholder::resolve_env_in_value;
+use crate::rate_limit::get_pacing_delay;
+use crate::rate_limit::record_rate_limit;
+use crate::rate_limit::RateLimit;
 use crate::reactive::*;
+use crate::robots::is_allowed_by_robots;
+use crate::secrets::resolve_secrets_for_url;
+use crate::service_discovery::resolve_service;
+use crate::session::get_session;
+use crate::session::get_session_proxy;
+use crate::session::merge_request_headers;
+use crate::session::store_cookies;
+use crate::ssrf::UrlPolicy;
+use crate::tls::RequestTlsConnector;
+use crate::tls::TlsPolicy;
+
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+const ACCEPT_LANGUAGE_HEADER: &str = "Accept-Language";
 
 entity_behaviour!(Http, HttpFactory, HttpFsm, HttpBehaviourTransitions, HttpValidator);
 
@@ -31,7 +153,7 @@ behaviour_validator!(
 impl BehaviourInit<ReactiveEntityInstance> for HttpBehaviourTransitions {
     fn init(&self) -> Result<(), BehaviourInitializationFailed> {
         if self.reactive_instance.as_bool(TRIGGER).unwrap_or(false) {
-            send_request(&self.reactive_instance);
+            send_request(&self.reactive_instance, None);
         }
         Ok(())
     }
@@ -40,11 +162,23 @@ impl BehaviourInit<ReactiveEntityInstance> for HttpBehaviourTransitions {
 impl BehaviourConnect<ReactiveEntityInstance> for HttpBehaviourTransitions {
     fn connect(&self) -> Result<(), BehaviourConnectFailed> {
         let reactive_instance = self.reactive_instance.clone();
+        let in_flight_requests = Arc::new(InFlightRequests::default());
         self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
-            if !trigger.as_bool().unwrap_or(false) {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            // The request is deferred without blocking the propagation of the trigger
+            let delay = reactive_instance.as_u64(DELAY_MS).unwrap_or(0);
+            if delay == 0 {
+                trigger_request(&reactive_instance, &in_flight_requests);
                 return;
             }
-            send_request(&reactive_instance);
+            let reactive_instance = reactive_instance.clone();
+            let in_flight_requests = in_flight_requests.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(delay));
+                trigger_request(&reactive_instance, &in_flight_requests);
+            });
         });
         Ok(())
     }
@@ -53,44 +187,1182 @@ impl BehaviourConnect<ReactiveEntityInstance> for HttpBehaviourTransitions {
 impl BehaviourShutdown<ReactiveEntityInstance> for HttpBehaviourTransitions {}
 impl BehaviourTransitions<ReactiveEntityInstance> for HttpBehaviourTransitions {}
 
-fn send_request(reactive_instance: &Arc<ReactiveEntityInstance>) {
-    let Some(method) = reactive_instance.as_string(METHOD) else {
-        return;
+/// Defines how a request is handled which is triggered while a previous request is still in flight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConcurrencyMode {
+    /// The requests are sent one after another.
+    Queue,
+    /// The requests are sent in parallel.
+    Parallel,
+    /// A request cancels the previous request, if it is still in flight. The cancelled request is neither retried nor
+    /// redirected and its TLS connection is shut down; a plain HTTP connection is closed after the response headers have
+    /// been received. Only the response of the latest request is applied.
+    LatestWins,
+}
+
+impl From<&str> for ConcurrencyMode {
+    fn from(concurrency_mode: &str) -> Self {
+        match concurrency_mode {
+            "parallel" => ConcurrencyMode::Parallel,
+            "latest-wins" => ConcurrencyMode::LatestWins,
+            _ => ConcurrencyMode::Queue,
+        }
+    }
+}
+
+/// Keeps track of the requests of a single instance.
+#[derive(Default)]
+struct InFlightRequests {
+    queue: Mutex<()>,
+    /// The id and the cancellation of the latest request.
+    latest: Mutex<(u64, Cancellation)>,
+}
+
+fn trigger_request(reactive_instance: &Arc<ReactiveEntityInstance>, in_flight_requests: &Arc<InFlightRequests>) {
+    let concurrency_mode = reactive_instance
+        .as_string(CONCURRENCY_MODE)
+        .map(|concurrency_mode| ConcurrencyMode::from(concurrency_mode.as_str()))
+        .unwrap_or(ConcurrencyMode::Queue);
+    match concurrency_mode {
+        ConcurrencyMode::Queue => {
+            let _queue = in_flight_requests.queue.lock().unwrap();
+            send_request(reactive_instance, None);
+        }
+        ConcurrencyMode::Parallel => {
+            let reactive_instance = reactive_instance.clone();
+            thread::spawn(move || {
+                send_request(&reactive_instance, None);
+            });
+        }
+        ConcurrencyMode::LatestWins => {
+            let cancellation = Cancellation::default();
+            let request_id = {
+                let mut latest = in_flight_requests.latest.lock().unwrap();
+                latest.1.cancel();
+                latest.0 += 1;
+                latest.1 = cancellation.clone();
+                latest.0
+            };
+            let reactive_instance = reactive_instance.clone();
+            let in_flight_requests = in_flight_requests.clone();
+            thread::spawn(move || {
+                let Some(response) = execute(&reactive_instance, None, false, &cancellation) else {
+                    return;
+                };
+                // A response which has been cached or received before the request was superseded is discarded
+                if in_flight_requests.latest.lock().unwrap().0 == request_id {
+                    let stale = response.stale;
+                    let success = response.apply(&reactive_instance) && !stale;
+                    pulse_outcome(&reactive_instance, success);
+                }
+            });
+        }
+    }
+}
+
+/// The response of a request which has not been written into the output properties yet.
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Value,
+    /// The parsed response body or none, if the body could not be parsed.
+    pub result: Option<Value>,
+    /// The base url or the fallback url which served the response.
+    pub upstream: Option<String>,
+    /// The hash of the response body, if a hash algorithm is given.
+    pub body_hash: Option<String>,
+    /// True, if the response has been served from the cache without sending the request.
+    pub from_cache: bool,
+    /// True, if the server is unreachable and the last known response is published instead.
+    pub stale: bool,
+    /// The urls and statuses of the requests including the followed redirects.
+    pub redirect_chain: Option<Vec<Value>>,
+    pub timing: Option<Timing>,
+    /// True, if the body is not interpreted because the parse mode is none.
+    pub discard_body: bool,
+}
+
+/// The durations of the phases of the last request. The total duration is published as response time.
+///
+/// The TLS handshake is only timed for HTTPS. The connect and the TLS handshake are part of the time to the first byte.
+pub struct Timing {
+    pub dns: Duration,
+    pub connect: Duration,
+    pub tls_handshake: Duration,
+    pub ttfb: Duration,
+}
+
+impl HttpResponse {
+    /// Writes the response into the output properties of the instance.
+    ///
+    /// If the instance is distinct, unchanged values are not written in order to not propagate them.
+    ///
+    /// Returns true, if the response body has been parsed successfully or is discarded.
+    pub fn apply(self, reactive_instance: &ReactiveEntityInstance) -> bool {
+        let distinct = reactive_instance.as_bool(DISTINCT).unwrap_or(false);
+        set_output(reactive_instance, STATUS, json!(self.status), distinct);
+        set_output(reactive_instance, RESPONSE_HEADERS, self.headers, distinct);
+        if let Some(upstream) = self.upstream {
+            set_output(reactive_instance, UPSTREAM, json!(upstream), distinct);
+        }
+        if let Some(body_hash) = self.body_hash {
+            set_output(reactive_instance, BODY_HASH, json!(body_hash), distinct);
+        }
+        set_output(reactive_instance, FROM_CACHE, json!(self.from_cache), distinct);
+        set_output(reactive_instance, STALE, json!(self.stale), distinct);
+        if let Some(redirect_chain) = self.redirect_chain {
+            set_output(reactive_instance, REDIRECT_CHAIN, json!(redirect_chain), distinct);
+        }
+        if let Some(timing) = self.timing {
+            set_output(reactive_instance, DNS_MS, json!(timing.dns.as_millis() as u64), distinct);
+            set_output(reactive_instance, CONNECT_MS, json!(timing.connect.as_millis() as u64), distinct);
+            set_output(reactive_instance, TLS_HANDSHAKE_MS, json!(timing.tls_handshake.as_millis() as u64), distinct);
+            set_output(reactive_instance, TTFB_MS, json!(timing.ttfb.as_millis() as u64), distinct);
+        }
+        match self.result {
+            Some(result) => {
+                if let Err(schema_errors) = validate_result(reactive_instance, &result) {
+                    warn!("The response of {} does not match the response schema", reactive_instance.id);
+                    set_output(reactive_instance, SCHEMA_VALID, json!(false), distinct);
+                    set_output(reactive_instance, SCHEMA_ERRORS, json!(schema_errors), distinct);
+                    return false;
+                }
+                set_output(reactive_instance, SCHEMA_VALID, json!(true), distinct);
+                set_output(reactive_instance, SCHEMA_ERRORS, json!([]), distinct);
+                let previous_result = reactive_instance.get(RESULT).unwrap_or_else(|| json!({}));
+                if !distinct || previous_result != result {
+                    reactive_instance.set(RESULT_DIFF, json!(diff(&previous_result, &result)));
+                    reactive_instance.set(RESULT, result);
+                }
+                true
+            }
+            None => self.discard_body,
+        }
+    }
+}
+
+/// Validates the response body against the response schema of the instance.
+fn validate_result(reactive_instance: &ReactiveEntityInstance, result: &Value) -> Result<(), Vec<String>> {
+    let Some(response_schema) = reactive_instance.get(RESPONSE_SCHEMA) else {
+        return Ok(());
     };
-    let Some(url) = reactive_instance.as_string(URL) else {
+    // The empty schema accepts every document
+    if response_schema.as_object().map(|response_schema| response_schema.is_empty()).unwrap_or(false) {
+        return Ok(());
+    }
+    let response_schema = JSONSchema::compile(&response_schema).map_err(|e| vec![format!("Invalid response schema: {}", e)])?;
+    response_schema
+        .validate(result)
+        .map_err(|errors| errors.map(|e| format!("{}: {}", e.instance_path, e)).collect())
+}
+
+fn set_output(reactive_instance: &ReactiveEntityInstance, property: HttpProperties, value: Value, distinct: bool) {
+    if distinct && reactive_instance.get(property.as_ref()).as_ref() == Some(&value) {
         return;
+    }
+    reactive_instance.set(property, value);
+}
+
+/// Sends the request described by the properties of the instance and writes the response into the output properties.
+///
+/// Returns true, if the request was successful.
+pub fn send_request(reactive_instance: &Arc<ReactiveEntityInstance>, timeout: Option<Duration>) -> bool {
+    let success = match execute_request(reactive_instance, timeout) {
+        Some(response) => {
+            // Publishing the last known response is not a successful request
+            let stale = response.stale;
+            response.apply(reactive_instance) && !stale
+        }
+        None => false,
     };
-    let Some(request_headers) = reactive_instance.as_object(REQUEST_HEADERS) else {
+    pulse_outcome(reactive_instance, success);
+    success
+}
+
+/// Pulses either on_success or on_error after the outputs have been written. Skipped requests pulse neither.
+fn pulse_outcome(reactive_instance: &ReactiveEntityInstance, success: bool) {
+    let skipped = !reactive_instance.as_string(SKIPPED_REASON).unwrap_or_default().is_empty()
+        || (reactive_instance.as_bool(RESPECT_ROBOTS).unwrap_or(false) && reactive_instance.as_bool(BLOCKED_BY_ROBOTS).unwrap_or(false));
+    if !success && skipped {
         return;
+    }
+    let outcome = if success { ON_SUCCESS } else { ON_ERROR };
+    reactive_instance.set(outcome, json!(true));
+    reactive_instance.set(outcome, json!(false));
+}
+
+/// Sends the request described by the properties of the instance without touching the output properties.
+pub fn execute_request(reactive_instance: &Arc<ReactiveEntityInstance>, timeout: Option<Duration>) -> Option<HttpResponse> {
+    execute(reactive_instance, timeout, false, &Cancellation::default())
+}
+
+/// If revalidating, the cached response is not served but replaced by the response of the request.
+///
+/// A request which has been superseded in the latest-wins mode is cancelled. From then on it doesn't write any output
+/// property.
+///
+/// Returns none, if the request has been cancelled.
+fn execute(reactive_instance: &Arc<ReactiveEntityInstance>, timeout: Option<Duration>, revalidate: bool, cancellation: &Cancellation) -> Option<HttpResponse> {
+    let method = reactive_instance.as_string(METHOD)?;
+    // The placeholders are resolved at send time and never written back into the properties
+    let url = resolve_env(&reactive_instance.as_string(URL)?);
+    let session = get_session(reactive_instance.id);
+    let mut request_headers = build_request_headers(reactive_instance, session.as_deref())?;
+    let api_keys = ApiKeys::from(reactive_instance.as_ref());
+    api_keys.insert(api_keys.active, &mut request_headers);
+    let mut payload = reactive_instance.get(PAYLOAD)?;
+    // The payload may be built from untrusted data, which must not be able to read the environment
+    if reactive_instance.as_bool(ENV_IN_PAYLOAD).unwrap_or(false) {
+        payload = resolve_env_in_value(&payload);
+    }
+    if is_skipped(reactive_instance, &payload, cancellation) {
+        return None;
+    }
+    let base_urls = get_base_urls(reactive_instance);
+    let load_balancing = reactive_instance
+        .as_string(LOAD_BALANCING)
+        .map(|load_balancing| LoadBalancing::from(load_balancing.as_str()))
+        .unwrap_or(LoadBalancing::RoundRobin);
+    let mut upstream = select_target(reactive_instance.id, &base_urls, load_balancing);
+    let relative_url = url;
+    let url = match &upstream {
+        Some(base_url) => join_url(base_url, &relative_url),
+        None => relative_url.clone(),
+    };
+    let fallback_urls: Vec<String> = inherit_array(reactive_instance, FALLBACK_URLS.as_ref())
+        .iter()
+        .filter_map(|fallback_url| fallback_url.as_str().map(resolve_env))
+        .collect();
+    let url_policy = UrlPolicy::from(reactive_instance);
+    if let Err(e) = url_policy.check(&url) {
+        error!("Refused to send request: {}", e);
+        return None;
+    }
+    // The request is built but not sent, even if a cached response is available
+    if reactive_instance.as_bool(DRY_RUN).unwrap_or(false) {
+        publish_request_preview(reactive_instance, cancellation, &method, &url, &request_headers, &payload, &api_keys.header);
+        return None;
+    }
+    // Only safe requests are cached
+    let cache = reactive_instance.as_bool(CACHE).unwrap_or(false) && method.eq_ignore_ascii_case("GET");
+    let cache_key = cache_key(&method, &relative_url, &base_urls, &request_headers);
+    if cache && !revalidate {
+        if let Some(response) = serve_cached_response(reactive_instance, timeout, &cache_key, &request_headers) {
+            return Some(response);
+        }
+    }
+    if is_blocked_by_robots(reactive_instance, cancellation, &url, &request_headers, &url_policy) {
+        warn!("Refused to send request: {} is disallowed by robots.txt", url);
+        return None;
+    }
+    let resolver = TimedResolver::with_policy(url_policy.clone());
+    let agent = match build_agent(reactive_instance, session.as_deref(), resolver.clone(), cancellation) {
+        Ok(agent) => agent,
+        Err(e) => {
+            error!("Failed to configure the agent of {}: {}", reactive_instance.id, e);
+            return None;
+        }
+    };
+    let max_retry_after = Duration::from_millis(reactive_instance.as_u64(MAX_RETRY_AFTER).unwrap_or(60000));
+    // The request waits for the quota of the host, but not longer than for a Retry-After
+    let respect_rate_limits = reactive_instance.as_bool(RESPECT_RATE_LIMITS).unwrap_or(false);
+    let host = Url::parse(&url).ok().and_then(|url| url.host_str().map(String::from)).unwrap_or_default();
+    if let Some(delay) = get_pacing_delay(&host).filter(|_| respect_rate_limits) {
+        debug!("Pacing the request of {} to {} by {} ms", reactive_instance.id, host, delay.as_millis());
+        cancellation.sleep(delay.min(max_retry_after));
+    }
+    // The permit is held until the response body has been read
+    let _permit = acquire_request_permit(reactive_instance.as_i64(PRIORITY).unwrap_or(DEFAULT_PRIORITY));
+    if cancellation.is_cancelled() {
+        debug!("Cancelled the request of {}", reactive_instance.id);
+        return None;
+    }
+    let request_started = Instant::now();
+    let request_started_event = json!({ "method": method, "url": url, "timestamp": Utc::now().to_rfc3339() });
+    set_unless_superseded(reactive_instance, cancellation, REQUEST_STARTED, request_started_event);
+    let request_options = RequestOptions {
+        agent,
+        resolver: resolver.clone(),
+        method: &method,
+        payload: &payload,
+        timeout,
+        url_policy: &url_policy,
+        max_redirects: reactive_instance.as_u64(MAX_REDIRECTS).unwrap_or(5),
+        api_key_header: &api_keys.header,
+        hawk: HawkCredentials::from_instance(reactive_instance),
+        cancellation,
     };
-    let Some(payload) = reactive_instance.get(PAYLOAD) else {
+    mirror_request(reactive_instance, session.as_deref(), &request_options, &url, &request_headers);
+    let max_retries = reactive_instance.as_u64(MAX_RETRIES).unwrap_or(3);
+    let sent_request = send_with_retries(reactive_instance, &request_options, &url, &request_headers, max_retries, max_retry_after);
+    let sent_request = rotate_api_keys(reactive_instance, &request_options, &url, &mut request_headers, &api_keys, sent_request);
+    if let Some(upstream) = &upstream {
+        if is_unavailable(&sent_request.result) {
+            report_failure(upstream);
+        } else {
+            report_success(upstream, sent_request.started.elapsed());
+        }
+    }
+    let (sent_request, fallback_url) = send_to_fallback_urls(&request_options, &fallback_urls, &request_headers, sent_request);
+    let sent_url = fallback_url.clone().unwrap_or_else(|| url.clone());
+    if fallback_url.is_some() {
+        upstream = fallback_url;
+    }
+    record_outcome(
+        reactive_instance,
+        &request_options,
+        &sent_url,
+        &request_headers,
+        &api_keys,
+        &sent_request.result,
+        request_started,
+    );
+    if let Some(session) = &session {
+        if let Ok(response) | Err(ureq::Error::Status(_, response)) = &sent_request.result {
+            store_cookies(session, response);
+        }
+    }
+    publish_rate_limit(reactive_instance, cancellation, &host, respect_rate_limits, &sent_request.result);
+    receive_response(reactive_instance, &request_options, sent_request, cache.then_some(cache_key), &request_headers, upstream)
+}
+
+/// Writes an output property, unless the request has been superseded by a later request of the latest-wins mode.
+fn set_unless_superseded(reactive_instance: &ReactiveEntityInstance, cancellation: &Cancellation, property: HttpProperties, value: Value) {
+    if cancellation.is_cancelled() {
         return;
+    }
+    reactive_instance.set(property, value);
+}
+
+/// Returns the request headers of the instance and of its session including the generated and the inherited headers.
+fn build_request_headers(reactive_instance: &ReactiveEntityInstance, session: Option<&ReactiveEntityInstance>) -> Option<Map<String, Value>> {
+    let request_headers = inherit_object(reactive_instance, REQUEST_HEADERS.as_ref())?;
+    let mut request_headers = match session {
+        Some(session) => merge_request_headers(session, request_headers),
+        None => request_headers,
+    };
+    // The key is generated per logical request: retries and fallbacks send the same key
+    let has_idempotency_key = request_headers.keys().any(|name| name.eq_ignore_ascii_case(IDEMPOTENCY_KEY_HEADER));
+    if reactive_instance.as_bool(IDEMPOTENCY).unwrap_or(false) && !has_idempotency_key {
+        request_headers.insert(String::from(IDEMPOTENCY_KEY_HEADER), json!(Uuid::new_v4().to_string()));
+    }
+    // The language may be inherited, for example from the entity representing the user
+    let has_accept_language = request_headers.keys().any(|name| name.eq_ignore_ascii_case(ACCEPT_LANGUAGE_HEADER));
+    if let Some(accept_language) = inherit_string(reactive_instance, ACCEPT_LANGUAGE.as_ref()).filter(|accept_language| !accept_language.is_empty()) {
+        if !has_accept_language {
+            request_headers.insert(String::from(ACCEPT_LANGUAGE_HEADER), json!(accept_language));
+        }
+    }
+    Some(request_headers)
+}
+
+/// The api keys of the instance. A rejected key is rotated out.
+struct ApiKeys {
+    keys: Vec<Value>,
+    header: String,
+    /// The index of the key which has been accepted last.
+    active: usize,
+}
+
+impl From<&ReactiveEntityInstance> for ApiKeys {
+    fn from(reactive_instance: &ReactiveEntityInstance) -> Self {
+        let keys = reactive_instance.as_array(API_KEYS).unwrap_or_default();
+        let header = reactive_instance.as_string(API_KEY_HEADER).unwrap_or_else(|| String::from("Authorization"));
+        let active = reactive_instance.as_u64(ACTIVE_API_KEY).unwrap_or(0) as usize % keys.len().max(1);
+        ApiKeys { keys, header, active }
+    }
+}
+
+impl ApiKeys {
+    /// Sets the key with the index as request header.
+    fn insert(&self, index: usize, request_headers: &mut Map<String, Value>) {
+        if let Some(api_key) = self.keys.get(index) {
+            request_headers.insert(self.header.clone(), api_key.clone());
+        }
+    }
+}
+
+/// Checks the send condition and the connectivity. The reason of a skipped request is published.
+///
+/// Returns true, if the request is skipped.
+fn is_skipped(reactive_instance: &ReactiveEntityInstance, payload: &Value, cancellation: &Cancellation) -> bool {
+    let send_condition = reactive_instance.get(SEND_CONDITION).unwrap_or(Value::Null);
+    let skipped_reason = match check_condition(&send_condition, payload) {
+        Ok(_) if reactive_instance.as_bool(REQUIRE_ONLINE).unwrap_or(false) && !is_online() => String::from("The network is offline"),
+        Ok(_) => String::new(),
+        Err(skipped_reason) => skipped_reason,
     };
-    let mut request = ureq::request(method.as_str(), url.as_str());
-    for (request_header, value) in request_headers.into_iter() {
-        if let Some(value) = value.as_str() {
-            request = request.set(request_header.as_ref(), value);
+    if reactive_instance.as_string(SKIPPED_REASON).as_ref() != Some(&skipped_reason) {
+        set_unless_superseded(reactive_instance, cancellation, SKIPPED_REASON, json!(skipped_reason));
+    }
+    if skipped_reason.is_empty() {
+        return false;
+    }
+    warn!("Skipped request of {}: {}", reactive_instance.id, skipped_reason);
+    true
+}
+
+/// Returns the base urls of the instance or, if a service is given, the discovered base urls.
+fn get_base_urls(reactive_instance: &ReactiveEntityInstance) -> Vec<String> {
+    match inherit_string(reactive_instance, SERVICE.as_ref()) {
+        Some(service) => resolve_service(&service, &reactive_instance.as_string(SERVICE_SCHEME).unwrap_or_else(|| String::from("http"))),
+        None => inherit_array(reactive_instance, BASE_URLS.as_ref())
+            .iter()
+            .filter_map(|base_url| base_url.as_str().map(String::from))
+            .collect(),
+    }
+}
+
+/// Returns the cached response, if it is usable for the request headers. A stale response is served immediately and
+/// refreshed in the background.
+fn serve_cached_response(
+    reactive_instance: &Arc<ReactiveEntityInstance>,
+    timeout: Option<Duration>,
+    cache_key: &str,
+    request_headers: &Map<String, Value>,
+) -> Option<HttpResponse> {
+    let cached_response = get_cached_response(cache_key).filter(|cached_response| cached_response.is_usable() && cached_response.matches(request_headers))?;
+    if !cached_response.is_fresh() && begin_revalidation(cache_key) {
+        let reactive_instance = reactive_instance.clone();
+        let cache_key = cache_key.to_string();
+        thread::spawn(move || {
+            if let Some(response) = execute(&reactive_instance, timeout, true, &Cancellation::default()) {
+                response.apply(&reactive_instance);
+            }
+            end_revalidation(&cache_key);
+        });
+    }
+    Some(to_http_response(
+        reactive_instance,
+        cached_response.status,
+        cached_response.headers,
+        &cached_response.body,
+        None,
+        true,
+    ))
+}
+
+/// Publishes the request like it would be sent instead of sending it.
+fn publish_request_preview(
+    reactive_instance: &ReactiveEntityInstance,
+    cancellation: &Cancellation,
+    method: &str,
+    url: &str,
+    request_headers: &Map<String, Value>,
+    payload: &Value,
+    api_key_header: &str,
+) {
+    let hawk = HawkCredentials::from_instance(reactive_instance);
+    let request_preview = preview_request(method, url, request_headers, payload, api_key_header, hawk.is_some());
+    set_unless_superseded(reactive_instance, cancellation, SKIPPED_REASON, json!("Dry run"));
+    set_unless_superseded(reactive_instance, cancellation, REQUEST_PREVIEW, request_preview);
+}
+
+/// Returns true, if the instance respects robots.txt and robots.txt of the host disallows the url. The result is
+/// published.
+fn is_blocked_by_robots(
+    reactive_instance: &ReactiveEntityInstance,
+    cancellation: &Cancellation,
+    url: &str,
+    request_headers: &Map<String, Value>,
+    url_policy: &UrlPolicy,
+) -> bool {
+    if !reactive_instance.as_bool(RESPECT_ROBOTS).unwrap_or(false) {
+        return false;
+    }
+    let user_agent = request_headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("user-agent"))
+        .and_then(|(_, user_agent)| user_agent.as_str())
+        .unwrap_or("ureq");
+    let blocked_by_robots = !is_allowed_by_robots(url, user_agent, url_policy);
+    if reactive_instance.as_bool(BLOCKED_BY_ROBOTS) != Some(blocked_by_robots) {
+        set_unless_superseded(reactive_instance, cancellation, BLOCKED_BY_ROBOTS, json!(blocked_by_robots));
+    }
+    blocked_by_robots
+}
+
+/// The outcome of sending a request and following its redirects.
+struct SentRequest {
+    result: Result<ureq::Response, ureq::Error>,
+    /// The urls and statuses of the requests including the followed redirects.
+    redirect_chain: Vec<Value>,
+    ttfb: Duration,
+    /// The start of the request.
+    started: Instant,
+}
+
+fn attempt(options: &RequestOptions, url: &str, request_headers: &Map<String, Value>) -> SentRequest {
+    let started = Instant::now();
+    let (result, redirect_chain, ttfb) = send(options, url, request_headers);
+    SentRequest {
+        result,
+        redirect_chain,
+        ttfb,
+        started,
+    }
+}
+
+/// Sends the request and repeats it at the time the server asked for, but not later than after the maximum delay.
+fn send_with_retries(
+    reactive_instance: &ReactiveEntityInstance,
+    options: &RequestOptions,
+    url: &str,
+    request_headers: &Map<String, Value>,
+    max_retries: u64,
+    max_retry_after: Duration,
+) -> SentRequest {
+    let mut sent_request = attempt(options, url, request_headers);
+    let mut retries = 0;
+    while retries < max_retries {
+        let Some(delay) = get_retry_after(&sent_request.result).map(|delay| delay.min(max_retry_after)) else {
+            break;
+        };
+        warn!("The server asked to retry the request of {} after {} ms", reactive_instance.id, delay.as_millis());
+        if !options.cancellation.sleep(delay) {
+            break;
+        }
+        retries += 1;
+        sent_request = attempt(options, url, request_headers);
+    }
+    sent_request
+}
+
+/// Rotates a rejected api key out: each of the other keys is tried once and the accepted key stays active.
+fn rotate_api_keys(
+    reactive_instance: &ReactiveEntityInstance,
+    options: &RequestOptions,
+    url: &str,
+    request_headers: &mut Map<String, Value>,
+    api_keys: &ApiKeys,
+    mut sent_request: SentRequest,
+) -> SentRequest {
+    let mut active_api_key = api_keys.active;
+    let mut rotations = 1;
+    while rotations < api_keys.keys.len() && is_unauthorized(&sent_request.result) && !options.cancellation.is_cancelled() {
+        active_api_key = (active_api_key + 1) % api_keys.keys.len();
+        warn!("The api key of {} has been rejected, rotating to key {}", reactive_instance.id, active_api_key);
+        set_unless_superseded(reactive_instance, options.cancellation, ACTIVE_API_KEY, json!(active_api_key));
+        api_keys.insert(active_api_key, request_headers);
+        rotations += 1;
+        sent_request = attempt(options, url, request_headers);
+    }
+    sent_request
+}
+
+/// Requests the fallback urls in order until one of them is available.
+///
+/// Returns the fallback url which has been requested last.
+fn send_to_fallback_urls(
+    options: &RequestOptions,
+    fallback_urls: &[String],
+    request_headers: &Map<String, Value>,
+    mut sent_request: SentRequest,
+) -> (SentRequest, Option<String>) {
+    let mut sent_fallback_url = None;
+    for fallback_url in fallback_urls.iter() {
+        if !is_unavailable(&sent_request.result) || options.cancellation.is_cancelled() {
+            break;
+        }
+        if let Err(e) = options.url_policy.check(fallback_url) {
+            warn!("Skipped fallback url: {}", e);
+            continue;
         }
+        if let Err(e) = &sent_request.result {
+            warn!("Failed to send request, falling back to {}: {}", fallback_url, e.to_string());
+        }
+        sent_request = attempt(options, fallback_url, request_headers);
+        sent_fallback_url = Some(fallback_url.clone());
+    }
+    (sent_request, sent_fallback_url)
+}
+
+/// Publishes the response time and the lifecycle event of the request and records the request in the audit log.
+fn record_outcome(
+    reactive_instance: &ReactiveEntityInstance,
+    options: &RequestOptions,
+    sent_url: &str,
+    request_headers: &Map<String, Value>,
+    api_keys: &ApiKeys,
+    result: &Result<ureq::Response, ureq::Error>,
+    request_started: Instant,
+) {
+    let (status, error) = match result {
+        Ok(response) => (Some(response.status()), None),
+        Err(ureq::Error::Status(status, response)) => (Some(*status), Some(response.status_text().to_string())),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    let mut audited_headers = request_headers.clone();
+    if !api_keys.keys.is_empty() {
+        audited_headers.insert(api_keys.header.clone(), json!("***"));
+    }
+    let duration_ms = request_started.elapsed().as_millis() as u64;
+    set_unless_superseded(reactive_instance, options.cancellation, RESPONSE_TIME_MS, json!(duration_ms));
+    let lifecycle_event = json!({ "method": options.method, "url": sent_url, "status": status, "duration_ms": duration_ms, "error": error });
+    record_request(AuditEntry {
+        id: reactive_instance.id,
+        behaviour: BEHAVIOUR_NAME_HTTP.to_string(),
+        timestamp: Utc::now(),
+        method: options.method.to_string(),
+        url: sent_url.to_string(),
+        request_headers: audited_headers,
+        payload: options.payload.clone(),
+        status,
+        duration_ms,
+        error,
+    });
+    let lifecycle_property = if result.is_ok() { REQUEST_SUCCEEDED } else { REQUEST_FAILED };
+    set_unless_superseded(reactive_instance, options.cancellation, lifecycle_property, lifecycle_event);
+}
+
+/// Publishes the rate limit of the response and, if the instance respects rate limits, paces the next requests to the
+/// host.
+fn publish_rate_limit(
+    reactive_instance: &ReactiveEntityInstance,
+    cancellation: &Cancellation,
+    host: &str,
+    respect_rate_limits: bool,
+    result: &Result<ureq::Response, ureq::Error>,
+) {
+    let rate_limit = match result {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => RateLimit::from_response(response),
+        Err(_) => None,
+    };
+    let Some(rate_limit) = rate_limit else {
+        return;
+    };
+    if respect_rate_limits {
+        record_rate_limit(host, &rate_limit);
     }
-    let result = request.send_json(payload);
+    set_unless_superseded(reactive_instance, cancellation, RATE_LIMIT_REMAINING, json!(rate_limit.remaining));
+    set_unless_superseded(reactive_instance, cancellation, RATE_LIMIT_RESET, json!(rate_limit.reset_timestamp()));
+}
+
+/// Reads the response body, stores the response in the cache and parses the body.
+///
+/// The response is stored, if a cache key is given and the response is cacheable.
+fn receive_response(
+    reactive_instance: &ReactiveEntityInstance,
+    options: &RequestOptions,
+    sent_request: SentRequest,
+    cache_key: Option<String>,
+    request_headers: &Map<String, Value>,
+    upstream: Option<String>,
+) -> Option<HttpResponse> {
+    let cancellation = options.cancellation;
+    let SentRequest {
+        result, redirect_chain, ttfb, ..
+    } = sent_request;
     match result {
         Ok(response) => {
-            reactive_instance.set(STATUS, json!(response.status()));
-            let mut response_headers = json!({});
+            let status = response.status();
+            let mut headers = json!({});
             for header_name in response.headers_names() {
-                response_headers[header_name] = json!(response.header(header_name.as_str()));
+                headers[header_name] = json!(response.header(header_name.as_str()));
             }
-            reactive_instance.set(RESPONSE_HEADERS, response_headers);
-            match response.into_json() {
-                Ok(result) => {
-                    reactive_instance.set(RESULT, result);
+            let cache_control = CacheControl::from(response.header("cache-control").unwrap_or_default());
+            let mut body = Vec::new();
+            if cancellation.is_cancelled() {
+                debug!("Cancelled the request of {}", reactive_instance.id);
+                return None;
+            }
+            if let Err(e) = response.into_reader().read_to_end(&mut body) {
+                if cancellation.is_cancelled() {
+                    debug!("Cancelled the request of {}", reactive_instance.id);
+                } else {
+                    error!("Failed to read response: {}", e.to_string());
                 }
-                Err(e) => error!("Failed to parse response as JSON: {}", e.to_string()),
+                return None;
+            }
+            // Responses which vary on anything else than the request headers are not stored
+            let vary = vary(&headers, request_headers);
+            if let (Some(cache_key), Some(max_age), Some(vary)) = (cache_key, cache_control.cache_duration().filter(|_| (200..300).contains(&status)), vary) {
+                let stale_while_revalidate = reactive_instance
+                    .as_u64(STALE_WHILE_REVALIDATE)
+                    .unwrap_or(0)
+                    .max(cache_control.stale_while_revalidate.unwrap_or(0));
+                let cached_response = CachedResponse {
+                    status,
+                    headers: headers.clone(),
+                    body: body.clone(),
+                    stored_at: SystemTime::now(),
+                    max_age,
+                    stale_while_revalidate: Duration::from_secs(stale_while_revalidate),
+                    vary,
+                };
+                store_response(cache_key, cached_response);
             }
+            let mut response = to_http_response(reactive_instance, status, headers, &body, upstream, false);
+            response.redirect_chain = Some(redirect_chain);
+            response.timing = Some(Timing {
+                dns: options.resolver.last_lookup(),
+                connect: options.resolver.last_connect(),
+                tls_handshake: options.resolver.last_handshake(),
+                ttfb,
+            });
+            Some(response)
+        }
+        // The network is unreachable: the flow continues with the last known response
+        Err(ureq::Error::Transport(e)) if reactive_instance.as_bool(OFFLINE_FALLBACK).unwrap_or(false) => {
+            warn!("Failed to send request, publishing the last known response: {}", e.to_string());
+            Some(HttpResponse {
+                status: reactive_instance.as_u64(STATUS).unwrap_or(0) as u16,
+                headers: reactive_instance.get(RESPONSE_HEADERS).unwrap_or_else(|| json!({})),
+                result: reactive_instance.get(RESULT),
+                upstream: None,
+                body_hash: None,
+                from_cache: false,
+                stale: true,
+                redirect_chain: None,
+                timing: None,
+                discard_body: false,
+            })
+        }
+        Err(_) if cancellation.is_cancelled() => {
+            debug!("Cancelled the request of {}", reactive_instance.id);
+            None
         }
         Err(e) => {
             error!("Failed to send request: {}", e.to_string());
+            None
+        }
+    }
+}
+
+/// Parses the response body and computes its hash.
+fn to_http_response(
+    reactive_instance: &ReactiveEntityInstance,
+    status: u16,
+    headers: Value,
+    body: &[u8],
+    upstream: Option<String>,
+    from_cache: bool,
+) -> HttpResponse {
+    let body_hash = reactive_instance
+        .as_string(HASH_ALGORITHM)
+        .and_then(|hash_algorithm| hash_body(&hash_algorithm, body));
+    let parse_mode = reactive_instance
+        .as_string(PARSE_MODE)
+        .map(|parse_mode| ParseMode::from(parse_mode.as_str()))
+        .unwrap_or(ParseMode::Auto);
+    let content_type = headers
+        .as_object()
+        .and_then(|headers| headers.iter().find(|(name, _)| name.eq_ignore_ascii_case("content-type")))
+        .and_then(|(_, content_type)| content_type.as_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let result = match parse_body(parse_mode, &content_type, body) {
+        Ok(result) => result,
+        Err(e) => {
+            error!("{}", e);
+            None
+        }
+    };
+    HttpResponse {
+        status,
+        headers,
+        result,
+        upstream,
+        body_hash,
+        from_cache,
+        stale: false,
+        redirect_chain: None,
+        timing: None,
+        discard_body: parse_mode == ParseMode::None,
+    }
+}
+
+/// How the response body is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParseMode {
+    /// Decides by the content type. Bodies of unknown type are parsed as JSON, as text or as binary, whatever succeeds first.
+    Auto,
+    Json,
+    Text,
+    /// The body is published as array of bytes.
+    Binary,
+    /// The body is not published.
+    None,
+}
+
+impl From<&str> for ParseMode {
+    fn from(parse_mode: &str) -> Self {
+        match parse_mode {
+            "json" => ParseMode::Json,
+            "text" => ParseMode::Text,
+            "binary" => ParseMode::Binary,
+            "none" => ParseMode::None,
+            _ => ParseMode::Auto,
+        }
+    }
+}
+
+/// Parses the body according to the parse mode. Returns none, if the body is discarded.
+fn parse_body(parse_mode: ParseMode, content_type: &str, body: &[u8]) -> Result<Option<Value>, String> {
+    let json = || serde_json::from_slice(body).map_err(|e| format!("Failed to parse response as JSON: {}", e));
+    let text = || String::from_utf8(body.to_vec()).map(Value::String);
+    match parse_mode {
+        ParseMode::Json => json().map(Some),
+        ParseMode::Text => Ok(Some(json!(String::from_utf8_lossy(body)))),
+        ParseMode::Binary => Ok(Some(json!(body))),
+        ParseMode::None => Ok(None),
+        ParseMode::Auto if content_type.contains("json") => json().map(Some),
+        ParseMode::Auto if content_type.starts_with("text/") || content_type.contains("xml") => Ok(Some(json!(String::from_utf8_lossy(body)))),
+        ParseMode::Auto => Ok(Some(json().or_else(|_| text()).unwrap_or_else(|_| json!(body)))),
+    }
+}
+
+/// Builds the agent which sends the requests of the instance.
+///
+/// HTTPS requests are tunneled through the proxy using CONNECT. The proxy credentials are part of the proxy url and
+/// must be allowed for the host of the proxy.
+///
+/// Redirects are followed by the behaviour instead of the agent, so that their targets can be checked.
+///
+/// The TLS connections are established with the TLS configuration of the instance and registered with the cancellation
+/// of the request.
+fn build_agent(
+    reactive_instance: &ReactiveEntityInstance,
+    session: Option<&ReactiveEntityInstance>,
+    resolver: TimedResolver,
+    cancellation: &Cancellation,
+) -> Result<ureq::Agent, String> {
+    let mut agent = ureq::AgentBuilder::new().redirects(0).resolver(resolver.clone());
+    let proxy = inherit_string(reactive_instance, PROXY.as_ref()).or_else(|| session.and_then(get_session_proxy));
+    if let Some(proxy) = proxy {
+        agent = agent.proxy(ureq::Proxy::new(resolve_secrets_for_url(&proxy, &proxy)?).map_err(|e| format!("Invalid proxy: {}", e))?);
+    }
+    let tls_connector = RequestTlsConnector {
+        client_config: TlsPolicy::from(reactive_instance).client_config()?,
+        cancellation: cancellation.clone(),
+        resolver: resolver.clone(),
+    };
+    Ok(agent.tls_connector(Arc::new(tls_connector)).build())
+}
+
+/// The parts of a request which are the same for retries, redirects and fallbacks.
+struct RequestOptions<'a> {
+    agent: ureq::Agent,
+    /// The resolver of the agent, which times the connect of plain HTTP requests.
+    resolver: TimedResolver,
+    method: &'a str,
+    payload: &'a Value,
+    timeout: Option<Duration>,
+    url_policy: &'a UrlPolicy,
+    max_redirects: u64,
+    api_key_header: &'a str,
+    hawk: Option<HawkCredentials>,
+    cancellation: &'a Cancellation,
+}
+
+/// Sends the request and follows the redirects.
+///
+/// Each redirect target is checked against the url policy. Credentials are not sent to other hosts. Urls of known HSTS
+/// hosts are upgraded to https.
+///
+/// With Hawk authentication every request is signed and a rejected timestamp leads to a single retry with the clock of
+/// the server.
+///
+/// Returns the result of the last request, the urls and statuses of all requests and the time to the first byte of the
+/// last request.
+fn send(options: &RequestOptions, url: &str, request_headers: &Map<String, Value>) -> (Result<ureq::Response, ureq::Error>, Vec<Value>, Duration) {
+    let mut method = options.method.to_string();
+    let mut url = url.to_string();
+    let mut request_headers = request_headers.clone();
+    let mut with_payload = true;
+    let mut redirect_chain = Vec::new();
+    let mut hawk = options.hawk.clone();
+    let mut hawk_synchronized = false;
+    loop {
+        // A cancelled request isn't retried or redirected
+        if options.cancellation.is_cancelled() {
+            let error = io::Error::new(io::ErrorKind::Interrupted, "The request has been cancelled");
+            return (Err(ureq::Error::from(error)), redirect_chain, Duration::ZERO);
+        }
+        url = upgrade_to_https(&url);
+        let started = Instant::now();
+        let mut request = options.agent.request(&method, &url);
+        if let Some(timeout) = options.timeout {
+            request = request.timeout(timeout);
+        }
+        let request = match set_request_headers(request, &url, &request_headers) {
+            Ok(request) => request,
+            Err(e) => return (Err(ureq::Error::from(io::Error::new(io::ErrorKind::InvalidInput, e))), redirect_chain, Duration::ZERO),
+        };
+        let hawk_url = Url::parse(&url).ok();
+        let authorize = |request: ureq::Request, payload: Option<(&str, &[u8])>| match (&hawk, &hawk_url) {
+            (Some(hawk), Some(hawk_url)) => request.set("Authorization", &hawk.authorization(&method, hawk_url, payload)),
+            _ => request,
+        };
+        // The body of a plain HTTP request is read after the connect, so it is sent with its length from a reader
+        let plain_http = url.starts_with("http://");
+        let result = if with_payload {
+            // A content type given by the request headers takes precedence
+            let (content_type, body) = encode_payload(options.payload, request.header("content-type"));
+            let request = match content_type {
+                Some(content_type) => request.set("Content-Type", content_type),
+                None => request,
+            };
+            let content_type = request.header("content-type").unwrap_or_default().to_string();
+            let request = authorize(request, Some((&content_type, &body)));
+            match plain_http {
+                true => request
+                    .set("Content-Length", &body.len().to_string())
+                    .send(options.resolver.time_connect(body.as_slice(), started)),
+                false => request.send_bytes(&body),
+            }
+        } else {
+            let request = authorize(request, None);
+            match plain_http {
+                true => request.set("Content-Length", "0").send(options.resolver.time_connect(io::empty(), started)),
+                false => request.call(),
+            }
+        };
+        if let (Some(hawk), Err(ureq::Error::Status(401, response))) = (&hawk, &result) {
+            let www_authenticate = response.header("www-authenticate").unwrap_or_default();
+            if !hawk_synchronized && hawk.synchronize(www_authenticate) {
+                hawk_synchronized = true;
+                continue;
+            }
+        }
+        // The result is available as soon as the status line and the headers have been received
+        let ttfb = started.elapsed();
+        if let Ok(response) | Err(ureq::Error::Status(_, response)) = &result {
+            record_hsts(&url, response.header("strict-transport-security"));
         }
+        let status = match &result {
+            Ok(response) => Some(response.status()),
+            Err(ureq::Error::Status(status, _)) => Some(*status),
+            Err(_) => None,
+        };
+        redirect_chain.push(json!({ "url": url, "status": status }));
+        let location = match &result {
+            Ok(response) if (300..400).contains(&response.status()) => {
+                response.header("location").and_then(|location| Url::parse(&url).ok()?.join(location).ok())
+            }
+            _ => None,
+        };
+        let Some(location) = location else {
+            return (result, redirect_chain, ttfb);
+        };
+        if redirect_chain.len() as u64 > options.max_redirects {
+            warn!("Stopped following the redirects of {} after {} redirects", url, options.max_redirects);
+            return (result, redirect_chain, ttfb);
+        }
+        if let Err(e) = options.url_policy.check(location.as_str()) {
+            warn!("Refused to follow redirect: {}", e);
+            return (result, redirect_chain, ttfb);
+        }
+        // See other and, like browsers do, moved for non-GET requests continue with GET without payload
+        if status == Some(303) || (matches!(status, Some(301 | 302)) && !method.eq_ignore_ascii_case("GET")) {
+            method = String::from("GET");
+            with_payload = false;
+        }
+        if Url::parse(&url).ok().and_then(|url| url.host_str().map(String::from)).as_deref() != location.host_str() {
+            request_headers.retain(|name, _| !is_credential_header(name) && !name.eq_ignore_ascii_case(options.api_key_header));
+            hawk = None;
+        }
+        url = location.to_string();
+    }
+}
+
+/// Sends a copy of the request to the mirror url in the background. The response of the mirror is only logged.
+///
+/// The path and the query of the url are appended to the mirror url. Redirects of the mirror are not followed and
+/// credentials are not sent, if the mirror is another host.
+fn mirror_request(
+    reactive_instance: &ReactiveEntityInstance,
+    session: Option<&ReactiveEntityInstance>,
+    options: &RequestOptions,
+    url: &str,
+    request_headers: &Map<String, Value>,
+) {
+    let Some(mirror_url) = inherit_string(reactive_instance, MIRROR_URL.as_ref()) else {
+        return;
+    };
+    let Ok(url) = Url::parse(url) else {
+        return;
+    };
+    let mut mirror_url = join_url(&resolve_env(&mirror_url), url.path());
+    if let Some(query) = url.query() {
+        mirror_url = format!("{}?{}", mirror_url, query);
+    }
+    if let Err(e) = options.url_policy.check(&mirror_url) {
+        warn!("Refused to mirror the request of {}: {}", reactive_instance.id, e);
+        return;
+    }
+    let mut request_headers = request_headers.clone();
+    if Url::parse(&mirror_url)
+        .ok()
+        .and_then(|mirror_url| mirror_url.host_str().map(String::from))
+        .as_deref()
+        != url.host_str()
+    {
+        request_headers.retain(|name, _| !is_credential_header(name) && !name.eq_ignore_ascii_case(options.api_key_header));
+    }
+    // The resolver checks the addresses the mirror connects to. The primary request measures its own lookups
+    let agent = match build_agent(reactive_instance, session, TimedResolver::with_policy(options.url_policy.clone()), &Cancellation::default()) {
+        Ok(agent) => agent,
+        Err(e) => {
+            error!("Failed to configure the agent of the mirror of {}: {}", reactive_instance.id, e);
+            return;
+        }
+    };
+    let id = reactive_instance.id;
+    let method = options.method.to_string();
+    let payload = options.payload.clone();
+    let timeout = options.timeout;
+    thread::spawn(move || {
+        let mut request = agent.request(&method, &mirror_url);
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+        let request = match set_request_headers(request, &mirror_url, &request_headers) {
+            Ok(request) => request,
+            Err(e) => {
+                warn!("Failed to mirror the request of {} to {}: {}", id, mirror_url, e);
+                return;
+            }
+        };
+        let (content_type, body) = encode_payload(&payload, request.header("content-type"));
+        let request = match content_type {
+            Some(content_type) => request.set("Content-Type", content_type),
+            None => request,
+        };
+        let started = Instant::now();
+        match request.send_bytes(&body) {
+            Ok(response) => debug!(
+                "Mirrored the request of {} to {}: {} after {} ms",
+                id,
+                mirror_url,
+                response.status(),
+                started.elapsed().as_millis()
+            ),
+            Err(ureq::Error::Status(status, _)) => {
+                warn!("Mirrored the request of {} to {}: {} after {} ms", id, mirror_url, status, started.elapsed().as_millis())
+            }
+            Err(e) => warn!("Failed to mirror the request of {} to {}: {}", id, mirror_url, e),
+        }
+    });
+}
+
+/// Builds the request like it would be sent and returns its method, url, headers and body.
+///
+/// Credentials are redacted. The body is output as text or, if it isn't valid UTF-8, as array of bytes.
+fn preview_request(method: &str, url: &str, request_headers: &Map<String, Value>, payload: &Value, api_key_header: &str, hawk: bool) -> Value {
+    let request = match set_request_headers(ureq::request(method, url), url, request_headers) {
+        Ok(request) => request,
+        Err(e) => return json!({ "method": method, "url": url, "error": e }),
+    };
+    let (content_type, body) = encode_payload(payload, request.header("content-type"));
+    let request = match content_type {
+        Some(content_type) => request.set("Content-Type", content_type),
+        None => request,
+    };
+    let mut headers = Map::new();
+    for name in request.header_names() {
+        let value = if is_credential_header(&name) || name.eq_ignore_ascii_case(api_key_header) {
+            json!("***")
+        } else {
+            json!(request.header(&name))
+        };
+        headers.insert(name, value);
+    }
+    // The Hawk header is computed per request
+    if hawk {
+        headers.insert(String::from("Authorization"), json!("Hawk ***"));
+    }
+    let body = match String::from_utf8(body) {
+        Ok(text) => json!(text),
+        Err(e) => json!(e.into_bytes()),
+    };
+    json!({ "method": method, "url": url, "headers": headers, "body": body })
+}
+
+/// Encodes the payload depending on its type and returns the content type to send.
+///
+/// Strings are sent as text and non-empty arrays of bytes as binary data. Everything else is sent as JSON. If the content
+/// type is given and is a JSON type, the payload is sent as JSON regardless of its type.
+fn encode_payload(payload: &Value, content_type: Option<&str>) -> (Option<&'static str>, Vec<u8>) {
+    let json = || serde_json::to_vec(payload).unwrap_or_default();
+    let default_content_type = |default_content_type| content_type.is_none().then_some(default_content_type);
+    if content_type.map(|content_type| content_type.contains("json")).unwrap_or(false) {
+        return (None, json());
+    }
+    match payload {
+        Value::String(text) => (default_content_type("text/plain; charset=utf-8"), text.as_bytes().to_vec()),
+        Value::Array(items) if !items.is_empty() && items.iter().all(|item| item.as_u64().map(|byte| byte <= 255).unwrap_or(false)) => (
+            default_content_type("application/octet-stream"),
+            items.iter().filter_map(Value::as_u64).map(|byte| byte as u8).collect(),
+        ),
+        _ => (default_content_type("application/json"), json()),
+    }
+}
+
+/// Returns the delay of the Retry-After header of a response with the status 429 or 503.
+fn get_retry_after(result: &Result<ureq::Response, ureq::Error>) -> Option<Duration> {
+    let Err(ureq::Error::Status(429 | 503, response)) = result else {
+        return None;
+    };
+    parse_retry_after(response.header("retry-after")?, Utc::now())
+}
+
+/// Parses a Retry-After header, which contains either the delay in seconds or a HTTP date.
+fn parse_retry_after(retry_after: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let retry_after = retry_after.trim();
+    if let Ok(seconds) = retry_after.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(retry_after).ok()?;
+    // A date in the past means that the request can be retried immediately
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+/// Returns true, if the server could not be reached or failed to handle the request.
+fn is_unavailable(result: &Result<ureq::Response, ureq::Error>) -> bool {
+    match result {
+        Ok(_) => false,
+        Err(ureq::Error::Status(status, _)) => *status >= 500,
+        Err(_) => true,
+    }
+}
+
+/// Returns true, if the server rejected the credentials of the request.
+fn is_unauthorized(result: &Result<ureq::Response, ureq::Error>) -> bool {
+    matches!(result, Err(ureq::Error::Status(401 | 403, _)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT").unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn parses_the_delay_in_seconds() {
+        assert_eq!(parse_retry_after("120", now()), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 ", now()), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("-5", now()), None);
+        assert_eq!(parse_retry_after("soon", now()), None);
+    }
+
+    #[test]
+    fn parses_the_http_date() {
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:30:00 GMT", now()), Some(Duration::from_secs(120)));
+        // A date in the past allows to retry immediately
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now()), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn only_429_and_503_are_retried_after_the_delay() {
+        let response = |status: u16| {
+            let response: ureq::Response = format!("HTTP/1.1 {} Status\r\nRetry-After: 7\r\n\r\n", status).parse().unwrap();
+            Err(ureq::Error::Status(status, response))
+        };
+        assert_eq!(get_retry_after(&response(429)), Some(Duration::from_secs(7)));
+        assert_eq!(get_retry_after(&response(503)), Some(Duration::from_secs(7)));
+        assert_eq!(get_retry_after(&response(500)), None);
     }
 }
 
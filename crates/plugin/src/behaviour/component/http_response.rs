@@ -0,0 +1,29 @@
+use serde_json::Value;
+
+use crate::model::*;
+use crate::model_http::HttpResponseProperties::COMPLETE;
+use crate::model_http::HttpResponseProperties::CONTENT_TYPES;
+use crate::reactive::*;
+use crate::response::complete_pending_response;
+
+entity_behaviour!(HttpResponse, HttpResponseFactory, HttpResponseFsm, HttpResponseBehaviourTransitions, HttpResponseValidator);
+
+behaviour_validator!(HttpResponseValidator, ReactiveEntityInstance, CONTENT_TYPES.as_ref(), COMPLETE.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for HttpResponseBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for HttpResponseBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        // The response itself is composed by the route, which waits for the completion of asynchronous responses
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(COMPLETE.as_ref(), move |complete: &Value| {
+            if complete.as_bool().unwrap_or(false) {
+                complete_pending_response(reactive_instance.id);
+            }
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for HttpResponseBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for HttpResponseBehaviourTransitions {}
@@ -0,0 +1,185 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::error;
+use serde_json::json;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::context::get_plugin_context;
+use crate::model::*;
+use crate::model_http::HttpResponseProperties::ASYNC;
+use crate::model_http::HttpResponseProperties::COMPLETE;
+use crate::model_http::HttpResponseProperties::TIMEOUT;
+use crate::model_http::HttpRouteProperties::DISPATCH_NAMESPACE;
+use crate::model_http::HttpRouteProperties::DISPATCH_TYPE;
+use crate::model_http::HttpRouteProperties::METHOD;
+use crate::model_http::HttpRouteProperties::PATH;
+use crate::model_http::HttpRouteProperties::PATH_PARAMS;
+use crate::model_http::HttpRouteProperties::PORT;
+use crate::model_http::HttpRouteProperties::QUERY;
+use crate::model_http::HttpRouteProperties::REMOTE_ADDR;
+use crate::model_http::HttpRouteProperties::REQUEST;
+use crate::model_http::HttpRouteProperties::REQUEST_BODY;
+use crate::model_http::HttpRouteProperties::REQUEST_HEADERS;
+use crate::model_http::HttpRouteProperties::REQUEST_METHOD;
+use crate::model_http::HttpRouteProperties::REQUEST_PATH;
+use crate::model_http::HttpRouteProperties::RESPONSE_BODY;
+use crate::model_http::HttpRouteProperties::RESPONSE_HEADERS;
+use crate::model_http::HttpRouteProperties::RESPONSE_STATUS;
+use crate::pausable::is_enabled;
+use crate::reactive::*;
+use crate::response::await_pending_response;
+use crate::response::begin_pending_response;
+use crate::response::compose_response;
+use crate::route_auth::authenticate;
+use crate::route_rate_limit::check_rate_limit;
+use crate::server::register_route;
+use crate::server::unregister_route;
+use crate::server::OutgoingResponse;
+use crate::server::Route;
+use crate::server::RouteHandler;
+
+entity_behaviour!(HttpRoute, HttpRouteFactory, HttpRouteFsm, HttpRouteBehaviourTransitions, HttpRouteValidator);
+
+behaviour_validator!(
+    HttpRouteValidator,
+    ReactiveEntityInstance,
+    PORT.as_ref(),
+    PATH.as_ref(),
+    REQUEST.as_ref(),
+    RESPONSE_STATUS.as_ref(),
+    RESPONSE_BODY.as_ref()
+);
+
+impl BehaviourInit<ReactiveEntityInstance> for HttpRouteBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for HttpRouteBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let port = self
+            .reactive_instance
+            .as_u64(PORT)
+            .and_then(|port| u16::try_from(port).ok())
+            .ok_or(BehaviourConnectFailed {})?;
+        let method = self.reactive_instance.as_string(METHOD).filter(|method| !method.is_empty() && method != "*");
+        let reactive_instance = self.reactive_instance.clone();
+        // In the shared mode the requests share the properties of the instance, so they are handled one after another
+        let lock = Arc::new(Mutex::new(()));
+        let route = Route {
+            id: self.reactive_instance.id,
+            method,
+            path: self.reactive_instance.as_string(PATH).unwrap_or_else(|| String::from("/")),
+            handler: RouteHandler::Buffered(Arc::new(move |request| {
+                if !is_enabled(&reactive_instance) {
+                    return OutgoingResponse::status(503);
+                }
+                if let Err(response) = check_rate_limit(&reactive_instance, &request) {
+                    return response;
+                }
+                let principal = match authenticate(&reactive_instance, &request) {
+                    Ok(principal) => principal,
+                    Err(response) => return response,
+                };
+                let mut request = request.to_json();
+                request["principal"] = principal;
+                let dispatch_type = reactive_instance.as_string(DISPATCH_TYPE).unwrap_or_default();
+                if !dispatch_type.is_empty() {
+                    return dispatch(&reactive_instance, &dispatch_type, request);
+                }
+                let _guard = lock.lock().unwrap();
+                handle(&reactive_instance, request)
+            })),
+            cors: Some(self.reactive_instance.clone()),
+        };
+        register_route(port, route).map_err(|e| {
+            error!("{}", e);
+            BehaviourConnectFailed {}
+        })
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        unregister_route(self.reactive_instance.id);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for HttpRouteBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for HttpRouteBehaviourTransitions {}
+
+/// Populates the request properties and answers with the response properties, which have been set by the flow
+/// while it propagated the request.
+///
+/// Asynchronous responses are sent as soon as the flow completed them.
+fn handle(reactive_instance: &ReactiveEntityInstance, request: Value) -> OutgoingResponse {
+    let asynchronous = reactive_instance.as_bool(ASYNC).unwrap_or(false);
+    if asynchronous {
+        reactive_instance.set(COMPLETE, json!(false));
+        begin_pending_response(reactive_instance.id);
+    }
+    reactive_instance.set(REQUEST_METHOD, request["method"].clone());
+    reactive_instance.set(REQUEST_PATH, request["path"].clone());
+    reactive_instance.set(PATH_PARAMS, request["params"].clone());
+    reactive_instance.set(QUERY, request["query"].clone());
+    reactive_instance.set(REQUEST_HEADERS, request["headers"].clone());
+    reactive_instance.set(REQUEST_BODY, request["body"].clone());
+    reactive_instance.set(REMOTE_ADDR, request["remote_addr"].clone());
+    reactive_instance.set(REQUEST, request.clone());
+    if asynchronous {
+        let timeout = Duration::from_millis(reactive_instance.as_u64(TIMEOUT).unwrap_or(30000));
+        if !await_pending_response(reactive_instance.id, timeout) {
+            return OutgoingResponse::json(504, &json!({ "error": "The response has not been completed within the timeout" }));
+        }
+    }
+    compose_response(reactive_instance, &request)
+}
+
+/// Handles the request by a short-lived instance of the entity type, so that concurrent requests don't share
+/// properties.
+///
+/// The instance has the request and response properties of the route. The response properties are initialized with
+/// the response properties of the route. The request properties are set after the instance has been created, so
+/// that the behaviours of the entity type propagate them.
+fn dispatch(reactive_instance: &ReactiveEntityInstance, dispatch_type: &str, request: Value) -> OutgoingResponse {
+    let Some(context) = get_plugin_context() else {
+        error!("Failed to dispatch the request: The plugin context is not available");
+        return OutgoingResponse::status(500);
+    };
+    let entity_instance_manager = context.get_entity_instance_manager();
+    let namespace = reactive_instance.as_string(DISPATCH_NAMESPACE).unwrap_or_default();
+    let entity_instance = serde_json::from_value::<EntityInstance>(json!({
+        "namespace": namespace,
+        "type_name": dispatch_type,
+        "id": Uuid::new_v4(),
+        "description": format!("{} {}", request["method"].as_str().unwrap_or_default(), request["path"].as_str().unwrap_or_default()),
+        "properties": {
+            "request_method": "",
+            "request_path": "",
+            "path_params": {},
+            "query": {},
+            "request_headers": {},
+            "request_body": {},
+            "remote_addr": "",
+            "request": {},
+            "response_status": reactive_instance.get(RESPONSE_STATUS).unwrap_or_else(|| json!(200)),
+            "response_headers": reactive_instance.get(RESPONSE_HEADERS).unwrap_or_else(|| json!({})),
+            "response_body": reactive_instance.get(RESPONSE_BODY).unwrap_or(Value::Null)
+        },
+        "extensions": []
+    }))
+    .map_err(|e| e.to_string())
+    .and_then(|entity_instance| entity_instance_manager.create(entity_instance).map_err(|e| format!("{:?}", e)));
+    let dispatched_instance = match entity_instance {
+        Ok(dispatched_instance) => dispatched_instance,
+        Err(e) => {
+            error!(
+                "Failed to create an instance of the entity type {} of the namespace {} for the request: {}",
+                dispatch_type, namespace, e
+            );
+            return OutgoingResponse::status(500);
+        }
+    };
+    let response = handle(&dispatched_instance, request);
+    entity_instance_manager.delete(dispatched_instance.id);
+    response
+}
@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use log::error;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use url::Url;
+
+use crate::headers::parse_link_header;
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::HypermediaProperties::BASE_URL;
+use crate::model_http::HypermediaProperties::DOCUMENT;
+use crate::model_http::HypermediaProperties::ERROR;
+use crate::model_http::HypermediaProperties::LINKS;
+use crate::model_http::HypermediaProperties::RELATION;
+use crate::model_http::HypermediaProperties::REQUEST_HEADERS;
+use crate::model_http::HypermediaProperties::RESPONSE_HEADERS;
+use crate::model_http::HypermediaProperties::STATUS;
+use crate::pausable::is_enabled;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+
+entity_behaviour!(Hypermedia, HypermediaFactory, HypermediaFsm, HypermediaBehaviourTransitions, HypermediaValidator);
+
+behaviour_validator!(HypermediaValidator, ReactiveEntityInstance, DOCUMENT.as_ref(), RESPONSE_HEADERS.as_ref(), LINKS.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for HypermediaBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for HypermediaBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        update_links(&self.reactive_instance);
+        for property in [DOCUMENT, RESPONSE_HEADERS, BASE_URL] {
+            let reactive_instance = self.reactive_instance.clone();
+            self.property_observers
+                .observe_with_handle(property.as_ref(), move |_: &Value| update_links(&reactive_instance));
+        }
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            follow(&reactive_instance);
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for HypermediaBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for HypermediaBehaviourTransitions {}
+
+fn update_links(reactive_instance: &ReactiveEntityInstance) {
+    let document = reactive_instance.get(DOCUMENT).unwrap_or(Value::Null);
+    let response_headers = reactive_instance.as_object(RESPONSE_HEADERS).unwrap_or_default();
+    let base_url = reactive_instance.as_string(BASE_URL).and_then(|base_url| Url::parse(&base_url).ok());
+    let links = extract_links(&document, &response_headers, base_url.as_ref());
+    if reactive_instance.as_object(LINKS).as_ref() != Some(&links) {
+        reactive_instance.set(LINKS, json!(links));
+    }
+}
+
+/// Follows the first link of the relation. The response becomes the new document, so that the next trigger follows the
+/// relation of the response.
+fn follow(reactive_instance: &Arc<ReactiveEntityInstance>) {
+    let relation = reactive_instance
+        .as_string(RELATION)
+        .unwrap_or_else(|| String::from("next"))
+        .to_ascii_lowercase();
+    let links = reactive_instance.as_object(LINKS).unwrap_or_default();
+    let Some(link) = links.get(&relation).and_then(|links| links.get(0)) else {
+        reactive_instance.set(ERROR, json!(format!("There is no link with the relation {}", relation)));
+        return;
+    };
+    let mut href = link["href"].as_str().unwrap_or_default().to_string();
+    // Without variables the expressions of a URI template expand to nothing
+    if link["templated"] == json!(true) {
+        href = expand_without_variables(&href);
+        if let Some(base_url) = reactive_instance.as_string(BASE_URL).and_then(|base_url| Url::parse(&base_url).ok()) {
+            href = base_url.join(&href).map(|href| href.to_string()).unwrap_or(href);
+        }
+    }
+    let url_policy = UrlPolicy::from(reactive_instance.as_ref());
+    if let Err(e) = url_policy.check(&href) {
+        error!("Refused to follow {}: {}", relation, e);
+        reactive_instance.set(ERROR, json!(e));
+        return;
+    }
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let request = match set_request_headers(url_policy.agent().get(&href), &href, &request_headers) {
+        Ok(request) => request,
+        Err(e) => {
+            error!("Refused to follow {}: {}", relation, e);
+            reactive_instance.set(ERROR, json!(e));
+            return;
+        }
+    };
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    let response = match request.call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(status, response)) => {
+            error!("Failed to follow {} to {}: {}", relation, href, response.status_text());
+            reactive_instance.set(STATUS, json!(status));
+            reactive_instance.set(ERROR, json!(response.status_text()));
+            return;
+        }
+        Err(e) => {
+            error!("Failed to follow {} to {}: {}", relation, href, e);
+            reactive_instance.set(ERROR, json!(e.to_string()));
+            return;
+        }
+    };
+    let status = response.status();
+    let response_url = response.get_url().to_string();
+    let mut response_headers = Map::new();
+    for name in response.headers_names() {
+        response_headers.insert(name.to_ascii_lowercase(), json!(response.header(&name)));
+    }
+    let document = match response.into_string() {
+        Ok(body) => serde_json::from_str(&body).unwrap_or(Value::String(body)),
+        Err(e) => {
+            reactive_instance.set(STATUS, json!(status));
+            reactive_instance.set(ERROR, json!(e.to_string()));
+            return;
+        }
+    };
+    reactive_instance.set(STATUS, json!(status));
+    reactive_instance.set(ERROR, json!(""));
+    reactive_instance.set(BASE_URL, json!(response_url));
+    reactive_instance.set(RESPONSE_HEADERS, json!(response_headers));
+    reactive_instance.set(DOCUMENT, document);
+}
+
+/// Extracts the links of HAL documents, JSON:API documents and the Link header by relation.
+///
+/// Relative links are resolved against the base url.
+fn extract_links(document: &Value, response_headers: &Map<String, Value>, base_url: Option<&Url>) -> Map<String, Value> {
+    let mut links = Map::new();
+    let mut add_link = |relation: &str, href: &str, title: Option<&str>, media_type: Option<&str>, templated: bool| {
+        // Templates are resolved after the expansion, as the expressions would be encoded
+        let href = match base_url {
+            Some(base_url) if !templated => base_url.join(href).map(|href| href.to_string()).unwrap_or_else(|_| href.to_string()),
+            _ => href.to_string(),
+        };
+        let link = json!({ "href": href, "title": title, "type": media_type, "templated": templated });
+        if let Value::Array(relation_links) = links.entry(relation.to_ascii_lowercase()).or_insert_with(|| json!([])) {
+            relation_links.push(link);
+        }
+    };
+    // HAL
+    if let Some(hal_links) = document.get("_links").and_then(Value::as_object) {
+        for (relation, relation_links) in hal_links {
+            let relation_links = match relation_links {
+                Value::Array(relation_links) => relation_links.iter().collect(),
+                relation_link => vec![relation_link],
+            };
+            for link in relation_links {
+                if let Some(href) = link.get("href").and_then(Value::as_str) {
+                    let title = link.get("title").and_then(Value::as_str);
+                    let media_type = link.get("type").and_then(Value::as_str);
+                    let templated = link.get("templated").and_then(Value::as_bool).unwrap_or(false);
+                    add_link(relation, href, title, media_type, templated);
+                }
+            }
+        }
+    }
+    // JSON:API links of the document and of a single resource
+    let json_api_links = [document.get("links"), document.get("data").and_then(|data| data.get("links"))];
+    for json_api_links in json_api_links.into_iter().flatten().filter_map(Value::as_object) {
+        for (relation, link) in json_api_links {
+            match link {
+                Value::String(href) => add_link(relation, href, None, None, false),
+                Value::Object(link) => {
+                    if let Some(href) = link.get("href").and_then(Value::as_str) {
+                        let title = link.get("title").and_then(Value::as_str);
+                        let media_type = link.get("type").and_then(Value::as_str);
+                        add_link(relation, href, title, media_type, false);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    // Link header
+    let link_header = response_headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("link"))
+        .and_then(|(_, link_header)| link_header.as_str());
+    for link in link_header.map(parse_link_header).unwrap_or_default() {
+        let parameter = |name: &str| link.parameters.iter().find(|(parameter, _)| parameter == name).map(|(_, value)| value.as_str());
+        for relation in link.relations.iter() {
+            add_link(relation, &link.href, parameter("title"), parameter("type"), false);
+        }
+    }
+    links
+}
+
+/// Removes the expressions of a URI template.
+fn expand_without_variables(template: &str) -> String {
+    let mut expanded = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        rest = rest[start..].find('}').map(|end| &rest[start + end + 1..]).unwrap_or("");
+    }
+    expanded.push_str(rest);
+    expanded
+}
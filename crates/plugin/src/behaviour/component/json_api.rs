@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::model::*;
+use crate::model_http::JsonApiProperties::DATA;
+use crate::model_http::JsonApiProperties::DOCUMENT;
+use crate::model_http::JsonApiProperties::ERRORS;
+use crate::model_http::JsonApiProperties::INCLUDED;
+use crate::model_http::JsonApiProperties::MAX_DEPTH;
+use crate::model_http::JsonApiProperties::META;
+use crate::pausable::is_enabled;
+use crate::reactive::*;
+
+entity_behaviour!(JsonApi, JsonApiFactory, JsonApiFsm, JsonApiBehaviourTransitions, JsonApiValidator);
+
+behaviour_validator!(JsonApiValidator, ReactiveEntityInstance, DOCUMENT.as_ref(), DATA.as_ref(), INCLUDED.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for JsonApiBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for JsonApiBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        for property in [DOCUMENT, MAX_DEPTH] {
+            let reactive_instance = self.reactive_instance.clone();
+            self.property_observers.observe_with_handle(property.as_ref(), move |_: &Value| {
+                if is_enabled(&reactive_instance) {
+                    normalize(&reactive_instance);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for JsonApiBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for JsonApiBehaviourTransitions {}
+
+fn normalize(reactive_instance: &ReactiveEntityInstance) {
+    let document = reactive_instance.get(DOCUMENT).unwrap_or(Value::Null);
+    let max_depth = reactive_instance.as_u64(MAX_DEPTH).unwrap_or(2) as usize;
+    let included: Vec<&Value> = document
+        .get("included")
+        .and_then(Value::as_array)
+        .map(|included| included.iter().collect())
+        .unwrap_or_default();
+    // Primary resources can be referenced by the included resources as well
+    let primary: Vec<&Value> = match document.get("data") {
+        Some(Value::Array(data)) => data.iter().collect(),
+        Some(data @ Value::Object(_)) => vec![data],
+        _ => Vec::new(),
+    };
+    let resources: HashMap<(&str, &str), &Value> = primary
+        .iter()
+        .chain(included.iter())
+        .filter_map(|resource| Some((identifier(resource)?, *resource)))
+        .collect();
+    let data = match document.get("data") {
+        Some(Value::Array(data)) => json!(data
+            .iter()
+            .map(|resource| flatten_resource(resource, &resources, max_depth, &mut Vec::new()))
+            .collect::<Vec<Value>>()),
+        Some(data @ Value::Object(_)) => flatten_resource(data, &resources, max_depth, &mut Vec::new()),
+        _ => Value::Null,
+    };
+    let included: Vec<Value> = included
+        .into_iter()
+        .map(|resource| flatten_resource(resource, &resources, max_depth, &mut Vec::new()))
+        .collect();
+    reactive_instance.set(META, document.get("meta").cloned().unwrap_or_else(|| json!({})));
+    reactive_instance.set(ERRORS, document.get("errors").cloned().unwrap_or_else(|| json!([])));
+    reactive_instance.set(INCLUDED, json!(included));
+    reactive_instance.set(DATA, data);
+}
+
+/// Merges the id, the type and the attributes of the resource and replaces the relationships by the flattened related
+/// resources.
+///
+/// Related resources which are not included, are nested deeper than the maximum depth or would lead to a cycle remain
+/// resource identifiers.
+fn flatten_resource<'a>(resource: &'a Value, resources: &HashMap<(&'a str, &'a str), &'a Value>, depth: usize, path: &mut Vec<(&'a str, &'a str)>) -> Value {
+    let mut flat = Map::new();
+    flat.insert(String::from("id"), resource.get("id").cloned().unwrap_or(Value::Null));
+    flat.insert(String::from("type"), resource.get("type").cloned().unwrap_or(Value::Null));
+    if let Some(attributes) = resource.get("attributes").and_then(Value::as_object) {
+        for (name, value) in attributes {
+            flat.insert(name.clone(), value.clone());
+        }
+    }
+    let key = identifier(resource);
+    if let Some(key) = key {
+        path.push(key);
+    }
+    if let Some(relationships) = resource.get("relationships").and_then(Value::as_object) {
+        for (name, relationship) in relationships {
+            let mut resolve = |resource_identifier: &'a Value| -> Value {
+                let related = identifier(resource_identifier)
+                    .filter(|related| depth > 0 && !path.contains(related))
+                    .and_then(|related| resources.get(&related).copied());
+                match related {
+                    Some(related) => flatten_resource(related, resources, depth - 1, path),
+                    None => resource_identifier.clone(),
+                }
+            };
+            let value = match relationship.get("data") {
+                Some(Value::Array(identifiers)) => json!(identifiers.iter().map(&mut resolve).collect::<Vec<Value>>()),
+                Some(resource_identifier @ Value::Object(_)) => resolve(resource_identifier),
+                // Relationships without data only have links
+                _ => Value::Null,
+            };
+            flat.insert(name.clone(), value);
+        }
+    }
+    if key.is_some() {
+        path.pop();
+    }
+    Value::Object(flat)
+}
+
+fn identifier(resource: &Value) -> Option<(&str, &str)> {
+    Some((resource.get("type")?.as_str()?, resource.get("id")?.as_str()?))
+}
@@ -1,7 +1,14 @@
+use std::time::Instant;
+
+use chrono::Utc;
 use log::error;
 use serde_json::json;
 use serde_json::Value;
 
+use crate::audit::record_request;
+use crate::audit::AuditEntry;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
 use crate::model::*;
 use crate::model_http::ActionProperties::TRIGGER;
 use crate::model_http::JsonRpcProperties::ERROR;
@@ -10,7 +17,10 @@ use crate::model_http::JsonRpcProperties::METHOD;
 use crate::model_http::JsonRpcProperties::PARAMS;
 use crate::model_http::JsonRpcProperties::RESULT;
 use crate::model_http::JsonRpcProperties::URL;
+use crate::model_http::BEHAVIOUR_NAME_JSON_RPC;
+use crate::pausable::is_enabled;
 use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
 
 entity_behaviour!(JsonRpc, JsonRpcFactory, JsonRpcFsm, JsonRpcBehaviourTransitions, JsonRpcValidator);
 
@@ -31,7 +41,7 @@ impl BehaviourConnect<ReactiveEntityInstance> for JsonRpcBehaviourTransitions {
     fn connect(&self) -> Result<(), BehaviourConnectFailed> {
         let reactive_instance = self.reactive_instance.clone();
         self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
-            if !trigger.is_boolean() || !trigger.as_bool().unwrap_or(false) {
+            if !trigger.is_boolean() || !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
                 return;
             }
             let Some(method) = reactive_instance.as_string(METHOD) else {
@@ -59,9 +69,31 @@ impl BehaviourConnect<ReactiveEntityInstance> for JsonRpcBehaviourTransitions {
                 "id": 1 as u32
             });
 
-            let request = ureq::post(url.as_str()).set("content-type", "application/json");
+            let request = UrlPolicy::from(reactive_instance.as_ref())
+                .agent()
+                .post(url.as_str())
+                .set("content-type", "application/json");
 
-            let result = request.send_json(payload);
+            let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+            let started = Instant::now();
+            let result = request.send_json(payload.clone());
+            let (status, error) = match &result {
+                Ok(response) => (Some(response.status()), None),
+                Err(ureq::Error::Status(status, response)) => (Some(*status), Some(response.status_text().to_string())),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            record_request(AuditEntry {
+                id: reactive_instance.id,
+                behaviour: BEHAVIOUR_NAME_JSON_RPC.to_string(),
+                timestamp: Utc::now(),
+                method: String::from("POST"),
+                url: url.clone(),
+                request_headers: Default::default(),
+                payload,
+                status,
+                duration_ms: started.elapsed().as_millis() as u64,
+                error,
+            });
             match result {
                 Ok(response) => match response.into_json() {
                     Ok(response_payload) => {
@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use log::error;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use url::Url;
+
+use crate::concurrency::map_concurrently;
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::LinkCheckerProperties::BROKEN_LINKS;
+use crate::model_http::LinkCheckerProperties::MAX_CONCURRENCY;
+use crate::model_http::LinkCheckerProperties::PAGE_URL;
+use crate::model_http::LinkCheckerProperties::REPORT;
+use crate::model_http::LinkCheckerProperties::REQUEST_HEADERS;
+use crate::model_http::LinkCheckerProperties::TIMEOUT;
+use crate::model_http::LinkCheckerProperties::URLS;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+
+entity_behaviour!(LinkChecker, LinkCheckerFactory, LinkCheckerFsm, LinkCheckerBehaviourTransitions, LinkCheckerValidator);
+
+behaviour_validator!(LinkCheckerValidator, ReactiveEntityInstance, URLS.as_ref(), REPORT.as_ref(), BROKEN_LINKS.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for LinkCheckerBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for LinkCheckerBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            check_links(&reactive_instance);
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for LinkCheckerBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for LinkCheckerBehaviourTransitions {}
+
+/// Checks the given urls and the links of the page.
+fn check_links(reactive_instance: &ReactiveEntityInstance) {
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let timeout = Duration::from_millis(reactive_instance.as_u64(TIMEOUT).unwrap_or(10000));
+    let agent = UrlPolicy::from(reactive_instance).agent_builder().timeout(timeout).build();
+    let mut urls: Vec<String> = reactive_instance
+        .as_array(URLS)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|url| url.as_str().map(resolve_env))
+        .collect();
+    if let Some(page_url) = reactive_instance
+        .as_string(PAGE_URL)
+        .map(|page_url| resolve_env(&page_url))
+        .filter(|page_url| !page_url.is_empty())
+    {
+        match fetch_page(&agent, &page_url, &request_headers) {
+            Ok(page) => urls.extend(extract_links(&page, &page_url)),
+            Err(e) => error!("Failed to fetch {}: {}", page_url, e),
+        }
+    }
+    let mut checked_urls = HashSet::new();
+    urls.retain(|url| checked_urls.insert(url.clone()));
+    let max_concurrency = reactive_instance.as_u64(MAX_CONCURRENCY).unwrap_or(4) as usize;
+    let report = map_concurrently(&urls, max_concurrency, |_, url| check_link(&agent, url, &request_headers));
+    let broken_links: Vec<Value> = report.iter().filter(|link| link["ok"] == json!(false)).cloned().collect();
+    reactive_instance.set(BROKEN_LINKS, json!(broken_links));
+    reactive_instance.set(REPORT, json!(report));
+}
+
+fn fetch_page(agent: &ureq::Agent, url: &str, request_headers: &Map<String, Value>) -> Result<String, String> {
+    let request = set_request_headers(agent.get(url), url, request_headers)?;
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    request.call().map_err(|e| e.to_string())?.into_string().map_err(|e| e.to_string())
+}
+
+/// Checks the link with HEAD. Servers which don't support HEAD are asked with GET.
+fn check_link(agent: &ureq::Agent, url: &str, request_headers: &Map<String, Value>) -> Value {
+    let (head, get) = match (
+        set_request_headers(agent.head(url), url, request_headers),
+        set_request_headers(agent.get(url), url, request_headers),
+    ) {
+        (Ok(head), Ok(get)) => (head, get),
+        (Err(e), _) | (_, Err(e)) => return json!({ "url": url, "status": null, "ok": false, "error": e }),
+    };
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    let mut result = head.call();
+    if let Err(ureq::Error::Status(405 | 501, _)) = result {
+        result = get.call();
+    }
+    match result {
+        Ok(response) => json!({ "url": url, "status": response.status(), "ok": true }),
+        Err(ureq::Error::Status(status, response)) => {
+            json!({ "url": url, "status": status, "ok": false, "error": response.status_text() })
+        }
+        Err(e) => json!({ "url": url, "status": null, "ok": false, "error": e.to_string() }),
+    }
+}
+
+/// Returns the http and https links of the href attributes of the page, resolved against the url of the page.
+///
+/// Fragments are removed, so that links to different anchors of the same page are checked once.
+fn extract_links(page: &str, page_url: &str) -> Vec<String> {
+    let Ok(base_url) = Url::parse(page_url) else {
+        return Vec::new();
+    };
+    let lower_case_page = page.to_ascii_lowercase();
+    let mut links = Vec::new();
+    let mut position = 0;
+    while let Some(offset) = lower_case_page[position..].find("href") {
+        position += offset + "href".len();
+        let rest = page[position..].trim_start();
+        let Some(rest) = rest.strip_prefix('=') else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        let href = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => rest[1..].split(quote).next(),
+            _ => rest.split(|c: char| c.is_whitespace() || c == '>').next(),
+        };
+        let Some(mut link) = href.and_then(|href| base_url.join(href.trim()).ok()) else {
+            continue;
+        };
+        if !matches!(link.scheme(), "http" | "https") {
+            continue;
+        }
+        link.set_fragment(None);
+        let link = link.to_string();
+        if !links.contains(&link) {
+            links.push(link);
+        }
+    }
+    links
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::error;
+use serde_json::json;
+
+use crate::behaviour::component::http::send_request;
+use crate::model::*;
+use crate::model_http::LongPollProperties::CRON;
+use crate::model_http::LongPollProperties::IDLE_DELAY;
+use crate::model_http::LongPollProperties::TIMEOUT;
+use crate::model_http::ReconnectProperties::CONNECTED;
+use crate::model_http::BEHAVIOUR_NAME_LONG_POLL;
+use crate::pausable::is_enabled;
+use crate::reactive::*;
+use crate::reconnect::ReconnectPolicy;
+use crate::schedule::parse_schedule;
+use crate::schedule::until_next;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+use crate::worker::WorkerSignal;
+
+entity_behaviour!(LongPoll, LongPollFactory, LongPollFsm, LongPollBehaviourTransitions, LongPollValidator);
+
+behaviour_validator!(LongPollValidator, ReactiveEntityInstance, IDLE_DELAY.as_ref(), TIMEOUT.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for LongPollBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for LongPollBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        start_worker(self.reactive_instance.id, BEHAVIOUR_NAME_LONG_POLL, move |signal| poll(reactive_instance, signal));
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        stop_worker(self.reactive_instance.id, BEHAVIOUR_NAME_LONG_POLL);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for LongPollBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for LongPollBehaviourTransitions {}
+
+/// The interval in which a paused instance checks whether it has been enabled again.
+const PAUSED_DELAY: Duration = Duration::from_secs(1);
+
+fn poll(reactive_instance: Arc<ReactiveEntityInstance>, signal: WorkerSignal) {
+    if let Some(cron) = reactive_instance.as_string(CRON).filter(|cron| !cron.trim().is_empty()) {
+        poll_by_schedule(reactive_instance, signal, &cron);
+        return;
+    }
+    let mut failed_attempts = 0;
+    while !signal.is_stopped() {
+        if !is_enabled(&reactive_instance) {
+            if !signal.sleep(PAUSED_DELAY) {
+                break;
+            }
+            continue;
+        }
+        let timeout = Duration::from_millis(reactive_instance.as_u64(TIMEOUT).unwrap_or(60000));
+        let connected = send_request(&reactive_instance, Some(timeout));
+        if reactive_instance.as_bool(CONNECTED) != Some(connected) {
+            reactive_instance.set(CONNECTED, json!(connected));
+        }
+        let delay = if connected {
+            failed_attempts = 0;
+            Duration::from_millis(reactive_instance.as_u64(IDLE_DELAY).unwrap_or(0))
+        } else {
+            failed_attempts += 1;
+            let reconnect_policy = ReconnectPolicy::from(reactive_instance.as_ref());
+            if reconnect_policy.is_exhausted(failed_attempts) {
+                error!("Stopped long polling of {} after {} failed attempts", reactive_instance.id, failed_attempts);
+                break;
+            }
+            reconnect_policy.delay(failed_attempts)
+        };
+        if !signal.sleep(delay) {
+            break;
+        }
+    }
+}
+
+/// Sends the request at every occurrence of the cron expression instead of polling continuously.
+fn poll_by_schedule(reactive_instance: Arc<ReactiveEntityInstance>, signal: WorkerSignal, cron: &str) {
+    let schedule = match parse_schedule(cron) {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            error!("Stopped polling of {}: {}", reactive_instance.id, e);
+            return;
+        }
+    };
+    while let Some(delay) = until_next(&schedule) {
+        if !signal.sleep(delay) {
+            break;
+        }
+        // A paused instance skips the occurrence
+        if !is_enabled(&reactive_instance) {
+            continue;
+        }
+        let timeout = Duration::from_millis(reactive_instance.as_u64(TIMEOUT).unwrap_or(60000));
+        let connected = send_request(&reactive_instance, Some(timeout));
+        if reactive_instance.as_bool(CONNECTED) != Some(connected) {
+            reactive_instance.set(CONNECTED, json!(connected));
+        }
+    }
+}
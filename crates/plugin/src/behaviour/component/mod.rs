@@ -1,2 +1,35 @@
+pub mod access_log;
+pub mod batch;
+pub mod connectivity_check;
+pub mod cors_preflight;
+pub mod dns_cache;
+pub mod download;
+pub mod fan_out;
+pub mod graphql;
 pub mod http;
+pub mod http_response;
+pub mod http_route;
+pub mod hypermedia;
+pub mod json_api;
 pub mod json_rpc;
+pub mod link_checker;
+pub mod long_poll;
+pub mod odata;
+pub mod openapi_import;
+pub mod openapi_operation;
+pub mod postman_import;
+pub mod presigned_url;
+pub mod security_headers;
+pub mod signalr;
+pub mod sitemap;
+pub mod socket_io;
+pub mod sse;
+pub mod stomp;
+pub mod tls_certificate_monitor;
+pub mod tunnel;
+pub mod upload;
+pub mod upload_endpoint;
+pub mod webdav;
+pub mod webhook_relay;
+pub mod webhook_sender;
+pub mod websocket_bridge;
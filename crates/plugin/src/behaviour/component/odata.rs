@@ -0,0 +1,238 @@
+use log::error;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use url::Url;
+
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::ODataProperties::COUNT;
+use crate::model_http::ODataProperties::ENTITIES;
+use crate::model_http::ODataProperties::ENTITY_SET;
+use crate::model_http::ODataProperties::ERROR;
+use crate::model_http::ODataProperties::EXPAND;
+use crate::model_http::ODataProperties::FILTER;
+use crate::model_http::ODataProperties::FOLLOW_NEXT_LINKS;
+use crate::model_http::ODataProperties::MAX_PAGES;
+use crate::model_http::ODataProperties::ODATA_VERSION;
+use crate::model_http::ODataProperties::ORDER_BY;
+use crate::model_http::ODataProperties::QUERY_URL;
+use crate::model_http::ODataProperties::REQUEST_HEADERS;
+use crate::model_http::ODataProperties::SELECT;
+use crate::model_http::ODataProperties::SERVICE_URL;
+use crate::model_http::ODataProperties::SKIP;
+use crate::model_http::ODataProperties::TOP;
+use crate::model_http::ODataProperties::TOTAL_COUNT;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+
+/// The comparison operators of structured filters.
+const FILTER_OPERATORS: [&str; 6] = ["eq", "ne", "gt", "ge", "lt", "le"];
+
+entity_behaviour!(OData, ODataFactory, ODataFsm, ODataBehaviourTransitions, ODataValidator);
+
+behaviour_validator!(ODataValidator, ReactiveEntityInstance, SERVICE_URL.as_ref(), ENTITY_SET.as_ref(), ENTITIES.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for ODataBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for ODataBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            query(&reactive_instance);
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for ODataBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for ODataBehaviourTransitions {}
+
+/// Queries the entity set and collects the entities of all pages.
+fn query(reactive_instance: &ReactiveEntityInstance) {
+    let version_2 = reactive_instance.as_string(ODATA_VERSION).as_deref() == Some("2");
+    let query_url = match build_query_url(reactive_instance, version_2) {
+        Ok(query_url) => query_url,
+        Err(e) => {
+            error!("{}", e);
+            reactive_instance.set(ERROR, json!(e));
+            return;
+        }
+    };
+    reactive_instance.set(QUERY_URL, json!(query_url));
+    let url_policy = UrlPolicy::from(reactive_instance);
+    let agent = url_policy.agent();
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let follow_next_links = reactive_instance.as_bool(FOLLOW_NEXT_LINKS).unwrap_or(true);
+    let max_pages = reactive_instance.as_u64(MAX_PAGES).unwrap_or(100).max(1);
+    let mut entities = Vec::new();
+    let mut total_count = None;
+    let mut error = String::new();
+    let mut next_link = Some(query_url);
+    let mut pages = 0;
+    while let Some(url) = next_link.take() {
+        if pages >= max_pages {
+            error = format!("Stopped following the next links after {} pages", max_pages);
+            break;
+        }
+        pages += 1;
+        let page = match url_policy.check(&url).and_then(|_| fetch_page(&agent, &url, &request_headers)) {
+            Ok(page) => page,
+            Err(e) => {
+                error = e;
+                break;
+            }
+        };
+        let (page_entities, page_count, page_next_link) = parse_page(&page);
+        entities.extend(page_entities);
+        total_count = total_count.or(page_count);
+        // Next links may be relative to the url of the page
+        next_link = page_next_link
+            .filter(|_| follow_next_links)
+            .and_then(|page_next_link| Url::parse(&url).ok()?.join(&page_next_link).ok())
+            .map(|page_next_link| page_next_link.to_string());
+    }
+    if !error.is_empty() {
+        error!("Failed to query {}: {}", reactive_instance.as_string(ENTITY_SET).unwrap_or_default(), error);
+    }
+    reactive_instance.set(ERROR, json!(error));
+    reactive_instance.set(TOTAL_COUNT, json!(total_count.unwrap_or(-1)));
+    reactive_instance.set(ENTITIES, json!(entities));
+}
+
+/// Builds the url of the entity set with the system query options.
+fn build_query_url(reactive_instance: &ReactiveEntityInstance, version_2: bool) -> Result<String, String> {
+    let service_url = resolve_env(&reactive_instance.as_string(SERVICE_URL).unwrap_or_default());
+    let entity_set = reactive_instance.as_string(ENTITY_SET).unwrap_or_default();
+    if service_url.is_empty() || entity_set.is_empty() {
+        return Err(String::from("The service url and the entity set are required"));
+    }
+    let mut query_options = Vec::new();
+    let filter = match reactive_instance.get(FILTER).unwrap_or(Value::Null) {
+        Value::String(filter) => filter,
+        Value::Object(filter) => build_filter(&filter)?,
+        Value::Null => String::new(),
+        filter => return Err(format!("Invalid filter {}", filter)),
+    };
+    if !filter.is_empty() {
+        query_options.push(("$filter", filter));
+    }
+    for (name, property) in [("$select", SELECT), ("$expand", EXPAND), ("$orderby", ORDER_BY)] {
+        let values = reactive_instance.as_array(property).unwrap_or_default();
+        let items: Vec<&str> = values.iter().filter_map(Value::as_str).collect();
+        if !items.is_empty() {
+            query_options.push((name, items.join(",")));
+        }
+    }
+    let top = reactive_instance.as_u64(TOP).unwrap_or(0);
+    if top > 0 {
+        query_options.push(("$top", top.to_string()));
+    }
+    let skip = reactive_instance.as_u64(SKIP).unwrap_or(0);
+    if skip > 0 {
+        query_options.push(("$skip", skip.to_string()));
+    }
+    if reactive_instance.as_bool(COUNT).unwrap_or(false) {
+        if version_2 {
+            query_options.push(("$inlinecount", String::from("allpages")));
+        } else {
+            query_options.push(("$count", String::from("true")));
+        }
+    }
+    // Services of version 2 answer with Atom unless asked for JSON
+    if version_2 {
+        query_options.push(("$format", String::from("json")));
+    }
+    let query = query_options
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, encode_query_value(value)))
+        .collect::<Vec<String>>()
+        .join("&");
+    let mut query_url = format!("{}/{}", service_url.trim_end_matches('/'), entity_set.trim_start_matches('/'));
+    if !query.is_empty() {
+        query_url.push(if query_url.contains('?') { '&' } else { '?' });
+        query_url.push_str(&query);
+    }
+    Ok(query_url)
+}
+
+/// Builds a filter expression from an object of property names to values or to objects of operators to values.
+///
+/// The comparisons are combined with and.
+fn build_filter(filter: &Map<String, Value>) -> Result<String, String> {
+    let mut comparisons = Vec::new();
+    for (property, condition) in filter {
+        match condition {
+            Value::Object(operators) => {
+                for (operator, value) in operators {
+                    if !FILTER_OPERATORS.contains(&operator.as_str()) {
+                        return Err(format!("Unsupported filter operator {} of {}", operator, property));
+                    }
+                    comparisons.push(format!("{} {} {}", property, operator, to_literal(value)?));
+                }
+            }
+            value => comparisons.push(format!("{} eq {}", property, to_literal(value)?)),
+        }
+    }
+    Ok(comparisons.join(" and "))
+}
+
+/// Converts the value into an OData literal. Single quotes in strings are escaped by doubling them.
+fn to_literal(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(value) => Ok(format!("'{}'", value.replace('\'', "''"))),
+        Value::Number(value) => Ok(value.to_string()),
+        Value::Bool(value) => Ok(value.to_string()),
+        Value::Null => Ok(String::from("null")),
+        value => Err(format!("Unsupported filter value {}", value)),
+    }
+}
+
+/// Percent encodes the value of a query option.
+///
+/// Spaces are encoded as %20 instead of +, because not all services decode + in the query.
+fn encode_query_value(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'$' | b'\'' | b'(' | b')' | b',' | b'/' | b':' | b'*' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn fetch_page(agent: &ureq::Agent, url: &str, request_headers: &Map<String, Value>) -> Result<Value, String> {
+    let request = set_request_headers(agent.get(url), url, request_headers)?.set("Accept", "application/json");
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    match request.call() {
+        Ok(response) => response.into_json().map_err(|e| e.to_string()),
+        Err(ureq::Error::Status(status, response)) => Err(format!("{} {}", status, response.status_text())),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Returns the entities, the total count and the next link of a page in the JSON format of version 4 or 2.
+fn parse_page(page: &Value) -> (Vec<Value>, Option<i64>, Option<String>) {
+    // Version 2 wraps the collection into d
+    if let Some(d) = page.get("d") {
+        let entities = d.get("results").unwrap_or(d).as_array().cloned().unwrap_or_default();
+        let count = d.get("__count").and_then(|count| count.as_str()?.parse().ok());
+        let next_link = d.get("__next").and_then(Value::as_str).map(String::from);
+        return (entities, count, next_link);
+    }
+    let entities = page.get("value").and_then(Value::as_array).cloned().unwrap_or_default();
+    let count = page.get("@odata.count").and_then(Value::as_i64);
+    let next_link = page.get("@odata.nextLink").and_then(Value::as_str).map(String::from);
+    (entities, count, next_link)
+}
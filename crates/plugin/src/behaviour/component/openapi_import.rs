@@ -0,0 +1,139 @@
+use log::error;
+use serde_json::json;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::context::get_plugin_context;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::OpenApiImportProperties::ENTITY_TYPES;
+use crate::model_http::OpenApiImportProperties::ERROR;
+use crate::model_http::OpenApiImportProperties::NAMESPACE;
+use crate::model_http::OpenApiImportProperties::SPEC;
+use crate::model_http::OpenApiImportProperties::SPEC_URL;
+use crate::model_http::OpenApiImportProperties::STARTER_FLOW;
+use crate::openapi::parse_operations;
+use crate::openapi::Operation;
+use crate::pausable::is_enabled;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+
+entity_behaviour!(
+    OpenApiImport,
+    OpenApiImportFactory,
+    OpenApiImportFsm,
+    OpenApiImportBehaviourTransitions,
+    OpenApiImportValidator
+);
+
+behaviour_validator!(
+    OpenApiImportValidator,
+    ReactiveEntityInstance,
+    SPEC.as_ref(),
+    SPEC_URL.as_ref(),
+    NAMESPACE.as_ref(),
+    ENTITY_TYPES.as_ref(),
+    STARTER_FLOW.as_ref(),
+    ERROR.as_ref()
+);
+
+impl BehaviourInit<ReactiveEntityInstance> for OpenApiImportBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for OpenApiImportBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            match import(&reactive_instance) {
+                Ok(_) => reactive_instance.set(ERROR, json!("")),
+                Err(e) => {
+                    error!("Failed to import OpenAPI document: {}", e);
+                    reactive_instance.set(ERROR, json!(e));
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for OpenApiImportBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for OpenApiImportBehaviourTransitions {}
+
+fn import(reactive_instance: &ReactiveEntityInstance) -> Result<(), String> {
+    let spec = load_spec(reactive_instance)?;
+    let operations = parse_operations(&spec)?;
+    let context = get_plugin_context().ok_or_else(|| String::from("The plugin context is not available"))?;
+    let entity_type_manager = context.get_entity_type_manager();
+    let namespace = reactive_instance.as_string(NAMESPACE).unwrap_or_else(|| String::from("openapi"));
+    let mut entity_types = Vec::new();
+    for operation in operations.iter() {
+        let entity_type: EntityType = serde_json::from_value(operation.to_entity_type(&namespace)).map_err(|e| e.to_string())?;
+        if !entity_type_manager.has(&entity_type.ty) {
+            entity_type_manager
+                .create(
+                    &entity_type.ty,
+                    entity_type.description.as_str(),
+                    entity_type.components.clone(),
+                    entity_type.properties.clone(),
+                    entity_type.extensions.clone(),
+                )
+                .map_err(|e| format!("Failed to create entity type {}: {:?}", operation.type_name, e))?;
+        }
+        entity_types.push(json!(operation.type_name));
+    }
+    reactive_instance.set(ENTITY_TYPES, json!(entity_types));
+    reactive_instance.set(STARTER_FLOW, starter_flow(&spec, &operations));
+    Ok(())
+}
+
+fn load_spec(reactive_instance: &ReactiveEntityInstance) -> Result<Value, String> {
+    if let Some(spec) = reactive_instance.get(SPEC) {
+        if spec.as_object().map(|spec| !spec.is_empty()).unwrap_or(false) {
+            return Ok(spec);
+        }
+    }
+    let spec_url = reactive_instance.as_string(SPEC_URL).unwrap_or_default();
+    if spec_url.is_empty() {
+        return Err(String::from("Neither spec nor spec_url is set"));
+    }
+    let url_policy = UrlPolicy::from(reactive_instance);
+    url_policy.check(&spec_url)?;
+    url_policy
+        .agent()
+        .get(spec_url.as_str())
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_json()
+        .map_err(|e| e.to_string())
+}
+
+/// Returns a flow with one instance of each imported operation.
+fn starter_flow(spec: &Value, operations: &[Operation]) -> Value {
+    let flow_id = Uuid::new_v4();
+    let title = spec.pointer("/info/title").and_then(Value::as_str).unwrap_or("OpenAPI");
+    let mut entities = vec![json!({
+        "type": "generic_flow",
+        "id": flow_id,
+        "properties": {}
+    })];
+    for operation in operations.iter() {
+        entities.push(json!({
+            "type": operation.type_name,
+            "id": Uuid::new_v4(),
+            "properties": {
+                "method": operation.method,
+                "url": format!("{}{}", operation.server_url.trim_end_matches('/'), operation.path)
+            }
+        }));
+    }
+    json!({
+        "id": flow_id,
+        "type": "generic_flow",
+        "name": title,
+        "description": format!("Operations of {}", title),
+        "entities": entities,
+        "relations": []
+    })
+}
@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use log::warn;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use url::form_urlencoded;
+
+use crate::context::get_plugin_context;
+use crate::model::*;
+use crate::model_http::HttpProperties::METHOD;
+use crate::model_http::HttpProperties::PAYLOAD;
+use crate::model_http::HttpProperties::REQUEST_HEADERS;
+use crate::model_http::HttpProperties::RESULT;
+use crate::model_http::HttpProperties::URL;
+use crate::model_http::EXTENSION_OPENAPI_OPERATION;
+use crate::model_http::NAMESPACE_HTTP;
+use crate::openapi::Operation;
+use crate::reactive::*;
+
+entity_behaviour!(
+    OpenApiOperation,
+    OpenApiOperationFactory,
+    OpenApiOperationFsm,
+    OpenApiOperationBehaviourTransitions,
+    OpenApiOperationValidator
+);
+
+behaviour_validator!(OpenApiOperationValidator, ReactiveEntityInstance, METHOD.as_ref(), URL.as_ref(), REQUEST_HEADERS.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for OpenApiOperationBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for OpenApiOperationBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let Some(operation) = get_operation(&self.reactive_instance) else {
+            warn!("The entity type of {} doesn't describe an OpenAPI operation", self.reactive_instance.id);
+            return Ok(());
+        };
+        let operation = Arc::new(operation);
+        update_request(&self.reactive_instance, &operation);
+        for parameter in operation.parameters.iter() {
+            let reactive_instance = self.reactive_instance.clone();
+            let operation = operation.clone();
+            self.property_observers
+                .observe_with_handle(parameter.property_name.as_str(), move |_: &Value| update_request(&reactive_instance, &operation));
+        }
+        if !operation.body_properties.is_empty() {
+            update_payload(&self.reactive_instance, &operation);
+        }
+        for body_property in operation.body_properties.iter() {
+            let reactive_instance = self.reactive_instance.clone();
+            let operation = operation.clone();
+            self.property_observers
+                .observe_with_handle(body_property.property_name.as_str(), move |_: &Value| update_payload(&reactive_instance, &operation));
+        }
+        if !operation.response_properties.is_empty() {
+            let reactive_instance = self.reactive_instance.clone();
+            let operation = operation.clone();
+            self.property_observers.observe_with_handle(RESULT.as_ref(), move |result: &Value| {
+                for response_property in operation.response_properties.iter() {
+                    let value = result.get(&response_property.name).cloned().unwrap_or(Value::Null);
+                    reactive_instance.set(response_property.property_name.as_str(), value);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for OpenApiOperationBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for OpenApiOperationBehaviourTransitions {}
+
+/// Reads the operation from the extension of the entity type which has been generated by the OpenAPI import.
+fn get_operation(reactive_instance: &ReactiveEntityInstance) -> Option<Operation> {
+    let entity_type = get_plugin_context()?.get_entity_type_manager().get(&reactive_instance.ty)?;
+    let extensions = serde_json::to_value(&entity_type.extensions).ok()?;
+    let extension = extensions
+        .as_array()?
+        .iter()
+        .find(|extension| extension["namespace"] == NAMESPACE_HTTP && extension["type_name"] == EXTENSION_OPENAPI_OPERATION)?;
+    serde_json::from_value(extension["extension"].clone()).ok()
+}
+
+/// Builds method, url and request headers from the parameter properties.
+fn update_request(reactive_instance: &ReactiveEntityInstance, operation: &Operation) {
+    let mut path = operation.path.clone();
+    let mut query = form_urlencoded::Serializer::new(String::new());
+    let mut request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    for parameter in operation.parameters.iter() {
+        let value = match reactive_instance.get(parameter.property_name.as_str()) {
+            Some(Value::Null) | None => continue,
+            Some(Value::String(value)) => value,
+            Some(value) => value.to_string(),
+        };
+        if value.is_empty() {
+            continue;
+        }
+        match parameter.location.as_str() {
+            "path" => {
+                let value: String = form_urlencoded::byte_serialize(value.as_bytes()).collect();
+                path = path.replace(&format!("{{{}}}", parameter.name), &value.replace('+', "%20"));
+            }
+            "query" => {
+                query.append_pair(&parameter.name, &value);
+            }
+            "header" => {
+                request_headers.insert(parameter.name.clone(), json!(value));
+            }
+            _ => {}
+        }
+    }
+    let mut url = format!("{}{}", operation.server_url.trim_end_matches('/'), path);
+    let query = query.finish();
+    if !query.is_empty() {
+        url.push('?');
+        url.push_str(&query);
+    }
+    reactive_instance.set(METHOD, json!(operation.method));
+    reactive_instance.set(URL, json!(url));
+    reactive_instance.set(REQUEST_HEADERS, json!(request_headers));
+}
+
+/// Builds the payload from the body properties. Unset properties and empty strings are omitted like empty parameters.
+fn update_payload(reactive_instance: &ReactiveEntityInstance, operation: &Operation) {
+    let mut payload = Map::new();
+    for body_property in operation.body_properties.iter() {
+        match reactive_instance.get(body_property.property_name.as_str()) {
+            Some(Value::Null) | None => {}
+            Some(Value::String(value)) if value.is_empty() => {}
+            Some(value) => {
+                payload.insert(body_property.name.clone(), value);
+            }
+        }
+    }
+    reactive_instance.set(PAYLOAD, json!(payload));
+}
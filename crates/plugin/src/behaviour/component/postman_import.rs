@@ -0,0 +1,114 @@
+use log::error;
+use serde_json::json;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::context::get_plugin_context;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::PostmanImportProperties::COLLECTION;
+use crate::model_http::PostmanImportProperties::COLLECTION_URL;
+use crate::model_http::PostmanImportProperties::ENTITY_INSTANCES;
+use crate::model_http::PostmanImportProperties::ERROR;
+use crate::model_http::ENTITY_TYPE_NAME_HTTP;
+use crate::model_http::NAMESPACE_HTTP;
+use crate::pausable::is_enabled;
+use crate::postman::parse_collection;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+
+entity_behaviour!(
+    PostmanImport,
+    PostmanImportFactory,
+    PostmanImportFsm,
+    PostmanImportBehaviourTransitions,
+    PostmanImportValidator
+);
+
+behaviour_validator!(
+    PostmanImportValidator,
+    ReactiveEntityInstance,
+    COLLECTION.as_ref(),
+    COLLECTION_URL.as_ref(),
+    ENTITY_INSTANCES.as_ref(),
+    ERROR.as_ref()
+);
+
+impl BehaviourInit<ReactiveEntityInstance> for PostmanImportBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for PostmanImportBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            match import(&reactive_instance) {
+                Ok(_) => reactive_instance.set(ERROR, json!("")),
+                Err(e) => {
+                    error!("Failed to import Postman collection: {}", e);
+                    reactive_instance.set(ERROR, json!(e));
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for PostmanImportBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for PostmanImportBehaviourTransitions {}
+
+fn import(reactive_instance: &ReactiveEntityInstance) -> Result<(), String> {
+    let collection = load_collection(reactive_instance)?;
+    let requests = parse_collection(&collection)?;
+    let context = get_plugin_context().ok_or_else(|| String::from("The plugin context is not available"))?;
+    let entity_instance_manager = context.get_entity_instance_manager();
+    let mut entity_instances = Vec::new();
+    for request in requests {
+        let entity_instance: EntityInstance = serde_json::from_value(json!({
+            "namespace": NAMESPACE_HTTP,
+            "type_name": ENTITY_TYPE_NAME_HTTP,
+            "id": Uuid::new_v4(),
+            "description": request.name,
+            "properties": {
+                "method": request.method,
+                "url": request.url,
+                "request_headers": request.request_headers,
+                "payload": request.payload,
+                "response_headers": {},
+                "result": {},
+                "status": 200,
+                "trigger": false
+            },
+            "extensions": []
+        }))
+        .map_err(|e| e.to_string())?;
+        let entity_instance = entity_instance_manager
+            .create(entity_instance)
+            .map_err(|e| format!("Failed to create http entity instance for request {}: {:?}", request.name, e))?;
+        entity_instances.push(json!(entity_instance.id));
+    }
+    reactive_instance.set(ENTITY_INSTANCES, json!(entity_instances));
+    Ok(())
+}
+
+fn load_collection(reactive_instance: &ReactiveEntityInstance) -> Result<Value, String> {
+    if let Some(collection) = reactive_instance.get(COLLECTION) {
+        if collection.as_object().map(|collection| !collection.is_empty()).unwrap_or(false) {
+            return Ok(collection);
+        }
+    }
+    let collection_url = reactive_instance.as_string(COLLECTION_URL).unwrap_or_default();
+    if collection_url.is_empty() {
+        return Err(String::from("Neither collection nor collection_url is set"));
+    }
+    let url_policy = UrlPolicy::from(reactive_instance);
+    url_policy.check(&collection_url)?;
+    url_policy
+        .agent()
+        .get(collection_url.as_str())
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_json()
+        .map_err(|e| e.to_string())
+}
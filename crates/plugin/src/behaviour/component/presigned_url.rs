@@ -0,0 +1,139 @@
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+use log::error;
+use serde_json::json;
+use serde_json::Value;
+use sha2::Digest;
+use sha2::Sha256;
+use url::Url;
+
+use crate::hashing::hmac_sha256;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::PresignedUrlProperties::ACCESS_KEY_ID;
+use crate::model_http::PresignedUrlProperties::BUCKET;
+use crate::model_http::PresignedUrlProperties::ENDPOINT;
+use crate::model_http::PresignedUrlProperties::ERROR;
+use crate::model_http::PresignedUrlProperties::EXPIRES;
+use crate::model_http::PresignedUrlProperties::EXPIRES_AT;
+use crate::model_http::PresignedUrlProperties::METHOD;
+use crate::model_http::PresignedUrlProperties::OBJECT_KEY;
+use crate::model_http::PresignedUrlProperties::PRESIGNED_URL;
+use crate::model_http::PresignedUrlProperties::REGION;
+use crate::model_http::PresignedUrlProperties::SECRET_ACCESS_KEY;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::secrets::resolve_secrets;
+
+/// The maximum validity of a presigned url accepted by S3.
+const MAX_EXPIRES: u64 = 7 * 24 * 3600;
+
+entity_behaviour!(PresignedUrl, PresignedUrlFactory, PresignedUrlFsm, PresignedUrlBehaviourTransitions, PresignedUrlValidator);
+
+behaviour_validator!(
+    PresignedUrlValidator,
+    ReactiveEntityInstance,
+    ENDPOINT.as_ref(),
+    OBJECT_KEY.as_ref(),
+    ACCESS_KEY_ID.as_ref(),
+    SECRET_ACCESS_KEY.as_ref(),
+    PRESIGNED_URL.as_ref()
+);
+
+impl BehaviourInit<ReactiveEntityInstance> for PresignedUrlBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for PresignedUrlBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            match presign(&reactive_instance, Utc::now()) {
+                Ok((presigned_url, expires_at)) => {
+                    reactive_instance.set(ERROR, json!(""));
+                    reactive_instance.set(EXPIRES_AT, json!(expires_at.to_rfc3339()));
+                    reactive_instance.set(PRESIGNED_URL, json!(presigned_url));
+                }
+                Err(e) => {
+                    error!("Failed to presign url: {}", e);
+                    reactive_instance.set(ERROR, json!(e));
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for PresignedUrlBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for PresignedUrlBehaviourTransitions {}
+
+/// Signs the url with AWS signature version 4 in the query string.
+///
+/// The bucket is addressed path-style. If the bucket is empty, the endpoint is expected to address the bucket.
+fn presign(reactive_instance: &ReactiveEntityInstance, now: DateTime<Utc>) -> Result<(String, DateTime<Utc>), String> {
+    let string = |property, default: &str| resolve_env(&reactive_instance.as_string(property).unwrap_or_else(|| default.to_string()));
+    let method = string(METHOD, "GET").to_uppercase();
+    let endpoint = Url::parse(&string(ENDPOINT, "")).map_err(|e| format!("Invalid endpoint: {}", e))?;
+    let bucket = string(BUCKET, "");
+    let object_key = string(OBJECT_KEY, "");
+    let region = string(REGION, "us-east-1");
+    let access_key_id = resolve_secrets(&string(ACCESS_KEY_ID, ""));
+    let secret_access_key = resolve_secrets(&string(SECRET_ACCESS_KEY, ""));
+    if access_key_id.is_empty() || secret_access_key.is_empty() {
+        return Err(String::from("The access key id and the secret access key are required"));
+    }
+    let expires = reactive_instance.as_u64(EXPIRES).unwrap_or(3600).clamp(1, MAX_EXPIRES);
+    let host = match endpoint.port() {
+        Some(port) => format!("{}:{}", endpoint.host_str().unwrap_or_default(), port),
+        None => endpoint.host_str().unwrap_or_default().to_string(),
+    };
+    let path = [endpoint.path().trim_end_matches('/'), bucket.as_str(), object_key.as_str()]
+        .iter()
+        .flat_map(|part| part.split('/'))
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| uri_encode(segment))
+        .collect::<Vec<String>>()
+        .join("/");
+    let canonical_uri = format!("/{}", path);
+    let date_time = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let credential_scope = format!("{}/{}/s3/aws4_request", date, region);
+    // The parameters are sorted by name
+    let query = [
+        ("X-Amz-Algorithm", String::from("AWS4-HMAC-SHA256")),
+        ("X-Amz-Credential", format!("{}/{}", access_key_id, credential_scope)),
+        ("X-Amz-Date", date_time.clone()),
+        ("X-Amz-Expires", expires.to_string()),
+        ("X-Amz-SignedHeaders", String::from("host")),
+    ]
+    .iter()
+    .map(|(name, value)| format!("{}={}", name, uri_encode(value)))
+    .collect::<Vec<String>>()
+    .join("&");
+    let canonical_request = format!("{}\n{}\n{}\nhost:{}\n\nhost\nUNSIGNED-PAYLOAD", method, canonical_uri, query, host);
+    let string_to_sign = format!("AWS4-HMAC-SHA256\n{}\n{}\n{:x}", date_time, credential_scope, Sha256::digest(canonical_request.as_bytes()));
+    let date_key = hmac_sha256(format!("AWS4{}", secret_access_key).as_bytes(), date.as_bytes());
+    let region_key = hmac_sha256(&date_key, region.as_bytes());
+    let service_key = hmac_sha256(&region_key, b"s3");
+    let signing_key = hmac_sha256(&service_key, b"aws4_request");
+    let signature: String = hmac_sha256(&signing_key, string_to_sign.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    let presigned_url = format!("{}://{}{}?{}&X-Amz-Signature={}", endpoint.scheme(), host, canonical_uri, query, signature);
+    Ok((presigned_url, now + Duration::seconds(expires as i64)))
+}
+
+/// Percent-encodes everything but the unreserved characters, as required by the canonical request.
+fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
@@ -0,0 +1,288 @@
+use std::time::Duration;
+
+use log::error;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::SecurityHeadersProperties::ERROR;
+use crate::model_http::SecurityHeadersProperties::GRADE;
+use crate::model_http::SecurityHeadersProperties::REPORT;
+use crate::model_http::SecurityHeadersProperties::REQUEST_HEADERS;
+use crate::model_http::SecurityHeadersProperties::SCORE;
+use crate::model_http::SecurityHeadersProperties::TIMEOUT;
+use crate::model_http::SecurityHeadersProperties::URL;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+
+/// HSTS max-age below 180 days is considered weak.
+const MIN_HSTS_MAX_AGE: u64 = 15552000;
+
+entity_behaviour!(
+    SecurityHeaders,
+    SecurityHeadersFactory,
+    SecurityHeadersFsm,
+    SecurityHeadersBehaviourTransitions,
+    SecurityHeadersValidator
+);
+
+behaviour_validator!(
+    SecurityHeadersValidator,
+    ReactiveEntityInstance,
+    URL.as_ref(),
+    REPORT.as_ref(),
+    SCORE.as_ref(),
+    GRADE.as_ref()
+);
+
+impl BehaviourInit<ReactiveEntityInstance> for SecurityHeadersBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for SecurityHeadersBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            analyze(&reactive_instance);
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for SecurityHeadersBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for SecurityHeadersBehaviourTransitions {}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    Weak,
+    Missing,
+}
+
+impl Status {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Weak => "weak",
+            Status::Missing => "missing",
+        }
+    }
+}
+
+/// The result of the evaluation of a single header.
+struct Finding {
+    header: &'static str,
+    value: Option<String>,
+    status: Status,
+    message: String,
+    /// The weight of the header in the score.
+    weight: u64,
+}
+
+impl Finding {
+    fn points(&self) -> u64 {
+        match self.status {
+            Status::Ok => self.weight,
+            Status::Weak => self.weight / 2,
+            Status::Missing => 0,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "header": self.header,
+            "value": self.value,
+            "status": self.status.as_str(),
+            "message": self.message,
+        })
+    }
+}
+
+/// Requests the url and evaluates the security headers of the response.
+///
+/// Error responses are evaluated as well, because their headers are what the client gets.
+fn analyze(reactive_instance: &ReactiveEntityInstance) {
+    let Some(url) = reactive_instance.as_string(URL).map(|url| resolve_env(&url)).filter(|url| !url.is_empty()) else {
+        return;
+    };
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let timeout = Duration::from_millis(reactive_instance.as_u64(TIMEOUT).unwrap_or(10000));
+    let agent = UrlPolicy::from(reactive_instance).agent_builder().timeout(timeout).build();
+    let request = match set_request_headers(agent.get(&url), &url, &request_headers) {
+        Ok(request) => request,
+        Err(e) => {
+            error!("Failed to analyze the security headers of {}: {}", url, e);
+            reactive_instance.set(ERROR, json!(e));
+            return;
+        }
+    };
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    let response = match request.call() {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+        Err(e) => {
+            error!("Failed to analyze the security headers of {}: {}", url, e);
+            reactive_instance.set(ERROR, json!(e.to_string()));
+            return;
+        }
+    };
+    // The response url is the url after redirects
+    let https = response.get_url().starts_with("https://");
+    let header = |name: &str| response.header(name).map(String::from);
+    let findings = evaluate(&header, https);
+    let max_points: u64 = findings.iter().map(|finding| finding.weight).sum();
+    let points: u64 = findings.iter().map(Finding::points).sum();
+    let score = if max_points > 0 { points * 100 / max_points } else { 0 };
+    let mut report: Vec<Value> = findings.iter().map(Finding::to_json).collect();
+    report.extend(disclosures(&header));
+    reactive_instance.set(ERROR, json!(""));
+    reactive_instance.set(REPORT, json!(report));
+    reactive_instance.set(SCORE, json!(score));
+    reactive_instance.set(GRADE, json!(grade(score)));
+}
+
+fn evaluate(header: &dyn Fn(&str) -> Option<String>, https: bool) -> Vec<Finding> {
+    let csp = header("content-security-policy");
+    let mut findings = Vec::new();
+    // Browsers ignore HSTS on plain http responses
+    if https {
+        findings.push(evaluate_hsts(header("strict-transport-security")));
+    }
+    findings.push(evaluate_csp(csp.clone()));
+    findings.push(evaluate_frame_options(header("x-frame-options"), csp.as_deref()));
+    let content_type_options = header("x-content-type-options");
+    findings.push(match content_type_options.as_deref().map(str::trim) {
+        Some(value) if value.eq_ignore_ascii_case("nosniff") => {
+            finding("x-content-type-options", content_type_options, Status::Ok, "MIME sniffing is disabled", 15)
+        }
+        Some(_) => finding("x-content-type-options", content_type_options, Status::Weak, "The only valid value is nosniff", 15),
+        None => finding("x-content-type-options", None, Status::Missing, "MIME sniffing is not disabled", 15),
+    });
+    findings.push(evaluate_referrer_policy(header("referrer-policy")));
+    let permissions_policy = header("permissions-policy");
+    findings.push(match permissions_policy {
+        Some(_) => finding("permissions-policy", permissions_policy, Status::Ok, "Browser features are restricted", 10),
+        None => finding("permissions-policy", None, Status::Missing, "Browser features are not restricted", 10),
+    });
+    findings
+}
+
+fn evaluate_hsts(value: Option<String>) -> Finding {
+    let Some(hsts) = value.clone() else {
+        return finding("strict-transport-security", None, Status::Missing, "Clients are not forced to use HTTPS", 25);
+    };
+    let max_age = hsts.split(';').find_map(|directive| {
+        let (name, value) = directive.split_once('=')?;
+        if name.trim().eq_ignore_ascii_case("max-age") {
+            value.trim().trim_matches('"').parse::<u64>().ok()
+        } else {
+            None
+        }
+    });
+    match max_age {
+        Some(max_age) if max_age >= MIN_HSTS_MAX_AGE => finding("strict-transport-security", value, Status::Ok, "HTTPS is enforced", 25),
+        Some(max_age) => finding(
+            "strict-transport-security",
+            value,
+            Status::Weak,
+            &format!("max-age of {} seconds is shorter than 180 days", max_age),
+            25,
+        ),
+        None => finding("strict-transport-security", value, Status::Weak, "max-age is missing", 25),
+    }
+}
+
+fn evaluate_csp(value: Option<String>) -> Finding {
+    let Some(csp) = value.clone() else {
+        return finding("content-security-policy", None, Status::Missing, "There is no content security policy", 25);
+    };
+    let csp = csp.to_ascii_lowercase();
+    let unsafe_sources: Vec<&str> = ["'unsafe-inline'", "'unsafe-eval'"].into_iter().filter(|source| csp.contains(source)).collect();
+    if !unsafe_sources.is_empty() {
+        return finding(
+            "content-security-policy",
+            value,
+            Status::Weak,
+            &format!("The policy allows {}", unsafe_sources.join(" and ")),
+            25,
+        );
+    }
+    if !csp.contains("default-src") && !csp.contains("script-src") {
+        return finding("content-security-policy", value, Status::Weak, "The policy doesn't restrict scripts", 25);
+    }
+    finding("content-security-policy", value, Status::Ok, "Scripts are restricted", 25)
+}
+
+/// The frame-ancestors directive of the content security policy supersedes X-Frame-Options.
+fn evaluate_frame_options(value: Option<String>, csp: Option<&str>) -> Finding {
+    if csp.map(|csp| csp.to_ascii_lowercase().contains("frame-ancestors")).unwrap_or(false) {
+        return finding("x-frame-options", value, Status::Ok, "Framing is restricted by frame-ancestors", 15);
+    }
+    match value.as_deref().map(|value| value.trim().to_ascii_uppercase()) {
+        Some(frame_options) if frame_options == "DENY" || frame_options == "SAMEORIGIN" => {
+            finding("x-frame-options", value, Status::Ok, "Framing is restricted", 15)
+        }
+        Some(_) => finding("x-frame-options", value, Status::Weak, "Only DENY and SAMEORIGIN are supported by browsers", 15),
+        None => finding("x-frame-options", None, Status::Missing, "The site can be framed by any origin", 15),
+    }
+}
+
+fn evaluate_referrer_policy(value: Option<String>) -> Finding {
+    // The last valid policy of a comma separated list wins
+    let policy = value
+        .as_deref()
+        .and_then(|value| value.rsplit(',').next())
+        .map(|policy| policy.trim().to_ascii_lowercase());
+    match policy.as_deref() {
+        Some("unsafe-url" | "no-referrer-when-downgrade") => finding("referrer-policy", value, Status::Weak, "The full url is leaked to other origins", 10),
+        Some(_) => finding("referrer-policy", value, Status::Ok, "The referrer is restricted", 10),
+        None => finding("referrer-policy", None, Status::Missing, "The browser default referrer policy applies", 10),
+    }
+}
+
+/// Headers which disclose the software of the server don't count into the score.
+fn disclosures(header: &dyn Fn(&str) -> Option<String>) -> Vec<Value> {
+    ["server", "x-powered-by", "x-aspnet-version"]
+        .into_iter()
+        .filter_map(|name| {
+            let value = header(name)?;
+            // A version number reveals more than the product name
+            if name == "server" && !value.chars().any(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            Some(json!({
+                "header": name,
+                "value": value,
+                "status": "disclosure",
+                "message": "The header discloses the software of the server",
+            }))
+        })
+        .collect()
+}
+
+fn finding(header: &'static str, value: Option<String>, status: Status, message: &str, weight: u64) -> Finding {
+    Finding {
+        header,
+        value,
+        status,
+        message: message.to_string(),
+        weight,
+    }
+}
+
+fn grade(score: u64) -> &'static str {
+    match score {
+        90.. => "A",
+        75..=89 => "B",
+        60..=74 => "C",
+        45..=59 => "D",
+        30..=44 => "E",
+        _ => "F",
+    }
+}
@@ -0,0 +1,254 @@
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use log::warn;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use tungstenite::Message;
+use url::Url;
+
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::ReconnectProperties::CONNECTED;
+use crate::model_http::SignalrProperties::ACCESS_TOKEN;
+use crate::model_http::SignalrProperties::ARGUMENTS;
+use crate::model_http::SignalrProperties::CONNECTION_ID;
+use crate::model_http::SignalrProperties::ERROR;
+use crate::model_http::SignalrProperties::INVOKE_ARGUMENTS;
+use crate::model_http::SignalrProperties::INVOKE_TARGET;
+use crate::model_http::SignalrProperties::REQUEST_HEADERS;
+use crate::model_http::SignalrProperties::RESULT;
+use crate::model_http::SignalrProperties::TARGET;
+use crate::model_http::SignalrProperties::URL;
+use crate::model_http::BEHAVIOUR_NAME_SIGNALR;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::reconnect::keep_connected;
+use crate::secrets::resolve_secrets_for_url;
+use crate::ssrf::UrlPolicy;
+use crate::websocket::clear_queued_websocket_messages;
+use crate::websocket::connect_websocket;
+use crate::websocket::queue_websocket_message;
+use crate::websocket::read_websocket_message;
+use crate::websocket::send_queued_websocket_messages;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+use crate::worker::WorkerSignal;
+
+/// Terminates every message of the JSON hub protocol.
+const RECORD_SEPARATOR: char = '\u{1e}';
+
+/// The interval of the pings of the client, which is the default of the SignalR clients.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// The time after which the connection is considered lost if the server sent nothing.
+const SERVER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The maximum number of redirects of the negotiation, for example to the Azure SignalR Service.
+const MAX_NEGOTIATE_REDIRECTS: usize = 5;
+
+/// The invocation ids of the invoked hub methods.
+static INVOCATION_IDS: AtomicU64 = AtomicU64::new(0);
+
+entity_behaviour!(Signalr, SignalrFactory, SignalrFsm, SignalrBehaviourTransitions, SignalrValidator);
+
+behaviour_validator!(SignalrValidator, ReactiveEntityInstance, URL.as_ref(), TARGET.as_ref(), ARGUMENTS.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for SignalrBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for SignalrBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            invoke(&reactive_instance);
+        });
+        let reactive_instance = self.reactive_instance.clone();
+        start_worker(self.reactive_instance.id, BEHAVIOUR_NAME_SIGNALR, move |signal| {
+            keep_connected(&reactive_instance, &signal, "SignalR", ERROR.as_ref(), session)
+        });
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        stop_worker(self.reactive_instance.id, BEHAVIOUR_NAME_SIGNALR);
+        clear_queued_websocket_messages(self.reactive_instance.id, BEHAVIOUR_NAME_SIGNALR);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for SignalrBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for SignalrBehaviourTransitions {}
+
+fn to_message(message: Value) -> Message {
+    Message::Text(format!("{}{}", message, RECORD_SEPARATOR))
+}
+
+/// Queues the invocation of the hub method. The result is output as result once the invocation has been completed.
+fn invoke(reactive_instance: &ReactiveEntityInstance) {
+    let target = reactive_instance.as_string(INVOKE_TARGET).unwrap_or_default();
+    if target.is_empty() {
+        return;
+    }
+    let arguments = reactive_instance.as_array(INVOKE_ARGUMENTS).unwrap_or_default();
+    let invocation = json!({
+        "type": 1,
+        "invocationId": INVOCATION_IDS.fetch_add(1, Ordering::Relaxed).to_string(),
+        "target": target,
+        "arguments": arguments,
+    });
+    queue_websocket_message(reactive_instance.id, BEHAVIOUR_NAME_SIGNALR, to_message(invocation));
+}
+
+/// The result of the negotiation.
+struct Negotiation {
+    websocket_url: String,
+    connection_id: String,
+    access_token: String,
+}
+
+/// Negotiates the connection with the hub and follows the redirects to other hubs.
+fn negotiate(reactive_instance: &ReactiveEntityInstance, request_headers: &Map<String, Value>) -> Result<Negotiation, String> {
+    let mut url = resolve_env(&reactive_instance.as_string(URL).unwrap_or_default());
+    let mut access_token = resolve_secrets_for_url(&resolve_env(&reactive_instance.as_string(ACCESS_TOKEN).unwrap_or_default()), &url)?;
+    let agent = UrlPolicy::from(reactive_instance).agent();
+    for _ in 0..=MAX_NEGOTIATE_REDIRECTS {
+        let mut hub_url = Url::parse(&url).map_err(|e| format!("Invalid url {}: {}", url, e))?;
+        let mut negotiate_url = hub_url.clone();
+        negotiate_url.set_path(&format!("{}/negotiate", hub_url.path().trim_end_matches('/')));
+        negotiate_url.query_pairs_mut().append_pair("negotiateVersion", "1");
+        let mut request = set_request_headers(agent.post(negotiate_url.as_str()), negotiate_url.as_str(), request_headers)?;
+        if !access_token.is_empty() {
+            request = request.set("Authorization", &format!("Bearer {}", access_token));
+        }
+        let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+        let response = match request.send_string("") {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => return Err(format!("Negotiation failed: {} {}", status, response.status_text())),
+            Err(e) => return Err(format!("Negotiation failed: {}", e)),
+        };
+        let negotiation: Value = response.into_json().map_err(|e| format!("Invalid negotiation response: {}", e))?;
+        if let Some(error) = negotiation["error"].as_str() {
+            return Err(format!("Negotiation failed: {}", error));
+        }
+        // The client is redirected to another hub, which issued the access token
+        if let Some(redirect_url) = negotiation["url"].as_str() {
+            url = redirect_url.to_string();
+            access_token = negotiation["accessToken"].as_str().unwrap_or_default().to_string();
+            continue;
+        }
+        let supports_websockets = negotiation["availableTransports"]
+            .as_array()
+            .map(|transports| transports.iter().any(|transport| transport["transport"] == json!("WebSockets")))
+            .unwrap_or(false);
+        if !supports_websockets {
+            return Err(String::from("The hub doesn't support the WebSockets transport"));
+        }
+        let connection_id = negotiation["connectionId"].as_str().unwrap_or_default().to_string();
+        // Since negotiation version 1 the connection token identifies the connection
+        let connection_token = negotiation["connectionToken"]
+            .as_str()
+            .map(String::from)
+            .unwrap_or_else(|| connection_id.clone());
+        hub_url.query_pairs_mut().append_pair("id", &connection_token);
+        return Ok(Negotiation {
+            websocket_url: hub_url.to_string(),
+            connection_id,
+            access_token,
+        });
+    }
+    Err(format!("Negotiation has been redirected more than {} times", MAX_NEGOTIATE_REDIRECTS))
+}
+
+/// Negotiates, connects to the hub and handles the messages until the connection fails, the worker is stopped or the
+/// instance is paused.
+fn session(reactive_instance: &ReactiveEntityInstance, signal: &WorkerSignal, failed_attempts: &mut u64) -> Result<(), String> {
+    let mut request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let negotiation = negotiate(reactive_instance, &request_headers)?;
+    if !negotiation.access_token.is_empty() {
+        request_headers.insert(String::from("Authorization"), json!(format!("Bearer {}", negotiation.access_token)));
+    }
+    let mut socket = connect_websocket(&negotiation.websocket_url, &request_headers, None, &UrlPolicy::from(reactive_instance))?;
+    socket
+        .write_message(to_message(json!({ "protocol": "json", "version": 1 })))
+        .map_err(|e| e.to_string())?;
+    let mut connected = false;
+    let mut last_sent = Instant::now();
+    let mut last_received = Instant::now();
+    while !signal.is_stopped() && is_enabled(reactive_instance) {
+        if last_received.elapsed() > SERVER_TIMEOUT {
+            return Err(String::from("The server didn't send anything within the server timeout"));
+        }
+        if connected {
+            send_queued_websocket_messages(&mut socket, reactive_instance.id, BEHAVIOUR_NAME_SIGNALR)?;
+            if last_sent.elapsed() >= KEEP_ALIVE_INTERVAL {
+                socket.write_message(to_message(json!({ "type": 6 }))).map_err(|e| e.to_string())?;
+                last_sent = Instant::now();
+            }
+        }
+        let Some(message) = read_websocket_message(&mut socket)? else {
+            continue;
+        };
+        last_received = Instant::now();
+        let Message::Text(message) = message else {
+            warn!("Ignored binary message of the SignalR connection of {}", reactive_instance.id);
+            continue;
+        };
+        for record in message.split(RECORD_SEPARATOR).filter(|record| !record.trim().is_empty()) {
+            let Ok(record) = serde_json::from_str::<Value>(record) else {
+                warn!("Ignored invalid SignalR message of {}", reactive_instance.id);
+                continue;
+            };
+            // The first message is the response to the handshake
+            if !connected {
+                if let Some(error) = record["error"].as_str() {
+                    return Err(format!("Handshake failed: {}", error));
+                }
+                connected = true;
+                *failed_attempts = 0;
+                reactive_instance.set(CONNECTION_ID, json!(negotiation.connection_id));
+                reactive_instance.set(ERROR, json!(""));
+                reactive_instance.set(CONNECTED, json!(true));
+                continue;
+            }
+            match record["type"].as_u64() {
+                // Invocation
+                Some(1) => {
+                    // Results of client methods are not supported, so that the server doesn't wait for them
+                    if let Some(invocation_id) = record.get("invocationId") {
+                        let completion = json!({ "type": 3, "invocationId": invocation_id, "error": "Client results are not supported" });
+                        socket.write_message(to_message(completion)).map_err(|e| e.to_string())?;
+                        last_sent = Instant::now();
+                    }
+                    reactive_instance.set(ARGUMENTS, record.get("arguments").cloned().unwrap_or_else(|| json!([])));
+                    reactive_instance.set(TARGET, record["target"].clone());
+                }
+                // Completion
+                Some(3) => match record["error"].as_str() {
+                    Some(error) => reactive_instance.set(ERROR, json!(error)),
+                    None => reactive_instance.set(RESULT, record.get("result").cloned().unwrap_or(Value::Null)),
+                },
+                // Close
+                Some(7) => {
+                    let error = record["error"].as_str().unwrap_or("The server closed the connection");
+                    return Err(error.to_string());
+                }
+                // Pings only keep the connection alive, stream items are not requested
+                _ => {}
+            }
+        }
+    }
+    // Closing the connection gracefully
+    let _ = socket.write_message(to_message(json!({ "type": 7 })));
+    let _ = socket.close(None);
+    Ok(())
+}
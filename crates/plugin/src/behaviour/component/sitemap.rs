@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::TimeZone;
+use chrono::Utc;
+use log::warn;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::SitemapProperties::DISCOVERED_URL;
+use crate::model_http::SitemapProperties::ENTRIES;
+use crate::model_http::SitemapProperties::ERRORS;
+use crate::model_http::SitemapProperties::MAX_SITEMAPS;
+use crate::model_http::SitemapProperties::MODIFIED_SINCE;
+use crate::model_http::SitemapProperties::REQUEST_HEADERS;
+use crate::model_http::SitemapProperties::URL;
+use crate::model_http::SitemapProperties::URLS;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+
+entity_behaviour!(Sitemap, SitemapFactory, SitemapFsm, SitemapBehaviourTransitions, SitemapValidator);
+
+behaviour_validator!(SitemapValidator, ReactiveEntityInstance, URL.as_ref(), URLS.as_ref(), ERRORS.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for SitemapBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for SitemapBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            crawl(&reactive_instance);
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for SitemapBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for SitemapBehaviourTransitions {}
+
+/// A page or a nested sitemap listed by a sitemap.
+struct SitemapEntry {
+    loc: String,
+    lastmod: Option<DateTime<Utc>>,
+}
+
+/// Fetches the sitemap and the nested sitemaps of sitemap indexes.
+///
+/// Each discovered url is published as soon as it is found. The complete list is published at the end.
+fn crawl(reactive_instance: &ReactiveEntityInstance) {
+    let Some(url) = reactive_instance.as_string(URL).map(|url| resolve_env(&url)).filter(|url| !url.is_empty()) else {
+        return;
+    };
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let modified_since = reactive_instance
+        .as_string(MODIFIED_SINCE)
+        .and_then(|modified_since| parse_lastmod(&modified_since));
+    let max_sitemaps = reactive_instance.as_u64(MAX_SITEMAPS).unwrap_or(100) as usize;
+    let agent = UrlPolicy::from(reactive_instance).agent();
+    let mut sitemaps = VecDeque::from([url]);
+    let mut visited = HashSet::new();
+    let mut urls = Vec::new();
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    while let Some(sitemap) = sitemaps.pop_front() {
+        if visited.len() >= max_sitemaps {
+            warn!("Stopped crawling the sitemaps of {} after {} sitemaps", reactive_instance.id, max_sitemaps);
+            break;
+        }
+        if !visited.insert(sitemap.clone()) {
+            continue;
+        }
+        let document = match fetch(&agent, &sitemap, &request_headers) {
+            Ok(document) => document,
+            Err(e) => {
+                errors.push(json!({ "url": sitemap, "error": e }));
+                continue;
+            }
+        };
+        let (nested_sitemaps, pages) = match parse_sitemap(&document) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                errors.push(json!({ "url": sitemap, "error": e }));
+                continue;
+            }
+        };
+        // Nested sitemaps are always crawled because their lastmod doesn't say anything about the pages
+        sitemaps.extend(nested_sitemaps.into_iter().map(|entry| entry.loc));
+        for page in pages {
+            // Pages without lastmod can't be filtered
+            if let (Some(modified_since), Some(lastmod)) = (modified_since, page.lastmod) {
+                if lastmod < modified_since {
+                    continue;
+                }
+            }
+            reactive_instance.set(DISCOVERED_URL, json!(page.loc));
+            entries.push(json!({ "url": page.loc, "lastmod": page.lastmod.map(|lastmod| lastmod.to_rfc3339()) }));
+            urls.push(json!(page.loc));
+        }
+    }
+    reactive_instance.set(ERRORS, json!(errors));
+    reactive_instance.set(ENTRIES, json!(entries));
+    reactive_instance.set(URLS, json!(urls));
+}
+
+fn fetch(agent: &ureq::Agent, url: &str, request_headers: &Map<String, Value>) -> Result<String, String> {
+    let request = set_request_headers(agent.get(url), url, request_headers)?;
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    match request.call() {
+        Ok(response) => response.into_string().map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Parses a sitemap or a sitemap index and returns the nested sitemaps and the pages.
+fn parse_sitemap(document: &str) -> Result<(Vec<SitemapEntry>, Vec<SitemapEntry>), String> {
+    let document = roxmltree::Document::parse(document).map_err(|e| format!("Invalid sitemap: {}", e))?;
+    let entries = |tag_name: &str| -> Vec<SitemapEntry> {
+        document
+            .root_element()
+            .children()
+            .filter(|node| node.has_tag_name(tag_name))
+            .filter_map(|node| {
+                let text = |child_name: &str| {
+                    node.children()
+                        .find(|child| child.has_tag_name(child_name))
+                        .and_then(|child| child.text())
+                        .map(str::trim)
+                };
+                Some(SitemapEntry {
+                    loc: text("loc")?.to_string(),
+                    lastmod: text("lastmod").and_then(parse_lastmod),
+                })
+            })
+            .collect()
+    };
+    Ok((entries("sitemap"), entries("url")))
+}
+
+/// Parses a W3C datetime, which is either a complete RFC 3339 timestamp or a date.
+fn parse_lastmod(lastmod: &str) -> Option<DateTime<Utc>> {
+    let lastmod = lastmod.trim();
+    if let Ok(lastmod) = DateTime::parse_from_rfc3339(lastmod) {
+        return Some(lastmod.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(lastmod, "%Y-%m-%d").ok()?;
+    Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?))
+}
@@ -0,0 +1,409 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+use log::debug;
+use log::warn;
+use serde_json::json;
+use serde_json::Value;
+use tungstenite::Message;
+use url::Url;
+use uuid::Uuid;
+
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::ReconnectProperties::CONNECTED;
+use crate::model_http::SocketIoProperties::ACK;
+use crate::model_http::SocketIoProperties::ACK_EVENT;
+use crate::model_http::SocketIoProperties::AUTH;
+use crate::model_http::SocketIoProperties::EMIT_EVENT;
+use crate::model_http::SocketIoProperties::EMIT_PAYLOAD;
+use crate::model_http::SocketIoProperties::ERROR;
+use crate::model_http::SocketIoProperties::EVENT;
+use crate::model_http::SocketIoProperties::NAMESPACE;
+use crate::model_http::SocketIoProperties::PATH;
+use crate::model_http::SocketIoProperties::PAYLOAD;
+use crate::model_http::SocketIoProperties::REQUEST_HEADERS;
+use crate::model_http::SocketIoProperties::SID;
+use crate::model_http::SocketIoProperties::URL;
+use crate::model_http::BEHAVIOUR_NAME_SOCKET_IO;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::reconnect::keep_connected;
+use crate::ssrf::UrlPolicy;
+use crate::websocket::clear_queued_websocket_messages;
+use crate::websocket::connect_websocket;
+use crate::websocket::queue_websocket_message;
+use crate::websocket::read_websocket_message;
+use crate::websocket::send_queued_websocket_messages;
+use crate::websocket::WebSocketStream;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+use crate::worker::WorkerSignal;
+
+/// The maximum number of emitted events per instance which wait for their acknowledgement. The oldest events are
+/// forgotten, for example if they were sent on a connection which failed.
+const MAX_PENDING_ACKS: usize = 1000;
+
+lazy_static! {
+    /// The emitted events which wait for their acknowledgement by instance.
+    static ref PENDING_ACKS: Mutex<HashMap<Uuid, PendingAcks>> = Mutex::new(HashMap::new());
+}
+
+entity_behaviour!(SocketIo, SocketIoFactory, SocketIoFsm, SocketIoBehaviourTransitions, SocketIoValidator);
+
+behaviour_validator!(SocketIoValidator, ReactiveEntityInstance, URL.as_ref(), EVENT.as_ref(), PAYLOAD.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for SocketIoBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for SocketIoBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            emit(&reactive_instance);
+        });
+        let reactive_instance = self.reactive_instance.clone();
+        start_worker(self.reactive_instance.id, BEHAVIOUR_NAME_SOCKET_IO, move |signal| {
+            keep_connected(&reactive_instance, &signal, "Socket.IO", ERROR.as_ref(), session)
+        });
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        stop_worker(self.reactive_instance.id, BEHAVIOUR_NAME_SOCKET_IO);
+        clear_queued_websocket_messages(self.reactive_instance.id, BEHAVIOUR_NAME_SOCKET_IO);
+        PENDING_ACKS.lock().unwrap().remove(&self.reactive_instance.id);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for SocketIoBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for SocketIoBehaviourTransitions {}
+
+/// A Socket.IO packet of protocol version 5.
+#[derive(Debug)]
+struct Packet {
+    packet_type: u8,
+    namespace: String,
+    ack_id: Option<u64>,
+    data: Option<Value>,
+}
+
+impl Packet {
+    /// Parses the packet of an Engine.IO message.
+    fn parse(packet: &str) -> Option<Packet> {
+        let packet_type = packet.chars().next()?.to_digit(10)? as u8;
+        let mut rest = &packet[1..];
+        // Attachments of binary packets are announced by their number
+        if matches!(packet_type, 5 | 6) {
+            rest = rest.split_once('-').map(|(_, rest)| rest)?;
+        }
+        let mut namespace = String::from("/");
+        if rest.starts_with('/') {
+            let (packet_namespace, packet_rest) = rest.split_once(',').unwrap_or((rest, ""));
+            namespace = packet_namespace.to_string();
+            rest = packet_rest;
+        }
+        let ack_id_length = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let ack_id = rest[..ack_id_length].parse().ok();
+        rest = &rest[ack_id_length..];
+        let data = if rest.is_empty() { None } else { serde_json::from_str(rest).ok() };
+        Some(Packet {
+            packet_type,
+            namespace,
+            ack_id,
+            data,
+        })
+    }
+
+    /// Encodes the packet, which is sent as Engine.IO message.
+    fn encode(&self) -> String {
+        let mut packet = format!("4{}", self.packet_type);
+        if self.namespace != "/" {
+            packet.push_str(&self.namespace);
+            packet.push(',');
+        }
+        if let Some(ack_id) = self.ack_id {
+            packet.push_str(&ack_id.to_string());
+        }
+        if let Some(data) = &self.data {
+            packet.push_str(&data.to_string());
+        }
+        packet
+    }
+}
+
+/// The emitted events of an instance by the id of the requested acknowledgement.
+#[derive(Debug, Default)]
+struct PendingAcks {
+    next_ack_id: u64,
+    events: BTreeMap<u64, Value>,
+}
+
+impl PendingAcks {
+    /// Remembers the event until it is acknowledged and returns the ack id of the event.
+    fn register(&mut self, event: &str, payload: Value) -> u64 {
+        let ack_id = self.next_ack_id;
+        self.next_ack_id += 1;
+        self.events.insert(ack_id, json!({ "id": ack_id, "event": event, "payload": payload }));
+        while self.events.len() > MAX_PENDING_ACKS {
+            self.events.pop_first();
+        }
+        ack_id
+    }
+
+    /// Returns the event which is acknowledged or none, if no event with the ack id is pending.
+    fn take(&mut self, ack_id: u64) -> Option<Value> {
+        self.events.remove(&ack_id)
+    }
+}
+
+fn get_namespace(reactive_instance: &ReactiveEntityInstance) -> String {
+    let namespace = reactive_instance.as_string(NAMESPACE).unwrap_or_default();
+    if namespace.starts_with('/') {
+        namespace
+    } else {
+        format!("/{}", namespace)
+    }
+}
+
+/// Queues the event. The event is requested to be acknowledged; the acknowledgement is output as ack together with the
+/// event as ack_event.
+fn emit(reactive_instance: &ReactiveEntityInstance) {
+    let event = reactive_instance.as_string(EMIT_EVENT).unwrap_or_default();
+    if event.is_empty() {
+        return;
+    }
+    let payload = reactive_instance.get(EMIT_PAYLOAD).unwrap_or(Value::Null);
+    let mut data = vec![json!(event)];
+    match &payload {
+        Value::Array(arguments) => data.extend(arguments.iter().cloned()),
+        Value::Null => {}
+        payload => data.push(payload.clone()),
+    }
+    let ack_id = PENDING_ACKS.lock().unwrap().entry(reactive_instance.id).or_default().register(&event, payload);
+    let packet = Packet {
+        packet_type: 2,
+        namespace: get_namespace(reactive_instance),
+        ack_id: Some(ack_id),
+        data: Some(json!(data)),
+    };
+    queue_websocket_message(reactive_instance.id, BEHAVIOUR_NAME_SOCKET_IO, Message::Text(packet.encode()));
+}
+
+/// Connects to the namespace and handles the packets until the connection fails, the worker is stopped or the instance
+/// is paused.
+fn session(reactive_instance: &ReactiveEntityInstance, signal: &WorkerSignal, failed_attempts: &mut u64) -> Result<(), String> {
+    let url = resolve_env(&reactive_instance.as_string(URL).unwrap_or_default());
+    let mut url = Url::parse(&url).map_err(|e| format!("Invalid url {}: {}", url, e))?;
+    url.set_path(&reactive_instance.as_string(PATH).unwrap_or_else(|| String::from("/socket.io/")));
+    url.set_query(Some("EIO=4&transport=websocket"));
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let mut socket = connect_websocket(url.as_str(), &request_headers, None, &UrlPolicy::from(reactive_instance))?;
+    let namespace = get_namespace(reactive_instance);
+    let mut connected = false;
+    // The server pings in the ping interval and considers the connection closed after the ping timeout
+    let mut ping_deadline = Instant::now() + Duration::from_secs(60);
+    let mut ping_window = Duration::from_secs(45);
+    while !signal.is_stopped() && is_enabled(reactive_instance) {
+        if Instant::now() > ping_deadline {
+            return Err(String::from("The server stopped pinging"));
+        }
+        if connected {
+            send_queued_websocket_messages(&mut socket, reactive_instance.id, BEHAVIOUR_NAME_SOCKET_IO)?;
+        }
+        let Some(message) = read_websocket_message(&mut socket)? else {
+            continue;
+        };
+        let Message::Text(message) = message else {
+            warn!("Ignored binary message of the Socket.IO connection of {}", reactive_instance.id);
+            continue;
+        };
+        match message.chars().next() {
+            // Open
+            Some('0') => {
+                let handshake: Value = serde_json::from_str(&message[1..]).unwrap_or_default();
+                let ping_interval = handshake["pingInterval"].as_u64().unwrap_or(25000);
+                let ping_timeout = handshake["pingTimeout"].as_u64().unwrap_or(20000);
+                ping_window = Duration::from_millis(ping_interval + ping_timeout);
+                ping_deadline = Instant::now() + ping_window;
+                let auth = reactive_instance.as_object(AUTH).unwrap_or_default();
+                let packet = Packet {
+                    packet_type: 0,
+                    namespace: namespace.clone(),
+                    ack_id: None,
+                    data: if auth.is_empty() { None } else { Some(json!(auth)) },
+                };
+                socket.write_message(Message::Text(packet.encode())).map_err(|e| e.to_string())?;
+            }
+            Some('1') => return Err(String::from("The server closed the connection")),
+            // Ping
+            Some('2') => {
+                ping_deadline = Instant::now() + ping_window;
+                socket.write_message(Message::Text(String::from("3"))).map_err(|e| e.to_string())?;
+            }
+            Some('4') => {
+                let Some(packet) = Packet::parse(&message[1..]).filter(|packet| packet.namespace == namespace) else {
+                    continue;
+                };
+                match packet.packet_type {
+                    0 => {
+                        connected = true;
+                        *failed_attempts = 0;
+                        let sid = packet.data.as_ref().and_then(|data| data["sid"].as_str()).unwrap_or_default();
+                        reactive_instance.set(SID, json!(sid));
+                        reactive_instance.set(ERROR, json!(""));
+                        reactive_instance.set(CONNECTED, json!(true));
+                    }
+                    1 => return Err(format!("The server disconnected the namespace {}", namespace)),
+                    2 => {
+                        let mut arguments = match packet.data {
+                            Some(Value::Array(arguments)) => arguments,
+                            _ => continue,
+                        };
+                        if arguments.is_empty() {
+                            continue;
+                        }
+                        let event = arguments.remove(0);
+                        // The event is acknowledged without arguments
+                        if let Some(ack_id) = packet.ack_id {
+                            let ack = Packet {
+                                packet_type: 3,
+                                namespace: namespace.clone(),
+                                ack_id: Some(ack_id),
+                                data: Some(json!([])),
+                            };
+                            socket.write_message(Message::Text(ack.encode())).map_err(|e| e.to_string())?;
+                        }
+                        let payload = if arguments.len() == 1 { arguments.remove(0) } else { json!(arguments) };
+                        reactive_instance.set(PAYLOAD, payload);
+                        reactive_instance.set(EVENT, event);
+                    }
+                    3 => {
+                        let event = packet.ack_id.and_then(|ack_id| {
+                            PENDING_ACKS
+                                .lock()
+                                .unwrap()
+                                .get_mut(&reactive_instance.id)
+                                .and_then(|pending_acks| pending_acks.take(ack_id))
+                        });
+                        // Acknowledgements of events which are not pending are ignored
+                        let Some(event) = event else {
+                            debug!("Ignored the acknowledgement {:?} of an unknown event of {}", packet.ack_id, reactive_instance.id);
+                            continue;
+                        };
+                        let ack = match packet.data {
+                            Some(Value::Array(mut arguments)) if arguments.len() == 1 => arguments.remove(0),
+                            Some(data) => data,
+                            None => Value::Null,
+                        };
+                        // The event is set first, so that observers of the acknowledgement can match it
+                        reactive_instance.set(ACK_EVENT, event);
+                        reactive_instance.set(ACK, ack);
+                    }
+                    4 => {
+                        let message = packet
+                            .data
+                            .as_ref()
+                            .and_then(|data| data["message"].as_str().map(String::from))
+                            .unwrap_or_else(|| String::from("Connection refused"));
+                        return Err(message);
+                    }
+                    _ => warn!("Ignored binary packet of the Socket.IO connection of {}", reactive_instance.id),
+                }
+            }
+            _ => {}
+        }
+    }
+    // Leaving the namespace gracefully
+    let _ = close(&mut socket, &namespace);
+    Ok(())
+}
+
+fn close(socket: &mut WebSocketStream, namespace: &str) -> Result<(), tungstenite::Error> {
+    let packet = Packet {
+        packet_type: 1,
+        namespace: namespace.to_string(),
+        ack_id: None,
+        data: None,
+    };
+    socket.write_message(Message::Text(packet.encode()))?;
+    socket.close(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_events() {
+        let packet = Packet::parse("2/chat,12[\"message\",{\"text\":\"hello\"}]").unwrap();
+        assert_eq!(packet.packet_type, 2);
+        assert_eq!(packet.namespace, "/chat");
+        assert_eq!(packet.ack_id, Some(12));
+        assert_eq!(packet.data, Some(json!(["message", {"text": "hello"}])));
+        let packet = Packet::parse("2[\"message\"]").unwrap();
+        assert_eq!(packet.namespace, "/");
+        assert_eq!(packet.ack_id, None);
+        let packet = Packet::parse("0/admin,").unwrap();
+        assert_eq!((packet.packet_type, packet.namespace.as_str(), packet.data), (0, "/admin", None));
+        assert!(Packet::parse("").is_none());
+        assert!(Packet::parse("x[]").is_none());
+    }
+
+    #[test]
+    fn skips_the_attachment_count_of_binary_packets() {
+        let packet = Packet::parse("51-/files,[\"upload\",{\"_placeholder\":true,\"num\":0}]").unwrap();
+        assert_eq!(packet.packet_type, 5);
+        assert_eq!(packet.namespace, "/files");
+        assert!(Packet::parse("5/files,[]").is_none());
+    }
+
+    #[test]
+    fn encodes_packets() {
+        let packet = Packet {
+            packet_type: 2,
+            namespace: String::from("/chat"),
+            ack_id: Some(3),
+            data: Some(json!(["message", "hello"])),
+        };
+        assert_eq!(packet.encode(), "42/chat,3[\"message\",\"hello\"]");
+        let packet = Packet {
+            packet_type: 0,
+            namespace: String::from("/"),
+            ack_id: None,
+            data: None,
+        };
+        assert_eq!(packet.encode(), "40");
+    }
+
+    #[test]
+    fn matches_acknowledgements_to_their_events() {
+        let mut pending_acks = PendingAcks::default();
+        let first = pending_acks.register("first", json!(["a", 1]));
+        let second = pending_acks.register("second", Value::Null);
+        assert_ne!(first, second);
+        assert_eq!(pending_acks.take(second), Some(json!({"id": second, "event": "second", "payload": null})));
+        assert_eq!(pending_acks.take(second), None);
+        assert_eq!(pending_acks.take(first), Some(json!({"id": first, "event": "first", "payload": ["a", 1]})));
+    }
+
+    #[test]
+    fn forgets_the_oldest_pending_events() {
+        let mut pending_acks = PendingAcks::default();
+        let oldest = pending_acks.register("oldest", Value::Null);
+        for _ in 0..MAX_PENDING_ACKS {
+            pending_acks.register("event", Value::Null);
+        }
+        assert_eq!(pending_acks.events.len(), MAX_PENDING_ACKS);
+        assert_eq!(pending_acks.take(oldest), None);
+        assert!(pending_acks.take(oldest + 1).is_some());
+    }
+}
@@ -0,0 +1,232 @@
+use std::io::BufRead;
+use std::io::BufReader;
+use std::time::Duration;
+
+use serde_json::json;
+use serde_json::Value;
+
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ReconnectProperties::CONNECTED;
+use crate::model_http::SseProperties::DATA;
+use crate::model_http::SseProperties::ERROR;
+use crate::model_http::SseProperties::EVENT;
+use crate::model_http::SseProperties::IDLE_TIMEOUT;
+use crate::model_http::SseProperties::LAST_EVENT_ID;
+use crate::model_http::SseProperties::REQUEST_HEADERS;
+use crate::model_http::SseProperties::URL;
+use crate::model_http::BEHAVIOUR_NAME_SSE;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::reconnect::keep_connected;
+use crate::ssrf::UrlPolicy;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+use crate::worker::WorkerSignal;
+
+/// The type of events without event field.
+const DEFAULT_EVENT_TYPE: &str = "message";
+
+entity_behaviour!(Sse, SseFactory, SseFsm, SseBehaviourTransitions, SseValidator);
+
+behaviour_validator!(SseValidator, ReactiveEntityInstance, URL.as_ref(), LAST_EVENT_ID.as_ref(), EVENT.as_ref(), DATA.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for SseBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for SseBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        start_worker(self.reactive_instance.id, BEHAVIOUR_NAME_SSE, move |signal| {
+            keep_connected(&reactive_instance, &signal, "Event stream", ERROR.as_ref(), session)
+        });
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        stop_worker(self.reactive_instance.id, BEHAVIOUR_NAME_SSE);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for SseBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for SseBehaviourTransitions {}
+
+/// Connects to the event stream and publishes the events until the stream ends, the worker is stopped or the instance
+/// is paused.
+///
+/// The id of the last event is sent as Last-Event-ID, so that the server resends the events which have been missed while
+/// reconnecting. The reconnect delays are taken from the reconnect component; retry fields of the stream are ignored.
+fn session(reactive_instance: &ReactiveEntityInstance, signal: &WorkerSignal, failed_attempts: &mut u64) -> Result<(), String> {
+    let url = resolve_env(&reactive_instance.as_string(URL).unwrap_or_default());
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let last_event_id = reactive_instance.as_string(LAST_EVENT_ID).unwrap_or_default();
+    let mut agent_builder = UrlPolicy::from(reactive_instance).agent_builder();
+    // The server is expected to send comments as heartbeats while there are no events
+    let idle_timeout = reactive_instance.as_u64(IDLE_TIMEOUT).unwrap_or(60000);
+    if idle_timeout > 0 {
+        agent_builder = agent_builder.timeout_read(Duration::from_millis(idle_timeout));
+    }
+    let agent = agent_builder.build();
+    let mut request = set_request_headers(agent.get(&url), &url, &request_headers)?
+        .set("Accept", "text/event-stream")
+        .set("Cache-Control", "no-cache");
+    if !last_event_id.is_empty() {
+        request = request.set("Last-Event-ID", &last_event_id);
+    }
+    // The permit is only held until the stream has been opened
+    let response = {
+        let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+        match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => return Err(format!("{} {}", status, response.status_text())),
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+    if response.content_type() != "text/event-stream" {
+        return Err(format!("Expected an event stream, but the server answered with {}", response.content_type()));
+    }
+    *failed_attempts = 0;
+    reactive_instance.set(ERROR, json!(""));
+    reactive_instance.set(CONNECTED, json!(true));
+    let mut parser = EventStreamParser::new(last_event_id);
+    let mut reader = BufReader::new(response.into_reader());
+    let mut line = String::new();
+    while !signal.is_stopped() && is_enabled(reactive_instance) {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => return Err(String::from("The server closed the event stream")),
+            Ok(_) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+        let Some(event) = parser.parse_line(line.trim_end_matches(['\r', '\n'])) else {
+            continue;
+        };
+        if reactive_instance.as_string(LAST_EVENT_ID).as_ref() != Some(&event.id) {
+            reactive_instance.set(LAST_EVENT_ID, json!(event.id));
+        }
+        reactive_instance.set(EVENT, json!(event.event_type));
+        reactive_instance.set(DATA, serde_json::from_str(&event.data).unwrap_or_else(|_| json!(event.data)));
+    }
+    Ok(())
+}
+
+/// An event of an event stream.
+#[derive(Debug, PartialEq, Eq)]
+struct ServerSentEvent {
+    /// The id of the last event which had an id.
+    id: String,
+    event_type: String,
+    data: String,
+}
+
+/// Parses the lines of an event stream as specified by the HTML living standard.
+struct EventStreamParser {
+    last_event_id: String,
+    event_type: String,
+    data: String,
+}
+
+impl EventStreamParser {
+    /// Creates a parser which continues with the id of the last event of the previous connection.
+    fn new(last_event_id: String) -> Self {
+        EventStreamParser {
+            last_event_id,
+            event_type: String::new(),
+            data: String::new(),
+        }
+    }
+
+    /// Parses a line without line terminator. Returns the event, if the line completes one.
+    fn parse_line(&mut self, line: &str) -> Option<ServerSentEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        // Comments keep the connection alive
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event_type = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            // Ids containing NULL are ignored
+            "id" if !value.contains('\0') => self.last_event_id = value.to_string(),
+            _ => {}
+        }
+        None
+    }
+
+    /// Completes the event. Events without data are not dispatched.
+    fn dispatch(&mut self) -> Option<ServerSentEvent> {
+        let event_type = std::mem::take(&mut self.event_type);
+        let mut data = std::mem::take(&mut self.data);
+        if data.is_empty() {
+            return None;
+        }
+        data.pop();
+        Some(ServerSentEvent {
+            id: self.last_event_id.clone(),
+            event_type: if event_type.is_empty() {
+                String::from(DEFAULT_EVENT_TYPE)
+            } else {
+                event_type
+            },
+            data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(parser: &mut EventStreamParser, stream: &str) -> Vec<ServerSentEvent> {
+        stream.split('\n').filter_map(|line| parser.parse_line(line)).collect()
+    }
+
+    #[test]
+    fn parses_events() {
+        let mut parser = EventStreamParser::new(String::new());
+        let events = parse(&mut parser, ": heartbeat\n\ndata: first\ndata:second\n\nevent: update\nid: 2\ndata: {\"value\": 1}\n\n");
+        assert_eq!(
+            events,
+            vec![
+                ServerSentEvent {
+                    id: String::new(),
+                    event_type: String::from("message"),
+                    data: String::from("first\nsecond"),
+                },
+                ServerSentEvent {
+                    id: String::from("2"),
+                    event_type: String::from("update"),
+                    data: String::from("{\"value\": 1}"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_last_event_id() {
+        let mut parser = EventStreamParser::new(String::from("41"));
+        let events = parse(&mut parser, "data: resumed\n\nid: 42\n\nid\0: ignored\ndata: next\n\nid: 4\0\ndata: last\n\n");
+        let ids: Vec<&str> = events.iter().map(|event| event.id.as_str()).collect();
+        assert_eq!(ids, vec!["41", "42", "42"]);
+    }
+
+    #[test]
+    fn resets_the_event_type_after_each_event() {
+        let mut parser = EventStreamParser::new(String::new());
+        let events = parse(&mut parser, "event: ping\n\ndata: message\n\n");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, "message");
+    }
+}
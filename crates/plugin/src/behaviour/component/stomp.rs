@@ -0,0 +1,361 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use log::warn;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use tungstenite::Message;
+use url::Url;
+
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::ReconnectProperties::CONNECTED;
+use crate::model_http::StompProperties::ERROR;
+use crate::model_http::StompProperties::HEARTBEAT;
+use crate::model_http::StompProperties::LOGIN;
+use crate::model_http::StompProperties::MESSAGE_BODY;
+use crate::model_http::StompProperties::MESSAGE_DESTINATION;
+use crate::model_http::StompProperties::MESSAGE_HEADERS;
+use crate::model_http::StompProperties::PASSCODE;
+use crate::model_http::StompProperties::REQUEST_HEADERS;
+use crate::model_http::StompProperties::SEND_BODY;
+use crate::model_http::StompProperties::SEND_DESTINATION;
+use crate::model_http::StompProperties::SEND_HEADERS;
+use crate::model_http::StompProperties::SUBSCRIPTIONS;
+use crate::model_http::StompProperties::URL;
+use crate::model_http::StompProperties::VIRTUAL_HOST;
+use crate::model_http::BEHAVIOUR_NAME_STOMP;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::reconnect::keep_connected;
+use crate::secrets::resolve_secrets_for_url;
+use crate::ssrf::UrlPolicy;
+use crate::websocket::clear_queued_websocket_messages;
+use crate::websocket::connect_websocket;
+use crate::websocket::queue_websocket_message;
+use crate::websocket::read_websocket_message;
+use crate::websocket::send_queued_websocket_messages;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+use crate::worker::WorkerSignal;
+
+/// The WebSocket subprotocols of the STOMP versions.
+const STOMP_SUBPROTOCOLS: &str = "v12.stomp, v11.stomp, v10.stomp";
+
+/// The time in which the broker has to answer the CONNECT frame.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+entity_behaviour!(Stomp, StompFactory, StompFsm, StompBehaviourTransitions, StompValidator);
+
+behaviour_validator!(StompValidator, ReactiveEntityInstance, URL.as_ref(), SUBSCRIPTIONS.as_ref(), MESSAGE_BODY.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for StompBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for StompBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            send(&reactive_instance);
+        });
+        let reactive_instance = self.reactive_instance.clone();
+        start_worker(self.reactive_instance.id, BEHAVIOUR_NAME_STOMP, move |signal| {
+            keep_connected(&reactive_instance, &signal, "STOMP", ERROR.as_ref(), session)
+        });
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        stop_worker(self.reactive_instance.id, BEHAVIOUR_NAME_STOMP);
+        clear_queued_websocket_messages(self.reactive_instance.id, BEHAVIOUR_NAME_STOMP);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for StompBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for StompBehaviourTransitions {}
+
+/// A STOMP frame.
+#[derive(Debug)]
+struct Frame {
+    command: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Frame {
+    fn new(command: &str, headers: Vec<(&str, String)>) -> Self {
+        Frame {
+            command: command.to_string(),
+            headers: headers.into_iter().map(|(name, value)| (name.to_string(), value)).collect(),
+            body: Vec::new(),
+        }
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        // The first occurrence of a repeated header wins
+        self.headers.iter().find(|(header, _)| header == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Encodes the frame. The headers of CONNECT frames are not escaped.
+    fn encode(&self) -> Vec<u8> {
+        let escape = self.command != "CONNECT";
+        let mut frame = format!("{}\n", self.command);
+        for (name, value) in self.headers.iter() {
+            if escape {
+                frame.push_str(&format!("{}:{}\n", escape_header(name), escape_header(value)));
+            } else {
+                frame.push_str(&format!("{}:{}\n", name, value));
+            }
+        }
+        frame.push('\n');
+        let mut frame = frame.into_bytes();
+        frame.extend_from_slice(&self.body);
+        frame.push(0);
+        frame
+    }
+
+    /// Parses a frame. The body is delimited by the content-length header or by the NUL octet.
+    fn parse(data: &[u8]) -> Option<Frame> {
+        let header_end = data.windows(2).position(|window| window == b"\n\n").map(|position| (position, 2));
+        let header_end = header_end.or_else(|| data.windows(4).position(|window| window == b"\r\n\r\n").map(|position| (position, 4)));
+        let (header_end, separator_length) = header_end?;
+        let head = String::from_utf8_lossy(&data[..header_end]);
+        let mut lines = head.lines().map(|line| line.trim_end_matches('\r'));
+        let command = lines.next()?.to_string();
+        let unescape = command != "CONNECTED";
+        let headers: Vec<(String, String)> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| {
+                if unescape {
+                    (unescape_header(name), unescape_header(value))
+                } else {
+                    (name.to_string(), value.to_string())
+                }
+            })
+            .collect();
+        let body = &data[header_end + separator_length..];
+        let content_length = headers
+            .iter()
+            .find(|(name, _)| name == "content-length")
+            .and_then(|(_, content_length)| content_length.parse::<usize>().ok());
+        let body = match content_length {
+            Some(content_length) => body.get(..content_length)?,
+            None => body.split(|byte| *byte == 0).next().unwrap_or_default(),
+        };
+        Some(Frame {
+            command,
+            headers,
+            body: body.to_vec(),
+        })
+    }
+}
+
+fn escape_header(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\r', "\\r").replace('\n', "\\n").replace(':', "\\c")
+}
+
+fn unescape_header(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('r') => unescaped.push('\r'),
+            Some('n') => unescaped.push('\n'),
+            Some('c') => unescaped.push(':'),
+            Some('\\') => unescaped.push('\\'),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Queues a SEND frame. Strings are sent as text, anything else as JSON.
+fn send(reactive_instance: &ReactiveEntityInstance) {
+    let destination = reactive_instance.as_string(SEND_DESTINATION).unwrap_or_default();
+    if destination.is_empty() {
+        return;
+    }
+    let (content_type, body) = match reactive_instance.get(SEND_BODY).unwrap_or(Value::Null) {
+        Value::String(body) => ("text/plain;charset=utf-8", body.into_bytes()),
+        body => ("application/json;charset=utf-8", body.to_string().into_bytes()),
+    };
+    let mut frame = Frame::new(
+        "SEND",
+        vec![
+            ("destination", destination),
+            ("content-type", content_type.to_string()),
+            ("content-length", body.len().to_string()),
+        ],
+    );
+    for (name, value) in reactive_instance.as_object(SEND_HEADERS).unwrap_or_default() {
+        if let Some(value) = value.as_str() {
+            frame.headers.push((name, value.to_string()));
+        }
+    }
+    frame.body = body;
+    queue_websocket_message(reactive_instance.id, BEHAVIOUR_NAME_STOMP, Message::Binary(frame.encode()));
+}
+
+/// Connects to the broker, keeps the subscriptions in sync with the subscriptions property and outputs the received
+/// messages.
+fn session(reactive_instance: &ReactiveEntityInstance, signal: &WorkerSignal, failed_attempts: &mut u64) -> Result<(), String> {
+    let url = resolve_env(&reactive_instance.as_string(URL).unwrap_or_default());
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let mut socket = connect_websocket(&url, &request_headers, Some(STOMP_SUBPROTOCOLS), &UrlPolicy::from(reactive_instance))?;
+    let virtual_host = reactive_instance
+        .as_string(VIRTUAL_HOST)
+        .filter(|virtual_host| !virtual_host.is_empty())
+        .or_else(|| Url::parse(&url).ok()?.host_str().map(String::from))
+        .unwrap_or_default();
+    let heartbeat = reactive_instance.as_u64(HEARTBEAT).unwrap_or(10000);
+    let mut connect = Frame::new(
+        "CONNECT",
+        vec![
+            ("accept-version", String::from("1.2,1.1,1.0")),
+            ("host", virtual_host),
+            ("heart-beat", format!("{},{}", heartbeat, heartbeat)),
+        ],
+    );
+    let login = reactive_instance.as_string(LOGIN).unwrap_or_default();
+    if !login.is_empty() {
+        connect.headers.push((String::from("login"), resolve_env(&login)));
+        let passcode = resolve_secrets_for_url(&resolve_env(&reactive_instance.as_string(PASSCODE).unwrap_or_default()), &url)?;
+        connect.headers.push((String::from("passcode"), passcode));
+    }
+    socket.write_message(Message::Binary(connect.encode())).map_err(|e| e.to_string())?;
+    let started = Instant::now();
+    let mut connected = false;
+    let mut send_interval = None;
+    let mut receive_interval = None;
+    let mut last_sent = Instant::now();
+    let mut last_received = Instant::now();
+    // The subscription ids by destination
+    let mut subscriptions: HashMap<String, String> = HashMap::new();
+    let mut next_subscription_id = 0;
+    while !signal.is_stopped() && is_enabled(reactive_instance) {
+        if !connected && started.elapsed() > CONNECT_TIMEOUT {
+            return Err(String::from("The broker didn't answer the CONNECT frame"));
+        }
+        // Missing heart-beats are tolerated twice, as recommended by the specification
+        if let Some(receive_interval) = receive_interval {
+            if last_received.elapsed() > receive_interval * 2 {
+                return Err(String::from("The broker stopped sending heart-beats"));
+            }
+        }
+        if connected {
+            let destinations: Vec<String> = reactive_instance
+                .as_array(SUBSCRIPTIONS)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|destination| destination.as_str().map(String::from))
+                .collect();
+            for (destination, id) in subscriptions.clone() {
+                if !destinations.contains(&destination) {
+                    let unsubscribe = Frame::new("UNSUBSCRIBE", vec![("id", id)]);
+                    socket.write_message(Message::Binary(unsubscribe.encode())).map_err(|e| e.to_string())?;
+                    subscriptions.remove(&destination);
+                    last_sent = Instant::now();
+                }
+            }
+            for destination in destinations {
+                if subscriptions.contains_key(&destination) {
+                    continue;
+                }
+                let id = format!("sub-{}", next_subscription_id);
+                next_subscription_id += 1;
+                let subscribe = Frame::new("SUBSCRIBE", vec![("id", id.clone()), ("destination", destination.clone()), ("ack", String::from("auto"))]);
+                socket.write_message(Message::Binary(subscribe.encode())).map_err(|e| e.to_string())?;
+                subscriptions.insert(destination, id);
+                last_sent = Instant::now();
+            }
+            send_queued_websocket_messages(&mut socket, reactive_instance.id, BEHAVIOUR_NAME_STOMP)?;
+            if let Some(send_interval) = send_interval {
+                if last_sent.elapsed() >= send_interval {
+                    socket.write_message(Message::Text(String::from("\n"))).map_err(|e| e.to_string())?;
+                    last_sent = Instant::now();
+                }
+            }
+        }
+        let Some(message) = read_websocket_message(&mut socket)? else {
+            continue;
+        };
+        last_received = Instant::now();
+        let data = match message {
+            Message::Text(text) => text.into_bytes(),
+            Message::Binary(data) => data,
+            _ => continue,
+        };
+        // Heart-beats are end of lines without frame
+        if data.iter().all(|byte| matches!(byte, b'\n' | b'\r' | 0)) {
+            continue;
+        }
+        let start = data.iter().position(|byte| !matches!(byte, b'\n' | b'\r')).unwrap_or(0);
+        let Some(frame) = Frame::parse(&data[start..]) else {
+            warn!("Ignored invalid STOMP frame of {}", reactive_instance.id);
+            continue;
+        };
+        match frame.command.as_str() {
+            "CONNECTED" => {
+                connected = true;
+                *failed_attempts = 0;
+                // The heart-beat intervals are negotiated, zero means no heart-beat
+                let (server_send, server_receive) = frame
+                    .header("heart-beat")
+                    .and_then(|heart_beat| heart_beat.split_once(','))
+                    .map(|(send, receive)| (send.trim().parse::<u64>().unwrap_or(0), receive.trim().parse::<u64>().unwrap_or(0)))
+                    .unwrap_or((0, 0));
+                if heartbeat > 0 && server_receive > 0 {
+                    send_interval = Some(Duration::from_millis(heartbeat.max(server_receive)));
+                }
+                if heartbeat > 0 && server_send > 0 {
+                    receive_interval = Some(Duration::from_millis(heartbeat.max(server_send)));
+                }
+                reactive_instance.set(ERROR, json!(""));
+                reactive_instance.set(CONNECTED, json!(true));
+            }
+            "MESSAGE" => {
+                let content_type = frame.header("content-type").unwrap_or_default().to_ascii_lowercase();
+                let body = if content_type.contains("json") {
+                    serde_json::from_slice(&frame.body).unwrap_or_else(|_| json!(String::from_utf8_lossy(&frame.body)))
+                } else {
+                    json!(String::from_utf8_lossy(&frame.body))
+                };
+                let mut headers = Map::new();
+                for (name, value) in frame.headers.iter() {
+                    headers.entry(name.clone()).or_insert_with(|| json!(value));
+                }
+                reactive_instance.set(MESSAGE_DESTINATION, json!(frame.header("destination").unwrap_or_default()));
+                reactive_instance.set(MESSAGE_HEADERS, json!(headers));
+                reactive_instance.set(MESSAGE_BODY, body);
+            }
+            "ERROR" => {
+                let message = frame.header("message").unwrap_or("ERROR frame");
+                let body = String::from_utf8_lossy(&frame.body);
+                return Err(if body.trim().is_empty() {
+                    message.to_string()
+                } else {
+                    format!("{}: {}", message, body.trim())
+                });
+            }
+            _ => {}
+        }
+    }
+    // Leaving the broker gracefully
+    let _ = socket.write_message(Message::Binary(Frame::new("DISCONNECT", Vec::new()).encode()));
+    let _ = socket.close(None);
+    Ok(())
+}
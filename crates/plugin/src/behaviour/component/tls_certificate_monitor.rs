@@ -0,0 +1,95 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::TimeZone;
+use chrono::Utc;
+use log::warn;
+use serde_json::json;
+
+use crate::model::*;
+use crate::model_http::TlsCertificateMonitorProperties::CERTIFICATE_CHAIN;
+use crate::model_http::TlsCertificateMonitorProperties::DAYS_REMAINING;
+use crate::model_http::TlsCertificateMonitorProperties::ERROR;
+use crate::model_http::TlsCertificateMonitorProperties::EXPIRES_AT;
+use crate::model_http::TlsCertificateMonitorProperties::HOSTNAME;
+use crate::model_http::TlsCertificateMonitorProperties::INTERVAL;
+use crate::model_http::TlsCertificateMonitorProperties::ISSUER;
+use crate::model_http::TlsCertificateMonitorProperties::PORT;
+use crate::model_http::TlsCertificateMonitorProperties::TIMEOUT;
+use crate::model_http::BEHAVIOUR_NAME_TLS_CERTIFICATE_MONITOR;
+use crate::pausable::is_enabled;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+use crate::tls::read_certificate_chain;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+use crate::worker::WorkerSignal;
+
+entity_behaviour!(
+    TlsCertificateMonitor,
+    TlsCertificateMonitorFactory,
+    TlsCertificateMonitorFsm,
+    TlsCertificateMonitorBehaviourTransitions,
+    TlsCertificateMonitorValidator
+);
+
+behaviour_validator!(TlsCertificateMonitorValidator, ReactiveEntityInstance, HOSTNAME.as_ref(), PORT.as_ref(), EXPIRES_AT.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for TlsCertificateMonitorBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for TlsCertificateMonitorBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        start_worker(self.reactive_instance.id, BEHAVIOUR_NAME_TLS_CERTIFICATE_MONITOR, move |signal| {
+            monitor(reactive_instance, signal)
+        });
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        stop_worker(self.reactive_instance.id, BEHAVIOUR_NAME_TLS_CERTIFICATE_MONITOR);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for TlsCertificateMonitorBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for TlsCertificateMonitorBehaviourTransitions {}
+
+fn monitor(reactive_instance: Arc<ReactiveEntityInstance>, signal: WorkerSignal) {
+    loop {
+        if let Some(hostname) = reactive_instance
+            .as_string(HOSTNAME)
+            .filter(|hostname| !hostname.is_empty() && is_enabled(&reactive_instance))
+        {
+            check(&reactive_instance, &hostname);
+        }
+        if !signal.sleep(Duration::from_millis(reactive_instance.as_u64(INTERVAL).unwrap_or(3600000))) {
+            break;
+        }
+    }
+}
+
+fn check(reactive_instance: &ReactiveEntityInstance, hostname: &str) {
+    let port = reactive_instance.as_u64(PORT).unwrap_or(443) as u16;
+    let timeout = Duration::from_millis(reactive_instance.as_u64(TIMEOUT).unwrap_or(5000));
+    let certificate_chain = match read_certificate_chain(hostname, port, timeout, &UrlPolicy::from(reactive_instance)) {
+        Ok(certificate_chain) => certificate_chain,
+        Err(e) => {
+            warn!("Failed to read the certificate of {}:{}: {}", hostname, port, e);
+            reactive_instance.set(ERROR, json!(e));
+            return;
+        }
+    };
+    // The first certificate of the chain is the certificate of the server
+    let Some(certificate) = certificate_chain.first() else {
+        reactive_instance.set(ERROR, json!(format!("{}:{} presented no certificate", hostname, port)));
+        return;
+    };
+    let expires_at = Utc.timestamp_opt(certificate.not_after, 0).single().map(|expires_at| expires_at.to_rfc3339());
+    let days_remaining = (certificate.not_after - Utc::now().timestamp()).div_euclid(86400);
+    reactive_instance.set(ERROR, json!(""));
+    reactive_instance.set(EXPIRES_AT, json!(expires_at.unwrap_or_default()));
+    reactive_instance.set(DAYS_REMAINING, json!(days_remaining));
+    reactive_instance.set(ISSUER, json!(certificate.issuer));
+    reactive_instance.set(CERTIFICATE_CHAIN, json!(certificate_chain));
+}
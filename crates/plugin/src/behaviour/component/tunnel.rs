@@ -0,0 +1,250 @@
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::process::Child;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Duration;
+
+use log::debug;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::config::get_config;
+use crate::model::*;
+use crate::model_http::ReconnectProperties::CONNECTED;
+use crate::model_http::TunnelProperties::AUTH_TOKEN;
+use crate::model_http::TunnelProperties::DOMAIN;
+use crate::model_http::TunnelProperties::ERROR;
+use crate::model_http::TunnelProperties::PORT;
+use crate::model_http::TunnelProperties::PROVIDER;
+use crate::model_http::TunnelProperties::PUBLIC_URL;
+use crate::model_http::BEHAVIOUR_NAME_TUNNEL;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::reconnect::keep_connected;
+use crate::secrets::resolve_secrets;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+use crate::worker::WorkerSignal;
+
+/// The interval in which the worker looks whether it has been stopped while the tunnel agent is running.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+entity_behaviour!(Tunnel, TunnelFactory, TunnelFsm, TunnelBehaviourTransitions, TunnelValidator);
+
+behaviour_validator!(TunnelValidator, ReactiveEntityInstance, PROVIDER.as_ref(), PORT.as_ref(), PUBLIC_URL.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for TunnelBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for TunnelBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        start_worker(self.reactive_instance.id, BEHAVIOUR_NAME_TUNNEL, move |signal| {
+            keep_connected(&reactive_instance, &signal, "Tunnel", ERROR.as_ref(), session)
+        });
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        // The worker terminates the tunnel agent
+        stop_worker(self.reactive_instance.id, BEHAVIOUR_NAME_TUNNEL);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for TunnelBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for TunnelBehaviourTransitions {}
+
+/// The supported tunnel agents. Both open an outbound connection, so that no port has to be forwarded.
+#[derive(Clone, Copy)]
+enum Provider {
+    /// The ngrok agent, which logs the public url as JSON to stdout.
+    Ngrok,
+    /// A quick tunnel of cloudflared, which prints the public url to stderr.
+    Cloudflared,
+}
+
+impl Provider {
+    fn parse(provider: &str) -> Result<Provider, String> {
+        match provider.to_ascii_lowercase().as_str() {
+            "ngrok" => Ok(Provider::Ngrok),
+            "cloudflared" | "cloudflare" => Ok(Provider::Cloudflared),
+            _ => Err(format!("Unknown tunnel provider {}", provider)),
+        }
+    }
+
+    /// Builds the command of the agent. The agent is configured in config/http.toml and instances can only set validated
+    /// arguments.
+    fn command(&self, reactive_instance: &ReactiveEntityInstance, port: u16) -> Result<Command, String> {
+        let config = &get_config().tunnel;
+        let domain = reactive_instance.as_string(DOMAIN).unwrap_or_default();
+        if !domain.is_empty() && !is_valid_domain(&domain) {
+            return Err(format!("Invalid domain {}", domain));
+        }
+        let mut command = match self {
+            Provider::Ngrok => {
+                let mut command = Command::new(&config.ngrok);
+                command.args(["http", &port.to_string(), "--log", "stdout", "--log-format", "json"]);
+                if !domain.is_empty() {
+                    command.arg(format!("--domain={}", domain));
+                }
+                let auth_token = resolve_secrets(&resolve_env(&reactive_instance.as_string(AUTH_TOKEN).unwrap_or_default()));
+                if !auth_token.is_empty() {
+                    command.env("NGROK_AUTHTOKEN", auth_token);
+                }
+                command
+            }
+            Provider::Cloudflared => {
+                // Quick tunnels always get a random subdomain of trycloudflare.com
+                if !domain.is_empty() {
+                    return Err(String::from("Quick tunnels of cloudflared don't support a reserved domain"));
+                }
+                let mut command = Command::new(&config.cloudflared);
+                command.args(["tunnel", "--no-autoupdate", "--url", &format!("http://localhost:{}", port)]);
+                command
+            }
+        };
+        command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+        Ok(command)
+    }
+
+    /// Returns the public url, if the line of the output of the agent announces the tunnel.
+    fn public_url(&self, line: &str) -> Option<String> {
+        match self {
+            Provider::Ngrok => {
+                let entry: Value = serde_json::from_str(line).ok()?;
+                if entry["msg"] != json!("started tunnel") {
+                    return None;
+                }
+                entry["url"].as_str().map(String::from)
+            }
+            Provider::Cloudflared => line
+                .split_whitespace()
+                .find(|word| word.starts_with("https://") && word.ends_with(".trycloudflare.com"))
+                .map(String::from),
+        }
+    }
+
+    /// Returns the error, if the line of the output of the agent reports one.
+    fn error(&self, line: &str) -> Option<String> {
+        match self {
+            Provider::Ngrok => {
+                let entry: Value = serde_json::from_str(line).ok()?;
+                if entry["lvl"] != json!("eror") && entry["lvl"] != json!("crit") {
+                    return None;
+                }
+                entry["err"].as_str().or_else(|| entry["msg"].as_str()).map(String::from)
+            }
+            Provider::Cloudflared => line.contains(" ERR ").then(|| line.trim().to_string()),
+        }
+    }
+}
+
+/// Returns true, if the domain only consists of labels of letters, digits and hyphens, so that it can't be mistaken for an
+/// option of the agent.
+fn is_valid_domain(domain: &str) -> bool {
+    domain.len() <= 253
+        && domain.split('.').all(|label| {
+            !label.is_empty() && label.len() <= 63 && !label.starts_with('-') && label.bytes().all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+        })
+}
+
+fn forward_lines<R: Read + Send + 'static>(reader: R, sender: Sender<String>) {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else {
+                return;
+            };
+            if sender.send(line).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+/// Runs the tunnel agent until it exits, the worker is stopped or the instance is paused.
+fn session(reactive_instance: &ReactiveEntityInstance, signal: &WorkerSignal, failed_attempts: &mut u64) -> Result<(), String> {
+    let provider = Provider::parse(&reactive_instance.as_string(PROVIDER).unwrap_or_else(|| String::from("ngrok")))?;
+    let port = reactive_instance
+        .as_u64(PORT)
+        .and_then(|port| u16::try_from(port).ok())
+        .ok_or_else(|| String::from("Invalid port"))?;
+    let mut child = provider
+        .command(reactive_instance, port)?
+        .spawn()
+        .map_err(|e| format!("Failed to start the tunnel agent: {}", e))?;
+    let result = watch(reactive_instance, signal, failed_attempts, provider, &mut child);
+    let _ = child.kill();
+    let _ = child.wait();
+    reactive_instance.set(PUBLIC_URL, json!(""));
+    result
+}
+
+fn watch(
+    reactive_instance: &ReactiveEntityInstance,
+    signal: &WorkerSignal,
+    failed_attempts: &mut u64,
+    provider: Provider,
+    child: &mut Child,
+) -> Result<(), String> {
+    let (sender, receiver) = channel();
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, sender.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, sender);
+    }
+    let mut last_error = None;
+    while !signal.is_stopped() && is_enabled(reactive_instance) {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            return Err(match last_error {
+                Some(e) => format!("The tunnel agent exited with {}: {}", status, e),
+                None => format!("The tunnel agent exited with {}", status),
+            });
+        }
+        let line = match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(line) => line,
+            Err(RecvTimeoutError::Timeout) => continue,
+            // Both outputs have been closed, the exit status is read in the next iteration
+            Err(RecvTimeoutError::Disconnected) => {
+                if !signal.sleep(POLL_INTERVAL) {
+                    break;
+                }
+                continue;
+            }
+        };
+        debug!("Tunnel agent of {}: {}", reactive_instance.id, line);
+        if let Some(public_url) = provider.public_url(&line) {
+            *failed_attempts = 0;
+            reactive_instance.set(PUBLIC_URL, json!(public_url));
+            reactive_instance.set(ERROR, json!(""));
+            reactive_instance.set(CONNECTED, json!(true));
+        } else if let Some(e) = provider.error(&line) {
+            reactive_instance.set(ERROR, json!(e));
+            last_error = Some(e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_domains_which_could_be_read_as_arguments() {
+        assert!(is_valid_domain("example.ngrok.app"));
+        assert!(is_valid_domain("my-tunnel.example.com"));
+        assert!(!is_valid_domain("--config=/etc/passwd"));
+        assert!(!is_valid_domain("-example.com"));
+        assert!(!is_valid_domain("example..com"));
+        assert!(!is_valid_domain("example.com/path"));
+        assert!(!is_valid_domain("example.com --region eu"));
+    }
+}
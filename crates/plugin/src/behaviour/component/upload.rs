@@ -0,0 +1,294 @@
+use std::fs::File;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use log::error;
+use log::warn;
+use serde_json::json;
+use serde_json::Value;
+use url::Url;
+use uuid::Uuid;
+
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::UploadProperties::BYTES_UPLOADED;
+use crate::model_http::UploadProperties::CHUNK_SIZE;
+use crate::model_http::UploadProperties::ERROR;
+use crate::model_http::UploadProperties::FIELD_NAME;
+use crate::model_http::UploadProperties::MAX_UPLOAD_KBPS;
+use crate::model_http::UploadProperties::METHOD;
+use crate::model_http::UploadProperties::PATH;
+use crate::model_http::UploadProperties::PROGRESS_GRANULARITY;
+use crate::model_http::UploadProperties::PROGRESS_PERCENT;
+use crate::model_http::UploadProperties::PROTOCOL;
+use crate::model_http::UploadProperties::REQUEST_HEADERS;
+use crate::model_http::UploadProperties::RESULT;
+use crate::model_http::UploadProperties::STATUS;
+use crate::model_http::UploadProperties::TOTAL_BYTES;
+use crate::model_http::UploadProperties::UPLOAD_URL;
+use crate::model_http::UploadProperties::URL;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::progress::Progress;
+use crate::progress::ProgressReader;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+use crate::throttle::Throttle;
+use crate::throttle::ThrottledReader;
+
+/// The version of the tus protocol.
+const TUS_VERSION: &str = "1.0.0";
+
+/// The number of attempts to send a chunk before the upload fails.
+const TUS_MAX_ATTEMPTS: u32 = 5;
+
+entity_behaviour!(Upload, UploadFactory, UploadFsm, UploadBehaviourTransitions, UploadValidator);
+
+behaviour_validator!(UploadValidator, ReactiveEntityInstance, URL.as_ref(), PATH.as_ref(), STATUS.as_ref(), ERROR.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for UploadBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for UploadBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            let reactive_instance = reactive_instance.clone();
+            thread::spawn(move || upload(reactive_instance));
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for UploadBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for UploadBehaviourTransitions {}
+
+fn upload(reactive_instance: Arc<ReactiveEntityInstance>) {
+    let result = match reactive_instance.as_string(PROTOCOL).as_deref() {
+        Some("tus") => send_file_tus(&reactive_instance),
+        _ => send_file(&reactive_instance),
+    };
+    match result {
+        Ok((status, result)) => {
+            reactive_instance.set(STATUS, json!(status));
+            reactive_instance.set(RESULT, result);
+            reactive_instance.set(ERROR, json!(""));
+        }
+        Err((status, e)) => {
+            error!("{}", e);
+            if let Some(status) = status {
+                reactive_instance.set(STATUS, json!(status));
+            }
+            reactive_instance.set(ERROR, json!(e));
+        }
+    }
+}
+
+/// Sends the file as request body or, if a field name is given, as multipart form data.
+///
+/// Returns the status and the response body or the status and the error.
+fn send_file(reactive_instance: &Arc<ReactiveEntityInstance>) -> Result<(u16, Value), (Option<u16>, String)> {
+    let method = reactive_instance.as_string(METHOD).unwrap_or_else(|| String::from("PUT"));
+    let url = resolve_env(&reactive_instance.as_string(URL).unwrap_or_default());
+    let url_policy = UrlPolicy::from(reactive_instance.as_ref());
+    url_policy.check(&url).map_err(|e| (None, e))?;
+    let path = reactive_instance.as_string(PATH).unwrap_or_default();
+    let file = File::open(&path).map_err(|e| (None, format!("Failed to open {}: {}", path, e)))?;
+    let file_size = file
+        .metadata()
+        .map(|metadata| metadata.len())
+        .map_err(|e| (None, format!("Failed to read {}: {}", path, e)))?;
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let mut request = set_request_headers(url_policy.agent().request(&method, &url), &url, &request_headers).map_err(|e| (None, e))?;
+    let field_name = reactive_instance.as_string(FIELD_NAME).unwrap_or_default();
+    let (body, content_length): (Box<dyn Read + Send>, u64) = if field_name.is_empty() {
+        (Box::new(file), file_size)
+    } else {
+        let boundary = format!("----inexor-{}", Uuid::new_v4().simple());
+        let file_name = Path::new(&path)
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let preamble = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+            boundary, field_name, file_name
+        );
+        let epilogue = format!("\r\n--{}--\r\n", boundary);
+        request = request.set("Content-Type", &format!("multipart/form-data; boundary={}", boundary));
+        let content_length = preamble.len() as u64 + file_size + epilogue.len() as u64;
+        (
+            Box::new(Cursor::new(preamble.into_bytes()).chain(file).chain(Cursor::new(epilogue.into_bytes()))),
+            content_length,
+        )
+    };
+    // The body is streamed instead of being sent chunked
+    request = request.set("Content-Length", &content_length.to_string());
+    let progress = Progress::new(Some(content_length), reactive_instance.as_u64(PROGRESS_GRANULARITY).unwrap_or(1));
+    report_progress(reactive_instance, &progress);
+    let progress_reactive_instance = reactive_instance.clone();
+    let body = ProgressReader::new(body, progress, move |progress| report_progress(&progress_reactive_instance, progress));
+    let body = ThrottledReader::new(body, Throttle::new(reactive_instance.as_u64(MAX_UPLOAD_KBPS).unwrap_or(0)));
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    match request.send(body) {
+        Ok(response) => {
+            let status = response.status();
+            let result = response.into_json().unwrap_or(Value::Null);
+            Ok((status, result))
+        }
+        Err(ureq::Error::Status(status, response)) => Err((Some(status), format!("Failed to upload {}: {}", path, response.status_text()))),
+        Err(e) => Err((None, format!("Failed to upload {}: {}", path, e))),
+    }
+}
+
+/// Uploads the file with the tus resumable upload protocol.
+///
+/// The upload is created on first use and its url is kept in upload_url. Later uploads continue at the offset known by
+/// the server, so that an upload survives restarts. Network errors lead to a retry at the offset of the server.
+///
+/// Returns the status of the last request and the upload url or the status and the error.
+fn send_file_tus(reactive_instance: &Arc<ReactiveEntityInstance>) -> Result<(u16, Value), (Option<u16>, String)> {
+    let url = resolve_env(&reactive_instance.as_string(URL).unwrap_or_default());
+    let url_policy = UrlPolicy::from(reactive_instance.as_ref());
+    url_policy.check(&url).map_err(|e| (None, e))?;
+    let path = reactive_instance.as_string(PATH).unwrap_or_default();
+    let mut file = File::open(&path).map_err(|e| (None, format!("Failed to open {}: {}", path, e)))?;
+    let file_size = file
+        .metadata()
+        .map(|metadata| metadata.len())
+        .map_err(|e| (None, format!("Failed to read {}: {}", path, e)))?;
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let agent = url_policy.agent();
+    let tus_request = |method: &str, url: &str| {
+        set_request_headers(agent.request(method, url), url, &request_headers)
+            .map(|request| request.set("Tus-Resumable", TUS_VERSION))
+            .map_err(|e| (None, e))
+    };
+    let chunk_size = reactive_instance.as_u64(CHUNK_SIZE).unwrap_or(5242880).max(1);
+    let granularity = reactive_instance.as_u64(PROGRESS_GRANULARITY).unwrap_or(1);
+    let max_upload_kbps = reactive_instance.as_u64(MAX_UPLOAD_KBPS).unwrap_or(0);
+    // An unknown or expired upload is created again
+    let mut upload_url = reactive_instance.as_string(UPLOAD_URL).unwrap_or_default();
+    let mut offset = None;
+    if !upload_url.is_empty() {
+        offset = match get_tus_offset(&tus_request, &upload_url) {
+            Ok(offset) => Some(offset),
+            Err((Some(403 | 404 | 410), _)) => None,
+            Err(e) => return Err(e),
+        };
+    }
+    // The status of the request which created or resumed the upload
+    let mut status = if offset.is_some() { 200 } else { 201 };
+    let mut offset = match offset {
+        Some(offset) => offset,
+        None => {
+            upload_url = create_tus_upload(&tus_request, &url, &path, file_size)?;
+            url_policy.check(&upload_url).map_err(|e| (None, e))?;
+            reactive_instance.set(UPLOAD_URL, json!(upload_url));
+            0
+        }
+    };
+    let mut attempts = 0;
+    while offset < file_size {
+        let mut progress = Progress::new(Some(file_size), granularity);
+        progress.advance(offset);
+        report_progress(reactive_instance, &progress);
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| (None, format!("Failed to read {}: {}", path, e)))?;
+        let length = chunk_size.min(file_size - offset);
+        let progress_reactive_instance = reactive_instance.clone();
+        let body = ProgressReader::new((&mut file).take(length), progress, move |progress| report_progress(&progress_reactive_instance, progress));
+        let body = ThrottledReader::new(body, Throttle::new(max_upload_kbps));
+        let request = tus_request("PATCH", &upload_url)?
+            .set("Content-Type", "application/offset+octet-stream")
+            .set("Content-Length", &length.to_string())
+            .set("Upload-Offset", &offset.to_string());
+        let result = {
+            let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+            request.send(body)
+        };
+        match result {
+            Ok(response) => {
+                status = response.status();
+                offset = parse_upload_offset(&response).ok_or((Some(status), format!("Failed to upload {}: Upload-Offset is missing", path)))?;
+                attempts = 0;
+            }
+            // Apart from an offset conflict, a rejected chunk is not solved by a retry
+            Err(ureq::Error::Status(status, response)) if status != 409 => {
+                return Err((Some(status), format!("Failed to upload {}: {}", path, response.status_text())));
+            }
+            Err(e) => {
+                attempts += 1;
+                if attempts >= TUS_MAX_ATTEMPTS {
+                    return Err((None, format!("Failed to upload {}: {}", path, e)));
+                }
+                warn!("Failed to upload a chunk of {}, retrying: {}", path, e);
+                thread::sleep(Duration::from_secs(attempts as u64));
+                offset = get_tus_offset(&tus_request, &upload_url)?;
+            }
+        }
+    }
+    let mut progress = Progress::new(Some(file_size), granularity);
+    progress.advance(file_size);
+    report_progress(reactive_instance, &progress);
+    // The upload is finished, the next upload starts from scratch
+    reactive_instance.set(UPLOAD_URL, json!(""));
+    Ok((status, json!({ "upload_url": upload_url })))
+}
+
+/// Creates the upload and returns its absolute url.
+fn create_tus_upload(
+    tus_request: &dyn Fn(&str, &str) -> Result<ureq::Request, (Option<u16>, String)>,
+    url: &str,
+    path: &str,
+    file_size: u64,
+) -> Result<String, (Option<u16>, String)> {
+    let file_name = Path::new(path)
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let request = tus_request("POST", url)?
+        .set("Upload-Length", &file_size.to_string())
+        .set("Upload-Metadata", &format!("filename {}", base64::encode(file_name)));
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    match request.call() {
+        Ok(response) => response
+            .header("location")
+            .and_then(|location| Url::parse(url).ok()?.join(location).ok())
+            .map(|location| location.to_string())
+            .ok_or((Some(response.status()), format!("Failed to create the upload of {}: Location is missing", path))),
+        Err(ureq::Error::Status(status, response)) => Err((Some(status), format!("Failed to create the upload of {}: {}", path, response.status_text()))),
+        Err(e) => Err((None, format!("Failed to create the upload of {}: {}", path, e))),
+    }
+}
+
+/// Returns the offset of the upload known by the server.
+fn get_tus_offset(tus_request: &dyn Fn(&str, &str) -> Result<ureq::Request, (Option<u16>, String)>, upload_url: &str) -> Result<u64, (Option<u16>, String)> {
+    let request = tus_request("HEAD", upload_url)?.set("Cache-Control", "no-store");
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    match request.call() {
+        Ok(response) => parse_upload_offset(&response).ok_or((Some(response.status()), format!("Upload-Offset of {} is missing", upload_url))),
+        Err(ureq::Error::Status(status, response)) => Err((Some(status), format!("Failed to resume {}: {}", upload_url, response.status_text()))),
+        Err(e) => Err((None, format!("Failed to resume {}: {}", upload_url, e))),
+    }
+}
+
+fn parse_upload_offset(response: &ureq::Response) -> Option<u64> {
+    response.header("upload-offset")?.trim().parse().ok()
+}
+
+fn report_progress(reactive_instance: &ReactiveEntityInstance, progress: &Progress) {
+    reactive_instance.set(BYTES_UPLOADED, json!(progress.transferred_bytes));
+    reactive_instance.set(TOTAL_BYTES, json!(progress.total_bytes.unwrap_or(0)));
+    reactive_instance.set(PROGRESS_PERCENT, json!(progress.percent()));
+}
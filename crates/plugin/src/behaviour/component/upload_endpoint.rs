@@ -0,0 +1,338 @@
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use log::error;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::model::*;
+use crate::model_http::UploadEndpointProperties::DIRECTORY;
+use crate::model_http::UploadEndpointProperties::ERROR;
+use crate::model_http::UploadEndpointProperties::FIELDS;
+use crate::model_http::UploadEndpointProperties::FILE;
+use crate::model_http::UploadEndpointProperties::FILES;
+use crate::model_http::UploadEndpointProperties::MAX_FILE_SIZE;
+use crate::model_http::UploadEndpointProperties::MAX_PARTS;
+use crate::model_http::UploadEndpointProperties::MAX_TOTAL_SIZE;
+use crate::model_http::UploadEndpointProperties::OVERWRITE;
+use crate::model_http::UploadEndpointProperties::PATH;
+use crate::model_http::UploadEndpointProperties::PORT;
+use crate::multipart::get_boundary;
+use crate::multipart::BodyError;
+use crate::multipart::MultipartStream;
+use crate::multipart::Part;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::route_auth::authenticate;
+use crate::route_rate_limit::check_rate_limit;
+use crate::server::register_route;
+use crate::server::unregister_route;
+use crate::server::IncomingRequest;
+use crate::server::OutgoingResponse;
+use crate::server::Route;
+use crate::server::RouteHandler;
+
+/// The maximum size of a form field, which is kept in memory.
+const MAX_FIELD_SIZE: u64 = 1048576;
+
+/// The maximum size of a file, if the instance doesn't specify one.
+const DEFAULT_MAX_FILE_SIZE: u64 = 104857600;
+
+/// The maximum size of all parts of an upload, if the instance doesn't specify one.
+const DEFAULT_MAX_TOTAL_SIZE: u64 = 1073741824;
+
+/// The maximum number of parts of an upload, if the instance doesn't specify one.
+const DEFAULT_MAX_PARTS: u64 = 100;
+
+entity_behaviour!(
+    UploadEndpoint,
+    UploadEndpointFactory,
+    UploadEndpointFsm,
+    UploadEndpointBehaviourTransitions,
+    UploadEndpointValidator
+);
+
+behaviour_validator!(
+    UploadEndpointValidator,
+    ReactiveEntityInstance,
+    PORT.as_ref(),
+    PATH.as_ref(),
+    DIRECTORY.as_ref(),
+    FILE.as_ref(),
+    FILES.as_ref(),
+    ERROR.as_ref()
+);
+
+impl BehaviourInit<ReactiveEntityInstance> for UploadEndpointBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for UploadEndpointBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let port = self
+            .reactive_instance
+            .as_u64(PORT)
+            .and_then(|port| u16::try_from(port).ok())
+            .ok_or(BehaviourConnectFailed {})?;
+        let reactive_instance = self.reactive_instance.clone();
+        let route = Route {
+            id: self.reactive_instance.id,
+            method: Some(String::from("POST")),
+            path: self.reactive_instance.as_string(PATH).unwrap_or_else(|| String::from("/upload")),
+            handler: RouteHandler::Streaming(Arc::new(move |request, body| receive(&reactive_instance, request, body))),
+            cors: Some(self.reactive_instance.clone()),
+        };
+        register_route(port, route).map_err(|e| {
+            error!("{}", e);
+            BehaviourConnectFailed {}
+        })
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        unregister_route(self.reactive_instance.id);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for UploadEndpointBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for UploadEndpointBehaviourTransitions {}
+
+/// Stores the files of the multipart body in the directory and answers with their metadata.
+///
+/// The metadata of each file is set as soon as the file has been stored. The files and the form fields of the upload
+/// are set after the whole body has been read. Uploads which exceed the size of a file, the total size or the number of
+/// parts are answered with 413 Payload Too Large.
+fn receive(reactive_instance: &ReactiveEntityInstance, request: IncomingRequest, body: &mut dyn Read) -> OutgoingResponse {
+    if !is_enabled(reactive_instance) {
+        return OutgoingResponse::status(503);
+    }
+    if let Err(response) = check_rate_limit(reactive_instance, &request) {
+        return response;
+    }
+    if let Err(response) = authenticate(reactive_instance, &request) {
+        return response;
+    }
+    let Some(boundary) = request.header("Content-Type").and_then(get_boundary) else {
+        return fail(reactive_instance, 415, String::from("The body is not multipart/form-data"));
+    };
+    let directory = PathBuf::from(resolve_env(&reactive_instance.as_string(DIRECTORY).unwrap_or_else(|| String::from("uploads"))));
+    if let Err(e) = fs::create_dir_all(&directory) {
+        return fail(reactive_instance, 500, format!("Failed to create the directory {}: {}", directory.display(), e));
+    }
+    let max_file_size = reactive_instance.as_u64(MAX_FILE_SIZE).unwrap_or(DEFAULT_MAX_FILE_SIZE);
+    let max_total_size = reactive_instance.as_u64(MAX_TOTAL_SIZE).unwrap_or(DEFAULT_MAX_TOTAL_SIZE);
+    let max_parts = reactive_instance.as_u64(MAX_PARTS).unwrap_or(DEFAULT_MAX_PARTS);
+    let mut stream = MultipartStream::new(body, &boundary);
+    let mut files = Vec::new();
+    let mut fields = Map::new();
+    let mut parts = 0;
+    let mut total_size = 0;
+    loop {
+        let part = match stream.next_part() {
+            Ok(Some(part)) => part,
+            Ok(None) => break,
+            Err(e) => return fail(reactive_instance, 400, e),
+        };
+        parts += 1;
+        if max_parts > 0 && parts > max_parts {
+            return fail(reactive_instance, 413, format!("The upload has more than {} parts", max_parts));
+        }
+        // The remaining total size limits the size of the part
+        let remaining_size = if max_total_size > 0 {
+            if total_size >= max_total_size {
+                return fail(reactive_instance, 413, format!("The upload is larger than {} bytes", max_total_size));
+            }
+            max_total_size - total_size
+        } else {
+            0
+        };
+        if part.file_name.is_none() {
+            let mut value = Vec::new();
+            match stream.read_body(&mut value, min_size_limit(MAX_FIELD_SIZE, remaining_size)) {
+                Ok(size) => total_size += size,
+                Err(e) => return fail(reactive_instance, status_of(&e), format!("Failed to read the field {}: {}", part.name, e)),
+            }
+            fields.insert(part.name, json!(String::from_utf8_lossy(&value)));
+            continue;
+        }
+        match store_file(reactive_instance, &directory, &part, &mut stream, min_size_limit(max_file_size, remaining_size)) {
+            Ok(file) => {
+                total_size += file["size"].as_u64().unwrap_or_default();
+                reactive_instance.set(FILE, file.clone());
+                files.push(file);
+            }
+            Err((status, e)) => return fail(reactive_instance, status, e),
+        }
+    }
+    reactive_instance.set(FIELDS, json!(fields));
+    reactive_instance.set(FILES, json!(files));
+    reactive_instance.set(ERROR, json!(""));
+    OutgoingResponse::json(201, &json!({ "files": files, "fields": fields }))
+}
+
+/// Streams the body of the part into a partial file, which is renamed after it has been written completely.
+///
+/// The partial file has a random name, so that concurrent uploads of files with the same name don't write into the same
+/// partial file.
+fn store_file(
+    reactive_instance: &ReactiveEntityInstance,
+    directory: &Path,
+    part: &Part,
+    stream: &mut MultipartStream,
+    max_size: u64,
+) -> Result<Value, (u16, String)> {
+    let file_name = sanitize_file_name(part.file_name.as_deref().unwrap_or_default());
+    let overwrite = reactive_instance.as_bool(OVERWRITE).unwrap_or(false);
+    let partial_path = directory.join(format!(".{}.part", Uuid::new_v4()));
+    let result = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&partial_path)
+        .map_err(|e| BodyError::Failed(format!("Failed to create {}: {}", partial_path.display(), e)))
+        .and_then(|mut file| stream.read_body(&mut file, max_size))
+        .and_then(|size| {
+            let path = if overwrite {
+                directory.join(&file_name)
+            } else {
+                claim_unique_path(directory, &file_name).map_err(|e| BodyError::Failed(format!("Failed to create the file {}: {}", file_name, e)))?
+            };
+            fs::rename(&partial_path, &path).map(|_| (size, path.clone())).map_err(|e| {
+                if !overwrite {
+                    let _ = fs::remove_file(&path);
+                }
+                BodyError::Failed(format!("Failed to move the file to {}: {}", path.display(), e))
+            })
+        });
+    let (size, path) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            let _ = fs::remove_file(&partial_path);
+            return Err((status_of(&e), format!("Failed to store the file {}: {}", file_name, e)));
+        }
+    };
+    Ok(json!({
+        "name": part.name,
+        "file_name": file_name,
+        "size": size,
+        "path": path.to_string_lossy(),
+        "content_type": part.content_type.clone().unwrap_or_else(|| String::from("application/octet-stream"))
+    }))
+}
+
+/// Returns the base name of the file name of the client without control characters, so that the file can't be stored
+/// outside the directory.
+fn sanitize_file_name(file_name: &str) -> String {
+    let file_name: String = file_name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    let file_name = file_name.trim().trim_start_matches('.');
+    if file_name.is_empty() {
+        return String::from("upload");
+    }
+    file_name.to_string()
+}
+
+/// Creates an empty file with the file name or, if a file with the same name exists already, with a suffix appended.
+///
+/// The file is created exclusively, so that concurrent uploads never claim the same path. The partial file is renamed
+/// over the claimed file afterwards.
+fn claim_unique_path(directory: &Path, file_name: &str) -> std::io::Result<PathBuf> {
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (file_name, String::new()),
+    };
+    let candidates = (0u64..).map(|suffix| match suffix {
+        0 => directory.join(file_name),
+        suffix => directory.join(format!("{}-{}{}", stem, suffix, extension)),
+    });
+    for path in candidates {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => return Ok(path),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!()
+}
+
+/// Returns the smaller of two size limits, of which zero means unlimited.
+fn min_size_limit(a: u64, b: u64) -> u64 {
+    match (a, b) {
+        (0, b) => b,
+        (a, 0) => a,
+        (a, b) => a.min(b),
+    }
+}
+
+fn status_of(e: &BodyError) -> u16 {
+    match e {
+        BodyError::TooLarge(_) => 413,
+        BodyError::Failed(_) => 400,
+    }
+}
+
+/// Logs the error and answers with a generic message, so that the client doesn't learn the paths and errors of the
+/// server.
+fn fail(reactive_instance: &ReactiveEntityInstance, status: u16, e: String) -> OutgoingResponse {
+    error!("Failed to receive the upload of {}: {}", reactive_instance.id, e);
+    reactive_instance.set(ERROR, json!(e));
+    let message = match status {
+        400 => "The multipart body is invalid",
+        413 => "The upload is too large",
+        415 => "The body is not multipart/form-data",
+        _ => "Failed to store the upload",
+    };
+    OutgoingResponse::json(status, &json!({ "error": message }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn strips_directories_and_control_characters_from_file_names() {
+        assert_eq!(sanitize_file_name("report.pdf"), "report.pdf");
+        assert_eq!(sanitize_file_name("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_file_name("..\\..\\windows\\win.ini"), "win.ini");
+        assert_eq!(sanitize_file_name("/absolute/path.txt"), "path.txt");
+        assert_eq!(sanitize_file_name(".htaccess"), "htaccess");
+        assert_eq!(sanitize_file_name("..."), "upload");
+        assert_eq!(sanitize_file_name("../"), "upload");
+        assert_eq!(sanitize_file_name(""), "upload");
+        assert_eq!(sanitize_file_name("evil\u{0}name\n.txt"), "evilname.txt");
+        assert_eq!(sanitize_file_name("  spaced.txt  "), "spaced.txt");
+    }
+
+    #[test]
+    fn appends_a_suffix_to_file_names_which_are_taken() {
+        let directory = env::temp_dir().join(format!("upload-endpoint-{}", Uuid::new_v4()));
+        fs::create_dir_all(&directory).unwrap();
+        assert_eq!(claim_unique_path(&directory, "file.txt").unwrap(), directory.join("file.txt"));
+        assert_eq!(claim_unique_path(&directory, "file.txt").unwrap(), directory.join("file-1.txt"));
+        assert_eq!(claim_unique_path(&directory, "file.txt").unwrap(), directory.join("file-2.txt"));
+        assert_eq!(claim_unique_path(&directory, "file").unwrap(), directory.join("file"));
+        assert_eq!(claim_unique_path(&directory, "file").unwrap(), directory.join("file-1"));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn takes_the_smaller_size_limit() {
+        assert_eq!(min_size_limit(100, 50), 50);
+        assert_eq!(min_size_limit(50, 100), 50);
+        assert_eq!(min_size_limit(0, 100), 100);
+        assert_eq!(min_size_limit(100, 0), 100);
+        assert_eq!(min_size_limit(0, 0), 0);
+    }
+}
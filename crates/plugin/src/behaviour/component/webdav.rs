@@ -0,0 +1,194 @@
+use log::error;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use url::Url;
+
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::WebDavProperties::DEPTH;
+use crate::model_http::WebDavProperties::DESTINATION;
+use crate::model_http::WebDavProperties::ERROR;
+use crate::model_http::WebDavProperties::METHOD;
+use crate::model_http::WebDavProperties::OVERWRITE;
+use crate::model_http::WebDavProperties::REQUEST_HEADERS;
+use crate::model_http::WebDavProperties::RESOURCES;
+use crate::model_http::WebDavProperties::STATUS;
+use crate::model_http::WebDavProperties::URL;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+
+/// The namespace of the WebDAV elements.
+const DAV_NAMESPACE: &str = "DAV:";
+
+/// Requests the properties which are shown by file managers.
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:">
+  <d:prop>
+    <d:resourcetype/>
+    <d:displayname/>
+    <d:getcontentlength/>
+    <d:getcontenttype/>
+    <d:getlastmodified/>
+    <d:getetag/>
+  </d:prop>
+</d:propfind>"#;
+
+entity_behaviour!(WebDav, WebDavFactory, WebDavFsm, WebDavBehaviourTransitions, WebDavValidator);
+
+behaviour_validator!(WebDavValidator, ReactiveEntityInstance, METHOD.as_ref(), URL.as_ref(), STATUS.as_ref(), RESOURCES.as_ref());
+
+impl BehaviourInit<ReactiveEntityInstance> for WebDavBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for WebDavBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            match send_webdav_request(&reactive_instance) {
+                Ok((status, resources)) => {
+                    reactive_instance.set(STATUS, json!(status));
+                    reactive_instance.set(RESOURCES, json!(resources));
+                    reactive_instance.set(ERROR, json!(""));
+                }
+                Err((status, e)) => {
+                    error!("{}", e);
+                    if let Some(status) = status {
+                        reactive_instance.set(STATUS, json!(status));
+                    }
+                    reactive_instance.set(RESOURCES, json!([]));
+                    reactive_instance.set(ERROR, json!(e));
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for WebDavBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for WebDavBehaviourTransitions {}
+
+/// Sends the WebDAV request and parses the multi-status response.
+///
+/// MOVE, COPY and DELETE answer with a multi-status response if some of the members of a collection failed.
+///
+/// Returns the status and the resources or the status and the error.
+fn send_webdav_request(reactive_instance: &ReactiveEntityInstance) -> Result<(u16, Vec<Value>), (Option<u16>, String)> {
+    let method = reactive_instance
+        .as_string(METHOD)
+        .unwrap_or_else(|| String::from("PROPFIND"))
+        .to_ascii_uppercase();
+    let url = resolve_env(&reactive_instance.as_string(URL).unwrap_or_default());
+    let url_policy = UrlPolicy::from(reactive_instance);
+    url_policy.check(&url).map_err(|e| (None, e))?;
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let request = set_request_headers(url_policy.agent().request(&method, &url), &url, &request_headers).map_err(|e| (None, e))?;
+    let result = match method.as_str() {
+        "PROPFIND" => {
+            let depth = reactive_instance.as_string(DEPTH).unwrap_or_else(|| String::from("1"));
+            let request = request.set("Depth", &depth).set("Content-Type", "application/xml; charset=utf-8");
+            let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+            request.send_string(PROPFIND_BODY)
+        }
+        "MOVE" | "COPY" => {
+            let destination = resolve_env(&reactive_instance.as_string(DESTINATION).unwrap_or_default());
+            // Relative destinations are resolved against the url of the resource
+            let destination = Url::parse(&url)
+                .and_then(|url| url.join(&destination))
+                .map_err(|e| (None, format!("Invalid destination {}: {}", destination, e)))?
+                .to_string();
+            url_policy.check(&destination).map_err(|e| (None, e))?;
+            let overwrite = if reactive_instance.as_bool(OVERWRITE).unwrap_or(true) { "T" } else { "F" };
+            let request = request.set("Destination", &destination).set("Overwrite", overwrite);
+            let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+            request.call()
+        }
+        "MKCOL" | "DELETE" => {
+            let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+            request.call()
+        }
+        _ => return Err((None, format!("Unsupported WebDAV method {}", method))),
+    };
+    match result {
+        Ok(response) if response.status() == 207 => {
+            let status = response.status();
+            let document = response
+                .into_string()
+                .map_err(|e| (Some(status), format!("Failed to read the response of {}: {}", url, e)))?;
+            let resources = parse_multistatus(&document).map_err(|e| (Some(status), e))?;
+            Ok((status, resources))
+        }
+        Ok(response) => Ok((response.status(), Vec::new())),
+        Err(ureq::Error::Status(status, response)) => Err((Some(status), format!("{} {} failed: {}", method, url, response.status_text()))),
+        Err(e) => Err((None, format!("{} {} failed: {}", method, url, e))),
+    }
+}
+
+/// Parses a multi-status response as specified by RFC 4918.
+///
+/// Each resource is reported with the properties of the successful propstat. Properties which are missing are null.
+fn parse_multistatus(document: &str) -> Result<Vec<Value>, String> {
+    let document = roxmltree::Document::parse(document).map_err(|e| format!("Invalid multi-status response: {}", e))?;
+    let resources = document
+        .root_element()
+        .children()
+        .filter(|node| is_dav(node, "response"))
+        .map(|response| {
+            let mut resource = Map::new();
+            resource.insert(String::from("href"), json!(dav_text(dav_child(response, "href"))));
+            for name in ["displayname", "content_length", "content_type", "last_modified", "etag"] {
+                resource.insert(String::from(name), Value::Null);
+            }
+            resource.insert(String::from("collection"), json!(false));
+            // A response without propstat reports the status of the resource itself
+            let mut status = dav_text(dav_child(response, "status")).and_then(|status| parse_status_line(&status));
+            for propstat in response.children().filter(|node| is_dav(node, "propstat")) {
+                let propstat_status = dav_text(dav_child(propstat, "status")).and_then(|status| parse_status_line(&status));
+                if !matches!(propstat_status, Some(200..=299)) {
+                    continue;
+                }
+                status = status.or(propstat_status);
+                let Some(prop) = dav_child(propstat, "prop") else {
+                    continue;
+                };
+                let collection = dav_child(prop, "resourcetype")
+                    .and_then(|resourcetype| dav_child(resourcetype, "collection"))
+                    .is_some();
+                resource.insert(String::from("collection"), json!(collection));
+                resource.insert(String::from("displayname"), json!(dav_text(dav_child(prop, "displayname"))));
+                let content_length = dav_text(dav_child(prop, "getcontentlength")).and_then(|content_length| content_length.parse::<u64>().ok());
+                resource.insert(String::from("content_length"), json!(content_length));
+                resource.insert(String::from("content_type"), json!(dav_text(dav_child(prop, "getcontenttype"))));
+                resource.insert(String::from("last_modified"), json!(dav_text(dav_child(prop, "getlastmodified"))));
+                resource.insert(String::from("etag"), json!(dav_text(dav_child(prop, "getetag"))));
+            }
+            resource.insert(String::from("status"), json!(status));
+            Value::Object(resource)
+        })
+        .collect();
+    Ok(resources)
+}
+
+fn is_dav(node: &roxmltree::Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name && node.tag_name().namespace() == Some(DAV_NAMESPACE)
+}
+
+fn dav_child<'a, 'input>(node: roxmltree::Node<'a, 'input>, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| is_dav(child, name))
+}
+
+fn dav_text(node: Option<roxmltree::Node>) -> Option<String> {
+    node.and_then(|node| node.text()).map(|text| text.trim().to_string())
+}
+
+/// Returns the status code of a status line like HTTP/1.1 200 OK.
+fn parse_status_line(status_line: &str) -> Option<u16> {
+    status_line.split_whitespace().nth(1)?.parse().ok()
+}
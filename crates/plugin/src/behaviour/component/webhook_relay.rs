@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use log::error;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::WebhookRelayProperties::ERROR;
+use crate::model_http::WebhookRelayProperties::INBOUND_BODY;
+use crate::model_http::WebhookRelayProperties::INBOUND_HEADERS;
+use crate::model_http::WebhookRelayProperties::INBOUND_METHOD;
+use crate::model_http::WebhookRelayProperties::METHOD;
+use crate::model_http::WebhookRelayProperties::OUTBOUND_RESULT;
+use crate::model_http::WebhookRelayProperties::OUTBOUND_STATUS;
+use crate::model_http::WebhookRelayProperties::PATH;
+use crate::model_http::WebhookRelayProperties::PORT;
+use crate::model_http::WebhookRelayProperties::REQUEST_HEADERS;
+use crate::model_http::WebhookRelayProperties::TARGET_URL;
+use crate::model_http::WebhookRelayProperties::TRANSFORM;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::route_auth::authenticate;
+use crate::route_rate_limit::check_rate_limit;
+use crate::server::register_route;
+use crate::server::unregister_route;
+use crate::server::IncomingRequest;
+use crate::server::OutgoingResponse;
+use crate::server::Route;
+use crate::server::RouteHandler;
+use crate::ssrf::UrlPolicy;
+use crate::template::render_template;
+
+entity_behaviour!(WebhookRelay, WebhookRelayFactory, WebhookRelayFsm, WebhookRelayBehaviourTransitions, WebhookRelayValidator);
+
+behaviour_validator!(
+    WebhookRelayValidator,
+    ReactiveEntityInstance,
+    PORT.as_ref(),
+    PATH.as_ref(),
+    TARGET_URL.as_ref(),
+    OUTBOUND_STATUS.as_ref(),
+    ERROR.as_ref()
+);
+
+impl BehaviourInit<ReactiveEntityInstance> for WebhookRelayBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for WebhookRelayBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let port = self
+            .reactive_instance
+            .as_u64(PORT)
+            .and_then(|port| u16::try_from(port).ok())
+            .ok_or(BehaviourConnectFailed {})?;
+        let reactive_instance = self.reactive_instance.clone();
+        let route = Route {
+            id: self.reactive_instance.id,
+            method: None,
+            path: self.reactive_instance.as_string(PATH).unwrap_or_default(),
+            handler: RouteHandler::Buffered(Arc::new(move |request| relay(&reactive_instance, request))),
+            cors: Some(self.reactive_instance.clone()),
+        };
+        register_route(port, route).map_err(|e| {
+            error!("{}", e);
+            BehaviourConnectFailed {}
+        })
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        unregister_route(self.reactive_instance.id);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for WebhookRelayBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for WebhookRelayBehaviourTransitions {}
+
+/// Forwards the inbound request to the target url and answers with the response of the target.
+fn relay(reactive_instance: &Arc<ReactiveEntityInstance>, request: IncomingRequest) -> OutgoingResponse {
+    // A paused relay refuses the webhooks, so that the sender retries them later
+    if !is_enabled(reactive_instance) {
+        return OutgoingResponse::status(503);
+    }
+    if let Err(response) = check_rate_limit(reactive_instance, &request) {
+        return response;
+    }
+    if let Err(response) = authenticate(reactive_instance, &request) {
+        return response;
+    }
+    let inbound = request.to_json();
+    reactive_instance.set(INBOUND_METHOD, json!(request.method));
+    reactive_instance.set(INBOUND_HEADERS, json!(request.headers));
+    reactive_instance.set(INBOUND_BODY, request.body_as_json());
+    match forward(reactive_instance, &inbound) {
+        Ok((status, result)) => {
+            reactive_instance.set(OUTBOUND_STATUS, json!(status));
+            reactive_instance.set(OUTBOUND_RESULT, result.clone());
+            reactive_instance.set(ERROR, json!(""));
+            OutgoingResponse::json(status, &result)
+        }
+        Err((status, e)) => {
+            error!("{}", e);
+            if let Some(status) = status {
+                reactive_instance.set(OUTBOUND_STATUS, json!(status));
+            }
+            reactive_instance.set(ERROR, json!(e));
+            OutgoingResponse::json(status.unwrap_or(502), &json!({ "error": e }))
+        }
+    }
+}
+
+/// Returns the status and the response body or the status and the error.
+fn forward(reactive_instance: &ReactiveEntityInstance, inbound: &Value) -> Result<(u16, Value), (Option<u16>, String)> {
+    let url = resolve_env(&reactive_instance.as_string(TARGET_URL).unwrap_or_default());
+    let url_policy = UrlPolicy::from(reactive_instance);
+    url_policy.check(&url).map_err(|e| (None, e))?;
+    let method = reactive_instance.as_string(METHOD).unwrap_or_else(|| String::from("POST"));
+    let transform = reactive_instance.get(TRANSFORM).unwrap_or_else(|| json!(""));
+    let payload = match transform {
+        Value::String(ref transform) if transform.is_empty() => inbound["body"].clone(),
+        transform => render_template(&transform, inbound.as_object().unwrap()),
+    };
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let request = set_request_headers(url_policy.agent().request(&method, &url), &url, &request_headers).map_err(|e| (None, e))?;
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    match request.send_json(payload) {
+        Ok(response) => {
+            let status = response.status();
+            Ok((status, response.into_json().unwrap_or(Value::Null)))
+        }
+        Err(ureq::Error::Status(status, response)) => {
+            let result = response.into_json().unwrap_or(Value::Null);
+            Ok((status, result))
+        }
+        Err(e) => Err((None, format!("Failed to forward the request to {}: {}", url, e))),
+    }
+}
@@ -0,0 +1,185 @@
+use std::io;
+use std::sync::mpsc::sync_channel;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::TrySendError;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use log::error;
+use log::warn;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::headers::set_request_headers;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::*;
+use crate::model_http::ActionProperties::TRIGGER;
+use crate::model_http::WebhookSenderProperties::DATA;
+use crate::model_http::WebhookSenderProperties::DELIVERY_STATUS;
+use crate::model_http::WebhookSenderProperties::ENV_IN_PAYLOAD;
+use crate::model_http::WebhookSenderProperties::MAX_RETRIES;
+use crate::model_http::WebhookSenderProperties::METHOD;
+use crate::model_http::WebhookSenderProperties::PAYLOAD_TEMPLATE;
+use crate::model_http::WebhookSenderProperties::PERSISTENT;
+use crate::model_http::WebhookSenderProperties::QUEUE_SIZE;
+use crate::model_http::WebhookSenderProperties::REQUEST_HEADERS;
+use crate::model_http::WebhookSenderProperties::RETRY_DELAY;
+use crate::model_http::WebhookSenderProperties::URL;
+use crate::outbox::Outbox;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::placeholder::resolve_env_in_value;
+use crate::reactive::*;
+use crate::ssrf::UrlPolicy;
+use crate::template::render_template;
+
+entity_behaviour!(
+    WebhookSender,
+    WebhookSenderFactory,
+    WebhookSenderFsm,
+    WebhookSenderBehaviourTransitions,
+    WebhookSenderValidator
+);
+
+behaviour_validator!(
+    WebhookSenderValidator,
+    ReactiveEntityInstance,
+    METHOD.as_ref(),
+    URL.as_ref(),
+    REQUEST_HEADERS.as_ref(),
+    PAYLOAD_TEMPLATE.as_ref(),
+    DATA.as_ref(),
+    DELIVERY_STATUS.as_ref()
+);
+
+impl BehaviourInit<ReactiveEntityInstance> for WebhookSenderBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for WebhookSenderBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        // The queue lives as long as the observer: removing the observer drops the sender and ends the delivery thread
+        let queue_size = self.reactive_instance.as_u64(QUEUE_SIZE).unwrap_or(10).max(1) as usize;
+        let (sender, receiver) = sync_channel::<Value>(queue_size);
+        let reactive_instance = self.reactive_instance.clone();
+        let persistent = self.reactive_instance.as_bool(PERSISTENT).unwrap_or(false);
+        if persistent {
+            thread::spawn(move || deliver_persistent_notifications(reactive_instance, receiver));
+        } else {
+            thread::spawn(move || deliver_notifications(reactive_instance, receiver));
+        }
+
+        let reactive_instance = self.reactive_instance.clone();
+        self.property_observers.observe_with_handle(TRIGGER.as_ref(), move |trigger: &Value| {
+            if !trigger.as_bool().unwrap_or(false) || !is_enabled(&reactive_instance) {
+                return;
+            }
+            let data = reactive_instance.as_object(DATA).unwrap_or_default();
+            let payload_template = reactive_instance.get(PAYLOAD_TEMPLATE).unwrap_or_else(|| json!({}));
+            let payload = render_template(&payload_template, &data);
+            if persistent {
+                // The outbox is unbounded: the channel only wakes up the delivery thread
+                match Outbox::new(reactive_instance.id).push(&payload) {
+                    Ok(_) => {
+                        reactive_instance.set(DELIVERY_STATUS, json!("queued"));
+                        let _ = sender.try_send(Value::Null);
+                    }
+                    Err(e) => {
+                        error!("Dropped webhook notification: failed to write the outbox of {}: {}", reactive_instance.id, e);
+                        reactive_instance.set(DELIVERY_STATUS, json!("dropped"));
+                    }
+                }
+                return;
+            }
+            match sender.try_send(payload) {
+                Ok(_) => reactive_instance.set(DELIVERY_STATUS, json!("queued")),
+                Err(TrySendError::Full(_)) => {
+                    warn!("Dropped webhook notification: the delivery queue of {} is full", reactive_instance.id);
+                    reactive_instance.set(DELIVERY_STATUS, json!("dropped"));
+                }
+                Err(TrySendError::Disconnected(_)) => {}
+            }
+        });
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for WebhookSenderBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for WebhookSenderBehaviourTransitions {}
+
+fn deliver_notifications(reactive_instance: Arc<ReactiveEntityInstance>, receiver: Receiver<Value>) {
+    for payload in receiver.iter() {
+        let max_retries = reactive_instance.as_u64(MAX_RETRIES).unwrap_or(3);
+        let retry_delay = Duration::from_millis(reactive_instance.as_u64(RETRY_DELAY).unwrap_or(1000));
+        let mut retries = 0;
+        loop {
+            match send_notification(&reactive_instance, &payload) {
+                Ok(_) => {
+                    reactive_instance.set(DELIVERY_STATUS, json!("delivered"));
+                    break;
+                }
+                Err(e) if retries < max_retries => {
+                    warn!("Failed to deliver webhook notification (retry {} of {}): {}", retries + 1, max_retries, e.to_string());
+                    reactive_instance.set(DELIVERY_STATUS, json!("retrying"));
+                    retries += 1;
+                    thread::sleep(retry_delay);
+                }
+                Err(e) => {
+                    error!("Failed to deliver webhook notification: {}", e.to_string());
+                    reactive_instance.set(DELIVERY_STATUS, json!("failed"));
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Delivers the notifications of the outbox in order. Notifications which couldn't be delivered because the server is
+/// unreachable remain in the outbox and are retried until they are delivered, even after a restart.
+fn deliver_persistent_notifications(reactive_instance: Arc<ReactiveEntityInstance>, receiver: Receiver<Value>) {
+    let outbox = Outbox::new(reactive_instance.id);
+    loop {
+        let retry_delay = Duration::from_millis(reactive_instance.as_u64(RETRY_DELAY).unwrap_or(1000));
+        let delay = match outbox.peek() {
+            Some((path, payload)) => match send_notification(&reactive_instance, &payload) {
+                Ok(_) => {
+                    outbox.remove(&path);
+                    reactive_instance.set(DELIVERY_STATUS, json!("delivered"));
+                    continue;
+                }
+                // The server has rejected the notification: retrying would not change the outcome
+                Err(ureq::Error::Status(status, _)) => {
+                    error!("Failed to deliver webhook notification: the server responded with {}", status);
+                    outbox.remove(&path);
+                    reactive_instance.set(DELIVERY_STATUS, json!("failed"));
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Failed to deliver webhook notification, keeping it in the outbox: {}", e.to_string());
+                    reactive_instance.set(DELIVERY_STATUS, json!("retrying"));
+                    retry_delay
+                }
+            },
+            // Waits for the next notification
+            None => Duration::MAX,
+        };
+        if let Err(RecvTimeoutError::Disconnected) = receiver.recv_timeout(delay) {
+            break;
+        }
+    }
+}
+
+fn send_notification(reactive_instance: &ReactiveEntityInstance, payload: &Value) -> Result<(), ureq::Error> {
+    let method = reactive_instance.as_string(METHOD).unwrap_or_else(|| String::from("POST"));
+    let url = resolve_env(&reactive_instance.as_string(URL).unwrap_or_default());
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let request = set_request_headers(UrlPolicy::from(reactive_instance).agent().request(method.as_str(), url.as_str()), &url, &request_headers)
+        .map_err(|e| ureq::Error::from(io::Error::new(io::ErrorKind::InvalidInput, e)))?;
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    // Fire and forget: the response body is not parsed
+    if reactive_instance.as_bool(ENV_IN_PAYLOAD).unwrap_or(false) {
+        return request.send_json(resolve_env_in_value(payload)).map(|_| ());
+    }
+    request.send_json(payload.clone()).map(|_| ())
+}
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use log::debug;
+use log::error;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use tungstenite::Message;
+use uuid::Uuid;
+
+use crate::model::*;
+use crate::model_http::ReconnectProperties::CONNECTED;
+use crate::model_http::WebSocketBridgeProperties::CLIENTS;
+use crate::model_http::WebSocketBridgeProperties::CLIENT_TRANSFORM;
+use crate::model_http::WebSocketBridgeProperties::ERROR;
+use crate::model_http::WebSocketBridgeProperties::PATH;
+use crate::model_http::WebSocketBridgeProperties::PORT;
+use crate::model_http::WebSocketBridgeProperties::REQUEST_HEADERS;
+use crate::model_http::WebSocketBridgeProperties::SUBPROTOCOL;
+use crate::model_http::WebSocketBridgeProperties::UPSTREAM_TRANSFORM;
+use crate::model_http::WebSocketBridgeProperties::URL;
+use crate::model_http::BEHAVIOUR_NAME_WEBSOCKET_BRIDGE;
+use crate::pausable::is_enabled;
+use crate::placeholder::resolve_env;
+use crate::reactive::*;
+use crate::reconnect::keep_connected;
+use crate::route_auth::authenticate;
+use crate::route_rate_limit::check_rate_limit;
+use crate::server::IncomingRequest;
+use crate::server::OutgoingResponse;
+use crate::ssrf::UrlPolicy;
+use crate::template::render_template;
+use crate::websocket::clear_queued_websocket_messages;
+use crate::websocket::connect_websocket;
+use crate::websocket::queue_websocket_message;
+use crate::websocket::read_websocket_message;
+use crate::websocket::send_queued_websocket_messages;
+use crate::websocket_server::register_websocket_route;
+use crate::websocket_server::unregister_websocket_route;
+use crate::websocket_server::WebSocketRoute;
+use crate::websocket_server::WebSocketServerStream;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+use crate::worker::WorkerSignal;
+
+lazy_static! {
+    /// The ids of the connected clients by instance. The messages of a client are queued by the id of the client.
+    static ref BRIDGE_CLIENTS: Mutex<HashMap<Uuid, HashSet<Uuid>>> = Mutex::new(HashMap::new());
+}
+
+entity_behaviour!(
+    WebSocketBridge,
+    WebSocketBridgeFactory,
+    WebSocketBridgeFsm,
+    WebSocketBridgeBehaviourTransitions,
+    WebSocketBridgeValidator
+);
+
+behaviour_validator!(
+    WebSocketBridgeValidator,
+    ReactiveEntityInstance,
+    URL.as_ref(),
+    PORT.as_ref(),
+    PATH.as_ref(),
+    CLIENTS.as_ref(),
+    ERROR.as_ref()
+);
+
+impl BehaviourInit<ReactiveEntityInstance> for WebSocketBridgeBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveEntityInstance> for WebSocketBridgeBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        let port = self
+            .reactive_instance
+            .as_u64(PORT)
+            .and_then(|port| u16::try_from(port).ok())
+            .ok_or(BehaviourConnectFailed {})?;
+        let acceptor_instance = self.reactive_instance.clone();
+        let handler_instance = self.reactive_instance.clone();
+        let route = WebSocketRoute {
+            id: self.reactive_instance.id,
+            path: self.reactive_instance.as_string(PATH).unwrap_or_else(|| String::from("/")),
+            acceptor: Arc::new(move |request| accept(&acceptor_instance, request)),
+            handler: Arc::new(move |request, socket| relay_client(&handler_instance, request, socket)),
+        };
+        register_websocket_route(port, route).map_err(|e| {
+            error!("{}", e);
+            BehaviourConnectFailed {}
+        })?;
+        let reactive_instance = self.reactive_instance.clone();
+        start_worker(self.reactive_instance.id, BEHAVIOUR_NAME_WEBSOCKET_BRIDGE, move |signal| {
+            keep_connected(&reactive_instance, &signal, "WebSocket", ERROR.as_ref(), session)
+        });
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        stop_worker(self.reactive_instance.id, BEHAVIOUR_NAME_WEBSOCKET_BRIDGE);
+        unregister_websocket_route(self.reactive_instance.id);
+        // The handlers close the connections of the clients which are no longer registered
+        BRIDGE_CLIENTS.lock().unwrap().remove(&self.reactive_instance.id);
+        clear_queued_websocket_messages(self.reactive_instance.id, BEHAVIOUR_NAME_WEBSOCKET_BRIDGE);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveEntityInstance> for WebSocketBridgeBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveEntityInstance> for WebSocketBridgeBehaviourTransitions {}
+
+/// Refuses the handshake of clients while the instance is paused, exceeded the rate limit or aren't authenticated.
+fn accept(reactive_instance: &ReactiveEntityInstance, request: &IncomingRequest) -> Result<(), OutgoingResponse> {
+    if !is_enabled(reactive_instance) {
+        return Err(OutgoingResponse::status(503));
+    }
+    check_rate_limit(reactive_instance, request)?;
+    authenticate(reactive_instance, request)?;
+    Ok(())
+}
+
+/// Connects to the upstream server and relays its messages to all connected clients.
+fn session(reactive_instance: &ReactiveEntityInstance, signal: &WorkerSignal, failed_attempts: &mut u64) -> Result<(), String> {
+    let url = resolve_env(&reactive_instance.as_string(URL).unwrap_or_default());
+    let request_headers = reactive_instance.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let subprotocol = reactive_instance.as_string(SUBPROTOCOL).filter(|subprotocol| !subprotocol.is_empty());
+    let mut socket = connect_websocket(&url, &request_headers, subprotocol.as_deref(), &UrlPolicy::from(reactive_instance))?;
+    *failed_attempts = 0;
+    reactive_instance.set(ERROR, json!(""));
+    reactive_instance.set(CONNECTED, json!(true));
+    while !signal.is_stopped() && is_enabled(reactive_instance) {
+        send_queued_websocket_messages(&mut socket, reactive_instance.id, BEHAVIOUR_NAME_WEBSOCKET_BRIDGE)?;
+        let Some(message) = read_websocket_message(&mut socket)? else {
+            continue;
+        };
+        let template = reactive_instance.get(UPSTREAM_TRANSFORM).unwrap_or_else(|| json!(""));
+        let Some(message) = transform(&template, message, None) else {
+            continue;
+        };
+        let clients = BRIDGE_CLIENTS.lock().unwrap().get(&reactive_instance.id).cloned().unwrap_or_default();
+        for client in clients {
+            queue_websocket_message(client, BEHAVIOUR_NAME_WEBSOCKET_BRIDGE, message.clone());
+        }
+    }
+    let _ = socket.close(None);
+    Ok(())
+}
+
+/// Relays the messages of a client to the upstream server and the queued messages of the upstream server to the client.
+///
+/// Messages of clients are dropped while the upstream server isn't connected.
+fn relay_client(reactive_instance: &ReactiveEntityInstance, request: IncomingRequest, mut socket: WebSocketServerStream) {
+    let client = Uuid::new_v4();
+    BRIDGE_CLIENTS.lock().unwrap().entry(reactive_instance.id).or_default().insert(client);
+    update_clients(reactive_instance);
+    debug!("Client {} of {} connected from {}", client, reactive_instance.id, request.remote_addr);
+    while is_connected_client(reactive_instance, client) && is_enabled(reactive_instance) {
+        if let Err(e) = send_queued_websocket_messages(&mut socket, client, BEHAVIOUR_NAME_WEBSOCKET_BRIDGE) {
+            debug!("Failed to send to client {} of {}: {}", client, reactive_instance.id, e);
+            break;
+        }
+        let message = match read_websocket_message(&mut socket) {
+            Ok(Some(message)) => message,
+            Ok(None) => continue,
+            Err(e) => {
+                debug!("Client {} of {} disconnected: {}", client, reactive_instance.id, e);
+                break;
+            }
+        };
+        if reactive_instance.as_bool(CONNECTED) != Some(true) {
+            debug!("Dropped a message of client {} of {}: The upstream server isn't connected", client, reactive_instance.id);
+            continue;
+        }
+        let template = reactive_instance.get(CLIENT_TRANSFORM).unwrap_or_else(|| json!(""));
+        if let Some(message) = transform(&template, message, Some(client)) {
+            queue_websocket_message(reactive_instance.id, BEHAVIOUR_NAME_WEBSOCKET_BRIDGE, message);
+        }
+    }
+    if let Some(clients) = BRIDGE_CLIENTS.lock().unwrap().get_mut(&reactive_instance.id) {
+        clients.remove(&client);
+    }
+    clear_queued_websocket_messages(client, BEHAVIOUR_NAME_WEBSOCKET_BRIDGE);
+    let _ = socket.close(None);
+    update_clients(reactive_instance);
+}
+
+fn is_connected_client(reactive_instance: &ReactiveEntityInstance, client: Uuid) -> bool {
+    BRIDGE_CLIENTS
+        .lock()
+        .unwrap()
+        .get(&reactive_instance.id)
+        .map(|clients| clients.contains(&client))
+        .unwrap_or(false)
+}
+
+fn update_clients(reactive_instance: &ReactiveEntityInstance) {
+    let clients = BRIDGE_CLIENTS.lock().unwrap().get(&reactive_instance.id).map(HashSet::len).unwrap_or(0);
+    reactive_instance.set(CLIENTS, json!(clients));
+}
+
+/// Renders the template with the message and the client. Text messages are parsed as JSON, if possible.
+///
+/// Without template and for binary messages the message is relayed unchanged. Rendered strings are sent as text,
+/// anything else as JSON.
+fn transform(template: &Value, message: Message, client: Option<Uuid>) -> Option<Message> {
+    let text = match message {
+        Message::Text(text) => text,
+        Message::Binary(data) => return Some(Message::Binary(data)),
+        _ => return None,
+    };
+    if matches!(template, Value::String(template) if template.is_empty()) || template.is_null() {
+        return Some(Message::Text(text));
+    }
+    let mut data = Map::new();
+    data.insert(String::from("message"), serde_json::from_str(&text).unwrap_or_else(|_| json!(text)));
+    if let Some(client) = client {
+        data.insert(String::from("client"), json!(client.to_string()));
+    }
+    match render_template(template, &data) {
+        Value::String(text) => Some(Message::Text(text)),
+        message => Some(Message::Text(message.to_string())),
+    }
+}
@@ -0,0 +1,31 @@
+use crate::inheritance::attach_template;
+use crate::inheritance::detach_template;
+use crate::model::*;
+use crate::reactive::*;
+
+relation_behaviour!(
+    HttpInheritsTemplate,
+    HttpInheritsTemplateFactory,
+    HttpInheritsTemplateFsm,
+    HttpInheritsTemplateBehaviourTransitions,
+    HttpInheritsTemplateValidator
+);
+
+behaviour_validator!(HttpInheritsTemplateValidator, ReactiveRelationInstance);
+
+impl BehaviourInit<ReactiveRelationInstance> for HttpInheritsTemplateBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveRelationInstance> for HttpInheritsTemplateBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        attach_template(self.reactive_instance.outbound.id, self.reactive_instance.inbound.clone());
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        detach_template(self.reactive_instance.outbound.id);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveRelationInstance> for HttpInheritsTemplateBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveRelationInstance> for HttpInheritsTemplateBehaviourTransitions {}
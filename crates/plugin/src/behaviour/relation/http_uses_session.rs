@@ -0,0 +1,31 @@
+use crate::model::*;
+use crate::reactive::*;
+use crate::session::attach_session;
+use crate::session::detach_session;
+
+relation_behaviour!(
+    HttpUsesSession,
+    HttpUsesSessionFactory,
+    HttpUsesSessionFsm,
+    HttpUsesSessionBehaviourTransitions,
+    HttpUsesSessionValidator
+);
+
+behaviour_validator!(HttpUsesSessionValidator, ReactiveRelationInstance);
+
+impl BehaviourInit<ReactiveRelationInstance> for HttpUsesSessionBehaviourTransitions {}
+
+impl BehaviourConnect<ReactiveRelationInstance> for HttpUsesSessionBehaviourTransitions {
+    fn connect(&self) -> Result<(), BehaviourConnectFailed> {
+        attach_session(self.reactive_instance.outbound.id, self.reactive_instance.inbound.clone());
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), BehaviourDisconnectFailed> {
+        detach_session(self.reactive_instance.outbound.id);
+        Ok(())
+    }
+}
+
+impl BehaviourShutdown<ReactiveRelationInstance> for HttpUsesSessionBehaviourTransitions {}
+impl BehaviourTransitions<ReactiveRelationInstance> for HttpUsesSessionBehaviourTransitions {}
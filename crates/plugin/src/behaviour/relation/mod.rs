@@ -0,0 +1,2 @@
+pub mod http_inherits_template;
+pub mod http_uses_session;
@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use lazy_static::lazy_static;
+use log::error;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::config::get_config;
+use crate::hashing::hash_body;
+
+/// The request headers which differ for each request and must not be part of the cache key.
+const PER_REQUEST_HEADERS: [&str; 6] = ["idempotency-key", "x-request-id", "x-correlation-id", "request-id", "traceparent", "tracestate"];
+
+/// A response which has been stored in the cache.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub status: u16,
+    pub headers: Value,
+    /// The body is stored separately by the disk store.
+    #[serde(skip)]
+    pub body: Vec<u8>,
+    pub stored_at: SystemTime,
+    pub max_age: Duration,
+    /// The duration after the max-age in which the stale response is served while it is refreshed.
+    #[serde(default)]
+    pub stale_while_revalidate: Duration,
+    /// The request headers named by the Vary header of the response by lower case name with the values they had in the
+    /// request. Missing headers are null.
+    #[serde(default)]
+    pub vary: Map<String, Value>,
+}
+
+impl CachedResponse {
+    /// Returns true, if the response is younger than its max-age.
+    pub fn is_fresh(&self) -> bool {
+        self.stored_at.elapsed().map(|age| age < self.max_age).unwrap_or(false)
+    }
+
+    /// Returns true, if the response may be served while it is refreshed.
+    pub fn is_usable(&self) -> bool {
+        self.stored_at
+            .elapsed()
+            .map(|age| age < self.max_age + self.stale_while_revalidate)
+            .unwrap_or(false)
+    }
+
+    /// Returns true, if the request headers have the values the response has been negotiated on.
+    pub fn matches(&self, request_headers: &Map<String, Value>) -> bool {
+        self.vary.iter().all(|(name, value)| header_value(request_headers, name) == value)
+    }
+}
+
+/// Returns the values of the request headers which are named by the Vary header of the response. Returns none, if the
+/// response varies on anything else than request headers and must not be stored.
+pub fn vary(response_headers: &Value, request_headers: &Map<String, Value>) -> Option<Map<String, Value>> {
+    let mut vary = Map::new();
+    let Some(response_headers) = response_headers.as_object() else {
+        return Some(vary);
+    };
+    let vary_headers = response_headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("vary"))
+        .filter_map(|(_, vary_header)| vary_header.as_str());
+    for name in vary_headers.flat_map(|vary_header| vary_header.split(',')) {
+        let name = name.trim().to_ascii_lowercase();
+        if name == "*" {
+            return None;
+        }
+        if !name.is_empty() {
+            let value = header_value(request_headers, &name).clone();
+            vary.insert(name, value);
+        }
+    }
+    Some(vary)
+}
+
+fn header_value<'a>(headers: &'a Map<String, Value>, name: &str) -> &'a Value {
+    headers
+        .iter()
+        .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value)
+        .unwrap_or(&Value::Null)
+}
+
+/// The directives of a Cache-Control header which are relevant for a private cache.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CacheControl {
+    pub no_store: bool,
+    pub no_cache: bool,
+    pub max_age: Option<u64>,
+    pub stale_while_revalidate: Option<u64>,
+}
+
+impl From<&str> for CacheControl {
+    fn from(cache_control: &str) -> Self {
+        let mut directives = CacheControl::default();
+        for directive in cache_control.split(',') {
+            let (name, value) = directive.split_once('=').unwrap_or((directive, ""));
+            match name.trim().to_ascii_lowercase().as_str() {
+                "no-store" => directives.no_store = true,
+                "no-cache" => directives.no_cache = true,
+                "max-age" => directives.max_age = value.trim().trim_matches('"').parse().ok(),
+                "stale-while-revalidate" => directives.stale_while_revalidate = value.trim().trim_matches('"').parse().ok(),
+                _ => {}
+            }
+        }
+        directives
+    }
+}
+
+impl CacheControl {
+    /// Returns the duration the response may be served from the cache or none, if the response must not be stored.
+    ///
+    /// Responses without max-age are not stored because their freshness is unknown.
+    pub fn cache_duration(&self) -> Option<Duration> {
+        if self.no_store || self.no_cache {
+            return None;
+        }
+        self.max_age.filter(|max_age| *max_age > 0).map(Duration::from_secs)
+    }
+}
+
+/// Stores the cached responses by key.
+pub trait CacheStore: Send + Sync {
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    fn store(&self, key: &str, response: CachedResponse);
+}
+
+/// Keeps the responses in memory. Expired responses are evicted when a response is stored.
+#[derive(Default)]
+pub struct MemoryCacheStore {
+    responses: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl CacheStore for MemoryCacheStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.responses.lock().unwrap().get(key).cloned()
+    }
+
+    fn store(&self, key: &str, response: CachedResponse) {
+        let mut responses = self.responses.lock().unwrap();
+        responses.retain(|_, cached_response| cached_response.is_usable());
+        responses.insert(key.to_string(), response);
+    }
+}
+
+/// Keeps the responses in a directory, so that they survive restarts and large bodies don't live in memory.
+///
+/// Each response consists of a metadata file and a body file which are named by the hash of the key. If the bodies
+/// exceed the maximum size, expired responses are evicted first and then the oldest responses.
+///
+/// The metadata of the stored responses and the sizes of their bodies are indexed in memory, so that the directory is
+/// only read once. The index also serializes the access to the files.
+pub struct DiskCacheStore {
+    pub path: PathBuf,
+    pub max_size: u64,
+    index: Mutex<HashMap<String, (CachedResponse, u64)>>,
+}
+
+impl DiskCacheStore {
+    pub fn new(path: PathBuf, max_size: u64) -> Self {
+        let index = DiskCacheStore::read_index(&path);
+        DiskCacheStore {
+            path,
+            max_size,
+            index: Mutex::new(index),
+        }
+    }
+
+    fn file_name(key: &str) -> String {
+        hash_body("sha256", key.as_bytes()).unwrap_or_default()
+    }
+
+    /// Reads the metadata of all stored responses with the size of their bodies.
+    fn read_index(path: &Path) -> HashMap<String, (CachedResponse, u64)> {
+        let Ok(dir) = fs::read_dir(path) else {
+            return HashMap::new();
+        };
+        dir.filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let metadata_path = entry.path();
+                if metadata_path.extension()? != "json" {
+                    return None;
+                }
+                let name = metadata_path.file_stem()?.to_string_lossy().to_string();
+                let response = serde_json::from_slice(&fs::read(&metadata_path).ok()?).ok()?;
+                let size = fs::metadata(path.join(format!("{}.body", name))).ok()?.len();
+                Some((name, (response, size)))
+            })
+            .collect()
+    }
+
+    fn remove(&self, index: &mut HashMap<String, (CachedResponse, u64)>, name: &str) {
+        index.remove(name);
+        let _ = fs::remove_file(self.path.join(format!("{}.json", name)));
+        let _ = fs::remove_file(self.path.join(format!("{}.body", name)));
+    }
+
+    fn evict(&self, index: &mut HashMap<String, (CachedResponse, u64)>, required: u64) {
+        let expired: Vec<String> = index
+            .iter()
+            .filter(|(_, (response, _))| !response.is_usable())
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in expired {
+            self.remove(index, &name);
+        }
+        let mut entries: Vec<(String, SystemTime, u64)> = index.iter().map(|(name, (response, size))| (name.clone(), response.stored_at, *size)).collect();
+        entries.sort_by_key(|(_, stored_at, _)| *stored_at);
+        let mut size: u64 = entries.iter().map(|(_, _, size)| size).sum();
+        for (name, _, entry_size) in entries.iter() {
+            if size + required <= self.max_size {
+                break;
+            }
+            self.remove(index, name);
+            size -= entry_size;
+        }
+    }
+}
+
+impl CacheStore for DiskCacheStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let name = DiskCacheStore::file_name(key);
+        let index = self.index.lock().unwrap();
+        let (response, _) = index.get(&name)?;
+        let mut response = response.clone();
+        response.body = fs::read(self.path.join(format!("{}.body", name))).ok()?;
+        Some(response)
+    }
+
+    fn store(&self, key: &str, response: CachedResponse) {
+        // Responses which would displace the whole cache are not stored
+        if response.body.len() as u64 > self.max_size {
+            return;
+        }
+        let mut index = self.index.lock().unwrap();
+        let name = DiskCacheStore::file_name(key);
+        self.remove(&mut index, &name);
+        self.evict(&mut index, response.body.len() as u64);
+        let result = fs::create_dir_all(&self.path)
+            .and_then(|_| fs::write(self.path.join(format!("{}.body", name)), &response.body))
+            .and_then(|_| fs::write(self.path.join(format!("{}.json", name)), json!(response).to_string()));
+        if let Err(e) = result {
+            error!("Failed to store the response in {}: {}", self.path.display(), e);
+            self.remove(&mut index, &name);
+            return;
+        }
+        let size = response.body.len() as u64;
+        index.insert(name, (CachedResponse { body: Vec::new(), ..response }, size));
+    }
+}
+
+lazy_static! {
+    static ref CACHE_STORE: Arc<dyn CacheStore> = create_cache_store();
+    /// The keys of the responses which are refreshed in the background.
+    static ref REVALIDATING: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+fn create_cache_store() -> Arc<dyn CacheStore> {
+    let config = &get_config().cache;
+    match config.store.as_str() {
+        "disk" => Arc::new(DiskCacheStore::new(PathBuf::from(&config.path), config.max_size)),
+        _ => Arc::new(MemoryCacheStore::default()),
+    }
+}
+
+/// Returns the key of a request. Requests with different headers are cached separately, except for the headers which
+/// identify a single request.
+pub fn cache_key(method: &str, url: &str, base_urls: &[String], request_headers: &Map<String, Value>) -> String {
+    let request_headers: Map<String, Value> = request_headers
+        .iter()
+        .map(|(name, value)| (name.to_ascii_lowercase(), value.clone()))
+        .filter(|(name, _)| !PER_REQUEST_HEADERS.contains(&name.as_str()))
+        .collect();
+    json!([method.to_ascii_uppercase(), url, base_urls, request_headers]).to_string()
+}
+
+pub fn get_cached_response(key: &str) -> Option<CachedResponse> {
+    CACHE_STORE.get(key)
+}
+
+pub fn store_response(key: String, response: CachedResponse) {
+    CACHE_STORE.store(&key, response);
+}
+
+/// Marks the response as being refreshed. Returns false, if the response is already being refreshed.
+pub fn begin_revalidation(key: &str) -> bool {
+    REVALIDATING.lock().unwrap().insert(key.to_string())
+}
+
+pub fn end_revalidation(key: &str) {
+    REVALIDATING.lock().unwrap().remove(key);
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn cached_response(body: &[u8], age: Duration, max_age: Duration) -> CachedResponse {
+        CachedResponse {
+            status: 200,
+            headers: json!({}),
+            body: body.to_vec(),
+            stored_at: SystemTime::now() - age,
+            max_age,
+            stale_while_revalidate: Duration::ZERO,
+            vary: Map::new(),
+        }
+    }
+
+    fn headers(headers: Value) -> Map<String, Value> {
+        headers.as_object().cloned().unwrap_or_default()
+    }
+
+    #[test]
+    fn parses_cache_control() {
+        assert_eq!(
+            CacheControl::from("public, Max-Age=\"60\", stale-while-revalidate=30"),
+            CacheControl {
+                no_store: false,
+                no_cache: false,
+                max_age: Some(60),
+                stale_while_revalidate: Some(30),
+            }
+        );
+        assert!(CacheControl::from("no-store").no_store);
+        assert!(CacheControl::from("private, no-cache").no_cache);
+        assert_eq!(CacheControl::from("max-age=soon").max_age, None);
+        assert_eq!(CacheControl::from(""), CacheControl::default());
+    }
+
+    #[test]
+    fn only_responses_with_max_age_are_stored() {
+        assert_eq!(CacheControl::from("max-age=60").cache_duration(), Some(Duration::from_secs(60)));
+        assert_eq!(CacheControl::from("max-age=0").cache_duration(), None);
+        assert_eq!(CacheControl::from("max-age=60, no-cache").cache_duration(), None);
+        assert_eq!(CacheControl::from("max-age=60, no-store").cache_duration(), None);
+        assert_eq!(CacheControl::from("public").cache_duration(), None);
+    }
+
+    #[test]
+    fn stale_responses_are_usable_while_revalidating() {
+        let fresh = cached_response(b"", Duration::from_secs(10), Duration::from_secs(60));
+        assert!(fresh.is_fresh());
+        assert!(fresh.is_usable());
+        let mut stale = cached_response(b"", Duration::from_secs(90), Duration::from_secs(60));
+        assert!(!stale.is_fresh());
+        assert!(!stale.is_usable());
+        stale.stale_while_revalidate = Duration::from_secs(60);
+        assert!(stale.is_usable());
+    }
+
+    #[test]
+    fn requests_are_cached_without_per_request_headers() {
+        let first = cache_key("get", "https://example.com/", &[], &headers(json!({"Accept": "text/html", "Idempotency-Key": "1"})));
+        let second = cache_key("GET", "https://example.com/", &[], &headers(json!({"accept": "text/html", "traceparent": "00-1"})));
+        let other = cache_key("GET", "https://example.com/", &[], &headers(json!({"Accept": "application/json"})));
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn responses_are_served_to_requests_with_the_same_varying_headers() {
+        let response_headers = json!({"vary": "Accept-Language, Accept"});
+        let request_headers = headers(json!({"Accept-Language": "de", "Accept": "text/html"}));
+        let mut response = cached_response(b"", Duration::ZERO, Duration::from_secs(60));
+        response.vary = vary(&response_headers, &request_headers).unwrap();
+        assert_eq!(json!(response.vary), json!({"accept-language": "de", "accept": "text/html"}));
+        assert!(response.matches(&headers(json!({"accept": "text/html", "accept-language": "de", "x-other": "1"}))));
+        assert!(!response.matches(&headers(json!({"Accept-Language": "en", "Accept": "text/html"}))));
+        assert!(!response.matches(&headers(json!({"Accept": "text/html"}))));
+        assert_eq!(vary(&json!({"vary": "*"}), &request_headers), None);
+        assert_eq!(vary(&json!({}), &request_headers), Some(Map::new()));
+    }
+
+    #[test]
+    fn evicts_the_oldest_responses_from_the_disk() {
+        let path = std::env::temp_dir().join(format!("cache-{}", Uuid::new_v4().simple()));
+        let store = DiskCacheStore::new(path.clone(), 10);
+        store.store("first", cached_response(b"12345", Duration::from_secs(2), Duration::from_secs(60)));
+        store.store("expired", cached_response(b"1", Duration::from_secs(90), Duration::from_secs(60)));
+        store.store("second", cached_response(b"12345", Duration::from_secs(1), Duration::from_secs(60)));
+        assert!(store.get("expired").is_none());
+        assert_eq!(store.get("first").map(|response| response.body), Some(b"12345".to_vec()));
+        store.store("third", cached_response(b"123", Duration::ZERO, Duration::from_secs(60)));
+        assert!(store.get("first").is_none());
+        assert!(store.get("second").is_some());
+        // The index is restored from the directory
+        let store = DiskCacheStore::new(path.clone(), 10);
+        assert_eq!(store.get("third").map(|response| response.body), Some(b"123".to_vec()));
+        assert!(store.get("first").is_none());
+        let _ = fs::remove_dir_all(path);
+    }
+}
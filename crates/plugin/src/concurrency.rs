@@ -0,0 +1,105 @@
+use std::net::Shutdown;
+use std::net::TcpStream;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// Applies the function to all items using at most max_concurrency threads at once.
+///
+/// The results are returned in the order of the items.
+pub fn map_concurrently<T, R, F>(items: &[T], max_concurrency: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(usize, &T) -> R + Sync,
+{
+    let next_index = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..max_concurrency.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next_index.fetch_add(1, Ordering::SeqCst);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let result = f(index, item);
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+    results.into_inner().unwrap().into_iter().flatten().collect()
+}
+
+/// Cancels a request which is in flight.
+///
+/// Cancelling shuts down the registered connections of the request, so that its blocking reads and writes fail
+/// immediately.
+#[derive(Clone, Default)]
+pub struct Cancellation(Arc<(Mutex<CancellationState>, Condvar)>);
+
+#[derive(Default)]
+struct CancellationState {
+    cancelled: bool,
+    connections: Vec<TcpStream>,
+}
+
+impl Cancellation {
+    pub fn cancel(&self) {
+        let (state, condvar) = &*self.0;
+        let mut state = state.lock().unwrap();
+        state.cancelled = true;
+        for connection in state.connections.drain(..) {
+            let _ = connection.shutdown(Shutdown::Both);
+        }
+        condvar.notify_all();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0 .0.lock().unwrap().cancelled
+    }
+
+    /// Sleeps for the given duration or until the request has been cancelled.
+    ///
+    /// Returns false, if the request has been cancelled.
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let (state, condvar) = &*self.0;
+        let state = state.lock().unwrap();
+        let (state, _) = condvar.wait_timeout_while(state, duration, |state| !state.cancelled).unwrap();
+        !state.cancelled
+    }
+
+    /// Registers a connection of the request. The connection is shut down immediately, if the request has already been
+    /// cancelled.
+    pub fn register_connection(&self, connection: &TcpStream) {
+        let Ok(connection) = connection.try_clone() else {
+            return;
+        };
+        let mut state = self.0 .0.lock().unwrap();
+        if state.cancelled {
+            let _ = connection.shutdown(Shutdown::Both);
+        } else {
+            state.connections.push(connection);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelling_wakes_up_a_sleeping_request() {
+        let cancellation = Cancellation::default();
+        assert!(!cancellation.is_cancelled());
+        assert!(cancellation.sleep(Duration::from_millis(1)));
+        let cancelled = cancellation.clone();
+        let sleeping = thread::spawn(move || cancelled.sleep(Duration::from_secs(60)));
+        cancellation.cancel();
+        assert!(!sleeping.join().unwrap());
+        assert!(cancellation.is_cancelled());
+    }
+}
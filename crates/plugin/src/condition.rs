@@ -0,0 +1,68 @@
+use serde_json::Value;
+
+const OPERATORS: [&str; 6] = [">=", "<=", "==", "!=", ">", "<"];
+
+/// Checks the condition which has to be fulfilled by the payload before the request is sent.
+///
+/// The condition is either a list of required fields or a simple expression like `temperature > 20` or
+/// `/device/state == "ready"`. An expression without operator requires the field to be truthy. Fields are
+/// given by name, by path (`device.state`) or by JSON pointer (`/device/state`).
+///
+/// Returns the reason, if the condition is not fulfilled.
+pub fn check_condition(condition: &Value, payload: &Value) -> Result<(), String> {
+    match condition {
+        Value::Array(required_fields) => {
+            for required_field in required_fields.iter().filter_map(Value::as_str) {
+                if get_field(payload, required_field).map(Value::is_null).unwrap_or(true) {
+                    return Err(format!("The required field {} is missing", required_field));
+                }
+            }
+            Ok(())
+        }
+        Value::String(expression) if !expression.trim().is_empty() => check_expression(expression.trim(), payload),
+        _ => Ok(()),
+    }
+}
+
+fn check_expression(expression: &str, payload: &Value) -> Result<(), String> {
+    let Some((position, operator)) = OPERATORS
+        .iter()
+        .filter_map(|operator| expression.find(operator).map(|position| (position, *operator)))
+        .min_by_key(|(position, operator)| (*position, usize::MAX - operator.len()))
+    else {
+        return match get_field(payload, expression) {
+            Some(Value::Null) | Some(Value::Bool(false)) | None => Err(format!("The condition {} is not fulfilled", expression)),
+            Some(_) => Ok(()),
+        };
+    };
+    let field = expression[..position].trim();
+    let literal = expression[position + operator.len()..].trim();
+    // Literals which are not valid JSON are compared as strings
+    let expected = serde_json::from_str(literal).unwrap_or_else(|_| Value::String(literal.to_string()));
+    let actual = get_field(payload, field).cloned().unwrap_or(Value::Null);
+    let fulfilled = match operator {
+        "==" => actual == expected,
+        "!=" => actual != expected,
+        _ => match (actual.as_f64(), expected.as_f64()) {
+            (Some(actual), Some(expected)) => match operator {
+                ">=" => actual >= expected,
+                "<=" => actual <= expected,
+                ">" => actual > expected,
+                _ => actual < expected,
+            },
+            _ => false,
+        },
+    };
+    if fulfilled {
+        Ok(())
+    } else {
+        Err(format!("The condition {} is not fulfilled", expression))
+    }
+}
+
+fn get_field<'a>(payload: &'a Value, field: &str) -> Option<&'a Value> {
+    if field.starts_with('/') {
+        return payload.pointer(field);
+    }
+    field.split('.').try_fold(payload, |value, name| value.get(name))
+}
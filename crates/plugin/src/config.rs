@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use std::fs;
+
+use lazy_static::lazy_static;
+use log::error;
+use serde::Deserialize;
+
+/// The location of the plugin configuration relative to the working directory of the runtime.
+const CONFIG_PATH: &str = "config/http.toml";
+
+/// The address on which the embedded servers listen, unless the settings of the port specify another one.
+const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1";
+
+/// The plugin-wide configuration. Missing sections and values fall back to their defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct HttpPluginConfig {
+    /// The maximum number of simultaneous outbound requests of all behaviours. Zero means unlimited.
+    pub max_concurrent_requests: usize,
+    pub ssrf: SsrfConfig,
+    pub secrets: SecretsConfig,
+    pub default_headers: Vec<DefaultHeadersConfig>,
+    pub cache: CacheConfig,
+    pub outbox: OutboxConfig,
+    pub audit: AuditConfig,
+    pub access_log: AccessLogConfig,
+    pub traffic_stream: TrafficStreamConfig,
+    pub health: HealthConfig,
+    pub metrics: MetricsConfig,
+    pub dns: DnsConfig,
+    pub tunnel: TunnelConfig,
+    pub server_limits: ServerLimitsConfig,
+    /// The settings of the embedded servers by port.
+    pub servers: Vec<ServerConfig>,
+}
+
+impl HttpPluginConfig {
+    /// Returns the address on which the embedded server of the port listens. Without settings only local clients can
+    /// connect.
+    pub fn get_bind_address(&self, port: u16) -> &str {
+        self.servers
+            .iter()
+            .find(|server| server.port == port && !server.bind_address.is_empty())
+            .map(|server| server.bind_address.as_str())
+            .unwrap_or(DEFAULT_BIND_ADDRESS)
+    }
+}
+
+/// The plugin-wide protection against server-side request forgery. Applies in addition to the settings of the instances.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SsrfConfig {
+    /// If true, requests to private, loopback and link-local addresses are blocked for all instances.
+    pub enabled: bool,
+    pub allowed_hosts: Vec<String>,
+    pub denied_hosts: Vec<String>,
+}
+
+/// The headers which are added to every request to a matching host.
+///
+/// ```toml
+/// [[default_headers]]
+/// host = "*.example.com"
+/// headers = { "X-Api-Key" = "${secret:EXAMPLE_API_KEY}" }
+/// ```
+///
+/// The referenced secret must be scoped to the host in the secrets file, otherwise the requests fail:
+///
+/// ```toml
+/// EXAMPLE_API_KEY = { value = "...", hosts = ["*.example.com"] }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DefaultHeadersConfig {
+    /// The host name pattern: `example.com` or `*.example.com`.
+    pub host: String,
+    pub headers: HashMap<String, String>,
+}
+
+/// The secret store which resolves `${secret:NAME}` references.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SecretsConfig {
+    /// The TOML file which contains the secrets.
+    pub path: String,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        SecretsConfig {
+            path: String::from("config/secrets.toml"),
+        }
+    }
+}
+
+/// The store of the response cache.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Either `memory` or `disk`.
+    pub store: String,
+    /// The directory of the disk store.
+    pub path: String,
+    /// The maximum size of the bodies in the disk store in bytes.
+    pub max_size: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            store: String::from("memory"),
+            path: String::from("cache/http"),
+            max_size: 100 * 1024 * 1024,
+        }
+    }
+}
+
+/// The durable queue of the notifications which haven't been delivered yet.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct OutboxConfig {
+    /// The directory which contains one queue per instance.
+    pub path: String,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        OutboxConfig {
+            path: String::from("outbox/http"),
+        }
+    }
+}
+
+/// The log of the recently sent requests.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// The maximum number of requests which are kept in memory. Zero disables the audit log.
+    pub capacity: usize,
+    /// If given, the requests are appended to this file as JSON lines.
+    pub path: String,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        AuditConfig {
+            capacity: 1000,
+            path: String::new(),
+        }
+    }
+}
+
+/// The log of the requests which have been served by the embedded servers.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct AccessLogConfig {
+    /// If true, the requests are logged by the logger of the runtime.
+    pub log: bool,
+    /// If given, the requests are appended to this file as JSON lines.
+    pub path: String,
+}
+
+impl Default for AccessLogConfig {
+    fn default() -> Self {
+        AccessLogConfig {
+            log: true,
+            path: String::new(),
+        }
+    }
+}
+
+/// The endpoint which streams the summaries of the requests as server-sent events.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct TrafficStreamConfig {
+    /// The port of the embedded server. Zero disables the traffic stream.
+    pub port: u16,
+    pub path: String,
+}
+
+impl Default for TrafficStreamConfig {
+    fn default() -> Self {
+        TrafficStreamConfig {
+            port: 0,
+            path: String::from("/traffic"),
+        }
+    }
+}
+
+/// The endpoint which reports the health of the plugin, for example for readiness probes.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// The port of the embedded server. Zero disables the endpoint.
+    pub port: u16,
+    pub path: String,
+    /// The time in seconds over which the error rates are calculated.
+    pub window: u64,
+    /// The error rate of the outbound requests above which the plugin is reported as unhealthy. Zero means unlimited.
+    pub max_error_rate: f64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        HealthConfig {
+            port: 0,
+            path: String::from("/health"),
+            window: 300,
+            max_error_rate: 0.0,
+        }
+    }
+}
+
+/// The endpoint which serves the metrics of the plugin in the text format of Prometheus.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// The port of the embedded server. Zero disables the endpoint.
+    pub port: u16,
+    pub path: String,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        MetricsConfig {
+            port: 0,
+            path: String::from("/metrics"),
+        }
+    }
+}
+
+/// The cache of the host name lookups.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct DnsConfig {
+    /// If true, the addresses are cached for the TTL of the records.
+    pub cache: bool,
+    /// The minimum time in seconds an address is cached, even if the TTL of the record is shorter.
+    pub min_ttl: u64,
+    /// The maximum time in seconds an address is cached, even if the TTL of the record is longer.
+    pub max_ttl: u64,
+}
+
+impl Default for DnsConfig {
+    fn default() -> Self {
+        DnsConfig {
+            cache: false,
+            min_ttl: 0,
+            max_ttl: 300,
+        }
+    }
+}
+
+/// The tunnel agents which are started by the tunnel behaviour.
+///
+/// The paths are only configurable here, so that instances can't start arbitrary programs.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct TunnelConfig {
+    /// The path of the ngrok agent.
+    pub ngrok: String,
+    /// The path of cloudflared.
+    pub cloudflared: String,
+}
+
+impl Default for TunnelConfig {
+    fn default() -> Self {
+        TunnelConfig {
+            ngrok: String::from("ngrok"),
+            cloudflared: String::from("cloudflared"),
+        }
+    }
+}
+
+/// The limits of all embedded servers.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ServerLimitsConfig {
+    /// The maximum size of a request body in bytes, which is read into memory. Routes which stream the body limit its size
+    /// themselves.
+    pub max_body_size: u64,
+    /// The number of threads of each embedded server which handle the requests. Streamed responses occupy a thread until
+    /// the stream ends.
+    pub workers: usize,
+    /// The number of requests which wait for a thread. Further requests are answered with 503.
+    pub queue_size: usize,
+}
+
+impl Default for ServerLimitsConfig {
+    fn default() -> Self {
+        ServerLimitsConfig {
+            max_body_size: 10 * 1024 * 1024,
+            workers: 8,
+            queue_size: 128,
+        }
+    }
+}
+
+/// The settings of the embedded server listening on a port. The certificate also applies to a WebSocket server on the
+/// port.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    pub port: u16,
+    /// The address on which the server listens, for example `0.0.0.0` for all IPv4 interfaces. Defaults to `127.0.0.1`.
+    pub bind_address: String,
+    /// The PEM file of the certificate chain. With a certificate and a private key the server only accepts HTTPS.
+    pub certificate: String,
+    /// The PEM file of the private key.
+    pub private_key: String,
+    /// If set, the certificate is ordered and renewed automatically.
+    pub acme: Option<AcmeConfig>,
+}
+
+impl ServerConfig {
+    pub fn has_tls(&self) -> bool {
+        !self.certificate.is_empty() && !self.private_key.is_empty()
+    }
+}
+
+/// The automatic certificates of an embedded server, which are ordered from an ACME certificate authority like Let's
+/// Encrypt. The domains are validated by HTTP-01 challenges.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct AcmeConfig {
+    pub domains: Vec<String>,
+    /// The contact urls of the account, for example mailto:admin@example.com.
+    pub contact: Vec<String>,
+    /// The directory url of the certificate authority.
+    pub directory: String,
+    /// The port of the embedded server which answers the challenges. The certificate authority connects to port 80, which
+    /// may be forwarded to another port. The server of the port must be bound to an address which is reachable by the
+    /// certificate authority.
+    pub challenge_port: u16,
+    /// The file of the account key, which is created with the first order.
+    pub account_key: String,
+    /// The number of days before the expiration at which the certificate is renewed.
+    pub renew_before_days: u64,
+}
+
+impl Default for AcmeConfig {
+    fn default() -> Self {
+        AcmeConfig {
+            domains: Vec::new(),
+            contact: Vec::new(),
+            directory: String::from("https://acme-v02.api.letsencrypt.org/directory"),
+            challenge_port: 80,
+            account_key: String::from("config/acme-account.key"),
+            renew_before_days: 30,
+        }
+    }
+}
+
+lazy_static! {
+    static ref CONFIG: HttpPluginConfig = load_config();
+}
+
+pub fn get_config() -> &'static HttpPluginConfig {
+    &CONFIG
+}
+
+fn load_config() -> HttpPluginConfig {
+    // The configuration file is optional
+    let Ok(config) = fs::read_to_string(CONFIG_PATH) else {
+        return HttpPluginConfig::default();
+    };
+    match toml::from_str(&config) {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Failed to parse {}: {}", CONFIG_PATH, e);
+            HttpPluginConfig::default()
+        }
+    }
+}
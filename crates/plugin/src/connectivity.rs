@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+use uuid::Uuid;
+
+lazy_static! {
+    /// The state of the connectivity checks by instance id.
+    static ref CONNECTIVITY: RwLock<HashMap<Uuid, bool>> = RwLock::new(HashMap::new());
+}
+
+pub fn set_online(id: Uuid, online: bool) {
+    CONNECTIVITY.write().unwrap().insert(id, online);
+}
+
+pub fn remove_connectivity_check(id: Uuid) {
+    CONNECTIVITY.write().unwrap().remove(&id);
+}
+
+/// Returns false, if any connectivity check reports that the network is unavailable.
+///
+/// Without connectivity checks the network is assumed to be available.
+pub fn is_online() -> bool {
+    CONNECTIVITY.read().unwrap().values().all(|online| *online)
+}
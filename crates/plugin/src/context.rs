@@ -0,0 +1,19 @@
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+
+use crate::plugins::plugin_context::PluginContext;
+
+lazy_static! {
+    /// The plugin context for behaviours which need access to the managers of the runtime.
+    static ref PLUGIN_CONTEXT: RwLock<Option<Arc<dyn PluginContext>>> = RwLock::new(None);
+}
+
+pub fn set_plugin_context(context: Option<Arc<dyn PluginContext>>) {
+    *PLUGIN_CONTEXT.write().unwrap() = context;
+}
+
+pub fn get_plugin_context() -> Option<Arc<dyn PluginContext>> {
+    PLUGIN_CONTEXT.read().unwrap().clone()
+}
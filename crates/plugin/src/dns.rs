@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::io;
+use std::io::Read;
+use std::net::IpAddr;
+use std::net::SocketAddr;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+use trust_dns_resolver::Resolver;
+
+use crate::config::get_config;
+use crate::ssrf::UrlPolicy;
+
+struct ResolvedHost {
+    addresses: Vec<IpAddr>,
+    valid_until: Instant,
+}
+
+lazy_static! {
+    /// The addresses by host name. The TTL of the records is clamped to the configured bounds.
+    static ref HOSTS: Mutex<HashMap<String, ResolvedHost>> = Mutex::new(HashMap::new());
+}
+
+/// Resolves host names and measures the duration of the last lookup. Together with the TLS connector of the agent or,
+/// for plain HTTP, the body of the request the durations of the last connect and the last TLS handshake are measured as
+/// well.
+///
+/// If the DNS cache is enabled, the addresses are cached until their TTL has expired. Otherwise the system resolver is
+/// used. If a url policy is given, hosts which are not allowed at the addresses they resolve to can't be connected to.
+#[derive(Clone, Default)]
+pub struct TimedResolver {
+    last_lookup_micros: Arc<AtomicU64>,
+    /// The end of the last lookup, at which the agent starts connecting.
+    resolved_at: Arc<Mutex<Option<Instant>>>,
+    last_connect_micros: Arc<AtomicU64>,
+    last_handshake_micros: Arc<AtomicU64>,
+    url_policy: Option<Arc<UrlPolicy>>,
+}
+
+impl TimedResolver {
+    pub fn with_policy(url_policy: UrlPolicy) -> Self {
+        TimedResolver {
+            url_policy: Some(Arc::new(url_policy)),
+            ..TimedResolver::default()
+        }
+    }
+
+    pub fn last_lookup(&self) -> Duration {
+        Duration::from_micros(self.last_lookup_micros.load(Ordering::SeqCst))
+    }
+
+    pub fn last_connect(&self) -> Duration {
+        Duration::from_micros(self.last_connect_micros.load(Ordering::SeqCst))
+    }
+
+    pub fn last_handshake(&self) -> Duration {
+        Duration::from_micros(self.last_handshake_micros.load(Ordering::SeqCst))
+    }
+
+    /// Records that the connection to the resolved addresses has been established.
+    pub fn record_connected(&self) {
+        if let Some(resolved_at) = *self.resolved_at.lock().unwrap() {
+            self.last_connect_micros.store(resolved_at.elapsed().as_micros() as u64, Ordering::SeqCst);
+        }
+    }
+
+    pub fn record_handshake(&self, duration: Duration) {
+        self.last_handshake_micros.store(duration.as_micros() as u64, Ordering::SeqCst);
+    }
+
+    /// Wraps the body of a plain HTTP request, which the agent starts reading as soon as the connection has been
+    /// established and the request headers have been written. There is no TLS handshake.
+    ///
+    /// A pooled connection is not resolved again, so the connect is only recorded, if the host has been resolved after
+    /// the request has been started.
+    pub fn time_connect<R: Read>(&self, body: R, started: Instant) -> ConnectTimingReader<R> {
+        ConnectTimingReader {
+            body,
+            resolver: self.clone(),
+            started: Some(started),
+        }
+    }
+}
+
+pub struct ConnectTimingReader<R> {
+    body: R,
+    resolver: TimedResolver,
+    started: Option<Instant>,
+}
+
+impl<R: Read> Read for ConnectTimingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(started) = self.started.take() {
+            let resolved_at = *self.resolver.resolved_at.lock().unwrap();
+            if resolved_at.map(|resolved_at| resolved_at >= started).unwrap_or(false) {
+                self.resolver.record_connected();
+                self.resolver.record_handshake(Duration::ZERO);
+            }
+        }
+        self.body.read(buf)
+    }
+}
+
+impl ureq::Resolver for TimedResolver {
+    fn resolve(&self, netloc: &str) -> io::Result<Vec<SocketAddr>> {
+        let started = Instant::now();
+        let addresses = resolve(netloc);
+        self.last_lookup_micros.store(started.elapsed().as_micros() as u64, Ordering::SeqCst);
+        *self.resolved_at.lock().unwrap() = Some(Instant::now());
+        let addresses = addresses?;
+        if let Some(url_policy) = &self.url_policy {
+            let (host, _) = split_netloc(netloc)?;
+            let ip_addresses: Vec<IpAddr> = addresses.iter().map(|address| address.ip()).collect();
+            url_policy
+                .check_addresses(host, &ip_addresses)
+                .map_err(|e| io::Error::new(io::ErrorKind::PermissionDenied, e))?;
+        }
+        Ok(addresses)
+    }
+}
+
+/// Resolves the host and port, by the DNS cache if it is enabled.
+pub fn resolve(netloc: &str) -> io::Result<Vec<SocketAddr>> {
+    if get_config().dns.cache {
+        resolve_cached(netloc)
+    } else {
+        netloc.to_socket_addrs().map(|addresses| addresses.collect())
+    }
+}
+
+/// Connects to the first address of the host which accepts the connection within the timeout.
+///
+/// The host is resolved like the requests of the agents do, so that only addresses allowed by the url policy are
+/// connected to.
+pub fn connect_checked(host: &str, port: u16, url_policy: &UrlPolicy, timeout: Duration) -> Result<TcpStream, String> {
+    let resolver = TimedResolver::with_policy(url_policy.clone());
+    let netloc = if host.contains(':') && !host.starts_with('[') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    };
+    let addresses = ureq::Resolver::resolve(&resolver, &netloc).map_err(|e| format!("Failed to resolve {}: {}", host, e))?;
+    let mut error = format!("Failed to resolve {}", host);
+    for address in addresses {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => error = format!("Failed to connect to {}: {}", address, e),
+        }
+    }
+    Err(error)
+}
+
+/// Removes all cached addresses. Returns the number of removed host names.
+pub fn flush_dns_cache() -> usize {
+    let mut hosts = HOSTS.lock().unwrap();
+    let flushed = hosts.len();
+    hosts.clear();
+    flushed
+}
+
+fn resolve_cached(netloc: &str) -> io::Result<Vec<SocketAddr>> {
+    let (host, port) = split_netloc(netloc)?;
+    if let Ok(address) = host.parse::<IpAddr>() {
+        return Ok(vec![SocketAddr::new(address, port)]);
+    }
+    let to_socket_addrs = |addresses: &[IpAddr]| addresses.iter().map(|address| SocketAddr::new(*address, port)).collect();
+    if let Some(resolved_host) = HOSTS.lock().unwrap().get(host) {
+        if resolved_host.valid_until > Instant::now() {
+            return Ok(to_socket_addrs(&resolved_host.addresses));
+        }
+    }
+    let resolved_host = lookup_host(host)?;
+    let addresses = to_socket_addrs(&resolved_host.addresses);
+    HOSTS.lock().unwrap().insert(host.to_string(), resolved_host);
+    Ok(addresses)
+}
+
+/// Splits `host:port` or `[address]:port` into the host without brackets and the port.
+fn split_netloc(netloc: &str) -> io::Result<(&str, u16)> {
+    netloc
+        .rsplit_once(':')
+        .and_then(|(host, port)| port.parse::<u16>().ok().map(|port| (host.trim_start_matches('[').trim_end_matches(']'), port)))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid address {}", netloc)))
+}
+
+fn lookup_host(host: &str) -> io::Result<ResolvedHost> {
+    let config = &get_config().dns;
+    let resolver = Resolver::from_system_conf().map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+    let lookup = resolver.lookup_ip(host).map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+    let now = Instant::now();
+    let ttl = lookup
+        .valid_until()
+        .saturating_duration_since(now)
+        .clamp(Duration::from_secs(config.min_ttl), Duration::from_secs(config.max_ttl.max(config.min_ttl)));
+    Ok(ResolvedHost {
+        addresses: lookup.iter().collect(),
+        valid_until: now + ttl,
+    })
+}
@@ -0,0 +1,57 @@
+use hmac::Hmac;
+use hmac::Mac;
+use md5::Md5;
+use sha2::Digest;
+use sha2::Sha256;
+
+/// Computes the hash of a body which is read in chunks.
+///
+/// Supported algorithms are sha256, md5 and crc32.
+pub enum BodyHasher {
+    Sha256(Sha256),
+    Md5(Md5),
+    Crc32(crc32fast::Hasher),
+}
+
+impl BodyHasher {
+    /// Returns none, if the algorithm is unknown.
+    pub fn new(algorithm: &str) -> Option<Self> {
+        match algorithm.to_lowercase().as_str() {
+            "sha256" => Some(BodyHasher::Sha256(Sha256::new())),
+            "md5" => Some(BodyHasher::Md5(Md5::new())),
+            "crc32" => Some(BodyHasher::Crc32(crc32fast::Hasher::new())),
+            _ => None,
+        }
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        match self {
+            BodyHasher::Sha256(hasher) => hasher.update(chunk),
+            BodyHasher::Md5(hasher) => hasher.update(chunk),
+            BodyHasher::Crc32(hasher) => hasher.update(chunk),
+        }
+    }
+
+    /// Returns the hex encoded hash.
+    pub fn finalize(self) -> String {
+        match self {
+            BodyHasher::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            BodyHasher::Md5(hasher) => format!("{:x}", hasher.finalize()),
+            BodyHasher::Crc32(hasher) => format!("{:08x}", hasher.finalize()),
+        }
+    }
+}
+
+/// Computes the HMAC-SHA256 of the data.
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Returns the hex encoded hash of the body or none, if the algorithm is unknown.
+pub fn hash_body(algorithm: &str, body: &[u8]) -> Option<String> {
+    let mut hasher = BodyHasher::new(algorithm)?;
+    hasher.update(body);
+    Some(hasher.finalize())
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use chrono::Utc;
+use lazy_static::lazy_static;
+use sha2::Digest;
+use sha2::Sha256;
+use url::Url;
+use uuid::Uuid;
+
+use crate::hashing::hmac_sha256;
+use crate::model::PropertyInstanceGetter;
+use crate::model::ReactiveEntityInstance;
+use crate::model_http::HttpProperties::HAWK_ID;
+use crate::model_http::HttpProperties::HAWK_KEY;
+use crate::secrets::resolve_secrets;
+
+lazy_static! {
+    /// The difference between the clock of the server and the local clock in seconds by instance id.
+    static ref CLOCK_OFFSETS: RwLock<HashMap<Uuid, i64>> = RwLock::new(HashMap::new());
+}
+
+/// The Hawk credentials of an instance. The MAC is computed with SHA-256.
+#[derive(Clone)]
+pub struct HawkCredentials {
+    /// The id of the instance which keeps track of the clock offset.
+    pub instance_id: Uuid,
+    pub id: String,
+    pub key: String,
+}
+
+impl HawkCredentials {
+    /// Returns none, if the instance doesn't use Hawk authentication.
+    pub fn from_instance(reactive_instance: &ReactiveEntityInstance) -> Option<Self> {
+        let id = reactive_instance.as_string(HAWK_ID).filter(|id| !id.is_empty())?;
+        Some(HawkCredentials {
+            instance_id: reactive_instance.id,
+            id: resolve_secrets(&id),
+            key: resolve_secrets(&reactive_instance.as_string(HAWK_KEY).unwrap_or_default()),
+        })
+    }
+
+    /// Returns the value of the Authorization header for the request.
+    ///
+    /// Each header has a new nonce. The payload is covered by the MAC, if given with its content type.
+    pub fn authorization(&self, method: &str, url: &Url, payload: Option<(&str, &[u8])>) -> String {
+        let offset = CLOCK_OFFSETS.read().unwrap().get(&self.instance_id).copied().unwrap_or(0);
+        let timestamp = Utc::now().timestamp() + offset;
+        let nonce = Uuid::new_v4().simple().to_string()[..12].to_string();
+        let resource = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let hash = payload.map(|(content_type, body)| payload_hash(content_type, body));
+        let normalized = format!(
+            "hawk.1.header\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n\n",
+            timestamp,
+            nonce,
+            method.to_uppercase(),
+            resource,
+            url.host_str().unwrap_or_default().to_lowercase(),
+            url.port_or_known_default().unwrap_or_default(),
+            hash.as_deref().unwrap_or_default()
+        );
+        let mac = base64::encode(hmac_sha256(self.key.as_bytes(), normalized.as_bytes()));
+        match hash {
+            Some(hash) => format!(r#"Hawk id="{}", ts="{}", nonce="{}", hash="{}", mac="{}""#, self.id, timestamp, nonce, hash, mac),
+            None => format!(r#"Hawk id="{}", ts="{}", nonce="{}", mac="{}""#, self.id, timestamp, nonce, mac),
+        }
+    }
+
+    /// Adopts the clock of the server, if it rejected the timestamp of the request.
+    ///
+    /// The timestamp of the server is only trusted if its MAC is valid. Returns true, if the request should be repeated.
+    pub fn synchronize(&self, www_authenticate: &str) -> bool {
+        let Some(attributes) = www_authenticate.trim().strip_prefix("Hawk ") else {
+            return false;
+        };
+        let attributes = parse_attributes(attributes);
+        let (Some(timestamp), Some(tsm)) = (attributes.get("ts"), attributes.get("tsm")) else {
+            return false;
+        };
+        let Ok(server_timestamp) = timestamp.parse::<i64>() else {
+            return false;
+        };
+        let expected_tsm = base64::encode(hmac_sha256(self.key.as_bytes(), format!("hawk.1.ts\n{}\n", timestamp).as_bytes()));
+        if &expected_tsm != tsm {
+            return false;
+        }
+        CLOCK_OFFSETS
+            .write()
+            .unwrap()
+            .insert(self.instance_id, server_timestamp - Utc::now().timestamp());
+        true
+    }
+}
+
+/// Computes the hash of the payload, which includes the content type without parameters.
+fn payload_hash(content_type: &str, body: &[u8]) -> String {
+    let content_type = content_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+    let mut hasher = Sha256::new();
+    hasher.update(format!("hawk.1.payload\n{}\n", content_type).as_bytes());
+    hasher.update(body);
+    hasher.update(b"\n");
+    base64::encode(hasher.finalize())
+}
+
+/// Parses the attributes of a Hawk header like `ts="1353832234", error="Stale timestamp"`.
+fn parse_attributes(attributes: &str) -> HashMap<String, String> {
+    let mut parsed = HashMap::new();
+    let mut rest = attributes;
+    while let Some((name, value)) = rest.split_once("=\"") {
+        let Some((value, remainder)) = value.split_once('"') else {
+            break;
+        };
+        parsed.insert(name.trim().trim_start_matches(',').trim().to_string(), value.to_string());
+        rest = remainder;
+    }
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credentials() -> HawkCredentials {
+        HawkCredentials {
+            instance_id: Uuid::new_v4(),
+            id: String::from("dh37fgj492je"),
+            key: String::from("werxhqb98rpaxn39848xrunpaw3489ruxnpa98w4rxn"),
+        }
+    }
+
+    #[test]
+    fn hashes_the_payload() {
+        let hash = "Yi9LfIIFRtBEPt74PVmbTF/xVAwPn7ub15ePICfgnuY=";
+        assert_eq!(payload_hash("text/plain", b"Thank you for flying Hawk"), hash);
+        assert_eq!(payload_hash("Text/Plain; charset=utf-8", b"Thank you for flying Hawk"), hash);
+    }
+
+    #[test]
+    fn parses_the_attributes() {
+        let attributes = parse_attributes(r#"ts="1353832234", tsm="2mw1eh/qXzl0wJZ/E6XvBhRMEJN7L3j8AyMA8eItEb0=", error="Stale timestamp""#);
+        assert_eq!(attributes.get("ts").map(String::as_str), Some("1353832234"));
+        assert_eq!(attributes.get("tsm").map(String::as_str), Some("2mw1eh/qXzl0wJZ/E6XvBhRMEJN7L3j8AyMA8eItEb0="));
+        assert_eq!(attributes.get("error").map(String::as_str), Some("Stale timestamp"));
+    }
+
+    #[test]
+    fn builds_the_authorization_header() {
+        let credentials = credentials();
+        let url = Url::parse("http://example.com:8000/resource/1?b=1&a=2").unwrap();
+        let authorization = credentials.authorization("post", &url, Some(("text/plain", b"Thank you for flying Hawk")));
+        let attributes = parse_attributes(authorization.strip_prefix("Hawk ").unwrap());
+        assert_eq!(attributes.get("id").map(String::as_str), Some("dh37fgj492je"));
+        assert_eq!(attributes.get("hash").map(String::as_str), Some("Yi9LfIIFRtBEPt74PVmbTF/xVAwPn7ub15ePICfgnuY="));
+        assert_eq!(attributes.get("nonce").map(String::len), Some(12));
+        let normalized = format!(
+            "hawk.1.header\n{}\n{}\nPOST\n/resource/1?b=1&a=2\nexample.com\n8000\n{}\n\n",
+            attributes["ts"], attributes["nonce"], attributes["hash"]
+        );
+        let mac = base64::encode(hmac_sha256(credentials.key.as_bytes(), normalized.as_bytes()));
+        assert_eq!(attributes.get("mac"), Some(&mac));
+        assert!(!credentials.authorization("GET", &url, None).contains("hash="));
+    }
+
+    #[test]
+    fn adopts_the_clock_of_the_server() {
+        let credentials = credentials();
+        assert!(!credentials.synchronize(r#"Hawk ts="1353832234", tsm="invalid", error="Stale timestamp""#));
+        assert!(!credentials.synchronize(r#"Basic realm="example""#));
+        assert!(credentials.synchronize(r#"Hawk ts="1353832234", tsm="2mw1eh/qXzl0wJZ/E6XvBhRMEJN7L3j8AyMA8eItEb0=", error="Stale timestamp""#));
+        let url = Url::parse("http://example.com/").unwrap();
+        let attributes = parse_attributes(credentials.authorization("GET", &url, None).strip_prefix("Hawk ").unwrap());
+        let timestamp: i64 = attributes["ts"].parse().unwrap();
+        assert!((timestamp - 1353832234).abs() <= 1);
+    }
+}
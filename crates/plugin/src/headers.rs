@@ -0,0 +1,164 @@
+use serde_json::Map;
+use serde_json::Value;
+use url::Url;
+
+use crate::config::get_config;
+use crate::placeholder::resolve_env;
+use crate::secrets::resolve_secrets_for_url;
+use crate::ssrf::matches_host_name;
+
+/// The headers which carry credentials.
+const CREDENTIAL_HEADERS: [&str; 5] = ["authorization", "proxy-authorization", "cookie", "x-api-key", "api-key"];
+
+/// Returns true, if the header carries credentials which must neither be logged nor sent to another host.
+pub fn is_credential_header(name: &str) -> bool {
+    CREDENTIAL_HEADERS.contains(&name.to_ascii_lowercase().as_str())
+}
+
+/// Sets the request headers including the default headers of the plugin configuration which match the host of the url.
+///
+/// The request headers of the instance override the default headers. Placeholders and the references to secrets are
+/// resolved. Fails, if a header references a secret which may not be sent to the host.
+pub fn set_request_headers(mut request: ureq::Request, url: &str, request_headers: &Map<String, Value>) -> Result<ureq::Request, String> {
+    if let Some(host) = Url::parse(url).ok().and_then(|url| url.host_str().map(String::from)) {
+        for default_headers in get_config().default_headers.iter() {
+            if !matches_host_name(&default_headers.host, &host) {
+                continue;
+            }
+            for (request_header, value) in default_headers.headers.iter() {
+                if !request_headers.keys().any(|name| name.eq_ignore_ascii_case(request_header)) {
+                    request = request.set(request_header, &resolve_secrets_for_url(&resolve_env(value), url)?);
+                }
+            }
+        }
+    }
+    for (request_header, value) in request_headers.iter() {
+        if let Some(value) = value.as_str() {
+            request = request.set(request_header.as_ref(), &resolve_secrets_for_url(&resolve_env(value), url)?);
+        }
+    }
+    Ok(request)
+}
+
+/// A link of a Link header as specified by RFC 8288.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkHeaderValue {
+    pub href: String,
+    /// The lower case relation types.
+    pub relations: Vec<String>,
+    /// The other parameters by lower case name.
+    pub parameters: Vec<(String, String)>,
+}
+
+/// Parses the links of a Link header. Commas and semicolons inside of the target and quoted strings are respected.
+pub fn parse_link_header(link_header: &str) -> Vec<LinkHeaderValue> {
+    let mut links = Vec::new();
+    let mut rest = link_header.trim_start();
+    while let Some(link) = rest.strip_prefix('<') {
+        let Some((href, mut parameters)) = link.split_once('>') else {
+            break;
+        };
+        let mut link = LinkHeaderValue {
+            href: href.trim().to_string(),
+            relations: Vec::new(),
+            parameters: Vec::new(),
+        };
+        loop {
+            parameters = parameters.trim_start();
+            let Some(parameter) = parameters.strip_prefix(';') else {
+                break;
+            };
+            let parameter = parameter.trim_start();
+            let name_end = parameter.find(|c: char| c == '=' || c == ';' || c == ',').unwrap_or(parameter.len());
+            let name = parameter[..name_end].trim().to_ascii_lowercase();
+            parameters = &parameter[name_end..];
+            let mut value = String::new();
+            if let Some(unparsed_value) = parameters.strip_prefix('=') {
+                let unparsed_value = unparsed_value.trim_start();
+                if let Some(quoted) = unparsed_value.strip_prefix('"') {
+                    let mut escaped = false;
+                    let mut end = quoted.len();
+                    for (position, c) in quoted.char_indices() {
+                        match c {
+                            _ if escaped => {
+                                value.push(c);
+                                escaped = false;
+                            }
+                            '\\' => escaped = true,
+                            '"' => {
+                                end = position + 1;
+                                break;
+                            }
+                            _ => value.push(c),
+                        }
+                    }
+                    parameters = &quoted[end..];
+                } else {
+                    let value_end = unparsed_value.find(|c: char| c == ';' || c == ',').unwrap_or(unparsed_value.len());
+                    value = unparsed_value[..value_end].trim().to_string();
+                    parameters = &unparsed_value[value_end..];
+                }
+            }
+            // Only the first occurrence of rel is respected
+            if name == "rel" {
+                if link.relations.is_empty() {
+                    link.relations = value.split_whitespace().map(|relation| relation.to_ascii_lowercase()).collect();
+                }
+            } else if !name.is_empty() {
+                link.parameters.push((name, value));
+            }
+        }
+        links.push(link);
+        rest = parameters.trim_start().strip_prefix(',').unwrap_or("").trim_start();
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_links() {
+        let links = parse_link_header("<https://example.com/?page=2>; rel=\"next last\", </style.css>;rel=preload;as=style");
+        assert_eq!(
+            links,
+            vec![
+                LinkHeaderValue {
+                    href: String::from("https://example.com/?page=2"),
+                    relations: vec![String::from("next"), String::from("last")],
+                    parameters: Vec::new(),
+                },
+                LinkHeaderValue {
+                    href: String::from("/style.css"),
+                    relations: vec![String::from("preload")],
+                    parameters: vec![(String::from("as"), String::from("style"))],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn respects_separators_in_targets_and_quoted_strings() {
+        let links = parse_link_header("<https://example.com/a,b;c>; title=\"Chapter 1; \\\"Intro\\\", part 2\"; REL=Chapter; rel=ignored");
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].href, "https://example.com/a,b;c");
+        assert_eq!(links[0].relations, vec![String::from("chapter")]);
+        assert_eq!(links[0].parameters, vec![(String::from("title"), String::from("Chapter 1; \"Intro\", part 2"))]);
+    }
+
+    #[test]
+    fn ignores_malformed_links() {
+        assert!(parse_link_header("").is_empty());
+        assert!(parse_link_header("https://example.com/; rel=next").is_empty());
+        assert_eq!(parse_link_header("</a>; rel=next, garbage").len(), 1);
+        assert_eq!(parse_link_header("</a>; crossorigin")[0].parameters, vec![(String::from("crossorigin"), String::new())]);
+    }
+
+    #[test]
+    fn detects_credential_headers() {
+        assert!(is_credential_header("Authorization"));
+        assert!(is_credential_header("X-API-Key"));
+        assert!(!is_credential_header("Accept"));
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+use log::error;
+use serde_json::json;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::access_log::subscribe_access_log;
+use crate::audit::subscribe_requests;
+use crate::config::get_config;
+use crate::limiter::get_request_limiter_status;
+use crate::outbox::count_outbox_payloads;
+use crate::server::get_server_status;
+use crate::server::register_route;
+use crate::server::unregister_route;
+use crate::server::OutgoingResponse;
+use crate::server::Route;
+use crate::server::RouteHandler;
+use crate::websocket::count_queued_websocket_messages;
+use crate::websocket_server::get_websocket_server_status;
+use crate::worker::count_workers;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+
+/// The name of the worker which counts the outcomes of the requests.
+const HEALTH_WORKER_NAME: &str = "health";
+
+/// The interval in which the worker collects the outcomes of the requests.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The outcomes of the requests within the window. True means the request failed.
+#[derive(Default)]
+struct Outcomes {
+    outbound: VecDeque<(Instant, bool)>,
+    inbound: VecDeque<(Instant, bool)>,
+}
+
+lazy_static! {
+    static ref HEALTH_ROUTE_ID: Uuid = Uuid::new_v4();
+    static ref OUTCOMES: Mutex<Outcomes> = Mutex::new(Outcomes::default());
+}
+
+/// Starts the endpoint which reports the health of the plugin, if a port is configured.
+///
+/// The endpoint answers with 503, if the error rate of the outbound requests exceeds the configured maximum.
+pub fn start_health() {
+    let config = &get_config().health;
+    if config.port == 0 {
+        return;
+    }
+    start_worker(*HEALTH_ROUTE_ID, HEALTH_WORKER_NAME, |signal| {
+        let requests = subscribe_requests();
+        let served_requests = subscribe_access_log();
+        while signal.sleep(POLL_INTERVAL) {
+            // Outbound requests fail without status or with a client or server error, served requests with a server error
+            let outbound = drain(&requests, |entry| entry.status.map(|status| status >= 400).unwrap_or(true));
+            let inbound = drain(&served_requests, |entry| entry.status >= 500);
+            let window = Duration::from_secs(get_config().health.window);
+            let mut outcomes = OUTCOMES.lock().unwrap();
+            outcomes.outbound.extend(outbound);
+            outcomes.inbound.extend(inbound);
+            expire(&mut outcomes.outbound, window);
+            expire(&mut outcomes.inbound, window);
+        }
+    });
+    let route = Route {
+        id: *HEALTH_ROUTE_ID,
+        method: Some(String::from("GET")),
+        path: config.path.clone(),
+        handler: RouteHandler::Buffered(Arc::new(|_| {
+            let (healthy, health) = get_health();
+            OutgoingResponse::json(if healthy { 200 } else { 503 }, &health)
+        })),
+        cors: None,
+    };
+    if let Err(e) = register_route(config.port, route) {
+        error!("Failed to start the health endpoint: {}", e);
+    }
+}
+
+pub fn stop_health() {
+    unregister_route(*HEALTH_ROUTE_ID);
+    stop_worker(*HEALTH_ROUTE_ID, HEALTH_WORKER_NAME);
+    *OUTCOMES.lock().unwrap() = Outcomes::default();
+}
+
+/// Returns whether the plugin is healthy and the report of the running behaviours, the queues, the error rates and
+/// the embedded servers.
+fn get_health() -> (bool, Value) {
+    let config = &get_config().health;
+    let (outbound, inbound) = {
+        let mut outcomes = OUTCOMES.lock().unwrap();
+        let window = Duration::from_secs(config.window);
+        expire(&mut outcomes.outbound, window);
+        expire(&mut outcomes.inbound, window);
+        (error_rate(&outcomes.outbound), error_rate(&outcomes.inbound))
+    };
+    let healthy = config.max_error_rate <= 0.0 || outbound["error_rate"].as_f64().unwrap_or(0.0) <= config.max_error_rate;
+    let (in_flight, waiting) = get_request_limiter_status();
+    let health = json!({
+        "status": if healthy { "ok" } else { "unhealthy" },
+        "workers": count_workers(),
+        "queues": {
+            "requests_in_flight": in_flight,
+            "requests_waiting": waiting,
+            "websocket_messages": count_queued_websocket_messages(),
+            "outbox_payloads": count_outbox_payloads()
+        },
+        "errors": {
+            "window": config.window,
+            "outbound": outbound,
+            "inbound": inbound
+        },
+        "servers": get_server_status(),
+        "websocket_servers": get_websocket_server_status()
+    });
+    (healthy, health)
+}
+
+fn drain<T>(receiver: &Receiver<T>, is_failed: impl Fn(&T) -> bool) -> Vec<(Instant, bool)> {
+    receiver.try_iter().map(|entry| (Instant::now(), is_failed(&entry))).collect()
+}
+
+fn expire(outcomes: &mut VecDeque<(Instant, bool)>, window: Duration) {
+    while outcomes.front().map(|(at, _)| at.elapsed() > window).unwrap_or(false) {
+        outcomes.pop_front();
+    }
+}
+
+fn error_rate(outcomes: &VecDeque<(Instant, bool)>) -> Value {
+    let requests = outcomes.len();
+    let failed = outcomes.iter().filter(|(_, failed)| *failed).count();
+    let error_rate = if requests == 0 { 0.0 } else { failed as f64 / requests as f64 };
+    json!({
+        "requests": requests,
+        "failed": failed,
+        "error_rate": error_rate
+    })
+}
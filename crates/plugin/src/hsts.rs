@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+use log::debug;
+use url::Host;
+use url::Url;
+
+/// A known HSTS host as specified by RFC 6797.
+#[derive(Debug)]
+struct HstsPolicy {
+    expires_at: Instant,
+    include_subdomains: bool,
+}
+
+lazy_static! {
+    /// The known HSTS hosts by lower case domain name. The store is shared between all instances.
+    static ref HSTS_HOSTS: RwLock<HashMap<String, HstsPolicy>> = RwLock::new(HashMap::new());
+}
+
+/// Records the Strict-Transport-Security header of a response of the url.
+///
+/// The header is only respected on https responses of domain names. A max-age of zero removes the host.
+pub fn record_hsts(url: &str, strict_transport_security: Option<&str>) {
+    let Some(strict_transport_security) = strict_transport_security else {
+        return;
+    };
+    let Ok(url) = Url::parse(url) else {
+        return;
+    };
+    let Some(Host::Domain(domain)) = url.host() else {
+        return;
+    };
+    if url.scheme() != "https" {
+        return;
+    }
+    let Some((max_age, include_subdomains)) = parse_strict_transport_security(strict_transport_security) else {
+        return;
+    };
+    let domain = domain.to_ascii_lowercase();
+    let mut hsts_hosts = HSTS_HOSTS.write().unwrap();
+    if max_age == 0 {
+        hsts_hosts.remove(&domain);
+        return;
+    }
+    hsts_hosts.insert(
+        domain,
+        HstsPolicy {
+            // Bounded, so that absurd values don't overflow the clock
+            expires_at: Instant::now() + Duration::from_secs(max_age.min(u32::MAX as u64)),
+            include_subdomains,
+        },
+    );
+}
+
+/// Upgrades http urls of known HSTS hosts to https. Other urls are returned unchanged.
+pub fn upgrade_to_https(url: &str) -> String {
+    let Ok(mut parsed_url) = Url::parse(url) else {
+        return url.to_string();
+    };
+    let Some(Host::Domain(domain)) = parsed_url.host() else {
+        return url.to_string();
+    };
+    if parsed_url.scheme() != "http" || !is_hsts_host(&domain.to_ascii_lowercase()) {
+        return url.to_string();
+    }
+    // The url crate omits the default port, so port 80 becomes the default port of https
+    if parsed_url.set_scheme("https").is_err() {
+        return url.to_string();
+    }
+    debug!("Upgraded {} to https because of HSTS", url);
+    parsed_url.to_string()
+}
+
+/// Returns true, if the domain or a superdomain which includes subdomains is a known HSTS host.
+fn is_hsts_host(domain: &str) -> bool {
+    let hsts_hosts = HSTS_HOSTS.read().unwrap();
+    let now = Instant::now();
+    let mut superdomain = domain;
+    let mut exact = true;
+    loop {
+        if let Some(policy) = hsts_hosts.get(superdomain) {
+            if policy.expires_at > now && (exact || policy.include_subdomains) {
+                return true;
+            }
+        }
+        let Some((_, parent)) = superdomain.split_once('.') else {
+            return false;
+        };
+        superdomain = parent;
+        exact = false;
+    }
+}
+
+/// Returns the max-age and whether subdomains are included. Headers without a valid max-age are ignored.
+fn parse_strict_transport_security(strict_transport_security: &str) -> Option<(u64, bool)> {
+    let mut max_age = None;
+    let mut include_subdomains = false;
+    for directive in strict_transport_security.split(';') {
+        let (name, value) = match directive.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+            None => (directive.trim(), None),
+        };
+        if name.eq_ignore_ascii_case("max-age") {
+            max_age = Some(value?.parse::<u64>().ok()?);
+        } else if name.eq_ignore_ascii_case("includesubdomains") {
+            include_subdomains = true;
+        }
+    }
+    max_age.map(|max_age| (max_age, include_subdomains))
+}
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+use serde_json::Map;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::model::PropertyInstanceGetter;
+use crate::model::ReactiveEntityInstance;
+
+lazy_static! {
+    /// The templates by the id of the inheriting instance. Maintained by the relation behaviour http_inherits_template.
+    static ref TEMPLATES: RwLock<HashMap<Uuid, Arc<ReactiveEntityInstance>>> = RwLock::new(HashMap::new());
+}
+
+pub fn attach_template(id: Uuid, template: Arc<ReactiveEntityInstance>) {
+    TEMPLATES.write().unwrap().insert(id, template);
+}
+
+pub fn detach_template(id: Uuid) {
+    TEMPLATES.write().unwrap().remove(&id);
+}
+
+pub fn get_template(id: Uuid) -> Option<Arc<ReactiveEntityInstance>> {
+    TEMPLATES.read().unwrap().get(&id).cloned()
+}
+
+/// Returns the string property of the instance or, if it is empty, the property of the template.
+pub fn inherit_string(reactive_instance: &ReactiveEntityInstance, property: &str) -> Option<String> {
+    reactive_instance
+        .as_string(property)
+        .filter(|value| !value.is_empty())
+        .or_else(|| get_template(reactive_instance.id)?.as_string(property))
+        .filter(|value| !value.is_empty())
+}
+
+/// Returns the array property of the instance or, if it is empty, the property of the template.
+pub fn inherit_array(reactive_instance: &ReactiveEntityInstance, property: &str) -> Vec<Value> {
+    let value = reactive_instance.as_array(property).unwrap_or_default();
+    if !value.is_empty() {
+        return value;
+    }
+    get_template(reactive_instance.id)
+        .and_then(|template| template.as_array(property))
+        .unwrap_or_default()
+}
+
+/// Returns the object property of the template merged with the object property of the instance, which takes precedence.
+///
+/// The keys are compared case-insensitive, because the objects contain headers.
+pub fn inherit_object(reactive_instance: &ReactiveEntityInstance, property: &str) -> Option<Map<String, Value>> {
+    let value = reactive_instance.as_object(property)?;
+    let Some(mut merged) = get_template(reactive_instance.id).and_then(|template| template.as_object(property)) else {
+        return Some(value);
+    };
+    for (key, value) in value.into_iter() {
+        merged.retain(|name, _| !name.eq_ignore_ascii_case(&key));
+        merged.insert(key, value);
+    }
+    Some(merged)
+}
@@ -22,9 +22,56 @@ use crate::plugins::Plugin;
 use crate::plugins::PluginDependency;
 use crate::plugins::PluginLoadingError;
 
+pub mod access_log;
+pub mod acme;
+pub mod audit;
 pub mod behaviour;
+pub mod cache;
+pub mod concurrency;
+pub mod condition;
+pub mod config;
+pub mod connectivity;
+pub mod context;
+pub mod dns;
+pub mod hashing;
+pub mod hawk;
+pub mod headers;
+pub mod health;
+pub mod hsts;
+pub mod inheritance;
+pub mod limiter;
+pub mod load_balancer;
+pub mod metrics;
+pub mod multipart;
+pub mod openapi;
+pub mod outbox;
+pub mod pausable;
+pub mod placeholder;
 pub mod plugin;
+pub mod postman;
+pub mod progress;
 pub mod providers;
+pub mod rate_limit;
+pub mod reconnect;
+pub mod response;
+pub mod robots;
+pub mod route_auth;
+pub mod route_cors;
+pub mod route_rate_limit;
+pub mod schedule;
+pub mod secrets;
+pub mod server;
+pub mod service_discovery;
+pub mod session;
+pub mod ssrf;
+pub mod template;
+pub mod throttle;
+pub mod tls;
+pub mod traffic;
+pub mod web_resource_provider;
+pub mod websocket;
+pub mod websocket_server;
+pub mod worker;
 
 pub static PLUGIN_NAME: &str = env!("CARGO_PKG_NAME");
 pub static PLUGIN_DESCRIPTION: &str = env!("CARGO_PKG_DESCRIPTION");
@@ -0,0 +1,77 @@
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::sync::Condvar;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+
+use crate::config::get_config;
+
+/// The priority of requests which don't specify a priority.
+pub const DEFAULT_PRIORITY: i64 = 0;
+
+#[derive(Default)]
+struct RequestLimiterState {
+    /// The number of requests in flight.
+    in_flight: usize,
+    /// The waiting requests ordered by descending priority and, within the same priority, by arrival.
+    waiting: BTreeSet<(Reverse<i64>, u64)>,
+    next_ticket: u64,
+}
+
+/// Limits the number of simultaneous outbound requests of all behaviours.
+struct RequestLimiter {
+    state: Mutex<RequestLimiterState>,
+    released: Condvar,
+}
+
+lazy_static! {
+    static ref REQUEST_LIMITER: RequestLimiter = RequestLimiter {
+        state: Mutex::new(RequestLimiterState::default()),
+        released: Condvar::new(),
+    };
+}
+
+/// Allows sending a request as long as the permit is alive.
+pub struct RequestPermit {
+    limited: bool,
+}
+
+impl Drop for RequestPermit {
+    fn drop(&mut self) {
+        if !self.limited {
+            return;
+        }
+        REQUEST_LIMITER.state.lock().unwrap().in_flight -= 1;
+        // Every waiting request checks whether it is the next one
+        REQUEST_LIMITER.released.notify_all();
+    }
+}
+
+/// Blocks until the request may be sent. The ceiling is configured by `max_concurrent_requests`, zero means unlimited.
+///
+/// If the ceiling is hit, requests with a higher priority are sent first.
+pub fn acquire_request_permit(priority: i64) -> RequestPermit {
+    let max_concurrent_requests = get_config().max_concurrent_requests;
+    if max_concurrent_requests == 0 {
+        return RequestPermit { limited: false };
+    }
+    let mut state = REQUEST_LIMITER.state.lock().unwrap();
+    let ticket = (Reverse(priority), state.next_ticket);
+    state.next_ticket += 1;
+    state.waiting.insert(ticket);
+    while state.in_flight >= max_concurrent_requests || state.waiting.iter().next() != Some(&ticket) {
+        state = REQUEST_LIMITER.released.wait(state).unwrap();
+    }
+    state.waiting.remove(&ticket);
+    state.in_flight += 1;
+    // The next waiting request may be allowed as well
+    REQUEST_LIMITER.released.notify_all();
+    RequestPermit { limited: true }
+}
+
+/// Returns the number of requests in flight and the number of requests which are waiting for a permit.
+pub fn get_request_limiter_status() -> (usize, usize) {
+    let state = REQUEST_LIMITER.state.lock().unwrap();
+    (state.in_flight, state.waiting.len())
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+use uuid::Uuid;
+
+/// The number of consecutive failures after which a target is considered unhealthy.
+const FAILURE_THRESHOLD: u32 = 3;
+
+/// The duration in which an unhealthy target is not selected.
+const UNHEALTHY_DURATION: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+struct TargetHealth {
+    consecutive_failures: u32,
+    /// The exponentially weighted moving average of the latency.
+    latency: Option<Duration>,
+    unhealthy_until: Option<Instant>,
+}
+
+impl TargetHealth {
+    fn is_healthy(&self) -> bool {
+        self.unhealthy_until.map(|unhealthy_until| unhealthy_until <= Instant::now()).unwrap_or(true)
+    }
+}
+
+lazy_static! {
+    /// The health of the targets by base url. The health is shared between all instances.
+    static ref TARGETS: Mutex<HashMap<String, TargetHealth>> = Mutex::new(HashMap::new());
+    /// The round robin position by instance id.
+    static ref ROUND_ROBIN: Mutex<HashMap<Uuid, usize>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadBalancing {
+    RoundRobin,
+    LeastLatency,
+}
+
+impl From<&str> for LoadBalancing {
+    fn from(load_balancing: &str) -> Self {
+        match load_balancing {
+            "least_latency" => LoadBalancing::LeastLatency,
+            _ => LoadBalancing::RoundRobin,
+        }
+    }
+}
+
+/// Selects one of the base urls for the next request of the given instance.
+///
+/// Unhealthy targets are skipped unless all targets are unhealthy.
+pub fn select_target(id: Uuid, base_urls: &[String], load_balancing: LoadBalancing) -> Option<String> {
+    if base_urls.is_empty() {
+        return None;
+    }
+    let targets = TARGETS.lock().unwrap();
+    let healthy: Vec<&String> = base_urls
+        .iter()
+        .filter(|base_url| targets.get(*base_url).map(TargetHealth::is_healthy).unwrap_or(true))
+        .collect();
+    let candidates: Vec<&String> = if healthy.is_empty() { base_urls.iter().collect() } else { healthy };
+    let target = match load_balancing {
+        LoadBalancing::RoundRobin => {
+            let mut round_robin = ROUND_ROBIN.lock().unwrap();
+            let position = round_robin.entry(id).or_insert(0);
+            let target = candidates[*position % candidates.len()];
+            *position = position.wrapping_add(1);
+            target
+        }
+        // Targets without measurement are preferred in order to measure them
+        LoadBalancing::LeastLatency => candidates
+            .iter()
+            .min_by_key(|base_url| targets.get(**base_url).and_then(|target| target.latency).unwrap_or_default())
+            .copied()?,
+    };
+    Some(target.clone())
+}
+
+/// Records a successful request to the target.
+pub fn report_success(base_url: &str, latency: Duration) {
+    let mut targets = TARGETS.lock().unwrap();
+    let target = targets.entry(base_url.to_string()).or_default();
+    target.consecutive_failures = 0;
+    target.unhealthy_until = None;
+    target.latency = Some(match target.latency {
+        Some(average) => (average * 4 + latency) / 5,
+        None => latency,
+    });
+}
+
+/// Records a failed request to the target.
+pub fn report_failure(base_url: &str) {
+    let mut targets = TARGETS.lock().unwrap();
+    let target = targets.entry(base_url.to_string()).or_default();
+    target.consecutive_failures += 1;
+    if target.consecutive_failures >= FAILURE_THRESHOLD {
+        target.unhealthy_until = Some(Instant::now() + UNHEALTHY_DURATION);
+    }
+}
+
+/// Joins the base url and the path.
+pub fn join_url(base_url: &str, path: &str) -> String {
+    if path.is_empty() {
+        return base_url.to_string();
+    }
+    format!("{}/{}", base_url.trim_end_matches('/'), path.trim_start_matches('/'))
+}
@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use log::error;
+use uuid::Uuid;
+
+use crate::access_log::subscribe_access_log;
+use crate::audit::subscribe_requests;
+use crate::config::get_config;
+use crate::limiter::get_request_limiter_status;
+use crate::outbox::count_outbox_payloads;
+use crate::server::get_server_status;
+use crate::server::register_route;
+use crate::server::unregister_route;
+use crate::server::OutgoingResponse;
+use crate::server::Route;
+use crate::server::RouteHandler;
+use crate::websocket::count_queued_websocket_messages;
+use crate::websocket_server::get_websocket_server_status;
+use crate::worker::count_workers;
+use crate::worker::start_worker;
+use crate::worker::stop_worker;
+
+/// The name of the worker which collects the metrics of the requests.
+const METRICS_WORKER_NAME: &str = "metrics";
+
+/// The interval in which the worker collects the metrics of the requests.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The upper bounds of the buckets of the request durations in seconds.
+const DURATION_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// The cumulative counts of the observed durations.
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(DURATION_BUCKETS.iter()) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+/// The metrics of the requests since the metrics endpoint has been started.
+#[derive(Default)]
+struct Metrics {
+    /// The sent requests by behaviour, method and status.
+    requests: BTreeMap<(String, String, String), u64>,
+    request_durations: BTreeMap<String, Histogram>,
+    /// The served requests by port, method and status.
+    served_requests: BTreeMap<(u16, String, u16), u64>,
+    served_request_durations: BTreeMap<u16, Histogram>,
+}
+
+lazy_static! {
+    static ref METRICS_ROUTE_ID: Uuid = Uuid::new_v4();
+    static ref METRICS: Mutex<Metrics> = Mutex::new(Metrics::default());
+}
+
+/// Starts the endpoint which serves the metrics in the text format of Prometheus, if a port is configured.
+pub fn start_metrics() {
+    let config = &get_config().metrics;
+    if config.port == 0 {
+        return;
+    }
+    start_worker(*METRICS_ROUTE_ID, METRICS_WORKER_NAME, |signal| {
+        let requests = subscribe_requests();
+        let served_requests = subscribe_access_log();
+        while signal.sleep(POLL_INTERVAL) {
+            let mut metrics = METRICS.lock().unwrap();
+            for entry in requests.try_iter() {
+                // Requests to unreachable servers have no status
+                let status = entry.status.map(|status| status.to_string()).unwrap_or_else(|| String::from("error"));
+                *metrics.requests.entry((entry.behaviour.clone(), entry.method, status)).or_insert(0) += 1;
+                metrics
+                    .request_durations
+                    .entry(entry.behaviour)
+                    .or_default()
+                    .observe(entry.duration_ms as f64 / 1000.0);
+            }
+            for entry in served_requests.try_iter() {
+                *metrics.served_requests.entry((entry.port, entry.method, entry.status)).or_insert(0) += 1;
+                metrics
+                    .served_request_durations
+                    .entry(entry.port)
+                    .or_default()
+                    .observe(entry.duration_ms as f64 / 1000.0);
+            }
+        }
+    });
+    let route = Route {
+        id: *METRICS_ROUTE_ID,
+        method: Some(String::from("GET")),
+        path: config.path.clone(),
+        handler: RouteHandler::Buffered(Arc::new(|_| OutgoingResponse {
+            status: 200,
+            headers: vec![(String::from("Content-Type"), String::from("text/plain; version=0.0.4; charset=utf-8"))],
+            body: render_metrics().into_bytes(),
+            stream: None,
+        })),
+        cors: None,
+    };
+    if let Err(e) = register_route(config.port, route) {
+        error!("Failed to start the metrics endpoint: {}", e);
+    }
+}
+
+pub fn stop_metrics() {
+    unregister_route(*METRICS_ROUTE_ID);
+    stop_worker(*METRICS_ROUTE_ID, METRICS_WORKER_NAME);
+    *METRICS.lock().unwrap() = Metrics::default();
+}
+
+/// Renders the counters of the requests and the current state of the workers, the queues and the embedded servers.
+fn render_metrics() -> String {
+    let mut output = String::new();
+    {
+        let metrics = METRICS.lock().unwrap();
+        header(&mut output, "http_plugin_requests_total", "counter", "The requests sent by the behaviours.");
+        for ((behaviour, method, status), count) in metrics.requests.iter() {
+            let labels = labels(&[("behaviour", behaviour), ("method", method), ("status", status)]);
+            let _ = writeln!(output, "http_plugin_requests_total{} {}", labels, count);
+        }
+        header(
+            &mut output,
+            "http_plugin_request_duration_seconds",
+            "histogram",
+            "The durations of the requests sent by the behaviours.",
+        );
+        for (behaviour, histogram) in metrics.request_durations.iter() {
+            histogram_samples(&mut output, "http_plugin_request_duration_seconds", &[("behaviour", behaviour)], histogram);
+        }
+        header(&mut output, "http_plugin_served_requests_total", "counter", "The requests served by the embedded servers.");
+        for ((port, method, status), count) in metrics.served_requests.iter() {
+            let labels = labels(&[("port", &port.to_string()), ("method", method), ("status", &status.to_string())]);
+            let _ = writeln!(output, "http_plugin_served_requests_total{} {}", labels, count);
+        }
+        header(
+            &mut output,
+            "http_plugin_served_request_duration_seconds",
+            "histogram",
+            "The durations of the requests served by the embedded servers.",
+        );
+        for (port, histogram) in metrics.served_request_durations.iter() {
+            histogram_samples(&mut output, "http_plugin_served_request_duration_seconds", &[("port", &port.to_string())], histogram);
+        }
+    }
+    let mut workers: Vec<(String, usize)> = count_workers().into_iter().collect();
+    workers.sort();
+    header(&mut output, "http_plugin_workers", "gauge", "The running workers by behaviour.");
+    for (behaviour, count) in workers {
+        let _ = writeln!(output, "http_plugin_workers{} {}", labels(&[("behaviour", &behaviour)]), count);
+    }
+    let (in_flight, waiting) = get_request_limiter_status();
+    gauge(&mut output, "http_plugin_requests_in_flight", "The outbound requests in flight.", in_flight);
+    gauge(
+        &mut output,
+        "http_plugin_requests_waiting",
+        "The outbound requests waiting for the concurrency limit.",
+        waiting,
+    );
+    gauge(
+        &mut output,
+        "http_plugin_websocket_queued_messages",
+        "The WebSocket messages waiting to be sent.",
+        count_queued_websocket_messages(),
+    );
+    gauge(&mut output, "http_plugin_outbox_payloads", "The payloads in the durable outboxes.", count_outbox_payloads());
+    header(&mut output, "http_plugin_server_routes", "gauge", "The routes of the running embedded servers.");
+    for status in get_server_status() {
+        let labels = labels(&[("port", &status.port.to_string()), ("tls", &status.tls.to_string())]);
+        let _ = writeln!(output, "http_plugin_server_routes{} {}", labels, status.routes);
+    }
+    header(&mut output, "http_plugin_websocket_server_routes", "gauge", "The routes of the running WebSocket servers.");
+    for status in get_websocket_server_status() {
+        let labels = labels(&[("port", &status.port.to_string()), ("tls", &status.tls.to_string())]);
+        let _ = writeln!(output, "http_plugin_websocket_server_routes{} {}", labels, status.routes);
+    }
+    output
+}
+
+fn header(output: &mut String, name: &str, metric_type: &str, help: &str) {
+    let _ = writeln!(output, "# HELP {} {}", name, help);
+    let _ = writeln!(output, "# TYPE {} {}", name, metric_type);
+}
+
+fn gauge(output: &mut String, name: &str, help: &str, value: usize) {
+    header(output, name, "gauge", help);
+    let _ = writeln!(output, "{} {}", name, value);
+}
+
+fn histogram_samples(output: &mut String, name: &str, base_labels: &[(&str, &str)], histogram: &Histogram) {
+    for (bound, count) in DURATION_BUCKETS.iter().zip(histogram.buckets.iter()) {
+        let bound = bound.to_string();
+        let mut bucket_labels = base_labels.to_vec();
+        bucket_labels.push(("le", &bound));
+        let _ = writeln!(output, "{}_bucket{} {}", name, labels(&bucket_labels), count);
+    }
+    let mut bucket_labels = base_labels.to_vec();
+    bucket_labels.push(("le", "+Inf"));
+    let _ = writeln!(output, "{}_bucket{} {}", name, labels(&bucket_labels), histogram.count);
+    let _ = writeln!(output, "{}_sum{} {}", name, labels(base_labels), histogram.sum);
+    let _ = writeln!(output, "{}_count{} {}", name, labels(base_labels), histogram.count);
+}
+
+/// Formats the labels of a sample. Backslashes, quotes and line feeds in the values are escaped.
+fn labels(labels: &[(&str, &str)]) -> String {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")))
+        .collect();
+    format!("{{{}}}", labels.join(","))
+}
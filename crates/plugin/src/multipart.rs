@@ -0,0 +1,302 @@
+use std::fmt;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+
+/// The maximum size of the headers of a part.
+const MAX_HEADER_SIZE: usize = 16384;
+
+const READ_SIZE: usize = 65536;
+
+/// The headers of a part of a multipart/form-data body.
+#[derive(Debug, Default)]
+pub struct Part {
+    pub name: String,
+    /// The file name or none, if the part is a form field.
+    pub file_name: Option<String>,
+    pub content_type: Option<String>,
+}
+
+/// The error of reading the body of a part.
+#[derive(Debug)]
+pub enum BodyError {
+    /// The body is larger than the maximum size.
+    TooLarge(u64),
+    Failed(String),
+}
+
+impl fmt::Display for BodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BodyError::TooLarge(max_size) => write!(f, "The part is larger than {} bytes", max_size),
+            BodyError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Reads a multipart/form-data body part by part without buffering the bodies of the parts.
+pub struct MultipartStream<'a> {
+    reader: &'a mut dyn Read,
+    /// The delimiter which precedes each part, including the line break of the previous part.
+    delimiter: Vec<u8>,
+    buffer: Vec<u8>,
+    eof: bool,
+    /// True, if the body of the current part has not been read yet.
+    in_body: bool,
+    finished: bool,
+}
+
+/// Returns the boundary of the Content-Type header or none, if the body is not multipart/form-data.
+pub fn get_boundary(content_type: &str) -> Option<String> {
+    let mut parameters = content_type.split(';');
+    if !parameters.next()?.trim().eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+    parameters
+        .filter_map(|parameter| parameter.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("boundary"))
+        .map(|(_, boundary)| boundary.trim().trim_matches('"').to_string())
+        .filter(|boundary| !boundary.is_empty())
+}
+
+impl<'a> MultipartStream<'a> {
+    pub fn new(reader: &'a mut dyn Read, boundary: &str) -> Self {
+        MultipartStream {
+            reader,
+            delimiter: format!("\r\n--{}", boundary).into_bytes(),
+            // The first delimiter is not preceded by a line break
+            buffer: b"\r\n".to_vec(),
+            eof: false,
+            in_body: false,
+            finished: false,
+        }
+    }
+
+    /// Reads more data into the buffer. Returns false at the end of the body.
+    fn fill(&mut self) -> Result<bool, String> {
+        if self.eof {
+            return Ok(false);
+        }
+        let mut chunk = vec![0; READ_SIZE];
+        let length = self.reader.read(&mut chunk).map_err(|e| format!("Failed to read the body: {}", e))?;
+        if length == 0 {
+            self.eof = true;
+            return Ok(false);
+        }
+        self.buffer.extend_from_slice(&chunk[..length]);
+        Ok(true)
+    }
+
+    /// Returns the headers of the next part or none after the last part. The body of the previous part is skipped, if it
+    /// has not been read.
+    pub fn next_part(&mut self) -> Result<Option<Part>, String> {
+        if self.in_body {
+            self.read_body(&mut io::sink(), 0).map_err(|e| e.to_string())?;
+        }
+        if self.finished {
+            return Ok(None);
+        }
+        // The preamble before the first delimiter is ignored
+        loop {
+            if let Some(position) = find(&self.buffer, &self.delimiter) {
+                self.buffer.drain(..position + self.delimiter.len());
+                break;
+            }
+            let keep = self.buffer.len().min(self.delimiter.len() - 1);
+            self.buffer.drain(..self.buffer.len() - keep);
+            if !self.fill()? {
+                return Err(String::from("Unexpected end of the multipart body"));
+            }
+        }
+        if !self.after_delimiter()? {
+            return Ok(None);
+        }
+        let header_end = loop {
+            if let Some(position) = find(&self.buffer, b"\r\n\r\n") {
+                break position;
+            }
+            if self.buffer.len() > MAX_HEADER_SIZE || !self.fill()? {
+                return Err(String::from("Invalid headers of a part"));
+            }
+        };
+        let headers = String::from_utf8_lossy(&self.buffer[..header_end]).to_string();
+        self.buffer.drain(..header_end + 4);
+        self.in_body = true;
+        Ok(Some(parse_part_headers(&headers)))
+    }
+
+    /// Consumes the line break after a delimiter. Returns false, if the delimiter was the closing delimiter.
+    fn after_delimiter(&mut self) -> Result<bool, String> {
+        while self.buffer.len() < 2 {
+            if !self.fill()? {
+                return Err(String::from("Unexpected end of the multipart body"));
+            }
+        }
+        if self.buffer.starts_with(b"--") {
+            self.finished = true;
+            return Ok(false);
+        }
+        // Transport padding is allowed before the line break
+        loop {
+            if let Some(position) = find(&self.buffer, b"\r\n") {
+                self.buffer.drain(..position + 2);
+                return Ok(true);
+            }
+            if self.buffer.len() > MAX_HEADER_SIZE || !self.fill()? {
+                return Err(String::from("Invalid delimiter"));
+            }
+        }
+    }
+
+    /// Writes the body of the current part and returns its size.
+    ///
+    /// Fails, if the body is larger than the maximum size. Zero means unlimited.
+    pub fn read_body(&mut self, writer: &mut dyn Write, max_size: u64) -> Result<u64, BodyError> {
+        if !self.in_body {
+            return Ok(0);
+        }
+        let mut size = 0;
+        let mut write = |data: &[u8]| -> Result<(), BodyError> {
+            size += data.len() as u64;
+            if max_size > 0 && size > max_size {
+                return Err(BodyError::TooLarge(max_size));
+            }
+            writer
+                .write_all(data)
+                .map_err(|e| BodyError::Failed(format!("Failed to write the part: {}", e)))
+        };
+        loop {
+            // The delimiter remains in the buffer for the next part
+            if let Some(position) = find(&self.buffer, &self.delimiter) {
+                write(&self.buffer[..position])?;
+                self.buffer.drain(..position);
+                self.in_body = false;
+                return Ok(size);
+            }
+            // The end of the buffer may be the beginning of the delimiter
+            let keep = self.buffer.len().min(self.delimiter.len() - 1);
+            let length = self.buffer.len() - keep;
+            write(&self.buffer[..length])?;
+            self.buffer.drain(..length);
+            if !self.fill().map_err(BodyError::Failed)? {
+                return Err(BodyError::Failed(String::from("Unexpected end of the multipart body")));
+            }
+        }
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || haystack.len() < needle.len() {
+        return None;
+    }
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn parse_part_headers(headers: &str) -> Part {
+    let mut part = Part::default();
+    for line in headers.split("\r\n") {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if name.trim().eq_ignore_ascii_case("content-type") {
+            part.content_type = Some(value.trim().to_string());
+        }
+        if !name.trim().eq_ignore_ascii_case("content-disposition") {
+            continue;
+        }
+        for parameter in value.split(';').skip(1) {
+            let Some((parameter, value)) = parameter.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_matches('"').to_string();
+            match parameter.trim().to_ascii_lowercase().as_str() {
+                "name" => part.name = value,
+                "filename" => part.file_name = Some(value),
+                _ => {}
+            }
+        }
+    }
+    part
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &[u8] = b"preamble\r\n--boundary\r\nContent-Disposition: form-data; name=\"field\"\r\n\r\nvalue\r\n--boundary  \r\nContent-Disposition: form-data; name=\"file\"; filename=\"data.txt\"\r\nContent-Type: text/plain\r\n\r\nline 1\r\n--not the boundary\r\nline 2\r\n--boundary--\r\nepilogue";
+
+    /// Returns the data in small chunks, so that delimiters are split between reads.
+    struct ChunkedReader {
+        data: &'static [u8],
+    }
+
+    impl Read for ChunkedReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let length = self.data.len().min(buf.len()).min(3);
+            buf[..length].copy_from_slice(&self.data[..length]);
+            self.data = &self.data[length..];
+            Ok(length)
+        }
+    }
+
+    #[test]
+    fn parses_the_boundary() {
+        assert_eq!(get_boundary("multipart/form-data; boundary=abc"), Some(String::from("abc")));
+        assert_eq!(get_boundary("Multipart/Form-Data; charset=utf-8; Boundary=\"a b\""), Some(String::from("a b")));
+        assert_eq!(get_boundary("multipart/form-data; boundary="), None);
+        assert_eq!(get_boundary("multipart/mixed; boundary=abc"), None);
+        assert_eq!(get_boundary("application/json"), None);
+    }
+
+    #[test]
+    fn parses_the_part_headers() {
+        let part = parse_part_headers("content-disposition: form-data; name=\"upload\"; filename=\"a.png\"\r\nContent-Type: image/png");
+        assert_eq!(part.name, "upload");
+        assert_eq!(part.file_name.as_deref(), Some("a.png"));
+        assert_eq!(part.content_type.as_deref(), Some("image/png"));
+    }
+
+    #[test]
+    fn reads_the_parts() {
+        let mut reader = ChunkedReader { data: BODY };
+        let mut stream = MultipartStream::new(&mut reader, "boundary");
+        let field = stream.next_part().unwrap().unwrap();
+        assert_eq!(field.name, "field");
+        assert_eq!(field.file_name, None);
+        let mut value = Vec::new();
+        assert_eq!(stream.read_body(&mut value, 0).unwrap(), 5);
+        assert_eq!(value, b"value");
+        let file = stream.next_part().unwrap().unwrap();
+        assert_eq!(file.file_name.as_deref(), Some("data.txt"));
+        let mut content = Vec::new();
+        stream.read_body(&mut content, 0).unwrap();
+        assert_eq!(content, b"line 1\r\n--not the boundary\r\nline 2");
+        assert!(stream.next_part().unwrap().is_none());
+    }
+
+    #[test]
+    fn skips_unread_bodies() {
+        let mut reader = BODY;
+        let mut stream = MultipartStream::new(&mut reader, "boundary");
+        stream.next_part().unwrap();
+        let file = stream.next_part().unwrap().unwrap();
+        assert_eq!(file.name, "file");
+        assert!(stream.next_part().unwrap().is_none());
+    }
+
+    #[test]
+    fn limits_the_size_of_the_parts() {
+        let mut reader = BODY;
+        let mut stream = MultipartStream::new(&mut reader, "boundary");
+        stream.next_part().unwrap();
+        assert!(matches!(stream.read_body(&mut io::sink(), 4), Err(BodyError::TooLarge(4))));
+    }
+
+    #[test]
+    fn fails_on_truncated_bodies() {
+        let mut reader: &[u8] = b"--boundary\r\nContent-Disposition: form-data; name=\"field\"\r\n\r\nvalue";
+        let mut stream = MultipartStream::new(&mut reader, "boundary");
+        stream.next_part().unwrap();
+        assert!(matches!(stream.read_body(&mut io::sink(), 0), Err(BodyError::Failed(_))));
+    }
+}
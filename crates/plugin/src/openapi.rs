@@ -0,0 +1,365 @@
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::model_http::COMPONENT_NAME_HTTP;
+use crate::model_http::COMPONENT_NAME_OPENAPI_OPERATION;
+use crate::model_http::EXTENSION_OPENAPI_OPERATION;
+use crate::model_http::NAMESPACE_HTTP;
+use crate::model_http::NAMESPACE_LOGICAL;
+
+const METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// An operation of an OpenAPI document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    pub type_name: String,
+    pub description: String,
+    pub method: String,
+    pub server_url: String,
+    pub path: String,
+    pub parameters: Vec<Parameter>,
+    /// The properties of the JSON request body, which are mapped to input properties.
+    #[serde(default)]
+    pub body_properties: Vec<BodyProperty>,
+    /// The properties of the JSON body of the successful response, which are mapped to output properties.
+    #[serde(default)]
+    pub response_properties: Vec<BodyProperty>,
+}
+
+/// A parameter of an OpenAPI operation which is mapped to an input property.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name: String,
+    /// The location of the parameter: path, query or header.
+    pub location: String,
+    pub property_name: String,
+    pub data_type: String,
+    pub description: String,
+}
+
+/// A top level property of the schema of a request or response body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BodyProperty {
+    pub name: String,
+    pub property_name: String,
+    pub data_type: String,
+    pub description: String,
+}
+
+impl Operation {
+    /// Returns the entity type of the operation in the same format as the type definitions of this plugin.
+    pub fn to_entity_type(&self, namespace: &str) -> Value {
+        let mut properties: Vec<Value> = self
+            .parameters
+            .iter()
+            .map(|parameter| {
+                json!({
+                    "name": parameter.property_name,
+                    "description": parameter.description,
+                    "data_type": parameter.data_type,
+                    "socket_type": "input"
+                })
+            })
+            .collect();
+        properties.extend(self.body_properties.iter().map(|body_property| {
+            json!({
+                "name": body_property.property_name,
+                "description": body_property.description,
+                "data_type": body_property.data_type,
+                "socket_type": "input"
+            })
+        }));
+        properties.extend(self.response_properties.iter().map(|response_property| {
+            json!({
+                "name": response_property.property_name,
+                "description": response_property.description,
+                "data_type": response_property.data_type,
+                "socket_type": "output",
+                "mutability": "immutable"
+            })
+        }));
+        json!({
+            "namespace": namespace,
+            "type_name": self.type_name,
+            "description": self.description,
+            "components": [
+                { "namespace": NAMESPACE_HTTP, "type_name": COMPONENT_NAME_HTTP },
+                { "namespace": NAMESPACE_LOGICAL, "type_name": "action" },
+                { "namespace": NAMESPACE_HTTP, "type_name": COMPONENT_NAME_OPENAPI_OPERATION }
+            ],
+            "properties": properties,
+            "extensions": [
+                { "namespace": NAMESPACE_HTTP, "type_name": EXTENSION_OPENAPI_OPERATION, "extension": self }
+            ]
+        })
+    }
+}
+
+/// Parses the operations of an OpenAPI 3 document in JSON format.
+pub fn parse_operations(spec: &Value) -> Result<Vec<Operation>, String> {
+    let is_openapi_3 = spec
+        .get("openapi")
+        .and_then(Value::as_str)
+        .map(|version| version.starts_with("3."))
+        .unwrap_or(false);
+    if !is_openapi_3 {
+        return Err(String::from("Only OpenAPI 3 documents are supported"));
+    }
+    let Some(paths) = spec.get("paths").and_then(Value::as_object) else {
+        return Err(String::from("The OpenAPI document has no paths"));
+    };
+    let server_url = spec.pointer("/servers/0/url").and_then(Value::as_str).unwrap_or_default().to_string();
+    let mut operations = Vec::new();
+    for (path, path_item) in paths.iter() {
+        let path_item = resolve(spec, path_item);
+        for method in METHODS {
+            let Some(operation) = path_item.get(method) else {
+                continue;
+            };
+            // Parameters of the operation override parameters of the path item with the same name and location
+            let mut parameters: Vec<Parameter> = Vec::new();
+            for parameter in parameters_of(path_item).chain(parameters_of(operation)) {
+                if let Some(parameter) = parse_parameter(resolve(spec, parameter)) {
+                    parameters.retain(|p| p.name != parameter.name || p.location != parameter.location);
+                    parameters.push(parameter);
+                }
+            }
+            let request_body = operation.get("requestBody").map(|request_body| resolve(spec, request_body));
+            let body_properties = request_body
+                .and_then(json_schema)
+                .map(|schema| body_properties(spec, schema, "body"))
+                .unwrap_or_default();
+            let response_properties = success_response(operation)
+                .map(|response| resolve(spec, response))
+                .and_then(json_schema)
+                .map(|schema| body_properties(spec, schema, "response"))
+                .unwrap_or_default();
+            let type_name = match operation.get("operationId").and_then(Value::as_str) {
+                Some(operation_id) => to_type_name(operation_id),
+                None => to_type_name(&format!("{} {}", method, path)),
+            };
+            let description = operation
+                .get("summary")
+                .or_else(|| operation.get("description"))
+                .and_then(Value::as_str)
+                .map(String::from)
+                .unwrap_or_else(|| format!("{} {}", method.to_uppercase(), path));
+            operations.push(Operation {
+                type_name,
+                description,
+                method: method.to_uppercase(),
+                server_url: server_url.clone(),
+                path: path.clone(),
+                parameters,
+                body_properties,
+                response_properties,
+            });
+        }
+    }
+    Ok(operations)
+}
+
+fn parameters_of(value: &Value) -> impl Iterator<Item = &Value> {
+    value.get("parameters").and_then(Value::as_array).into_iter().flatten()
+}
+
+fn parse_parameter(parameter: &Value) -> Option<Parameter> {
+    let name = parameter.get("name").and_then(Value::as_str)?;
+    let location = parameter.get("in").and_then(Value::as_str)?;
+    if location == "cookie" {
+        return None;
+    }
+    Some(Parameter {
+        name: name.to_string(),
+        location: location.to_string(),
+        // The location prefix prevents clashes with the properties of the component http
+        property_name: format!("{}_{}", location, to_type_name(name)),
+        data_type: to_data_type(parameter.get("schema").unwrap_or(&Value::Null)).to_string(),
+        description: parameter.get("description").and_then(Value::as_str).unwrap_or_default().to_string(),
+    })
+}
+
+fn to_data_type(schema: &Value) -> &'static str {
+    match schema.get("type").and_then(Value::as_str) {
+        Some("integer") | Some("number") => "number",
+        Some("string") => "string",
+        Some("boolean") => "bool",
+        Some("array") => "array",
+        Some("object") => "object",
+        _ => "any",
+    }
+}
+
+/// Returns the response of the lowest 2xx status code or else of the range 2XX.
+fn success_response(operation: &Value) -> Option<&Value> {
+    let responses = operation.get("responses").and_then(Value::as_object)?;
+    let mut statuses: Vec<&String> = responses
+        .keys()
+        .filter(|status| status.len() == 3 && status.starts_with('2') && status.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    statuses.sort();
+    statuses.first().and_then(|status| responses.get(*status)).or_else(|| responses.get("2XX"))
+}
+
+/// Returns the schema of the JSON content of a request body or a response.
+fn json_schema(body: &Value) -> Option<&Value> {
+    let content = body.get("content").and_then(Value::as_object)?;
+    content
+        .get("application/json")
+        .or_else(|| {
+            content
+                .iter()
+                .find(|(media_type, _)| media_type.ends_with("+json"))
+                .map(|(_, media_type_content)| media_type_content)
+        })?
+        .get("schema")
+}
+
+/// Maps the top level properties of an object schema to properties with the given prefix. Other schemas aren't mapped:
+/// the body is the payload or the result of the component http.
+fn body_properties(spec: &Value, schema: &Value, prefix: &str) -> Vec<BodyProperty> {
+    let schema = resolve(spec, schema);
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    properties
+        .iter()
+        .map(|(name, property)| {
+            let property = resolve(spec, property);
+            BodyProperty {
+                name: name.clone(),
+                // The prefix prevents clashes with the properties of the component http and the parameters
+                property_name: format!("{}_{}", prefix, to_type_name(name)),
+                data_type: to_data_type(property).to_string(),
+                description: property.get("description").and_then(Value::as_str).unwrap_or_default().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Resolves a local reference like `#/components/parameters/limit`.
+fn resolve<'a>(spec: &'a Value, value: &'a Value) -> &'a Value {
+    match value.get("$ref").and_then(Value::as_str).and_then(|reference| reference.strip_prefix('#')) {
+        Some(pointer) => spec.pointer(pointer).unwrap_or(value),
+        None => value,
+    }
+}
+
+/// Converts names like `getPetById` or `get /pets/{petId}` into `get_pet_by_id` and `get_pets_pet_id`.
+fn to_type_name(name: &str) -> String {
+    let mut type_name = String::new();
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if !type_name.is_empty() && !type_name.ends_with('_') {
+                type_name.push('_');
+            }
+            type_name.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            type_name.push(c);
+        } else if !type_name.is_empty() && !type_name.ends_with('_') {
+            type_name.push('_');
+        }
+    }
+    type_name.trim_end_matches('_').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_the_request_body_and_the_response_to_typed_properties() {
+        let spec = json!({
+            "openapi": "3.0.3",
+            "servers": [{ "url": "https://petstore.example.com/v1" }],
+            "paths": {
+                "/pets": {
+                    "post": {
+                        "operationId": "createPet",
+                        "requestBody": {
+                            "content": {
+                                "application/json": { "schema": { "$ref": "#/components/schemas/NewPet" } }
+                            }
+                        },
+                        "responses": {
+                            "default": { "description": "Error" },
+                            "201": {
+                                "content": {
+                                    "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "NewPet": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string", "description": "The name of the pet" },
+                            "tagIds": { "type": "array", "items": { "type": "integer" } }
+                        }
+                    },
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "integer" },
+                            "vaccinated": { "type": "boolean" }
+                        }
+                    }
+                }
+            }
+        });
+        let operations = parse_operations(&spec).unwrap();
+        assert_eq!(operations.len(), 1);
+        let operation = &operations[0];
+        assert_eq!(operation.type_name, "create_pet");
+        let body_properties: Vec<(&str, &str, &str)> = operation
+            .body_properties
+            .iter()
+            .map(|property| (property.name.as_str(), property.property_name.as_str(), property.data_type.as_str()))
+            .collect();
+        assert_eq!(body_properties, vec![("name", "body_name", "string"), ("tagIds", "body_tag_ids", "array")]);
+        assert_eq!(operation.body_properties[0].description, "The name of the pet");
+        let response_properties: Vec<(&str, &str, &str)> = operation
+            .response_properties
+            .iter()
+            .map(|property| (property.name.as_str(), property.property_name.as_str(), property.data_type.as_str()))
+            .collect();
+        assert_eq!(response_properties, vec![("id", "response_id", "number"), ("vaccinated", "response_vaccinated", "bool")]);
+        let entity_type = operation.to_entity_type("petstore");
+        let response_id = entity_type["properties"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|property| property["name"] == "response_id")
+            .unwrap();
+        assert_eq!(response_id["socket_type"], "output");
+    }
+
+    #[test]
+    fn does_not_map_bodies_without_object_schema() {
+        let spec = json!({
+            "openapi": "3.1.0",
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": { "schema": { "type": "array", "items": { "type": "object" } } }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        let operations = parse_operations(&spec).unwrap();
+        assert!(operations[0].body_properties.is_empty());
+        assert!(operations[0].response_properties.is_empty());
+    }
+}
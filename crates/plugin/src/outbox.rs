@@ -0,0 +1,85 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use log::error;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::config::get_config;
+
+/// Distinguishes payloads which are queued within the same nanosecond.
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// A durable queue of payloads which have not been delivered yet.
+///
+/// Each payload is stored as a file in the directory of the instance, so that the queue survives restarts. The files
+/// are named by the time they have been queued, so that they are delivered in order.
+pub struct Outbox {
+    pub path: PathBuf,
+}
+
+impl Outbox {
+    pub fn new(id: Uuid) -> Self {
+        Outbox {
+            path: PathBuf::from(&get_config().outbox.path).join(id.to_string()),
+        }
+    }
+
+    pub fn push(&self, payload: &Value) -> io::Result<()> {
+        fs::create_dir_all(&self.path)?;
+        let queued_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+        let sequence = SEQUENCE.fetch_add(1, Ordering::SeqCst) % 1000;
+        let path = self.path.join(format!("{:024}-{:03}.json", queued_at, sequence));
+        // The payload is written under a temporary name, so that a partially written payload is never delivered
+        let part = path.with_extension("part");
+        fs::write(&part, payload.to_string())?;
+        fs::rename(&part, &path)
+    }
+
+    /// Returns the oldest payload with the file it is stored in.
+    pub fn peek(&self) -> Option<(PathBuf, Value)> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(&self.path)
+            .ok()?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().map(|extension| extension == "json").unwrap_or(false))
+            .collect();
+        paths.sort();
+        for path in paths {
+            match fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|payload| serde_json::from_slice(&payload).map_err(|e| e.to_string()))
+            {
+                Ok(payload) => return Some((path, payload)),
+                Err(e) => {
+                    error!("Discarded unreadable payload {}: {}", path.display(), e);
+                    self.remove(&path);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn remove(&self, path: &Path) {
+        if let Err(e) = fs::remove_file(path) {
+            error!("Failed to remove {} from the outbox: {}", path.display(), e);
+        }
+    }
+}
+
+/// Returns the number of payloads in the queues of all instances.
+pub fn count_outbox_payloads() -> usize {
+    let Ok(entries) = fs::read_dir(&get_config().outbox.path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| fs::read_dir(entry.ok()?.path()).ok())
+        .flat_map(|payloads| payloads.filter_map(|payload| payload.ok()))
+        .filter(|payload| payload.path().extension().map(|extension| extension == "json").unwrap_or(false))
+        .count()
+}
@@ -0,0 +1,10 @@
+use crate::model::PropertyInstanceGetter;
+use crate::model::ReactiveEntityInstance;
+use crate::model_http::PausableProperties::ENABLED;
+
+/// Returns true, if the behaviours of the instance are enabled.
+///
+/// Instances without the component pausable are always enabled.
+pub fn is_enabled(reactive_instance: &ReactiveEntityInstance) -> bool {
+    reactive_instance.as_bool(ENABLED).unwrap_or(true)
+}
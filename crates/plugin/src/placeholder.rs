@@ -0,0 +1,47 @@
+use std::env;
+
+use serde_json::Value;
+
+/// Replaces the placeholders `${<kind>:<name>}` of the given kind in the text.
+///
+/// Placeholders which cannot be resolved are kept unchanged.
+pub fn replace_placeholders<F>(text: &str, kind: &str, resolve: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let prefix = format!("${{{}:", kind);
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(&prefix) {
+        let Some(length) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + prefix.len()..start + length];
+        result.push_str(&rest[..start]);
+        match resolve(name) {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(&rest[start..start + length + 1]),
+        }
+        rest = &rest[start + length + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Resolves the placeholders `${env:VAR}` in the text against the environment variables of the runtime.
+pub fn resolve_env(text: &str) -> String {
+    if !text.contains("${env:") {
+        return text.to_string();
+    }
+    replace_placeholders(text, "env", |name| env::var(name).ok())
+}
+
+/// Resolves the placeholders `${env:VAR}` in all strings of the value.
+pub fn resolve_env_in_value(value: &Value) -> Value {
+    match value {
+        Value::String(text) => Value::String(resolve_env(text)),
+        Value::Array(items) => Value::Array(items.iter().map(resolve_env_in_value).collect()),
+        Value::Object(properties) => Value::Object(properties.iter().map(|(name, value)| (name.clone(), resolve_env_in_value(value))).collect()),
+        value => value.clone(),
+    }
+}
@@ -1,16 +1,130 @@
+use crate::acme::start_acme;
+use crate::acme::stop_acme;
+use crate::behaviour::component::access_log::AccessLogFactory;
+use crate::behaviour::component::batch::BatchFactory;
+use crate::behaviour::component::connectivity_check::ConnectivityCheckFactory;
+use crate::behaviour::component::cors_preflight::CorsPreflightFactory;
+use crate::behaviour::component::dns_cache::DnsCacheFactory;
+use crate::behaviour::component::download::DownloadFactory;
+use crate::behaviour::component::fan_out::FanOutFactory;
+use crate::behaviour::component::graphql::GraphQlFactory;
 use crate::behaviour::component::http::HttpFactory;
+use crate::behaviour::component::http_response::HttpResponseFactory;
+use crate::behaviour::component::http_route::HttpRouteFactory;
+use crate::behaviour::component::hypermedia::HypermediaFactory;
+use crate::behaviour::component::json_api::JsonApiFactory;
 use crate::behaviour::component::json_rpc::JsonRpcFactory;
+use crate::behaviour::component::link_checker::LinkCheckerFactory;
+use crate::behaviour::component::long_poll::LongPollFactory;
+use crate::behaviour::component::odata::ODataFactory;
+use crate::behaviour::component::openapi_import::OpenApiImportFactory;
+use crate::behaviour::component::openapi_operation::OpenApiOperationFactory;
+use crate::behaviour::component::postman_import::PostmanImportFactory;
+use crate::behaviour::component::presigned_url::PresignedUrlFactory;
+use crate::behaviour::component::security_headers::SecurityHeadersFactory;
+use crate::behaviour::component::signalr::SignalrFactory;
+use crate::behaviour::component::sitemap::SitemapFactory;
+use crate::behaviour::component::socket_io::SocketIoFactory;
+use crate::behaviour::component::sse::SseFactory;
+use crate::behaviour::component::stomp::StompFactory;
+use crate::behaviour::component::tls_certificate_monitor::TlsCertificateMonitorFactory;
+use crate::behaviour::component::tunnel::TunnelFactory;
+use crate::behaviour::component::upload::UploadFactory;
+use crate::behaviour::component::upload_endpoint::UploadEndpointFactory;
+use crate::behaviour::component::webdav::WebDavFactory;
+use crate::behaviour::component::webhook_relay::WebhookRelayFactory;
+use crate::behaviour::component::webhook_sender::WebhookSenderFactory;
+use crate::behaviour::component::websocket_bridge::WebSocketBridgeFactory;
+use crate::behaviour::relation::http_inherits_template::HttpInheritsTemplateFactory;
+use crate::behaviour::relation::http_uses_session::HttpUsesSessionFactory;
+use crate::health::start_health;
+use crate::health::stop_health;
+use crate::metrics::start_metrics;
+use crate::metrics::stop_metrics;
 use std::sync::Arc;
 use std::sync::RwLock;
 
+use crate::context::set_plugin_context;
 use crate::di::*;
+use crate::model_http::BEHAVIOUR_ACCESS_LOG;
+use crate::model_http::BEHAVIOUR_BATCH;
+use crate::model_http::BEHAVIOUR_CONNECTIVITY_CHECK;
+use crate::model_http::BEHAVIOUR_CORS_PREFLIGHT;
+use crate::model_http::BEHAVIOUR_DNS_CACHE;
+use crate::model_http::BEHAVIOUR_DOWNLOAD;
+use crate::model_http::BEHAVIOUR_FAN_OUT;
+use crate::model_http::BEHAVIOUR_GRAPHQL;
 use crate::model_http::BEHAVIOUR_HTTP;
+use crate::model_http::BEHAVIOUR_HTTP_INHERITS_TEMPLATE;
+use crate::model_http::BEHAVIOUR_HTTP_RESPONSE;
+use crate::model_http::BEHAVIOUR_HTTP_ROUTE;
+use crate::model_http::BEHAVIOUR_HTTP_USES_SESSION;
+use crate::model_http::BEHAVIOUR_HYPERMEDIA;
+use crate::model_http::BEHAVIOUR_JSON_API;
 use crate::model_http::BEHAVIOUR_JSON_RPC;
+use crate::model_http::BEHAVIOUR_LINK_CHECKER;
+use crate::model_http::BEHAVIOUR_LONG_POLL;
+use crate::model_http::BEHAVIOUR_ODATA;
+use crate::model_http::BEHAVIOUR_OPENAPI_IMPORT;
+use crate::model_http::BEHAVIOUR_OPENAPI_OPERATION;
+use crate::model_http::BEHAVIOUR_POSTMAN_IMPORT;
+use crate::model_http::BEHAVIOUR_PRESIGNED_URL;
+use crate::model_http::BEHAVIOUR_SECURITY_HEADERS;
+use crate::model_http::BEHAVIOUR_SIGNALR;
+use crate::model_http::BEHAVIOUR_SITEMAP;
+use crate::model_http::BEHAVIOUR_SOCKET_IO;
+use crate::model_http::BEHAVIOUR_SSE;
+use crate::model_http::BEHAVIOUR_STOMP;
+use crate::model_http::BEHAVIOUR_TLS_CERTIFICATE_MONITOR;
+use crate::model_http::BEHAVIOUR_TUNNEL;
+use crate::model_http::BEHAVIOUR_UPLOAD;
+use crate::model_http::BEHAVIOUR_UPLOAD_ENDPOINT;
+use crate::model_http::BEHAVIOUR_WEBDAV;
+use crate::model_http::BEHAVIOUR_WEBHOOK_RELAY;
+use crate::model_http::BEHAVIOUR_WEBHOOK_SENDER;
+use crate::model_http::BEHAVIOUR_WEBSOCKET_BRIDGE;
+use crate::model_http::COMPONENT_BEHAVIOUR_ACCESS_LOG;
+use crate::model_http::COMPONENT_BEHAVIOUR_BATCH;
+use crate::model_http::COMPONENT_BEHAVIOUR_CONNECTIVITY_CHECK;
+use crate::model_http::COMPONENT_BEHAVIOUR_CORS_PREFLIGHT;
+use crate::model_http::COMPONENT_BEHAVIOUR_DNS_CACHE;
+use crate::model_http::COMPONENT_BEHAVIOUR_DOWNLOAD;
+use crate::model_http::COMPONENT_BEHAVIOUR_FAN_OUT;
+use crate::model_http::COMPONENT_BEHAVIOUR_GRAPHQL;
 use crate::model_http::COMPONENT_BEHAVIOUR_HTTP;
+use crate::model_http::COMPONENT_BEHAVIOUR_HTTP_RESPONSE;
+use crate::model_http::COMPONENT_BEHAVIOUR_HTTP_ROUTE;
+use crate::model_http::COMPONENT_BEHAVIOUR_HYPERMEDIA;
+use crate::model_http::COMPONENT_BEHAVIOUR_JSON_API;
 use crate::model_http::COMPONENT_BEHAVIOUR_JSON_RPC;
+use crate::model_http::COMPONENT_BEHAVIOUR_LINK_CHECKER;
+use crate::model_http::COMPONENT_BEHAVIOUR_LONG_POLL;
+use crate::model_http::COMPONENT_BEHAVIOUR_ODATA;
+use crate::model_http::COMPONENT_BEHAVIOUR_OPENAPI_IMPORT;
+use crate::model_http::COMPONENT_BEHAVIOUR_OPENAPI_OPERATION;
+use crate::model_http::COMPONENT_BEHAVIOUR_POSTMAN_IMPORT;
+use crate::model_http::COMPONENT_BEHAVIOUR_PRESIGNED_URL;
+use crate::model_http::COMPONENT_BEHAVIOUR_SECURITY_HEADERS;
+use crate::model_http::COMPONENT_BEHAVIOUR_SIGNALR;
+use crate::model_http::COMPONENT_BEHAVIOUR_SITEMAP;
+use crate::model_http::COMPONENT_BEHAVIOUR_SOCKET_IO;
+use crate::model_http::COMPONENT_BEHAVIOUR_SSE;
+use crate::model_http::COMPONENT_BEHAVIOUR_STOMP;
+use crate::model_http::COMPONENT_BEHAVIOUR_TLS_CERTIFICATE_MONITOR;
+use crate::model_http::COMPONENT_BEHAVIOUR_TUNNEL;
+use crate::model_http::COMPONENT_BEHAVIOUR_UPLOAD;
+use crate::model_http::COMPONENT_BEHAVIOUR_UPLOAD_ENDPOINT;
+use crate::model_http::COMPONENT_BEHAVIOUR_WEBDAV;
+use crate::model_http::COMPONENT_BEHAVIOUR_WEBHOOK_RELAY;
+use crate::model_http::COMPONENT_BEHAVIOUR_WEBHOOK_SENDER;
+use crate::model_http::COMPONENT_BEHAVIOUR_WEBSOCKET_BRIDGE;
+use crate::model_http::RELATION_BEHAVIOUR_HTTP_INHERITS_TEMPLATE;
+use crate::model_http::RELATION_BEHAVIOUR_HTTP_USES_SESSION;
 use crate::plugins::component_provider;
 use crate::plugins::entity_type_provider;
 use crate::plugins::plugin_context::PluginContext;
+use crate::plugins::relation_type_provider;
+use crate::plugins::web_resource_provider;
 use crate::plugins::ComponentProvider;
 use crate::plugins::ComponentProviderError;
 use crate::plugins::EntityTypeProvider;
@@ -20,8 +134,16 @@ use crate::plugins::PluginActivationError;
 use crate::plugins::PluginContextDeinitializationError;
 use crate::plugins::PluginContextInitializationError;
 use crate::plugins::PluginDeactivationError;
+use crate::plugins::RelationTypeProvider;
+use crate::plugins::RelationTypeProviderError;
+use crate::plugins::WebResourceProvider;
+use crate::plugins::WebResourceProviderError;
 use crate::providers::HttpComponentProviderImpl;
 use crate::providers::HttpEntityTypeProviderImpl;
+use crate::providers::HttpRelationTypeProviderImpl;
+use crate::traffic::start_traffic_stream;
+use crate::traffic::stop_traffic_stream;
+use crate::web_resource_provider::HttpWebResourceProviderImpl;
 
 #[wrapper]
 pub struct PluginContextContainer(RwLock<Option<std::sync::Arc<dyn PluginContext>>>);
@@ -37,6 +159,8 @@ pub trait HttpPlugin: Plugin + Send + Sync {}
 pub struct HttpPluginImpl {
     component_provider: Wrc<HttpComponentProviderImpl>,
     entity_type_provider: Wrc<HttpEntityTypeProviderImpl>,
+    relation_type_provider: Wrc<HttpRelationTypeProviderImpl>,
+    web_resource_provider: Wrc<HttpWebResourceProviderImpl>,
 
     context: PluginContextContainer,
 }
@@ -62,28 +186,215 @@ impl Plugin for HttpPluginImpl {
             // JSON_RPC
             let factory = Arc::new(JsonRpcFactory::new(BEHAVIOUR_JSON_RPC.clone()));
             entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_JSON_RPC.clone(), factory);
+
+            // WEBHOOK_SENDER
+            let factory = Arc::new(WebhookSenderFactory::new(BEHAVIOUR_WEBHOOK_SENDER.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_WEBHOOK_SENDER.clone(), factory);
+
+            // LONG_POLL
+            let factory = Arc::new(LongPollFactory::new(BEHAVIOUR_LONG_POLL.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_LONG_POLL.clone(), factory);
+
+            // OPENAPI_IMPORT
+            let factory = Arc::new(OpenApiImportFactory::new(BEHAVIOUR_OPENAPI_IMPORT.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_OPENAPI_IMPORT.clone(), factory);
+
+            // OPENAPI_OPERATION
+            let factory = Arc::new(OpenApiOperationFactory::new(BEHAVIOUR_OPENAPI_OPERATION.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_OPENAPI_OPERATION.clone(), factory);
+
+            // POSTMAN_IMPORT
+            let factory = Arc::new(PostmanImportFactory::new(BEHAVIOUR_POSTMAN_IMPORT.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_POSTMAN_IMPORT.clone(), factory);
+
+            // FAN_OUT
+            let factory = Arc::new(FanOutFactory::new(BEHAVIOUR_FAN_OUT.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_FAN_OUT.clone(), factory);
+
+            // DOWNLOAD
+            let factory = Arc::new(DownloadFactory::new(BEHAVIOUR_DOWNLOAD.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_DOWNLOAD.clone(), factory);
+
+            // UPLOAD
+            let factory = Arc::new(UploadFactory::new(BEHAVIOUR_UPLOAD.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_UPLOAD.clone(), factory);
+
+            // WEBHOOK_RELAY
+            let factory = Arc::new(WebhookRelayFactory::new(BEHAVIOUR_WEBHOOK_RELAY.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_WEBHOOK_RELAY.clone(), factory);
+
+            // CONNECTIVITY_CHECK
+            let factory = Arc::new(ConnectivityCheckFactory::new(BEHAVIOUR_CONNECTIVITY_CHECK.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_CONNECTIVITY_CHECK.clone(), factory);
+
+            // DNS_CACHE
+            let factory = Arc::new(DnsCacheFactory::new(BEHAVIOUR_DNS_CACHE.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_DNS_CACHE.clone(), factory);
+
+            // TLS_CERTIFICATE_MONITOR
+            let factory = Arc::new(TlsCertificateMonitorFactory::new(BEHAVIOUR_TLS_CERTIFICATE_MONITOR.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_TLS_CERTIFICATE_MONITOR.clone(), factory);
+
+            // SITEMAP
+            let factory = Arc::new(SitemapFactory::new(BEHAVIOUR_SITEMAP.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_SITEMAP.clone(), factory);
+
+            // LINK_CHECKER
+            let factory = Arc::new(LinkCheckerFactory::new(BEHAVIOUR_LINK_CHECKER.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_LINK_CHECKER.clone(), factory);
+
+            // BATCH
+            let factory = Arc::new(BatchFactory::new(BEHAVIOUR_BATCH.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_BATCH.clone(), factory);
+
+            // PRESIGNED_URL
+            let factory = Arc::new(PresignedUrlFactory::new(BEHAVIOUR_PRESIGNED_URL.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_PRESIGNED_URL.clone(), factory);
+
+            // SECURITY_HEADERS
+            let factory = Arc::new(SecurityHeadersFactory::new(BEHAVIOUR_SECURITY_HEADERS.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_SECURITY_HEADERS.clone(), factory);
+
+            // CORS_PREFLIGHT
+            let factory = Arc::new(CorsPreflightFactory::new(BEHAVIOUR_CORS_PREFLIGHT.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_CORS_PREFLIGHT.clone(), factory);
+
+            // WEBDAV
+            let factory = Arc::new(WebDavFactory::new(BEHAVIOUR_WEBDAV.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_WEBDAV.clone(), factory);
+
+            // ODATA
+            let factory = Arc::new(ODataFactory::new(BEHAVIOUR_ODATA.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_ODATA.clone(), factory);
+
+            // HYPERMEDIA
+            let factory = Arc::new(HypermediaFactory::new(BEHAVIOUR_HYPERMEDIA.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_HYPERMEDIA.clone(), factory);
+
+            // JSON_API
+            let factory = Arc::new(JsonApiFactory::new(BEHAVIOUR_JSON_API.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_JSON_API.clone(), factory);
+
+            // GRAPHQL
+            let factory = Arc::new(GraphQlFactory::new(BEHAVIOUR_GRAPHQL.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_GRAPHQL.clone(), factory);
+
+            // SSE
+            let factory = Arc::new(SseFactory::new(BEHAVIOUR_SSE.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_SSE.clone(), factory);
+            // SOCKET_IO
+            let factory = Arc::new(SocketIoFactory::new(BEHAVIOUR_SOCKET_IO.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_SOCKET_IO.clone(), factory);
+
+            // STOMP
+            let factory = Arc::new(StompFactory::new(BEHAVIOUR_STOMP.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_STOMP.clone(), factory);
+
+            // SIGNALR
+            let factory = Arc::new(SignalrFactory::new(BEHAVIOUR_SIGNALR.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_SIGNALR.clone(), factory);
+
+            // TUNNEL
+            let factory = Arc::new(TunnelFactory::new(BEHAVIOUR_TUNNEL.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_TUNNEL.clone(), factory);
+
+            // HTTP_ROUTE
+            let factory = Arc::new(HttpRouteFactory::new(BEHAVIOUR_HTTP_ROUTE.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_HTTP_ROUTE.clone(), factory);
+
+            // HTTP_RESPONSE
+            let factory = Arc::new(HttpResponseFactory::new(BEHAVIOUR_HTTP_RESPONSE.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_HTTP_RESPONSE.clone(), factory);
+
+            // UPLOAD_ENDPOINT
+            let factory = Arc::new(UploadEndpointFactory::new(BEHAVIOUR_UPLOAD_ENDPOINT.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_UPLOAD_ENDPOINT.clone(), factory);
+
+            // ACCESS_LOG
+            let factory = Arc::new(AccessLogFactory::new(BEHAVIOUR_ACCESS_LOG.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_ACCESS_LOG.clone(), factory);
+
+            // WEBSOCKET_BRIDGE
+            let factory = Arc::new(WebSocketBridgeFactory::new(BEHAVIOUR_WEBSOCKET_BRIDGE.clone()));
+            entity_component_behaviour_registry.register(COMPONENT_BEHAVIOUR_WEBSOCKET_BRIDGE.clone(), factory);
+
+            let relation_behaviour_registry = context.get_relation_behaviour_registry();
+            // HTTP_USES_SESSION
+            let factory = Arc::new(HttpUsesSessionFactory::new(BEHAVIOUR_HTTP_USES_SESSION.clone()));
+            relation_behaviour_registry.register(RELATION_BEHAVIOUR_HTTP_USES_SESSION.clone(), factory);
+
+            // HTTP_INHERITS_TEMPLATE
+            let factory = Arc::new(HttpInheritsTemplateFactory::new(BEHAVIOUR_HTTP_INHERITS_TEMPLATE.clone()));
+            relation_behaviour_registry.register(RELATION_BEHAVIOUR_HTTP_INHERITS_TEMPLATE.clone(), factory);
         }
+        start_traffic_stream();
+        start_health();
+        start_metrics();
+        start_acme();
         Ok(())
     }
 
     fn deactivate(&self) -> Result<(), PluginDeactivationError> {
+        stop_traffic_stream();
+        stop_health();
+        stop_metrics();
+        stop_acme();
         let guard = self.context.0.read().unwrap();
         if let Some(context) = guard.clone() {
             let entity_component_behaviour_registry = context.get_entity_component_behaviour_registry();
             entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_HTTP);
             entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_JSON_RPC);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_WEBHOOK_SENDER);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_LONG_POLL);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_OPENAPI_IMPORT);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_OPENAPI_OPERATION);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_POSTMAN_IMPORT);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_FAN_OUT);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_DOWNLOAD);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_UPLOAD);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_WEBHOOK_RELAY);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_CONNECTIVITY_CHECK);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_DNS_CACHE);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_TLS_CERTIFICATE_MONITOR);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_SITEMAP);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_LINK_CHECKER);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_BATCH);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_PRESIGNED_URL);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_SECURITY_HEADERS);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_CORS_PREFLIGHT);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_WEBDAV);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_ODATA);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_HYPERMEDIA);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_JSON_API);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_GRAPHQL);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_SSE);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_SOCKET_IO);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_STOMP);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_SIGNALR);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_TUNNEL);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_HTTP_ROUTE);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_HTTP_RESPONSE);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_UPLOAD_ENDPOINT);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_ACCESS_LOG);
+            entity_component_behaviour_registry.unregister(&COMPONENT_BEHAVIOUR_WEBSOCKET_BRIDGE);
+
+            let relation_behaviour_registry = context.get_relation_behaviour_registry();
+            relation_behaviour_registry.unregister(&RELATION_BEHAVIOUR_HTTP_USES_SESSION);
+            relation_behaviour_registry.unregister(&RELATION_BEHAVIOUR_HTTP_INHERITS_TEMPLATE);
         }
         Ok(())
     }
 
     fn set_context(&self, context: Arc<dyn PluginContext>) -> Result<(), PluginContextInitializationError> {
         self.context.0.write().unwrap().replace(context.clone());
+        set_plugin_context(Some(context));
         Ok(())
     }
 
     fn remove_context(&self) -> Result<(), PluginContextDeinitializationError> {
         let mut writer = self.context.0.write().unwrap();
         *writer = None;
+        set_plugin_context(None);
         Ok(())
     }
 
@@ -94,4 +405,12 @@ impl Plugin for HttpPluginImpl {
     fn get_entity_type_provider(&self) -> Result<Option<Arc<dyn EntityTypeProvider>>, EntityTypeProviderError> {
         entity_type_provider!(self.entity_type_provider)
     }
+
+    fn get_relation_type_provider(&self) -> Result<Option<Arc<dyn RelationTypeProvider>>, RelationTypeProviderError> {
+        relation_type_provider!(self.relation_type_provider)
+    }
+
+    fn get_web_resource_provider(&self) -> Result<Option<Arc<dyn WebResourceProvider>>, WebResourceProviderError> {
+        web_resource_provider!(self.web_resource_provider)
+    }
 }
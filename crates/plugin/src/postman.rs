@@ -0,0 +1,128 @@
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::template::render_template;
+
+/// A request of a Postman collection.
+#[derive(Debug, Clone)]
+pub struct PostmanRequest {
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    pub request_headers: Value,
+    pub payload: Value,
+}
+
+/// Parses the requests of a Postman collection (format v2.0 or v2.1). The requests of folders are flattened.
+///
+/// Variables of the collection are substituted and the authentication is converted into request headers.
+pub fn parse_collection(collection: &Value) -> Result<Vec<PostmanRequest>, String> {
+    let Some(items) = collection.get("item").and_then(Value::as_array) else {
+        return Err(String::from("The Postman collection has no items"));
+    };
+    let variables: Map<String, Value> = collection
+        .get("variable")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|variable| Some((variable.get("key")?.as_str()?.to_string(), variable.get("value")?.clone())))
+        .collect();
+    let mut requests = Vec::new();
+    collect_requests(items, collection.get("auth"), &variables, &mut requests);
+    Ok(requests)
+}
+
+fn collect_requests(items: &[Value], auth: Option<&Value>, variables: &Map<String, Value>, requests: &mut Vec<PostmanRequest>) {
+    for item in items.iter() {
+        // Folders and requests inherit the authentication of their parent
+        if let Some(folder_items) = item.get("item").and_then(Value::as_array) {
+            collect_requests(folder_items, item.get("auth").or(auth), variables, requests);
+            continue;
+        }
+        let Some(request) = item.get("request") else {
+            continue;
+        };
+        let name = item.get("name").and_then(Value::as_str).unwrap_or_default();
+        if let Some(request) = parse_request(name, request, request.get("auth").or(auth), variables) {
+            requests.push(request);
+        }
+    }
+}
+
+fn parse_request(name: &str, request: &Value, auth: Option<&Value>, variables: &Map<String, Value>) -> Option<PostmanRequest> {
+    // The request may consist of the url only
+    let mut url = match request.get("url").unwrap_or(request) {
+        Value::String(url) => url.clone(),
+        url => url.get("raw")?.as_str()?.to_string(),
+    };
+    let method = request.get("method").and_then(Value::as_str).unwrap_or("GET").to_uppercase();
+    let mut request_headers = Map::new();
+    for header in request.get("header").and_then(Value::as_array).into_iter().flatten() {
+        if header.get("disabled").and_then(Value::as_bool).unwrap_or(false) {
+            continue;
+        }
+        if let (Some(key), Some(value)) = (header.get("key").and_then(Value::as_str), header.get("value")) {
+            request_headers.insert(key.to_string(), value.clone());
+        }
+    }
+    let payload = match request.pointer("/body/mode").and_then(Value::as_str) {
+        Some("raw") => {
+            let raw = request.pointer("/body/raw").and_then(Value::as_str).unwrap_or_default();
+            serde_json::from_str(raw).unwrap_or_else(|_| json!(raw))
+        }
+        Some("urlencoded") => Value::Object(
+            request
+                .pointer("/body/urlencoded")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|field| Some((field.get("key")?.as_str()?.to_string(), field.get("value")?.clone())))
+                .collect(),
+        ),
+        _ => json!({}),
+    };
+    apply_auth(auth, &mut request_headers, &mut url);
+    Some(PostmanRequest {
+        name: name.to_string(),
+        method,
+        url: render_template(&json!(url), variables).as_str().unwrap_or_default().to_string(),
+        request_headers: render_template(&Value::Object(request_headers), variables),
+        payload: render_template(&payload, variables),
+    })
+}
+
+fn apply_auth(auth: Option<&Value>, request_headers: &mut Map<String, Value>, url: &mut String) {
+    let Some(auth) = auth else {
+        return;
+    };
+    let auth_type = auth.get("type").and_then(Value::as_str).unwrap_or("noauth");
+    // Format v2.1 stores the attributes as list of key value pairs, format v2.0 as object
+    let attributes = auth.get(auth_type);
+    let attribute = |key: &str| -> String {
+        let value = match attributes {
+            Some(Value::Array(attributes)) => attributes.iter().find(|attribute| attribute["key"] == key).map(|attribute| &attribute["value"]),
+            Some(Value::Object(attributes)) => attributes.get(key),
+            _ => None,
+        };
+        value.and_then(Value::as_str).unwrap_or_default().to_string()
+    };
+    match auth_type {
+        "bearer" => {
+            request_headers.insert(String::from("Authorization"), json!(format!("Bearer {}", attribute("token"))));
+        }
+        "basic" => {
+            let credentials = base64::encode(format!("{}:{}", attribute("username"), attribute("password")));
+            request_headers.insert(String::from("Authorization"), json!(format!("Basic {}", credentials)));
+        }
+        "apikey" => {
+            if attribute("in") == "query" {
+                url.push(if url.contains('?') { '&' } else { '?' });
+                url.push_str(&format!("{}={}", attribute("key"), attribute("value")));
+            } else {
+                request_headers.insert(attribute("key"), json!(attribute("value")));
+            }
+        }
+        _ => {}
+    }
+}
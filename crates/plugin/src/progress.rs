@@ -0,0 +1,76 @@
+use std::io;
+use std::io::Read;
+
+/// The number of bytes after which the progress is reported if the total size is unknown.
+const UNKNOWN_TOTAL_GRANULARITY: u64 = 1024 * 1024;
+
+/// Keeps track of the progress of a transfer and decides when the progress has to be reported.
+///
+/// Reporting every chunk would flood the flows with events. Instead, the progress is reported whenever the
+/// percentage has advanced by the granularity.
+pub struct Progress {
+    pub transferred_bytes: u64,
+    /// The total size or none, if the size is unknown.
+    pub total_bytes: Option<u64>,
+    granularity: u64,
+    reported_bytes: u64,
+}
+
+impl Progress {
+    pub fn new(total_bytes: Option<u64>, granularity: u64) -> Self {
+        Progress {
+            transferred_bytes: 0,
+            total_bytes,
+            granularity: granularity.clamp(1, 100),
+            reported_bytes: 0,
+        }
+    }
+
+    /// Returns the progress in percent. If the total size is unknown, the progress is zero until the transfer is completed.
+    pub fn percent(&self) -> u64 {
+        match self.total_bytes {
+            Some(0) => 100,
+            Some(total_bytes) => (self.transferred_bytes * 100 / total_bytes).min(100),
+            None => 0,
+        }
+    }
+
+    /// Advances the progress. Returns true, if the progress has to be reported.
+    pub fn advance(&mut self, bytes: u64) -> bool {
+        self.transferred_bytes += bytes;
+        let report = match self.total_bytes {
+            Some(total_bytes) if total_bytes > 0 => {
+                let reported_percent = self.reported_bytes * 100 / total_bytes;
+                self.percent() >= reported_percent + self.granularity || self.transferred_bytes >= total_bytes
+            }
+            _ => self.transferred_bytes - self.reported_bytes >= UNKNOWN_TOTAL_GRANULARITY,
+        };
+        if report {
+            self.reported_bytes = self.transferred_bytes;
+        }
+        report
+    }
+}
+
+/// Reports the progress while the inner reader is read, for example by the http client which sends a request body.
+pub struct ProgressReader<R: Read, F: FnMut(&Progress)> {
+    inner: R,
+    progress: Progress,
+    report: F,
+}
+
+impl<R: Read, F: FnMut(&Progress)> ProgressReader<R, F> {
+    pub fn new(inner: R, progress: Progress, report: F) -> Self {
+        ProgressReader { inner, progress, report }
+    }
+}
+
+impl<R: Read, F: FnMut(&Progress)> Read for ProgressReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let length = self.inner.read(buf)?;
+        if self.progress.advance(length as u64) {
+            (self.report)(&self.progress);
+        }
+        Ok(length)
+    }
+}
@@ -1,2 +1,3 @@
 crate::plugins::component_provider_impl!(Http, "../../assets/types/components");
 crate::plugins::entity_type_provider_impl!(Http, "../../assets/types/entities");
+crate::plugins::relation_type_provider_impl!(Http, "../../assets/types/relations");
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use lazy_static::lazy_static;
+
+/// Values of the reset header above this threshold are unix timestamps, smaller values are delays in seconds.
+const TIMESTAMP_THRESHOLD: u64 = 1_000_000_000;
+
+/// The quota announced by the rate limit headers of a response.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub remaining: u64,
+    /// The delay until the quota is reset.
+    pub reset: Duration,
+}
+
+impl RateLimit {
+    /// Parses the headers X-RateLimit-Remaining and X-RateLimit-Reset or their standardized variants without prefix.
+    pub fn from_response(response: &ureq::Response) -> Option<Self> {
+        let header = |name: &str| {
+            response
+                .header(&format!("x-ratelimit-{}", name))
+                .or_else(|| response.header(&format!("ratelimit-{}", name)))
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+        let remaining = header("remaining")?;
+        let reset = match header("reset").unwrap_or(0) {
+            reset if reset > TIMESTAMP_THRESHOLD => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+                Duration::from_secs(reset).saturating_sub(now)
+            }
+            reset => Duration::from_secs(reset),
+        };
+        Some(RateLimit { remaining, reset })
+    }
+
+    /// Returns the time of the reset as unix timestamp.
+    pub fn reset_timestamp(&self) -> u64 {
+        (SystemTime::now() + self.reset).duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+    }
+}
+
+struct Quota {
+    remaining: u64,
+    reset_at: Instant,
+}
+
+lazy_static! {
+    /// The known quotas by host. The quota of a host is shared between all instances.
+    static ref QUOTAS: Mutex<HashMap<String, Quota>> = Mutex::new(HashMap::new());
+}
+
+/// Records the quota of the host.
+pub fn record_rate_limit(host: &str, rate_limit: &RateLimit) {
+    QUOTAS.lock().unwrap().insert(
+        host.to_string(),
+        Quota {
+            remaining: rate_limit.remaining,
+            reset_at: Instant::now() + rate_limit.reset,
+        },
+    );
+}
+
+/// Returns the delay before the next request to the host can be sent without exceeding the quota.
+///
+/// The remaining requests are spread evenly until the reset. If the quota is exhausted, the delay lasts until the reset.
+pub fn get_pacing_delay(host: &str) -> Option<Duration> {
+    let mut quotas = QUOTAS.lock().unwrap();
+    let quota = quotas.get_mut(host)?;
+    let now = Instant::now();
+    if quota.reset_at <= now {
+        quotas.remove(host);
+        return None;
+    }
+    let until_reset = quota.reset_at - now;
+    if quota.remaining == 0 {
+        return Some(until_reset);
+    }
+    let delay = until_reset / quota.remaining as u32;
+    // The request is accounted for until the response announces the actual quota
+    quota.remaining -= 1;
+    Some(delay)
+}
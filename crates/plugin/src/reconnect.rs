@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use log::error;
+use serde_json::json;
+
+use crate::model::PropertyInstanceGetter;
+use crate::model::PropertyInstanceSetter;
+use crate::model::ReactiveEntityInstance;
+use crate::model_http::ReconnectProperties::CONNECTED;
+use crate::model_http::ReconnectProperties::RECONNECT_INITIAL_DELAY;
+use crate::model_http::ReconnectProperties::RECONNECT_MAX_ATTEMPTS;
+use crate::model_http::ReconnectProperties::RECONNECT_MAX_DELAY;
+use crate::pausable::is_enabled;
+use crate::worker::WorkerSignal;
+
+/// The interval in which a paused instance checks whether it has been enabled again.
+const PAUSED_DELAY: Duration = Duration::from_secs(1);
+
+/// Exponential backoff for behaviours which keep a connection or a polling loop alive.
+///
+/// The policy is read from the properties of the component reconnect.
+pub struct ReconnectPolicy {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// The maximum number of consecutive failed attempts. Zero means unlimited.
+    pub max_attempts: u64,
+}
+
+impl ReconnectPolicy {
+    /// Returns the delay before the next attempt after the given number of consecutive failed attempts.
+    pub fn delay(&self, failed_attempts: u64) -> Duration {
+        let exponent = failed_attempts.saturating_sub(1).min(31) as u32;
+        self.initial_delay.saturating_mul(2u32.saturating_pow(exponent)).min(self.max_delay)
+    }
+
+    /// Returns true, if no further attempt should be made.
+    pub fn is_exhausted(&self, failed_attempts: u64) -> bool {
+        self.max_attempts > 0 && failed_attempts >= self.max_attempts
+    }
+}
+
+impl From<&ReactiveEntityInstance> for ReconnectPolicy {
+    fn from(reactive_instance: &ReactiveEntityInstance) -> Self {
+        ReconnectPolicy {
+            initial_delay: Duration::from_millis(reactive_instance.as_u64(RECONNECT_INITIAL_DELAY).unwrap_or(1000)),
+            max_delay: Duration::from_millis(reactive_instance.as_u64(RECONNECT_MAX_DELAY).unwrap_or(30000)),
+            max_attempts: reactive_instance.as_u64(RECONNECT_MAX_ATTEMPTS).unwrap_or(0),
+        }
+    }
+}
+
+/// Runs the sessions of a connection until the worker is stopped and reconnects according to the reconnect policy.
+///
+/// A session returns an error if the connection failed and nothing, if the worker has been stopped or the instance has been
+/// paused. Sessions reset the failed attempts as soon as they are established.
+pub fn keep_connected<F>(reactive_instance: &ReactiveEntityInstance, signal: &WorkerSignal, protocol: &str, error_property: &str, mut session: F)
+where
+    F: FnMut(&ReactiveEntityInstance, &WorkerSignal, &mut u64) -> Result<(), String>,
+{
+    let mut failed_attempts = 0;
+    while !signal.is_stopped() {
+        if !is_enabled(reactive_instance) {
+            if !signal.sleep(PAUSED_DELAY) {
+                break;
+            }
+            continue;
+        }
+        let result = session(reactive_instance, signal, &mut failed_attempts);
+        if reactive_instance.as_bool(CONNECTED) != Some(false) {
+            reactive_instance.set(CONNECTED, json!(false));
+        }
+        let Err(e) = result else {
+            continue;
+        };
+        error!("{} connection of {} failed: {}", protocol, reactive_instance.id, e);
+        reactive_instance.set(error_property, json!(e));
+        failed_attempts += 1;
+        let reconnect_policy = ReconnectPolicy::from(reactive_instance);
+        if reconnect_policy.is_exhausted(failed_attempts) {
+            error!("Stopped reconnecting {} after {} failed attempts", reactive_instance.id, failed_attempts);
+            break;
+        }
+        if !signal.sleep(reconnect_policy.delay(failed_attempts)) {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubles_the_delay_up_to_the_maximum() {
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(5),
+            max_attempts: 0,
+        };
+        let delays: Vec<u128> = (1..=6).map(|failed_attempts| policy.delay(failed_attempts).as_millis()).collect();
+        assert_eq!(delays, vec![500, 1000, 2000, 4000, 5000, 5000]);
+        assert_eq!(policy.delay(0), Duration::from_millis(500));
+        assert_eq!(policy.delay(u64::MAX), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn stops_after_the_maximum_attempts() {
+        let mut policy = ReconnectPolicy {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            max_attempts: 3,
+        };
+        assert!(!policy.is_exhausted(2));
+        assert!(policy.is_exhausted(3));
+        policy.max_attempts = 0;
+        assert!(!policy.is_exhausted(1000));
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use url::form_urlencoded;
+use uuid::Uuid;
+
+use crate::model::PropertyInstanceGetter;
+use crate::model::PropertyInstanceSetter;
+use crate::model::ReactiveEntityInstance;
+use crate::model_http::HttpResponseProperties::BODY_TEMPLATE;
+use crate::model_http::HttpResponseProperties::CONTENT_TYPE;
+use crate::model_http::HttpResponseProperties::CONTENT_TYPES;
+use crate::model_http::HttpRouteProperties::RESPONSE_BODY;
+use crate::model_http::HttpRouteProperties::RESPONSE_HEADERS;
+use crate::model_http::HttpRouteProperties::RESPONSE_STATUS;
+use crate::server::OutgoingResponse;
+use crate::template::render_template;
+
+type PendingResponse = Arc<(Mutex<bool>, Condvar)>;
+
+lazy_static! {
+    /// The asynchronous responses which have not been completed yet, by instance id.
+    static ref PENDING_RESPONSES: Mutex<HashMap<Uuid, PendingResponse>> = Mutex::new(HashMap::new());
+}
+
+/// Marks the response of the instance as pending. It has to be registered before the request is propagated, as the
+/// flow may complete the response while the request is propagated.
+pub fn begin_pending_response(id: Uuid) {
+    PENDING_RESPONSES.lock().unwrap().insert(id, Arc::new((Mutex::new(false), Condvar::new())));
+}
+
+/// Completes the pending response of the instance. Does nothing, if no response is pending.
+pub fn complete_pending_response(id: Uuid) {
+    let Some(pending_response) = PENDING_RESPONSES.lock().unwrap().get(&id).cloned() else {
+        return;
+    };
+    let (completed, condvar) = pending_response.as_ref();
+    *completed.lock().unwrap() = true;
+    condvar.notify_all();
+}
+
+/// Waits until the pending response of the instance has been completed. Returns false, if the timeout elapsed.
+pub fn await_pending_response(id: Uuid, timeout: Duration) -> bool {
+    let Some(pending_response) = PENDING_RESPONSES.lock().unwrap().get(&id).cloned() else {
+        return false;
+    };
+    let (completed, condvar) = pending_response.as_ref();
+    let completed = condvar
+        .wait_timeout_while(completed.lock().unwrap(), timeout, |completed| !*completed)
+        .map(|(completed, _)| *completed)
+        .unwrap_or(false);
+    PENDING_RESPONSES.lock().unwrap().remove(&id);
+    completed
+}
+
+/// Composes the response of the response properties of the instance.
+///
+/// The body template and the values of the response headers are rendered with the request, the response body and
+/// the response status. Without an explicit Content-Type header the content type is selected from the content
+/// types by the Accept header of the request.
+pub fn compose_response(reactive_instance: &ReactiveEntityInstance, request: &Value) -> OutgoingResponse {
+    let status = reactive_instance
+        .as_u64(RESPONSE_STATUS)
+        .and_then(|status| u16::try_from(status).ok())
+        .filter(|status| (100..600).contains(status))
+        .unwrap_or(200);
+    let response_body = reactive_instance.get(RESPONSE_BODY).unwrap_or(Value::Null);
+    let mut data = request.as_object().cloned().unwrap_or_default();
+    data.insert(String::from("response_body"), response_body.clone());
+    data.insert(String::from("response_status"), json!(status));
+    let mut headers: Vec<(String, String)> = reactive_instance
+        .as_object(RESPONSE_HEADERS)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(name, value)| match render_template(&value, &data) {
+            Value::String(value) => Some((name, value)),
+            _ => None,
+        })
+        .collect();
+    let body = match reactive_instance.get(BODY_TEMPLATE) {
+        Some(Value::String(template)) if template.is_empty() => response_body,
+        Some(Value::Null) | None => response_body,
+        Some(template) => render_template(&template, &data),
+    };
+    if body.is_null() {
+        return OutgoingResponse {
+            status,
+            headers,
+            body: Vec::new(),
+            stream: None,
+        };
+    }
+    let explicit_content_type = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, content_type)| content_type.clone());
+    let content_type = match explicit_content_type {
+        Some(content_type) => content_type,
+        None => {
+            let content_types = reactive_instance
+                .as_array(CONTENT_TYPES)
+                .map(|content_types| {
+                    content_types
+                        .iter()
+                        .filter_map(|content_type| content_type.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_else(|| vec![default_content_type(&body).to_string()]);
+            let accept = data
+                .get("headers")
+                .and_then(Value::as_object)
+                .and_then(|headers| headers.iter().find(|(name, _)| name.eq_ignore_ascii_case("accept")))
+                .and_then(|(_, accept)| accept.as_str())
+                .unwrap_or_default();
+            let Some(content_type) = select_content_type(accept, &content_types) else {
+                return OutgoingResponse::json(406, &json!({ "error": "None of the accepted content types can be produced", "content_types": content_types }));
+            };
+            headers.push((String::from("Content-Type"), content_type.clone()));
+            content_type
+        }
+    };
+    if reactive_instance.as_string(CONTENT_TYPE).as_ref() != Some(&content_type) {
+        reactive_instance.set(CONTENT_TYPE, json!(content_type));
+    }
+    OutgoingResponse {
+        status,
+        headers,
+        body: encode_body(&content_type, &body),
+        stream: None,
+    }
+}
+
+fn default_content_type(body: &Value) -> &'static str {
+    match body {
+        Value::String(_) => "text/plain; charset=utf-8",
+        _ => "application/json",
+    }
+}
+
+/// Selects the content type which is accepted with the highest quality. Content types with equal quality are
+/// selected in the given order.
+///
+/// Returns the first content type, if the request doesn't have an Accept header, and none, if no content type is
+/// accepted.
+pub fn select_content_type(accept: &str, content_types: &[String]) -> Option<String> {
+    if accept.trim().is_empty() {
+        return content_types.first().cloned();
+    }
+    let media_ranges: Vec<(String, f32)> = accept
+        .split(',')
+        .filter_map(|media_range| {
+            let mut parts = media_range.split(';');
+            let media_range = parts.next()?.trim().to_ascii_lowercase();
+            let quality = parts
+                .filter_map(|parameter| parameter.trim().strip_prefix("q="))
+                .find_map(|quality| quality.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            Some((media_range, quality))
+        })
+        .collect();
+    let mut selected: Option<(&String, f32)> = None;
+    for content_type in content_types {
+        let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        let main_type = essence.split('/').next().unwrap_or_default();
+        // The most specific media range determines the quality
+        let quality = media_ranges
+            .iter()
+            .filter_map(|(media_range, quality)| {
+                let specificity = if *media_range == essence {
+                    2
+                } else if media_range.strip_suffix("/*") == Some(main_type) {
+                    1
+                } else if media_range == "*/*" {
+                    0
+                } else {
+                    return None;
+                };
+                Some((specificity, *quality))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .map(|(_, quality)| quality)
+            .unwrap_or(0.0);
+        if quality > 0.0 && selected.map(|(_, selected_quality)| quality > selected_quality).unwrap_or(true) {
+            selected = Some((content_type, quality));
+        }
+    }
+    selected.map(|(content_type, _)| content_type.clone())
+}
+
+/// Encodes the body in the content type. Strings are sent as they are, unless the content type is JSON.
+fn encode_body(content_type: &str, body: &Value) -> Vec<u8> {
+    let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    if essence == "application/json" || essence.ends_with("+json") {
+        return body.to_string().into_bytes();
+    }
+    match body {
+        Value::String(body) => body.clone().into_bytes(),
+        Value::Object(fields) if essence == "application/x-www-form-urlencoded" => encode_form(fields).into_bytes(),
+        body => body.to_string().into_bytes(),
+    }
+}
+
+fn encode_form(fields: &Map<String, Value>) -> String {
+    let mut serializer = form_urlencoded::Serializer::new(String::new());
+    for (name, value) in fields {
+        match value {
+            Value::String(value) => serializer.append_pair(name, value),
+            Value::Null => serializer.append_pair(name, ""),
+            value => serializer.append_pair(name, &value.to_string()),
+        };
+    }
+    serializer.finish()
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+use log::warn;
+use url::Url;
+
+use crate::ssrf::UrlPolicy;
+
+/// The duration for which a robots.txt is cached.
+const ROBOTS_TTL: Duration = Duration::from_secs(3600);
+
+/// The timeout for fetching a robots.txt.
+const ROBOTS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A parsed robots.txt as specified by RFC 9309.
+#[derive(Debug, Default)]
+struct Robots {
+    groups: Vec<Group>,
+    /// True, if the robots.txt could not be fetched because of a server error. Everything is disallowed then.
+    unavailable: bool,
+}
+
+#[derive(Debug, Default)]
+struct Group {
+    /// The lower case product tokens of the user agents of the group.
+    user_agents: Vec<String>,
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug)]
+struct Rule {
+    allow: bool,
+    path: String,
+}
+
+lazy_static! {
+    /// The robots.txt by origin.
+    static ref ROBOTS: RwLock<HashMap<String, (Instant, Arc<Robots>)>> = RwLock::new(HashMap::new());
+}
+
+/// Returns true, if the robots.txt of the host allows the user agent to request the url.
+///
+/// The robots.txt is fetched on first use, by an agent of the url policy, and cached per origin.
+pub fn is_allowed_by_robots(url: &str, user_agent: &str, url_policy: &UrlPolicy) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return true;
+    };
+    let origin = url.origin().ascii_serialization();
+    let cached = ROBOTS
+        .read()
+        .unwrap()
+        .get(&origin)
+        .filter(|(fetched_at, _)| fetched_at.elapsed() < ROBOTS_TTL)
+        .map(|(_, robots)| robots.clone());
+    let robots = match cached {
+        Some(robots) => robots,
+        None => {
+            let robots = Arc::new(fetch_robots(&origin, url_policy));
+            ROBOTS.write().unwrap().insert(origin, (Instant::now(), robots.clone()));
+            robots
+        }
+    };
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    robots.is_allowed(&path, user_agent)
+}
+
+fn fetch_robots(origin: &str, url_policy: &UrlPolicy) -> Robots {
+    let agent = url_policy.agent_builder().timeout(ROBOTS_TIMEOUT).build();
+    match agent.get(&format!("{}/robots.txt", origin)).call() {
+        Ok(response) => Robots::from(response.into_string().unwrap_or_default().as_str()),
+        // A missing robots.txt allows everything
+        Err(ureq::Error::Status(status, _)) if status < 500 => Robots::default(),
+        Err(e) => {
+            warn!("Failed to fetch the robots.txt of {}: {}", origin, e);
+            Robots {
+                groups: Vec::new(),
+                unavailable: true,
+            }
+        }
+    }
+}
+
+impl From<&str> for Robots {
+    fn from(robots: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        // Consecutive user-agent lines belong to the same group
+        let mut in_user_agents = false;
+        for line in robots.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_lowercase().as_str() {
+                "user-agent" => {
+                    if !in_user_agents {
+                        groups.push(Group::default());
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.user_agents.push(value.to_lowercase());
+                    }
+                    in_user_agents = true;
+                }
+                key @ ("allow" | "disallow") => {
+                    in_user_agents = false;
+                    // An empty disallow rule allows everything
+                    if value.is_empty() {
+                        continue;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.rules.push(Rule {
+                            allow: key == "allow",
+                            path: value.to_string(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Robots { groups, unavailable: false }
+    }
+}
+
+impl Robots {
+    /// The most specific matching rule decides. If an allow and a disallow rule are equally specific, the allow rule wins.
+    fn is_allowed(&self, path: &str, user_agent: &str) -> bool {
+        if self.unavailable {
+            return false;
+        }
+        let user_agent = user_agent.to_lowercase();
+        let mut groups: Vec<&Group> = self
+            .groups
+            .iter()
+            .filter(|group| group.user_agents.iter().any(|name| name != "*" && user_agent.contains(name.as_str())))
+            .collect();
+        if groups.is_empty() {
+            groups = self.groups.iter().filter(|group| group.user_agents.iter().any(|name| name == "*")).collect();
+        }
+        groups
+            .iter()
+            .flat_map(|group| group.rules.iter())
+            .filter(|rule| matches_path(&rule.path, path))
+            .max_by_key(|rule| (rule.path.len(), rule.allow))
+            .map(|rule| rule.allow)
+            .unwrap_or(true)
+    }
+}
+
+/// Matches the path against a rule path, which may contain the wildcard `*` and the end anchor `$`.
+fn matches_path(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let Some(mut rest) = path.strip_prefix(parts[0]) else {
+        return false;
+    };
+    for (index, part) in parts.iter().enumerate().skip(1) {
+        if anchored && index == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS_TXT: &str = "# Example\nUser-agent: *\nDisallow: /private\nAllow: /private/public\n\nUser-agent: inexor\nUser-agent: other\nDisallow: /*.json$\nDisallow:\n\nUser-agent: blocked\nDisallow: /\n";
+
+    #[test]
+    fn parses_the_groups() {
+        let robots = Robots::from(ROBOTS_TXT);
+        assert_eq!(robots.groups.len(), 3);
+        assert_eq!(robots.groups[1].user_agents, vec!["inexor", "other"]);
+        assert_eq!(robots.groups[1].rules.len(), 1);
+    }
+
+    #[test]
+    fn applies_the_most_specific_rule() {
+        let robots = Robots::from(ROBOTS_TXT);
+        assert!(robots.is_allowed("/index.html", "Mozilla/5.0"));
+        assert!(!robots.is_allowed("/private/data", "Mozilla/5.0"));
+        assert!(robots.is_allowed("/private/public/data", "Mozilla/5.0"));
+        assert!(robots.is_allowed("/private/data", "Inexor/1.0"));
+        assert!(!robots.is_allowed("/data/values.json", "Inexor/1.0"));
+        assert!(robots.is_allowed("/data/values.json?page=2", "Inexor/1.0"));
+        assert!(!robots.is_allowed("/", "blocked-bot"));
+    }
+
+    #[test]
+    fn prefers_allow_rules_of_the_same_length() {
+        let robots = Robots::from("User-agent: *\nDisallow: /page\nAllow: /page\n");
+        assert!(robots.is_allowed("/page", "Inexor"));
+    }
+
+    #[test]
+    fn disallows_everything_if_unavailable() {
+        let robots = Robots {
+            groups: Vec::new(),
+            unavailable: true,
+        };
+        assert!(!robots.is_allowed("/", "Inexor"));
+        assert!(Robots::default().is_allowed("/", "Inexor"));
+    }
+
+    #[test]
+    fn matches_wildcards_and_anchors() {
+        assert!(matches_path("/fish", "/fish.html"));
+        assert!(!matches_path("/fish", "/Fish.asp"));
+        assert!(matches_path("/*.php", "/folder/filename.php?parameters"));
+        assert!(matches_path("/*.php$", "/folder/filename.php"));
+        assert!(!matches_path("/*.php$", "/filename.php?parameters"));
+        assert!(matches_path("/fish*.php", "/fish-and-chips.php"));
+        assert!(!matches_path("/$", "/index"));
+        assert!(matches_path("/$", "/"));
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+use log::warn;
+use ring::constant_time::verify_slices_are_equal;
+use ring::signature;
+use ring::signature::RsaPublicKeyComponents;
+use ring::signature::UnparsedPublicKey;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::hashing::hmac_sha256;
+use crate::limiter::acquire_request_permit;
+use crate::limiter::DEFAULT_PRIORITY;
+use crate::model::PropertyInstanceGetter;
+use crate::model::ReactiveEntityInstance;
+use crate::model_http::RouteAuthProperties::API_KEYS;
+use crate::model_http::RouteAuthProperties::API_KEY_HEADER;
+use crate::model_http::RouteAuthProperties::AUTH_TYPE;
+use crate::model_http::RouteAuthProperties::BEARER_TOKENS;
+use crate::model_http::RouteAuthProperties::JWKS_URL;
+use crate::model_http::RouteAuthProperties::JWT_AUDIENCE;
+use crate::model_http::RouteAuthProperties::JWT_ISSUER;
+use crate::model_http::RouteAuthProperties::JWT_SECRET;
+use crate::model_http::RouteAuthProperties::REALM;
+use crate::model_http::RouteAuthProperties::USERS;
+use crate::placeholder::resolve_env;
+use crate::secrets::resolve_secrets;
+use crate::server::IncomingRequest;
+use crate::server::OutgoingResponse;
+use crate::ssrf::UrlPolicy;
+
+/// The time after which a key set is fetched again.
+const JWKS_MAX_AGE: Duration = Duration::from_secs(3600);
+
+/// The minimum time between two fetches of a key set, which are caused by unknown key ids.
+const JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The tolerated clock skew in seconds when the expiration and the not before time of a JWT are checked.
+const JWT_LEEWAY: i64 = 60;
+
+lazy_static! {
+    /// The keys of the JSON Web Key Sets and the time they have been fetched, by url.
+    static ref JWKS: RwLock<HashMap<String, (Instant, Vec<Value>)>> = RwLock::new(HashMap::new());
+}
+
+/// Authenticates the request with the scheme of the component route_auth.
+///
+/// Returns the principal, which is null for routes without authentication, or the 401 response which is sent instead
+/// of propagating the request. The reason of the refusal is only logged, so that clients can't probe for user names.
+pub fn authenticate(reactive_instance: &ReactiveEntityInstance, request: &IncomingRequest) -> Result<Value, OutgoingResponse> {
+    let auth_type = reactive_instance.as_string(AUTH_TYPE).unwrap_or_else(|| String::from("none"));
+    let realm = reactive_instance.as_string(REALM).unwrap_or_else(|| String::from("Inexor"));
+    let result = match auth_type.as_str() {
+        "none" | "" => return Ok(Value::Null),
+        "api_key" => authenticate_api_key(reactive_instance, request),
+        "basic" => authenticate_basic(reactive_instance, request),
+        "bearer" => authenticate_bearer(reactive_instance, request),
+        _ => {
+            warn!("Refused the request to {}: Unknown authentication type {}", request.path, auth_type);
+            return Err(OutgoingResponse::status(500));
+        }
+    };
+    result.map_err(|e| {
+        warn!("Refused the request of {} to {}: {}", request.remote_addr, request.path, e);
+        let mut response = OutgoingResponse::json(401, &json!({ "error": "Unauthorized" }));
+        let challenge = match auth_type.as_str() {
+            "basic" => format!("Basic realm=\"{}\", charset=\"UTF-8\"", realm),
+            "bearer" => format!("Bearer realm=\"{}\", error=\"invalid_token\"", realm),
+            _ => format!("ApiKey realm=\"{}\"", realm),
+        };
+        response.headers.push((String::from("WWW-Authenticate"), challenge));
+        response
+    })
+}
+
+fn get_credentials(reactive_instance: &ReactiveEntityInstance, property: &str) -> Vec<String> {
+    reactive_instance
+        .as_array(property)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Value::as_str)
+        .map(|credential| resolve_secrets(&resolve_env(credential)))
+        .filter(|credential| !credential.is_empty())
+        .collect()
+}
+
+fn is_equal(a: &str, b: &str) -> bool {
+    verify_slices_are_equal(a.as_bytes(), b.as_bytes()).is_ok()
+}
+
+fn authenticate_api_key(reactive_instance: &ReactiveEntityInstance, request: &IncomingRequest) -> Result<Value, String> {
+    let header = reactive_instance.as_string(API_KEY_HEADER).unwrap_or_else(|| String::from("X-API-Key"));
+    let api_key = request.header(&header).ok_or_else(|| format!("The header {} is missing", header))?;
+    let api_keys = get_credentials(reactive_instance, API_KEYS.as_ref());
+    let index = api_keys
+        .iter()
+        .position(|accepted| is_equal(accepted, api_key))
+        .ok_or_else(|| String::from("Invalid API key"))?;
+    Ok(json!({ "scheme": "api_key", "key": index }))
+}
+
+fn authenticate_basic(reactive_instance: &ReactiveEntityInstance, request: &IncomingRequest) -> Result<Value, String> {
+    let credentials = request
+        .header("Authorization")
+        .and_then(|authorization| authorization.strip_prefix("Basic ").or_else(|| authorization.strip_prefix("basic ")))
+        .ok_or_else(|| String::from("Basic credentials are missing"))?;
+    let credentials = base64::decode(credentials.trim()).map_err(|_| String::from("Invalid basic credentials"))?;
+    let credentials = String::from_utf8(credentials).map_err(|_| String::from("Invalid basic credentials"))?;
+    let (name, password) = credentials.split_once(':').ok_or_else(|| String::from("Invalid basic credentials"))?;
+    let users = reactive_instance.as_object(USERS).unwrap_or_default();
+    let expected = users
+        .get(name)
+        .and_then(Value::as_str)
+        .map(|expected| resolve_secrets(&resolve_env(expected)))
+        .filter(|expected| !expected.is_empty())
+        .ok_or_else(|| format!("The user {} is unknown", name))?;
+    if !is_equal(&expected, password) {
+        return Err(format!("Invalid password of user {}", name));
+    }
+    Ok(json!({ "scheme": "basic", "name": name }))
+}
+
+fn authenticate_bearer(reactive_instance: &ReactiveEntityInstance, request: &IncomingRequest) -> Result<Value, String> {
+    let token = request
+        .header("Authorization")
+        .and_then(|authorization| authorization.strip_prefix("Bearer ").or_else(|| authorization.strip_prefix("bearer ")))
+        .map(str::trim)
+        .ok_or_else(|| String::from("The bearer token is missing"))?;
+    let jwks_url = reactive_instance.as_string(JWKS_URL).unwrap_or_default();
+    let jwt_secret = resolve_secrets(&resolve_env(&reactive_instance.as_string(JWT_SECRET).unwrap_or_default()));
+    if !jwks_url.is_empty() || !jwt_secret.is_empty() {
+        let claims = validate_jwt(reactive_instance, token, &resolve_env(&jwks_url), &jwt_secret)?;
+        return Ok(json!({ "scheme": "bearer", "name": claims["sub"], "claims": claims }));
+    }
+    let index = get_credentials(reactive_instance, BEARER_TOKENS.as_ref())
+        .iter()
+        .position(|accepted| is_equal(accepted, token))
+        .ok_or_else(|| String::from("Invalid bearer token"))?;
+    Ok(json!({ "scheme": "bearer", "token": index }))
+}
+
+fn decode_segment(segment: &str) -> Result<Vec<u8>, String> {
+    base64::decode_config(segment, base64::URL_SAFE_NO_PAD).map_err(|_| String::from("Invalid JWT"))
+}
+
+/// Validates the signature and the claims of the JWT and returns the claims.
+fn validate_jwt(reactive_instance: &ReactiveEntityInstance, token: &str, jwks_url: &str, jwt_secret: &str) -> Result<Value, String> {
+    let segments: Vec<&str> = token.split('.').collect();
+    let [header, claims, signature] = segments[..] else {
+        return Err(String::from("Invalid JWT"));
+    };
+    let header: Value = serde_json::from_slice(&decode_segment(header)?).map_err(|_| String::from("Invalid JWT header"))?;
+    let claims_json: Value = serde_json::from_slice(&decode_segment(claims)?).map_err(|_| String::from("Invalid JWT claims"))?;
+    let signature = decode_segment(signature)?;
+    let signing_input = &token[..token.rfind('.').unwrap_or(0)];
+    let algorithm = header["alg"].as_str().unwrap_or_default();
+    match algorithm {
+        "HS256" if !jwt_secret.is_empty() => {
+            verify_slices_are_equal(&hmac_sha256(jwt_secret.as_bytes(), signing_input.as_bytes()), &signature)
+                .map_err(|_| String::from("Invalid JWT signature"))?;
+        }
+        "RS256" | "RS384" | "RS512" | "ES256" | "ES384" if !jwks_url.is_empty() => {
+            let key = find_jwk(reactive_instance, jwks_url, header["kid"].as_str(), algorithm)?;
+            verify_signature(&key, algorithm, signing_input.as_bytes(), &signature)?;
+        }
+        // The algorithm none and algorithms without configured key are refused
+        _ => return Err(format!("The JWT algorithm {} is not accepted", algorithm)),
+    }
+    check_claims(reactive_instance, &claims_json)?;
+    Ok(claims_json)
+}
+
+fn check_claims(reactive_instance: &ReactiveEntityInstance, claims: &Value) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp();
+    if let Some(expiration) = claims["exp"].as_i64() {
+        if now > expiration + JWT_LEEWAY {
+            return Err(String::from("The JWT has expired"));
+        }
+    }
+    if let Some(not_before) = claims["nbf"].as_i64() {
+        if now + JWT_LEEWAY < not_before {
+            return Err(String::from("The JWT is not valid yet"));
+        }
+    }
+    let issuer = reactive_instance.as_string(JWT_ISSUER).unwrap_or_default();
+    if !issuer.is_empty() && claims["iss"].as_str() != Some(issuer.as_str()) {
+        return Err(String::from("The JWT has been issued by another issuer"));
+    }
+    let audience = reactive_instance.as_string(JWT_AUDIENCE).unwrap_or_default();
+    if !audience.is_empty() {
+        let audiences = match &claims["aud"] {
+            Value::String(audience) => vec![audience.as_str()],
+            Value::Array(audiences) => audiences.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !audiences.contains(&audience.as_str()) {
+            return Err(String::from("The JWT has been issued for another audience"));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the key of the key set by key id. The key set is fetched again if it is outdated or doesn't contain the key,
+/// as the keys may have been rotated.
+fn find_jwk(reactive_instance: &ReactiveEntityInstance, jwks_url: &str, key_id: Option<&str>, algorithm: &str) -> Result<Value, String> {
+    let key_type = if algorithm.starts_with("RS") { "RSA" } else { "EC" };
+    let find = |keys: &[Value]| {
+        keys.iter()
+            .find(|key| {
+                key["kty"] == json!(key_type)
+                    && key_id.map(|key_id| key["kid"] == json!(key_id)).unwrap_or(true)
+                    && key["alg"].as_str().map(|key_algorithm| key_algorithm == algorithm).unwrap_or(true)
+                    && key["use"].as_str().map(|key_use| key_use == "sig").unwrap_or(true)
+            })
+            .cloned()
+    };
+    let cached = JWKS.read().unwrap().get(jwks_url).cloned();
+    if let Some((fetched, keys)) = cached.as_ref() {
+        if fetched.elapsed() < JWKS_MAX_AGE {
+            if let Some(key) = find(keys) {
+                return Ok(key);
+            }
+            if fetched.elapsed() < JWKS_MIN_REFRESH_INTERVAL {
+                return Err(String::from("The key of the JWT is unknown"));
+            }
+        }
+    }
+    let keys = fetch_jwks(reactive_instance, jwks_url)?;
+    let key = find(&keys);
+    JWKS.write().unwrap().insert(jwks_url.to_string(), (Instant::now(), keys));
+    key.ok_or_else(|| String::from("The key of the JWT is unknown"))
+}
+
+fn fetch_jwks(reactive_instance: &ReactiveEntityInstance, jwks_url: &str) -> Result<Vec<Value>, String> {
+    let url_policy = UrlPolicy::from(reactive_instance);
+    url_policy.check(jwks_url)?;
+    let _permit = acquire_request_permit(DEFAULT_PRIORITY);
+    let jwks: Value = url_policy
+        .agent()
+        .get(jwks_url)
+        .call()
+        .map_err(|e| format!("Failed to fetch the key set {}: {}", jwks_url, e))?
+        .into_json()
+        .map_err(|e| format!("Invalid key set {}: {}", jwks_url, e))?;
+    Ok(jwks["keys"].as_array().cloned().unwrap_or_default())
+}
+
+fn verify_signature(key: &Value, algorithm: &str, message: &[u8], signature: &[u8]) -> Result<(), String> {
+    let parameter = |name: &str| decode_segment(key[name].as_str().unwrap_or_default()).map_err(|_| format!("Invalid parameter {} of the key", name));
+    let result = match algorithm {
+        "RS256" | "RS384" | "RS512" => {
+            let n = parameter("n")?;
+            let e = parameter("e")?;
+            let parameters = match algorithm {
+                "RS256" => &signature::RSA_PKCS1_2048_8192_SHA256,
+                "RS384" => &signature::RSA_PKCS1_2048_8192_SHA384,
+                _ => &signature::RSA_PKCS1_2048_8192_SHA512,
+            };
+            RsaPublicKeyComponents { n: &n, e: &e }.verify(parameters, message, signature)
+        }
+        _ => {
+            // Uncompressed point of the coordinates
+            let mut public_key = vec![0x04];
+            public_key.extend(parameter("x")?);
+            public_key.extend(parameter("y")?);
+            let parameters = match algorithm {
+                "ES256" => &signature::ECDSA_P256_SHA256_FIXED,
+                _ => &signature::ECDSA_P384_SHA384_FIXED,
+            };
+            UnparsedPublicKey::new(parameters, public_key).verify(message, signature)
+        }
+    };
+    result.map_err(|_| String::from("Invalid JWT signature"))
+}
@@ -0,0 +1,110 @@
+use log::debug;
+use serde_json::json;
+
+use crate::model::PropertyInstanceGetter;
+use crate::model::ReactiveEntityInstance;
+use crate::model_http::RouteCorsProperties::CORS_CREDENTIALS;
+use crate::model_http::RouteCorsProperties::CORS_EXPOSE_HEADERS;
+use crate::model_http::RouteCorsProperties::CORS_HEADERS;
+use crate::model_http::RouteCorsProperties::CORS_MAX_AGE;
+use crate::model_http::RouteCorsProperties::CORS_METHODS;
+use crate::model_http::RouteCorsProperties::CORS_ORIGINS;
+use crate::server::OutgoingResponse;
+
+/// The CORS settings of a route of the embedded server.
+#[derive(Debug, Clone, Default)]
+pub struct CorsPolicy {
+    /// The allowed origins. Empty, if CORS is disabled.
+    pub origins: Vec<String>,
+    pub methods: Vec<String>,
+    pub headers: Vec<String>,
+    pub expose_headers: Vec<String>,
+    pub credentials: bool,
+    pub max_age: u64,
+}
+
+impl From<&ReactiveEntityInstance> for CorsPolicy {
+    fn from(reactive_instance: &ReactiveEntityInstance) -> Self {
+        let strings = |property| -> Vec<String> {
+            reactive_instance
+                .as_array(property)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|value| value.as_str().map(String::from))
+                .collect()
+        };
+        CorsPolicy {
+            origins: strings(CORS_ORIGINS),
+            methods: strings(CORS_METHODS),
+            headers: strings(CORS_HEADERS),
+            expose_headers: strings(CORS_EXPOSE_HEADERS),
+            credentials: reactive_instance.as_bool(CORS_CREDENTIALS).unwrap_or(false),
+            max_age: reactive_instance.as_u64(CORS_MAX_AGE).unwrap_or(600),
+        }
+    }
+}
+
+impl CorsPolicy {
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.origins.iter().any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+    }
+
+    /// Answers the preflight request of the origin for the requested method and headers.
+    ///
+    /// Without explicit methods the method of the route is allowed, which is none for routes accepting all methods.
+    pub fn preflight(&self, origin: &str, route_method: Option<&str>, requested_method: &str, requested_headers: Option<&str>) -> OutgoingResponse {
+        let method_allowed = if self.methods.is_empty() {
+            route_method.map(|method| method.eq_ignore_ascii_case(requested_method)).unwrap_or(true)
+        } else {
+            self.methods.iter().any(|method| method == "*" || method.eq_ignore_ascii_case(requested_method))
+        };
+        if !self.allows_origin(origin) || !method_allowed {
+            debug!("Refused the preflight request of {} for {}", origin, requested_method);
+            return OutgoingResponse::json(403, &json!({ "error": "The origin or the method is not allowed" }));
+        }
+        let mut response = OutgoingResponse::status(204);
+        self.add_origin_headers(origin, &mut response);
+        let methods = if self.methods.is_empty() {
+            requested_method.to_string()
+        } else {
+            self.methods.join(", ")
+        };
+        response.headers.push((String::from("Access-Control-Allow-Methods"), methods));
+        let headers = if self.headers.is_empty() {
+            requested_headers.unwrap_or_default().to_string()
+        } else {
+            self.headers.join(", ")
+        };
+        if !headers.is_empty() {
+            response.headers.push((String::from("Access-Control-Allow-Headers"), headers));
+        }
+        response.headers.push((String::from("Access-Control-Max-Age"), self.max_age.to_string()));
+        response
+    }
+
+    /// Adds the CORS headers to the response of an actual request of the origin.
+    pub fn apply(&self, origin: &str, response: &mut OutgoingResponse) {
+        if !self.allows_origin(origin) {
+            return;
+        }
+        self.add_origin_headers(origin, response);
+        if !self.expose_headers.is_empty() {
+            response
+                .headers
+                .push((String::from("Access-Control-Expose-Headers"), self.expose_headers.join(", ")));
+        }
+    }
+
+    fn add_origin_headers(&self, origin: &str, response: &mut OutgoingResponse) {
+        // Credentials are not allowed with the wildcard
+        if self.origins.iter().any(|allowed| allowed == "*") && !self.credentials {
+            response.headers.push((String::from("Access-Control-Allow-Origin"), String::from("*")));
+        } else {
+            response.headers.push((String::from("Access-Control-Allow-Origin"), origin.to_string()));
+            response.headers.push((String::from("Vary"), String::from("Origin")));
+        }
+        if self.credentials {
+            response.headers.push((String::from("Access-Control-Allow-Credentials"), String::from("true")));
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+use log::warn;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::model::PropertyInstanceGetter;
+use crate::model::ReactiveEntityInstance;
+use crate::model_http::RouteRateLimitProperties::RATE_LIMIT;
+use crate::model_http::RouteRateLimitProperties::RATE_LIMIT_BURST;
+use crate::model_http::RouteRateLimitProperties::RATE_LIMIT_WINDOW;
+use crate::model_http::RouteRateLimitProperties::TRUST_FORWARDED_FOR;
+use crate::server::IncomingRequest;
+use crate::server::OutgoingResponse;
+
+/// The interval in which the buckets of clients, which haven't sent requests recently, are removed.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The tokens of a client. Each request takes a token, the tokens are refilled at the rate of the route.
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+struct Buckets {
+    buckets: HashMap<(Uuid, String), Bucket>,
+    swept_at: Instant,
+}
+
+lazy_static! {
+    /// The buckets by route and client.
+    static ref BUCKETS: Mutex<Buckets> = Mutex::new(Buckets {
+        buckets: HashMap::new(),
+        swept_at: Instant::now(),
+    });
+}
+
+/// Limits the rate of the requests of the client with the settings of the component route_rate_limit.
+///
+/// A client may send the rate limit of requests per window plus the burst at once. Returns the 429 response which is
+/// sent instead of propagating the request, if the client exceeded the limit.
+pub fn check_rate_limit(reactive_instance: &ReactiveEntityInstance, request: &IncomingRequest) -> Result<(), OutgoingResponse> {
+    let rate_limit = reactive_instance.as_u64(RATE_LIMIT).unwrap_or(0);
+    if rate_limit == 0 {
+        return Ok(());
+    }
+    let window = Duration::from_millis(reactive_instance.as_u64(RATE_LIMIT_WINDOW).unwrap_or(60000).max(1));
+    let capacity = (rate_limit + reactive_instance.as_u64(RATE_LIMIT_BURST).unwrap_or(0)) as f64;
+    let tokens_per_second = rate_limit as f64 / window.as_secs_f64();
+    let client = get_client(reactive_instance, request);
+    let now = Instant::now();
+    let mut buckets = BUCKETS.lock().unwrap();
+    if now.duration_since(buckets.swept_at) > SWEEP_INTERVAL {
+        // Without requests for a whole window the bucket would be full again
+        buckets
+            .buckets
+            .retain(|_, bucket| now.duration_since(bucket.updated_at) < window.max(SWEEP_INTERVAL));
+        buckets.swept_at = now;
+    }
+    let bucket = buckets.buckets.entry((reactive_instance.id, client.clone())).or_insert(Bucket {
+        tokens: capacity,
+        updated_at: now,
+    });
+    bucket.tokens = (bucket.tokens + now.duration_since(bucket.updated_at).as_secs_f64() * tokens_per_second).min(capacity);
+    bucket.updated_at = now;
+    if bucket.tokens >= 1.0 {
+        bucket.tokens -= 1.0;
+        return Ok(());
+    }
+    let retry_after = ((1.0 - bucket.tokens) / tokens_per_second).ceil().max(1.0) as u64;
+    warn!("Refused the request of {} to {}: The rate limit has been exceeded", client, request.path);
+    let mut response = OutgoingResponse::json(429, &json!({ "error": "The rate limit has been exceeded", "retry_after": retry_after }));
+    response.headers.push((String::from("Retry-After"), retry_after.to_string()));
+    response.headers.push((String::from("RateLimit-Limit"), rate_limit.to_string()));
+    response.headers.push((String::from("RateLimit-Remaining"), String::from("0")));
+    response.headers.push((String::from("RateLimit-Reset"), retry_after.to_string()));
+    Err(response)
+}
+
+/// Returns the address of the client without the port, so that all connections of a client share the bucket.
+fn get_client(reactive_instance: &ReactiveEntityInstance, request: &IncomingRequest) -> String {
+    if reactive_instance.as_bool(TRUST_FORWARDED_FOR).unwrap_or(false) {
+        if let Some(client) = request
+            .header("X-Forwarded-For")
+            .and_then(|forwarded_for| forwarded_for.split(',').next())
+            .map(str::trim)
+            .filter(|client| !client.is_empty())
+        {
+            return client.to_string();
+        }
+    }
+    request
+        .remote_addr
+        .parse::<SocketAddr>()
+        .map(|remote_addr| remote_addr.ip().to_string())
+        .unwrap_or_else(|_| request.remote_addr.clone())
+}
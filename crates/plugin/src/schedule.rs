@@ -0,0 +1,23 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::Local;
+use cron::Schedule;
+
+/// Parses a cron expression. Expressions with five fields (minute, hour, day of month, month, day of week) are
+/// accepted as well as expressions which start with the seconds.
+pub fn parse_schedule(expression: &str) -> Result<Schedule, String> {
+    let expression = expression.trim();
+    let expression = if expression.split_whitespace().count() == 5 {
+        format!("0 {}", expression)
+    } else {
+        expression.to_string()
+    };
+    Schedule::from_str(&expression).map_err(|e| format!("Invalid cron expression {}: {}", expression, e))
+}
+
+/// Returns the duration until the next occurrence in local time or none, if there is no next occurrence.
+pub fn until_next(schedule: &Schedule) -> Option<Duration> {
+    let next = schedule.upcoming(Local).next()?;
+    (next - Local::now()).to_std().ok()
+}
@@ -0,0 +1,210 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+use log::error;
+use log::warn;
+use serde::Deserialize;
+use url::Url;
+
+use crate::config::get_config;
+use crate::placeholder::replace_placeholders;
+use crate::ssrf::matches_host_name;
+
+/// A secret and the hosts to which it may be sent.
+pub struct Secret {
+    pub value: String,
+    /// The host patterns like `example.com` or `*.example.com`. A secret without hosts isn't sent to any host: requests
+    /// which reference it fail.
+    pub hosts: Vec<String>,
+}
+
+/// Provides the secrets which are referenced by `${secret:NAME}` in credential properties.
+pub trait SecretStore: Send + Sync {
+    fn get_secret(&self, name: &str) -> Option<Secret>;
+}
+
+/// A secret in the secrets file, either `NAME = "value"` or `NAME = { value = "value", hosts = ["example.com"] }`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SecretEntry {
+    Value(String),
+    Scoped {
+        value: String,
+        #[serde(default)]
+        hosts: Vec<String>,
+    },
+}
+
+impl From<SecretEntry> for Secret {
+    fn from(entry: SecretEntry) -> Self {
+        match entry {
+            SecretEntry::Value(value) => Secret { value, hosts: Vec::new() },
+            SecretEntry::Scoped { value, hosts } => Secret { value, hosts },
+        }
+    }
+}
+
+/// Reads the secrets from a TOML file with one entry per secret. Secrets which are sent to other hosts must list the
+/// hosts.
+///
+/// The file is read on every lookup, so that rotated secrets are used without restarting the runtime.
+pub struct FileSecretStore {
+    pub path: String,
+}
+
+impl SecretStore for FileSecretStore {
+    fn get_secret(&self, name: &str) -> Option<Secret> {
+        let secrets = match fs::read_to_string(&self.path) {
+            Ok(secrets) => secrets,
+            Err(e) => {
+                error!("Failed to read secrets from {}: {}", self.path, e);
+                return None;
+            }
+        };
+        match toml::from_str::<HashMap<String, SecretEntry>>(&secrets) {
+            Ok(mut secrets) => secrets.remove(name).map(Secret::from),
+            Err(e) => {
+                error!("Failed to parse secrets from {}: {}", self.path, e);
+                None
+            }
+        }
+    }
+}
+
+lazy_static! {
+    static ref SECRET_STORE: RwLock<Arc<dyn SecretStore>> = RwLock::new(Arc::new(FileSecretStore {
+        path: get_config().secrets.path.clone()
+    }));
+}
+
+/// Replaces the secret store, for example by a store which queries a vault.
+pub fn set_secret_store(secret_store: Arc<dyn SecretStore>) {
+    *SECRET_STORE.write().unwrap() = secret_store;
+}
+
+/// Resolves the references `${secret:NAME}` in the text against the secret store, regardless of the hosts of the
+/// secrets. Only for secrets which aren't sent to another host, like the credentials of the routes.
+///
+/// The resolved value must not be written back into a property, because properties are serialized with the flows.
+pub fn resolve_secrets(text: &str) -> String {
+    if !text.contains("${secret:") {
+        return text.to_string();
+    }
+    let secret_store = SECRET_STORE.read().unwrap().clone();
+    replace_placeholders(text, "secret", |name| {
+        let secret = secret_store.get_secret(name);
+        if secret.is_none() {
+            warn!("The secret {} is unknown", name);
+        }
+        secret.map(|secret| secret.value)
+    })
+}
+
+/// Resolves the references `${secret:NAME}` in text which is sent to the host of the url.
+///
+/// Fails, if a referenced secret is unknown or isn't allowed for the host, so that a flow can neither send a secret to
+/// another host nor send the unresolved reference instead of the secret. Plain secrets like `NAME = "value"` aren't
+/// allowed for any host.
+pub fn resolve_secrets_for_url(text: &str, url: &str) -> Result<String, String> {
+    if !text.contains("${secret:") {
+        return Ok(text.to_string());
+    }
+    // Proxies may be given without scheme
+    let url = if url.contains("://") {
+        Url::parse(url)
+    } else {
+        Url::parse(&format!("http://{}", url))
+    };
+    let host = url.ok().and_then(|url| url.host_str().map(String::from)).unwrap_or_default();
+    let secret_store = SECRET_STORE.read().unwrap().clone();
+    let error = RefCell::new(None);
+    let text = replace_placeholders(text, "secret", |name| {
+        let secret = match secret_store.get_secret(name) {
+            Some(secret) if secret.hosts.is_empty() => Err(format!("The secret {} has no hosts to which it may be sent", name)),
+            Some(secret) if !is_allowed_host(&secret, &host) => Err(format!("The secret {} may not be sent to {}", name, host)),
+            Some(secret) => Ok(secret.value),
+            None => Err(format!("The secret {} is unknown", name)),
+        };
+        secret.map_err(|e| error.borrow_mut().get_or_insert(e).clone()).ok()
+    });
+    match error.into_inner() {
+        Some(e) => Err(e),
+        None => Ok(text),
+    }
+}
+
+fn is_allowed_host(secret: &Secret, host: &str) -> bool {
+    !host.is_empty() && secret.hosts.iter().any(|pattern| matches_host_name(pattern, host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plain_and_scoped_secrets() {
+        let secrets: HashMap<String, SecretEntry> = toml::from_str(
+            r#"
+            PLAIN = "plain"
+            SCOPED = { value = "scoped", hosts = ["api.example.com", "*.example.org"] }
+            "#,
+        )
+        .unwrap();
+        let secrets: HashMap<String, Secret> = secrets.into_iter().map(|(name, entry)| (name, Secret::from(entry))).collect();
+        assert_eq!(secrets["PLAIN"].value, "plain");
+        assert!(secrets["PLAIN"].hosts.is_empty());
+        assert_eq!(secrets["SCOPED"].value, "scoped");
+        assert_eq!(secrets["SCOPED"].hosts, vec!["api.example.com", "*.example.org"]);
+    }
+
+    #[test]
+    fn allows_only_the_hosts_of_the_secret() {
+        let secret = Secret {
+            value: String::from("value"),
+            hosts: vec![String::from("api.example.com"), String::from("*.example.org")],
+        };
+        assert!(is_allowed_host(&secret, "api.example.com"));
+        assert!(is_allowed_host(&secret, "cdn.example.org"));
+        assert!(!is_allowed_host(&secret, "example.org"));
+        assert!(!is_allowed_host(&secret, "attacker.example"));
+        assert!(!is_allowed_host(&secret, ""));
+        let unscoped = Secret {
+            value: String::from("value"),
+            hosts: Vec::new(),
+        };
+        assert!(!is_allowed_host(&unscoped, "api.example.com"));
+    }
+
+    struct StaticSecretStore;
+
+    impl SecretStore for StaticSecretStore {
+        fn get_secret(&self, name: &str) -> Option<Secret> {
+            match name {
+                "SCOPED" => Some(Secret {
+                    value: String::from("scoped"),
+                    hosts: vec![String::from("api.example.com")],
+                }),
+                "UNSCOPED" => Some(Secret {
+                    value: String::from("unscoped"),
+                    hosts: Vec::new(),
+                }),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn fails_for_secrets_which_may_not_be_sent() {
+        set_secret_store(Arc::new(StaticSecretStore));
+        let url = "https://api.example.com/items";
+        assert_eq!(resolve_secrets_for_url("Bearer ${secret:SCOPED}", url), Ok(String::from("Bearer scoped")));
+        assert_eq!(resolve_secrets_for_url("no secret", url), Ok(String::from("no secret")));
+        assert!(resolve_secrets_for_url("${secret:SCOPED}", "https://attacker.example").is_err());
+        assert!(resolve_secrets_for_url("${secret:UNSCOPED}", url).is_err());
+        assert!(resolve_secrets_for_url("${secret:UNKNOWN}", url).is_err());
+    }
+}
@@ -0,0 +1,567 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Write;
+use std::sync::mpsc::sync_channel;
+use std::sync::mpsc::TrySendError;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use chrono::Utc;
+use lazy_static::lazy_static;
+use log::error;
+use log::info;
+use log::warn;
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use tiny_http::Header;
+use tiny_http::Request;
+use tiny_http::Response;
+use tiny_http::Server;
+use tiny_http::SslConfig;
+use tiny_http::StatusCode;
+use url::Url;
+use uuid::Uuid;
+
+use crate::access_log::record_access;
+use crate::access_log::AccessLogEntry;
+use crate::config::get_config;
+use crate::model::ReactiveEntityInstance;
+use crate::route_cors::CorsPolicy;
+
+/// The number of attempts to bind the port again, while a server is restarted.
+const RESTART_ATTEMPTS: u32 = 50;
+
+const RESTART_DELAY: Duration = Duration::from_millis(100);
+
+/// A request which has been received by the embedded server.
+pub struct IncomingRequest {
+    pub method: String,
+    pub path: String,
+    /// The parameters of the path pattern of the route.
+    pub params: Map<String, Value>,
+    pub query: Map<String, Value>,
+    pub headers: Map<String, Value>,
+    pub body: Vec<u8>,
+    pub remote_addr: String,
+}
+
+impl IncomingRequest {
+    /// Returns the value of the header, regardless of the case of the name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .and_then(|(_, value)| value.as_str())
+    }
+
+    /// Returns the body as JSON or, if the body is not valid JSON, as string.
+    pub fn body_as_json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or_else(|_| json!(String::from_utf8_lossy(&self.body)))
+    }
+
+    /// Returns the request as object, for example for an output property.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "method": self.method,
+            "path": self.path,
+            "params": self.params,
+            "query": self.query,
+            "headers": self.headers,
+            "body": self.body_as_json(),
+            "remote_addr": self.remote_addr
+        })
+    }
+}
+
+/// The response which is sent back to the client.
+pub struct OutgoingResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// If given, the body is streamed from the reader instead.
+    pub stream: Option<Box<dyn Read + Send>>,
+}
+
+impl OutgoingResponse {
+    pub fn json(status: u16, body: &Value) -> Self {
+        OutgoingResponse {
+            status,
+            headers: vec![(String::from("Content-Type"), String::from("application/json"))],
+            body: body.to_string().into_bytes(),
+            stream: None,
+        }
+    }
+
+    pub fn status(status: u16) -> Self {
+        OutgoingResponse {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+            stream: None,
+        }
+    }
+
+    pub fn stream(status: u16, headers: Vec<(String, String)>, stream: Box<dyn Read + Send>) -> Self {
+        OutgoingResponse {
+            status,
+            headers,
+            body: Vec::new(),
+            stream: Some(stream),
+        }
+    }
+}
+
+/// Handles the requests of a route.
+#[derive(Clone)]
+pub enum RouteHandler {
+    /// Receives the request with the complete body.
+    Buffered(Arc<dyn Fn(IncomingRequest) -> OutgoingResponse + Send + Sync>),
+    /// Receives the request without body and reads the body from the stream, for example to store large uploads.
+    Streaming(Arc<dyn Fn(IncomingRequest, &mut dyn Read) -> OutgoingResponse + Send + Sync>),
+}
+
+/// Binds requests with the given method and path to a handler. Behaviours register their routes while they are connected.
+#[derive(Clone)]
+pub struct Route {
+    pub id: Uuid,
+    /// The method or none, if the route accepts all methods.
+    pub method: Option<String>,
+    /// The path or a path pattern. Segments like `:id` or `{id}` match a single segment, a trailing `*` or `*rest`
+    /// matches the remaining segments.
+    pub path: String,
+    pub handler: RouteHandler,
+    /// The instance whose component route_cors configures the CORS headers of the route or none, if the route doesn't
+    /// support CORS. The settings are read for each request.
+    pub cors: Option<Arc<ReactiveEntityInstance>>,
+}
+
+impl Route {
+    fn matches_method(&self, method: &str) -> bool {
+        self.method.as_ref().map(|m| m.eq_ignore_ascii_case(method)).unwrap_or(true)
+    }
+
+    /// The number of parameters of the path pattern. Routes with less parameters are preferred.
+    fn specificity(&self) -> usize {
+        self.path
+            .split('/')
+            .filter(|segment| segment.starts_with(':') || segment.starts_with('{') || segment.starts_with('*'))
+            .count()
+    }
+}
+
+/// Matches the path against the path pattern. Returns the parameters, if the path matches.
+pub fn match_path(pattern: &str, path: &str) -> Option<Map<String, Value>> {
+    let mut params = Map::new();
+    let mut segments = path.trim_start_matches('/').split('/');
+    let mut pattern_segments = pattern.trim_start_matches('/').split('/').peekable();
+    while let Some(pattern_segment) = pattern_segments.next() {
+        if let Some(name) = pattern_segment.strip_prefix('*') {
+            if pattern_segments.peek().is_some() {
+                return None;
+            }
+            let rest: Vec<&str> = segments.collect();
+            if !name.is_empty() {
+                params.insert(name.to_string(), json!(decode_segment(&rest.join("/"))));
+            }
+            return Some(params);
+        }
+        let segment = segments.next()?;
+        let name = pattern_segment
+            .strip_prefix(':')
+            .or_else(|| pattern_segment.strip_prefix('{').and_then(|name| name.strip_suffix('}')));
+        match name {
+            Some(name) if !segment.is_empty() => {
+                params.insert(name.to_string(), json!(decode_segment(segment)));
+            }
+            Some(_) => return None,
+            None if pattern_segment == segment => {}
+            None => return None,
+        }
+    }
+    segments.next().is_none().then_some(params)
+}
+
+fn decode_segment(segment: &str) -> String {
+    percent_decode_str(segment).decode_utf8_lossy().to_string()
+}
+
+/// An embedded server listening on a port. The server is started with the first route and stopped with the last route.
+struct EmbeddedServer {
+    /// None while the server is restarted.
+    server: Option<Arc<Server>>,
+    routes: Arc<Mutex<Vec<Route>>>,
+}
+
+lazy_static! {
+    static ref SERVERS: Mutex<HashMap<u16, EmbeddedServer>> = Mutex::new(HashMap::new());
+}
+
+/// The status of a running embedded server.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerStatus {
+    pub port: u16,
+    pub tls: bool,
+    /// The number of registered routes.
+    pub routes: usize,
+}
+
+/// Registers the route on the embedded server listening on the given port. The server is started, if necessary.
+pub fn register_route(port: u16, route: Route) -> Result<(), String> {
+    let mut servers = SERVERS.lock().unwrap();
+    if let Some(embedded_server) = servers.get(&port) {
+        embedded_server.routes.lock().unwrap().push(route);
+        return Ok(());
+    }
+    let server = Arc::new(start_server(port)?);
+    let routes = Arc::new(Mutex::new(vec![route]));
+    let embedded_server = EmbeddedServer {
+        server: Some(server.clone()),
+        routes: routes.clone(),
+    };
+    servers.insert(port, embedded_server);
+    thread::spawn(move || serve(port, server, routes));
+    Ok(())
+}
+
+/// Removes the route. An embedded server is stopped after its last route has been removed.
+pub fn unregister_route(id: Uuid) {
+    let mut servers = SERVERS.lock().unwrap();
+    let ports: Vec<u16> = servers
+        .iter()
+        .filter_map(|(port, embedded_server)| {
+            let mut routes = embedded_server.routes.lock().unwrap();
+            routes.retain(|route| route.id != id);
+            routes.is_empty().then_some(*port)
+        })
+        .collect();
+    for port in ports {
+        if let Some(server) = servers.remove(&port).and_then(|embedded_server| embedded_server.server) {
+            server.unblock();
+            info!("Stopped the embedded server on port {}", port);
+        }
+    }
+}
+
+/// Returns the status of the running embedded servers ordered by port.
+pub fn get_server_status() -> Vec<ServerStatus> {
+    let mut status: Vec<ServerStatus> = SERVERS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(port, embedded_server)| ServerStatus {
+            port: *port,
+            tls: get_config().servers.iter().any(|server| server.port == *port && server.has_tls()),
+            routes: embedded_server.routes.lock().unwrap().len(),
+        })
+        .collect();
+    status.sort_by_key(|status| status.port);
+    status
+}
+
+/// Restarts the embedded server on the port with the same routes, for example after its certificate has been renewed.
+/// Does nothing, if no server is running on the port.
+///
+/// The servers aren't locked while the port is bound again, so that routes can be registered and removed meanwhile.
+pub fn restart_server(port: u16) -> Result<(), String> {
+    let routes = {
+        let mut servers = SERVERS.lock().unwrap();
+        let Some(embedded_server) = servers.get_mut(&port) else {
+            return Ok(());
+        };
+        let Some(server) = embedded_server.server.take() else {
+            // The server is being restarted already
+            return Ok(());
+        };
+        server.unblock();
+        embedded_server.routes.clone()
+    };
+    // The port is released after the serving thread has dropped the server
+    let mut attempts = 0;
+    let server = loop {
+        match start_server(port) {
+            Ok(server) => break Arc::new(server),
+            Err(_) if attempts < RESTART_ATTEMPTS => {
+                attempts += 1;
+                thread::sleep(RESTART_DELAY);
+            }
+            Err(e) => {
+                // Without a server the routes are dropped, so that the next route starts a new server
+                remove_restarting_server(port, &routes);
+                return Err(e);
+            }
+        }
+    };
+    let mut servers = SERVERS.lock().unwrap();
+    match servers.get_mut(&port) {
+        Some(embedded_server) if Arc::ptr_eq(&embedded_server.routes, &routes) => {
+            embedded_server.server = Some(server.clone());
+            drop(servers);
+            thread::spawn(move || serve(port, server, routes));
+        }
+        // The last route has been removed meanwhile
+        _ => {
+            drop(servers);
+            server.unblock();
+        }
+    }
+    Ok(())
+}
+
+fn remove_restarting_server(port: u16, routes: &Arc<Mutex<Vec<Route>>>) {
+    let mut servers = SERVERS.lock().unwrap();
+    if servers
+        .get(&port)
+        .map(|embedded_server| Arc::ptr_eq(&embedded_server.routes, routes))
+        .unwrap_or(false)
+    {
+        servers.remove(&port);
+    }
+}
+
+/// Starts the embedded server on the port. The server accepts HTTPS, if a certificate is configured for the port.
+fn start_server(port: u16) -> Result<Server, String> {
+    let bind_address = get_config().get_bind_address(port);
+    let tls = get_config().servers.iter().find(|server| server.port == port && server.has_tls());
+    let Some(tls) = tls else {
+        let server = Server::http((bind_address, port)).map_err(|e| format!("Failed to start the embedded server on port {}: {}", port, e))?;
+        info!("Started the embedded server on port {}", port);
+        return Ok(server);
+    };
+    let read = |path: &str| fs::read(path).map_err(|e| format!("Failed to read {} of the embedded server on port {}: {}", path, port, e));
+    let ssl_config = SslConfig {
+        certificate: read(&tls.certificate)?,
+        private_key: read(&tls.private_key)?,
+    };
+    let server = Server::https((bind_address, port), ssl_config).map_err(|e| format!("Failed to start the embedded server on port {}: {}", port, e))?;
+    info!("Started the embedded server on port {} with TLS", port);
+    Ok(server)
+}
+
+/// Dispatches the requests to a fixed number of threads. Requests which can't be queued are answered with 503.
+fn serve(port: u16, server: Arc<Server>, routes: Arc<Mutex<Vec<Route>>>) {
+    let limits = &get_config().server_limits;
+    let (sender, receiver) = sync_channel::<Request>(limits.queue_size);
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..limits.workers.max(1) {
+        let receiver = receiver.clone();
+        let routes = routes.clone();
+        thread::spawn(move || loop {
+            // The receiver is unlocked before the request is handled
+            let request = receiver.lock().unwrap().recv();
+            match request {
+                Ok(request) => handle(port, &routes, request),
+                // The server has been stopped
+                Err(_) => return,
+            }
+        });
+    }
+    for request in server.incoming_requests() {
+        if let Err(TrySendError::Full(request)) = sender.try_send(request) {
+            warn!("Refused a request to the embedded server on port {}: All threads are busy", port);
+            if let Err(e) = request.respond(Response::empty(503)) {
+                error!("Failed to send the response: {}", e);
+            }
+        }
+    }
+}
+
+fn handle(port: u16, routes: &Mutex<Vec<Route>>, mut request: Request) {
+    let started_at = Instant::now();
+    let url = Url::parse(&format!("http://localhost{}", request.url())).ok();
+    let path = url.as_ref().map(|url| url.path().to_string()).unwrap_or_default();
+    let method = request.method().as_str().to_string();
+    let mut access_log_entry = AccessLogEntry {
+        timestamp: Utc::now(),
+        port,
+        route: None,
+        method: method.clone(),
+        path: path.clone(),
+        status: 0,
+        duration_ms: 0,
+        remote_addr: request.remote_addr().map(|remote_addr| remote_addr.to_string()).unwrap_or_default(),
+    };
+    let candidates: Vec<(Route, Map<String, Value>)> = routes
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|route| Some((route.clone(), match_path(&route.path, &path)?)))
+        .collect();
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv(name))
+            .map(|header| header.value.to_string())
+    };
+    let origin = header("Origin");
+    // Preflight requests are answered for the route of the requested method
+    let requested_method = header("Access-Control-Request-Method").filter(|_| method.eq_ignore_ascii_case("OPTIONS"));
+    let route = candidates
+        .iter()
+        .filter(|(route, _)| route.matches_method(requested_method.as_deref().unwrap_or(&method)))
+        .min_by_key(|(route, _)| route.specificity())
+        .cloned();
+    let preflight = match (&route, &origin, &requested_method) {
+        (Some((route, _)), Some(origin), Some(requested_method)) => route
+            .cors
+            .as_ref()
+            .map(|cors| CorsPolicy::from(cors.as_ref()))
+            .filter(|cors| !cors.origins.is_empty())
+            .map(|cors| cors.preflight(origin, route.method.as_deref(), requested_method, header("Access-Control-Request-Headers").as_deref())),
+        _ => None,
+    };
+    // Other OPTIONS requests are handled by a route of the method OPTIONS
+    let route = match requested_method {
+        Some(_) if preflight.is_none() => candidates
+            .iter()
+            .filter(|(route, _)| route.matches_method(&method))
+            .min_by_key(|(route, _)| route.specificity())
+            .cloned(),
+        _ => route,
+    };
+    access_log_entry.route = route.as_ref().map(|(route, _)| route.id);
+    let response = match (preflight, route) {
+        (Some(preflight), _) => preflight,
+        (None, Some((route, params))) => {
+            let cors = route.cors.clone();
+            let mut incoming_request = IncomingRequest {
+                method,
+                path,
+                params,
+                query: url
+                    .as_ref()
+                    .map(|url| url.query_pairs().map(|(name, value)| (name.to_string(), json!(value))).collect())
+                    .unwrap_or_default(),
+                headers: request
+                    .headers()
+                    .iter()
+                    .map(|header| (header.field.to_string(), json!(header.value.to_string())))
+                    .collect(),
+                body: Vec::new(),
+                remote_addr: access_log_entry.remote_addr.clone(),
+            };
+            let mut response = match route.handler {
+                RouteHandler::Buffered(handler) => match read_body(&mut request) {
+                    Ok(body) => {
+                        incoming_request.body = body;
+                        handler(incoming_request)
+                    }
+                    Err(response) => response,
+                },
+                RouteHandler::Streaming(handler) => handler(incoming_request, request.as_reader()),
+            };
+            if let (Some(cors), Some(origin)) = (cors, &origin) {
+                CorsPolicy::from(cors.as_ref()).apply(origin, &mut response);
+            }
+            response
+        }
+        // The path exists, but not with the method
+        (None, None) if !candidates.is_empty() => {
+            let mut allowed_methods: Vec<String> = candidates.iter().filter_map(|(route, _)| route.method.clone()).collect();
+            allowed_methods.sort();
+            allowed_methods.dedup();
+            let mut response = OutgoingResponse::status(405);
+            response.headers.push((String::from("Allow"), allowed_methods.join(", ")));
+            response
+        }
+        (None, None) => OutgoingResponse::status(404),
+    };
+    access_log_entry.status = response.status;
+    let result = match response.stream {
+        Some(stream) => write_stream(request.into_writer(), response.status, &response.headers, stream),
+        None => request.respond(to_response(response)),
+    };
+    if let Err(e) = result {
+        error!("Failed to send the response: {}", e);
+    }
+    access_log_entry.duration_ms = started_at.elapsed().as_millis() as u64;
+    record_access(access_log_entry);
+}
+
+/// Reads the body into memory. Bodies which are larger than the configured maximum are refused with 413, bodies which
+/// can't be read completely with 400.
+fn read_body(request: &mut Request) -> Result<Vec<u8>, OutgoingResponse> {
+    let max_body_size = get_config().server_limits.max_body_size;
+    if request.body_length().map(|length| length as u64 > max_body_size).unwrap_or(false) {
+        return Err(OutgoingResponse::status(413));
+    }
+    // Chunked bodies have no announced length
+    let mut body = Vec::new();
+    if let Err(e) = request.as_reader().take(max_body_size.saturating_add(1)).read_to_end(&mut body) {
+        error!("Failed to read the request body: {}", e);
+        return Err(OutgoingResponse::status(400));
+    }
+    if body.len() as u64 > max_body_size {
+        return Err(OutgoingResponse::status(413));
+    }
+    Ok(body)
+}
+
+fn to_response(response: OutgoingResponse) -> Response<Cursor<Vec<u8>>> {
+    let mut tiny_response = Response::from_data(response.body).with_status_code(response.status);
+    for (name, value) in response.headers.iter() {
+        if let Ok(header) = Header::from_bytes(name.as_bytes(), value.as_bytes()) {
+            tiny_response.add_header(header);
+        }
+    }
+    tiny_response
+}
+
+/// Writes a streamed response without chunked transfer encoding. The connection is closed at the end of the stream.
+///
+/// The writer is flushed after every read, so that events are not delayed by buffering.
+fn write_stream(mut writer: Box<dyn Write + Send>, status: u16, headers: &[(String, String)], mut stream: Box<dyn Read + Send>) -> io::Result<()> {
+    write!(writer, "HTTP/1.1 {} {}\r\n", status, StatusCode(status).default_reason_phrase())?;
+    for (name, value) in headers.iter() {
+        write!(writer, "{}: {}\r\n", name, value)?;
+    }
+    write!(writer, "Connection: close\r\n\r\n")?;
+    writer.flush()?;
+    let mut buffer = [0; 8192];
+    loop {
+        let length = stream.read(&mut buffer)?;
+        if length == 0 {
+            return Ok(());
+        }
+        writer.write_all(&buffer[..length])?;
+        writer.flush()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_static_paths() {
+        assert_eq!(match_path("/status", "/status"), Some(Map::new()));
+        assert_eq!(match_path("status", "/status"), Some(Map::new()));
+        assert_eq!(match_path("/status", "/status/more"), None);
+        assert_eq!(match_path("/status/more", "/status"), None);
+        assert_eq!(match_path("/status", "/other"), None);
+    }
+
+    #[test]
+    fn extracts_the_parameters() {
+        let params = match_path("/users/:id/posts/{post}", "/users/42/posts/hello%20world").unwrap();
+        assert_eq!(json!(params), json!({"id": "42", "post": "hello world"}));
+        assert_eq!(match_path("/users/:id", "/users/"), None);
+    }
+
+    #[test]
+    fn matches_the_rest_of_the_path() {
+        let params = match_path("/files/*path", "/files/a/b%2Fc.txt").unwrap();
+        assert_eq!(json!(params), json!({"path": "a/b/c.txt"}));
+        assert_eq!(match_path("/files/*", "/files/a/b"), Some(Map::new()));
+        assert_eq!(match_path("/files/*path/more", "/files/a/more"), None);
+    }
+}
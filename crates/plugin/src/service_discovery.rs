@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+use log::error;
+use trust_dns_resolver::Resolver;
+
+struct ResolvedService {
+    base_urls: Vec<String>,
+    valid_until: Instant,
+}
+
+lazy_static! {
+    /// The resolved services by service name and scheme. The entries are refreshed after the TTL of the SRV records has expired.
+    static ref SERVICES: Mutex<HashMap<(String, String), ResolvedService>> = Mutex::new(HashMap::new());
+}
+
+/// Resolves the SRV records of a service like `_http._tcp.example.com` into base urls.
+///
+/// Only the targets with the highest priority (lowest value) are returned. The load balancer selects one of them.
+pub fn resolve_service(service: &str, scheme: &str) -> Vec<String> {
+    let key = (service.to_string(), scheme.to_string());
+    if let Some(resolved_service) = SERVICES.lock().unwrap().get(&key) {
+        if resolved_service.valid_until > Instant::now() {
+            return resolved_service.base_urls.clone();
+        }
+    }
+    match lookup_service(service, scheme) {
+        Ok(resolved_service) => {
+            let base_urls = resolved_service.base_urls.clone();
+            SERVICES.lock().unwrap().insert(key, resolved_service);
+            base_urls
+        }
+        Err(e) => {
+            error!("Failed to resolve service {}: {}", service, e);
+            // Keep using the expired targets until the service can be resolved again
+            SERVICES
+                .lock()
+                .unwrap()
+                .get(&key)
+                .map(|resolved_service| resolved_service.base_urls.clone())
+                .unwrap_or_default()
+        }
+    }
+}
+
+fn lookup_service(service: &str, scheme: &str) -> Result<ResolvedService, String> {
+    let resolver = Resolver::from_system_conf().map_err(|e| e.to_string())?;
+    let lookup = resolver.srv_lookup(service).map_err(|e| e.to_string())?;
+    let priority = lookup.iter().map(|srv| srv.priority()).min().unwrap_or_default();
+    let base_urls = lookup
+        .iter()
+        .filter(|srv| srv.priority() == priority)
+        .map(|srv| format!("{}://{}:{}", scheme, srv.target().to_utf8().trim_end_matches('.'), srv.port()))
+        .collect();
+    Ok(ResolvedService {
+        base_urls,
+        valid_until: lookup.as_lookup().valid_until(),
+    })
+}
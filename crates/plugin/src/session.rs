@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::model::PropertyInstanceGetter;
+use crate::model::PropertyInstanceSetter;
+use crate::model::ReactiveEntityInstance;
+use crate::model_http::HttpSessionProperties::COOKIES;
+use crate::model_http::HttpSessionProperties::PROXY;
+use crate::model_http::HttpSessionProperties::REQUEST_HEADERS;
+
+lazy_static! {
+    /// The sessions by the id of the instance which uses the session. Maintained by the relation behaviour http_uses_session.
+    static ref SESSIONS: RwLock<HashMap<Uuid, Arc<ReactiveEntityInstance>>> = RwLock::new(HashMap::new());
+}
+
+pub fn attach_session(id: Uuid, session: Arc<ReactiveEntityInstance>) {
+    SESSIONS.write().unwrap().insert(id, session);
+}
+
+pub fn detach_session(id: Uuid) {
+    SESSIONS.write().unwrap().remove(&id);
+}
+
+pub fn get_session(id: Uuid) -> Option<Arc<ReactiveEntityInstance>> {
+    SESSIONS.read().unwrap().get(&id).cloned()
+}
+
+/// Returns the request headers of the session merged with the given request headers, which take precedence.
+///
+/// The cookies of the session are sent unless the request headers contain a cookie header.
+pub fn merge_request_headers(session: &ReactiveEntityInstance, request_headers: Map<String, Value>) -> Map<String, Value> {
+    let mut merged_headers = session.as_object(REQUEST_HEADERS).unwrap_or_default();
+    let cookies = session.as_object(COOKIES).unwrap_or_default();
+    if !cookies.is_empty() {
+        let cookie = cookies
+            .iter()
+            .filter_map(|(name, value)| value.as_str().map(|value| format!("{}={}", name, value)))
+            .collect::<Vec<String>>()
+            .join("; ");
+        merged_headers.insert(String::from("Cookie"), json!(cookie));
+    }
+    for (request_header, value) in request_headers.into_iter() {
+        merged_headers.retain(|name, _| !name.eq_ignore_ascii_case(&request_header));
+        merged_headers.insert(request_header, value);
+    }
+    merged_headers
+}
+
+pub fn get_session_proxy(session: &ReactiveEntityInstance) -> Option<String> {
+    session.as_string(PROXY).filter(|proxy| !proxy.is_empty())
+}
+
+/// Stores the cookies of the response in the cookie jar of the session.
+///
+/// The attributes of the cookies are not evaluated: cookies are kept until they are deleted by the server.
+pub fn store_cookies(session: &ReactiveEntityInstance, response: &ureq::Response) {
+    let set_cookies = response.all("set-cookie");
+    if set_cookies.is_empty() {
+        return;
+    }
+    let mut cookies = session.as_object(COOKIES).unwrap_or_default();
+    for set_cookie in set_cookies {
+        let cookie = set_cookie.split(';').next().unwrap_or_default();
+        let Some((name, value)) = cookie.split_once('=') else {
+            continue;
+        };
+        let is_expired = set_cookie.to_lowercase().contains("max-age=0");
+        if value.is_empty() || is_expired {
+            cookies.remove(name.trim());
+        } else {
+            cookies.insert(name.trim().to_string(), json!(value.trim()));
+        }
+    }
+    session.set(COOKIES, json!(cookies));
+}
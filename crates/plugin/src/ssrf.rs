@@ -0,0 +1,287 @@
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+
+use url::Url;
+
+use crate::config::get_config;
+use crate::dns::resolve;
+use crate::dns::TimedResolver;
+use crate::model::PropertyInstanceGetter;
+use crate::model::ReactiveEntityInstance;
+use crate::model_http::HttpProperties::ALLOWED_HOSTS;
+use crate::model_http::HttpProperties::DENIED_HOSTS;
+use crate::model_http::HttpProperties::SSRF_PROTECTION;
+
+/// Decides which urls an instance is allowed to request.
+///
+/// Hosts are matched by name (`example.com`, `*.example.com`), by address or by CIDR block (`10.0.0.0/8`).
+#[derive(Debug, Clone, Default)]
+pub struct UrlPolicy {
+    /// If true, private, loopback and link-local addresses are blocked unless they are allowed explicitly.
+    pub enabled: bool,
+    pub allowed_hosts: Vec<String>,
+    pub denied_hosts: Vec<String>,
+}
+
+impl From<&ReactiveEntityInstance> for UrlPolicy {
+    /// Combines the settings of the instance with the plugin-wide settings.
+    fn from(reactive_instance: &ReactiveEntityInstance) -> Self {
+        let ssrf = &get_config().ssrf;
+        let hosts = |property| -> Vec<String> {
+            reactive_instance
+                .as_array(property)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|host| host.as_str().map(String::from))
+                .collect()
+        };
+        let mut allowed_hosts = hosts(ALLOWED_HOSTS);
+        allowed_hosts.extend(ssrf.allowed_hosts.iter().cloned());
+        let mut denied_hosts = hosts(DENIED_HOSTS);
+        denied_hosts.extend(ssrf.denied_hosts.iter().cloned());
+        UrlPolicy {
+            enabled: ssrf.enabled || reactive_instance.as_bool(SSRF_PROTECTION).unwrap_or(false),
+            allowed_hosts,
+            denied_hosts,
+        }
+    }
+}
+
+impl UrlPolicy {
+    /// Returns true, if no restriction applies.
+    pub fn is_unrestricted(&self) -> bool {
+        !self.enabled && self.allowed_hosts.is_empty() && self.denied_hosts.is_empty()
+    }
+
+    /// Checks if the url may be requested.
+    ///
+    /// The host is resolved in order to check the addresses. This check only fails early: the connection itself has to
+    /// be made by the agent of the policy or to the addresses checked by `check_addresses`, as the host may resolve to
+    /// another address the next time.
+    pub fn check(&self, url: &str) -> Result<(), String> {
+        if self.is_unrestricted() {
+            return Ok(());
+        }
+        let url = Url::parse(url).map_err(|e| format!("Invalid url {}: {}", url, e))?;
+        let host = url.host_str().ok_or_else(|| format!("The url {} has no host", url))?;
+        let addresses: Vec<IpAddr> = resolve(&format!("{}:{}", host, url.port_or_known_default().unwrap_or(80)))
+            .map_err(|e| format!("Failed to resolve {}: {}", host, e))?
+            .iter()
+            .map(|socket_address| socket_address.ip())
+            .collect();
+        self.check_addresses(host, &addresses)
+    }
+
+    /// Checks if the host may be connected to at the addresses it has been resolved to.
+    pub fn check_addresses(&self, host: &str, addresses: &[IpAddr]) -> Result<(), String> {
+        if self.is_unrestricted() {
+            return Ok(());
+        }
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let matches = |patterns: &[String]| patterns.iter().any(|pattern| matches_host(pattern, host, addresses));
+        if matches(&self.denied_hosts) {
+            return Err(format!("The host {} is denied", host));
+        }
+        if matches(&self.allowed_hosts) {
+            return Ok(());
+        }
+        if !self.allowed_hosts.is_empty() {
+            return Err(format!("The host {} is not allowed", host));
+        }
+        if self.enabled && addresses.iter().any(is_internal) {
+            return Err(format!("The host {} resolves to an internal address", host));
+        }
+        Ok(())
+    }
+
+    /// Returns the builder of an agent which only connects to the addresses allowed by the policy.
+    ///
+    /// The resolver of the agent checks the addresses of every connection, including the connections to the targets of
+    /// redirects, and the agent connects to exactly the addresses which have been checked. With a proxy the address of
+    /// the proxy is checked, so the proxy has to be allowed explicitly, if it is internal.
+    pub fn agent_builder(&self) -> ureq::AgentBuilder {
+        ureq::AgentBuilder::new().resolver(TimedResolver::with_policy(self.clone()))
+    }
+
+    pub fn agent(&self) -> ureq::Agent {
+        self.agent_builder().build()
+    }
+}
+
+fn matches_host(pattern: &str, host: &str, addresses: &[IpAddr]) -> bool {
+    if let Some((network, prefix_length)) = pattern.split_once('/') {
+        let (Ok(network), Ok(prefix_length)) = (network.parse::<IpAddr>(), prefix_length.parse::<u32>()) else {
+            return false;
+        };
+        return addresses.iter().any(|address| in_network(address, &network, prefix_length));
+    }
+    if let Ok(pattern) = pattern.parse::<IpAddr>() {
+        return addresses.contains(&pattern);
+    }
+    matches_host_name(pattern, host)
+}
+
+/// Returns true, if the host matches the name pattern `example.com` or `*.example.com`.
+pub fn matches_host_name(pattern: &str, host: &str) -> bool {
+    let host = host.to_lowercase();
+    let pattern = pattern.to_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host.ends_with(&format!(".{}", domain)),
+        None => host == pattern,
+    }
+}
+
+fn in_network(address: &IpAddr, network: &IpAddr, prefix_length: u32) -> bool {
+    match (address, network) {
+        (IpAddr::V4(address), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix_length.min(32)).unwrap_or(0);
+            u32::from(*address) & mask == u32::from(*network) & mask
+        }
+        (IpAddr::V6(address), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix_length.min(128)).unwrap_or(0);
+            u128::from(*address) & mask == u128::from(*network) & mask
+        }
+        _ => false,
+    }
+}
+
+/// The IPv4 networks which are internal apart from the private, loopback, link-local and broadcast addresses: the
+/// current network, the shared address space of carrier-grade NAT and the IETF protocol assignments.
+const INTERNAL_IPV4_NETWORKS: [(Ipv4Addr, u32); 3] = [
+    (Ipv4Addr::new(0, 0, 0, 0), 8),
+    (Ipv4Addr::new(100, 64, 0, 0), 10),
+    (Ipv4Addr::new(192, 0, 0, 0), 24),
+];
+
+/// The well-known prefix of NAT64, which embeds the IPv4 address in the last 32 bits.
+const NAT64_NETWORK: (Ipv6Addr, u32) = (Ipv6Addr::new(0x64, 0xff9b, 0, 0, 0, 0, 0, 0), 96);
+
+/// Returns true for private (RFC 1918), shared (RFC 6598), loopback, link-local, unspecified and unique local addresses
+/// and for the other networks which aren't reachable on the internet. IPv4 addresses embedded in IPv6 addresses are
+/// checked as IPv4 addresses.
+fn is_internal(address: &IpAddr) -> bool {
+    match address {
+        IpAddr::V4(ipv4_address) => {
+            ipv4_address.is_private()
+                || ipv4_address.is_loopback()
+                || ipv4_address.is_link_local()
+                || ipv4_address.is_broadcast()
+                || INTERNAL_IPV4_NETWORKS
+                    .iter()
+                    .any(|(network, prefix_length)| in_network(address, &IpAddr::V4(*network), *prefix_length))
+        }
+        IpAddr::V6(ipv6_address) => {
+            if let Some(ipv4_address) = ipv6_address.to_ipv4_mapped() {
+                return is_internal(&IpAddr::V4(ipv4_address));
+            }
+            if in_network(address, &IpAddr::V6(NAT64_NETWORK.0), NAT64_NETWORK.1) {
+                let [.., a, b, c, d] = ipv6_address.octets();
+                return is_internal(&IpAddr::V4(Ipv4Addr::new(a, b, c, d)));
+            }
+            let segment = ipv6_address.segments()[0];
+            ipv6_address.is_loopback() || ipv6_address.is_unspecified() || (segment & 0xfe00) == 0xfc00 || (segment & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses(addresses: &[&str]) -> Vec<IpAddr> {
+        addresses.iter().map(|address| address.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn matches_host_names() {
+        assert!(matches_host("example.com", "Example.COM", &[]));
+        assert!(matches_host("*.example.com", "api.example.com", &[]));
+        assert!(!matches_host("*.example.com", "example.com", &[]));
+        assert!(!matches_host("*.example.com", "badexample.com", &[]));
+        assert!(!matches_host("example.com", "example.com.evil.org", &[]));
+    }
+
+    #[test]
+    fn matches_addresses_and_networks() {
+        let resolved = addresses(&["93.184.216.34", "2606:2800:220:1::1"]);
+        assert!(matches_host("93.184.216.34", "example.com", &resolved));
+        assert!(matches_host("93.184.0.0/16", "example.com", &resolved));
+        assert!(matches_host("2606:2800::/32", "example.com", &resolved));
+        assert!(!matches_host("10.0.0.0/8", "example.com", &resolved));
+        assert!(!matches_host("10.0.0.0/abc", "example.com", &resolved));
+    }
+
+    #[test]
+    fn checks_network_prefixes() {
+        let address = "192.168.1.20".parse().unwrap();
+        assert!(in_network(&address, &"192.168.1.0".parse().unwrap(), 24));
+        assert!(!in_network(&address, &"192.168.2.0".parse().unwrap(), 24));
+        assert!(in_network(&address, &"192.168.1.20".parse().unwrap(), 32));
+        assert!(in_network(&address, &"0.0.0.0".parse().unwrap(), 0));
+        assert!(!in_network(&address, &"::".parse().unwrap(), 0));
+        assert!(in_network(&"fd12::1".parse().unwrap(), &"fc00::".parse().unwrap(), 7));
+    }
+
+    #[test]
+    fn detects_internal_addresses() {
+        for address in addresses(&[
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.0.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "0.1.2.3",
+            "100.64.0.1",
+            "100.127.255.254",
+            "192.0.0.8",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+            "64:ff9b::7f00:1",
+            "64:ff9b::a9fe:a9fe",
+        ]) {
+            assert!(is_internal(&address), "{}", address);
+        }
+        for address in addresses(&[
+            "93.184.216.34",
+            "100.128.0.1",
+            "192.0.1.1",
+            "2606:2800:220:1::1",
+            "::ffff:93.184.216.34",
+            "64:ff9b::5db8:d822",
+        ]) {
+            assert!(!is_internal(&address), "{}", address);
+        }
+    }
+
+    #[test]
+    fn blocks_internal_addresses() {
+        let policy = UrlPolicy {
+            enabled: true,
+            allowed_hosts: Vec::new(),
+            denied_hosts: vec![String::from("*.evil.org")],
+        };
+        assert!(policy.check_addresses("example.com", &addresses(&["93.184.216.34"])).is_ok());
+        assert!(policy.check_addresses("localhost", &addresses(&["127.0.0.1"])).is_err());
+        assert!(policy.check_addresses("mapped", &addresses(&["::ffff:192.168.0.1"])).is_err());
+        assert!(policy.check_addresses("[fe80::1]", &addresses(&["fe80::1"])).is_err());
+        assert!(policy.check_addresses("www.evil.org", &addresses(&["93.184.216.34"])).is_err());
+        assert!(UrlPolicy::default().check_addresses("localhost", &addresses(&["127.0.0.1"])).is_ok());
+    }
+
+    #[test]
+    fn only_allows_the_allowed_hosts() {
+        let policy = UrlPolicy {
+            enabled: true,
+            allowed_hosts: vec![String::from("10.1.0.0/16"), String::from("*.example.com")],
+            denied_hosts: vec![String::from("admin.example.com")],
+        };
+        assert!(policy.check_addresses("internal", &addresses(&["10.1.2.3"])).is_ok());
+        assert!(policy.check_addresses("api.example.com", &addresses(&["93.184.216.34"])).is_ok());
+        assert!(policy.check_addresses("admin.example.com", &addresses(&["93.184.216.34"])).is_err());
+        assert!(policy.check_addresses("example.org", &addresses(&["93.184.216.34"])).is_err());
+    }
+}
@@ -0,0 +1,41 @@
+use serde_json::Map;
+use serde_json::Value;
+
+/// Replaces placeholders like `{{name}}` in all strings of the template by the values of the data object.
+///
+/// A string which consists of a single placeholder is replaced by the value itself, so that numbers, booleans and
+/// objects keep their type.
+pub fn render_template(template: &Value, data: &Map<String, Value>) -> Value {
+    match template {
+        Value::String(template) => render_string(template, data),
+        Value::Array(items) => Value::Array(items.iter().map(|item| render_template(item, data)).collect()),
+        Value::Object(entries) => Value::Object(entries.iter().map(|(key, value)| (key.clone(), render_template(value, data))).collect()),
+        _ => template.clone(),
+    }
+}
+
+fn render_string(template: &str, data: &Map<String, Value>) -> Value {
+    if let Some(name) = template.strip_prefix("{{").and_then(|template| template.strip_suffix("}}")) {
+        if !name.contains("{{") && !name.contains("}}") {
+            if let Some(value) = data.get(name.trim()) {
+                return value.clone();
+            }
+        }
+    }
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}").map(|end| start + end) else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        match data.get(rest[start + 2..end].trim()) {
+            Some(Value::String(value)) => rendered.push_str(value),
+            Some(value) => rendered.push_str(&value.to_string()),
+            None => rendered.push_str(&rest[start..end + 2]),
+        }
+        rest = &rest[end + 2..];
+    }
+    rendered.push_str(rest);
+    Value::String(rendered)
+}
@@ -0,0 +1,57 @@
+use std::io;
+use std::io::Read;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Limits the bandwidth of a transfer by sleeping whenever the transfer is ahead of the allowed rate.
+pub struct Throttle {
+    /// The allowed bytes per second or none, if the bandwidth is unlimited.
+    bytes_per_second: Option<u64>,
+    started: Instant,
+    transferred_bytes: u64,
+}
+
+impl Throttle {
+    /// Creates a throttle for the given kilobits per second. Zero means unlimited.
+    pub fn new(kbps: u64) -> Self {
+        Throttle {
+            bytes_per_second: if kbps > 0 { Some(kbps * 1000 / 8) } else { None },
+            started: Instant::now(),
+            transferred_bytes: 0,
+        }
+    }
+
+    /// Blocks until the transfer of the given number of bytes is allowed.
+    pub fn consume(&mut self, bytes: u64) {
+        let Some(bytes_per_second) = self.bytes_per_second else {
+            return;
+        };
+        self.transferred_bytes += bytes;
+        let allowed_elapsed = Duration::from_secs_f64(self.transferred_bytes as f64 / bytes_per_second as f64);
+        let elapsed = self.started.elapsed();
+        if allowed_elapsed > elapsed {
+            thread::sleep(allowed_elapsed - elapsed);
+        }
+    }
+}
+
+/// Throttles the reading of the inner reader, for example of a request body.
+pub struct ThrottledReader<R: Read> {
+    inner: R,
+    throttle: Throttle,
+}
+
+impl<R: Read> ThrottledReader<R> {
+    pub fn new(inner: R, throttle: Throttle) -> Self {
+        ThrottledReader { inner, throttle }
+    }
+}
+
+impl<R: Read> Read for ThrottledReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let length = self.inner.read(buf)?;
+        self.throttle.consume(length as u64);
+        Ok(length)
+    }
+}
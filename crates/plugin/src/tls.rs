@@ -0,0 +1,289 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use rustls::client::ServerCertVerified;
+use rustls::client::ServerCertVerifier;
+use rustls::client::WebPkiVerifier;
+use rustls::version::TLS12;
+use rustls::version::TLS13;
+use rustls::Certificate;
+use rustls::ClientConfig;
+use rustls::ClientConnection;
+use rustls::OwnedTrustAnchor;
+use rustls::RootCertStore;
+use rustls::ServerName;
+use rustls::StreamOwned;
+use rustls::SupportedProtocolVersion;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use x509_parser::prelude::FromDer;
+use x509_parser::prelude::X509Certificate;
+
+use crate::concurrency::Cancellation;
+use crate::dns::connect_checked;
+use crate::dns::TimedResolver;
+use crate::model::PropertyInstanceGetter;
+use crate::model::ReactiveEntityInstance;
+use crate::model_http::HttpProperties::PINNED_SPKI_SHA256;
+use crate::model_http::HttpProperties::TLS_MAX_VERSION;
+use crate::model_http::HttpProperties::TLS_MIN_VERSION;
+use crate::ssrf::UrlPolicy;
+
+/// Decides which TLS connections an instance accepts.
+pub struct TlsPolicy {
+    pub min_version: Option<String>,
+    pub max_version: Option<String>,
+    /// The base64 encoded SHA-256 hashes of the subject public key infos of which one has to be part of the chain.
+    pub pinned_spki_sha256: Vec<String>,
+}
+
+impl From<&ReactiveEntityInstance> for TlsPolicy {
+    fn from(reactive_instance: &ReactiveEntityInstance) -> Self {
+        let version = |property| reactive_instance.as_string(property).filter(|version| !version.trim().is_empty());
+        TlsPolicy {
+            min_version: version(TLS_MIN_VERSION),
+            max_version: version(TLS_MAX_VERSION),
+            pinned_spki_sha256: reactive_instance
+                .as_array(PINNED_SPKI_SHA256)
+                .unwrap_or_default()
+                .iter()
+                // The pins are accepted with the prefix sha256/ as well
+                .filter_map(|pin| pin.as_str().map(|pin| pin.trim().trim_start_matches("sha256/").to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl TlsPolicy {
+    /// Builds the TLS configuration of the agent.
+    pub fn client_config(&self) -> Result<Arc<ClientConfig>, String> {
+        let mut client_config = ClientConfig::builder()
+            .with_safe_default_cipher_suites()
+            .with_safe_default_kx_groups()
+            .with_protocol_versions(&self.protocol_versions()?)
+            .map_err(|e| e.to_string())?
+            .with_root_certificates(root_certificates())
+            .with_no_client_auth();
+        if !self.pinned_spki_sha256.is_empty() {
+            client_config.dangerous().set_certificate_verifier(Arc::new(PinningVerifier {
+                verifier: WebPkiVerifier::new(root_certificates(), None),
+                pinned_spki_sha256: self.pinned_spki_sha256.clone(),
+            }));
+        }
+        Ok(Arc::new(client_config))
+    }
+
+    /// Returns the supported protocol versions within the range of the policy.
+    ///
+    /// TLS 1.0 and TLS 1.1 are rejected, because they are not supported by rustls.
+    fn protocol_versions(&self) -> Result<Vec<&'static SupportedProtocolVersion>, String> {
+        let min_version = parse_version(self.min_version.as_deref().unwrap_or("1.2"))?;
+        let max_version = parse_version(self.max_version.as_deref().unwrap_or("1.3"))?;
+        let protocol_versions: Vec<&'static SupportedProtocolVersion> = [(12, &TLS12), (13, &TLS13)]
+            .into_iter()
+            .filter(|(version, _)| (min_version..=max_version).contains(version))
+            .map(|(_, protocol_version)| protocol_version)
+            .collect();
+        if protocol_versions.is_empty() {
+            return Err(format!(
+                "No supported TLS version between {} and {}: only TLS 1.2 and TLS 1.3 are supported",
+                self.min_version.as_deref().unwrap_or("1.2"),
+                self.max_version.as_deref().unwrap_or("1.3")
+            ));
+        }
+        Ok(protocol_versions)
+    }
+}
+
+/// Establishes the TLS connections of a request with the TLS configuration of the instance. The connections are
+/// registered, so that the request can be cancelled.
+///
+/// The agent calls the connector as soon as the connection has been established, so the connect and the TLS handshake
+/// are timed by the resolver of the agent. Plain HTTP connections aren't passed to the connector, their connect is timed
+/// by the body of the request.
+pub struct RequestTlsConnector {
+    pub client_config: Arc<ClientConfig>,
+    pub cancellation: Cancellation,
+    pub resolver: TimedResolver,
+}
+
+impl ureq::TlsConnector for RequestTlsConnector {
+    fn connect(&self, dns_name: &str, io: Box<dyn ureq::ReadWrite>) -> Result<Box<dyn ureq::ReadWrite>, ureq::Error> {
+        self.resolver.record_connected();
+        if let Some(socket) = io.socket() {
+            self.cancellation.register_connection(socket);
+        }
+        let started = Instant::now();
+        let server_name = ServerName::try_from(dns_name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+        let connection = ClientConnection::new(self.client_config.clone(), server_name).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let mut stream = StreamOwned::new(connection, io);
+        // The handshake is completed now instead of by the first write of the request, so that it can be timed
+        while stream.conn.is_handshaking() {
+            stream.conn.complete_io(&mut stream.sock)?;
+        }
+        self.resolver.record_handshake(started.elapsed());
+        Ok(Box::new(TlsStream(stream)))
+    }
+}
+
+struct TlsStream(StreamOwned<ClientConnection, Box<dyn ureq::ReadWrite>>);
+
+impl Read for TlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for TlsStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl fmt::Debug for TlsStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TlsStream").field(&self.0.sock).finish()
+    }
+}
+
+impl ureq::ReadWrite for TlsStream {
+    fn socket(&self) -> Option<&TcpStream> {
+        self.0.sock.socket()
+    }
+}
+
+/// Verifies the certificate chain as usual and additionally requires one of the certificates to match a pin.
+struct PinningVerifier {
+    verifier: WebPkiVerifier,
+    pinned_spki_sha256: Vec<String>,
+}
+
+impl ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self
+            .verifier
+            .verify_server_cert(end_entity, intermediates, server_name, scts, ocsp_response, now)?;
+        let pinned = std::iter::once(end_entity)
+            .chain(intermediates.iter())
+            .filter_map(spki_sha256)
+            .any(|spki_sha256| self.pinned_spki_sha256.contains(&spki_sha256));
+        if !pinned {
+            return Err(rustls::Error::General(String::from(
+                "Certificate pinning failed: no certificate of the chain matches a pinned public key",
+            )));
+        }
+        Ok(verified)
+    }
+}
+
+/// Accepts any certificate, because the certificates are only inspected.
+struct AcceptAnyCertificate;
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+/// A certificate of the chain presented by a server.
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    /// The start of the validity as unix timestamp.
+    pub not_before: i64,
+    /// The end of the validity as unix timestamp.
+    pub not_after: i64,
+    pub spki_sha256: Option<String>,
+}
+
+/// Connects to the server and returns the certificate chain it presents, starting with its own certificate.
+///
+/// The chain is not verified, so that expired or otherwise invalid certificates can be inspected as well. Only addresses
+/// allowed by the url policy are connected to.
+pub fn read_certificate_chain(hostname: &str, port: u16, timeout: Duration, url_policy: &UrlPolicy) -> Result<Vec<CertificateInfo>, String> {
+    let mut client_config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(RootCertStore::empty())
+        .with_no_client_auth();
+    client_config.dangerous().set_certificate_verifier(Arc::new(AcceptAnyCertificate));
+    let server_name = ServerName::try_from(hostname).map_err(|e| format!("Invalid host name {}: {}", hostname, e))?;
+    let mut connection = ClientConnection::new(Arc::new(client_config), server_name).map_err(|e| e.to_string())?;
+    let mut stream = connect_checked(hostname, port, url_policy, timeout)?;
+    stream.set_read_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+    while connection.is_handshaking() {
+        connection.complete_io(&mut stream).map_err(|e| e.to_string())?;
+    }
+    let certificates = connection.peer_certificates().unwrap_or_default();
+    Ok(certificates
+        .iter()
+        .filter_map(|certificate| {
+            let (_, x509_certificate) = X509Certificate::from_der(&certificate.0).ok()?;
+            Some(CertificateInfo {
+                subject: x509_certificate.subject().to_string(),
+                issuer: x509_certificate.issuer().to_string(),
+                not_before: x509_certificate.validity().not_before.timestamp(),
+                not_after: x509_certificate.validity().not_after.timestamp(),
+                spki_sha256: spki_sha256(certificate),
+            })
+        })
+        .collect())
+}
+
+/// Returns the base64 encoded SHA-256 hash of the subject public key info of the certificate.
+pub fn spki_sha256(certificate: &Certificate) -> Option<String> {
+    let (_, certificate) = X509Certificate::from_der(&certificate.0).ok()?;
+    Some(base64::encode(Sha256::digest(certificate.public_key().raw)))
+}
+
+/// Parses a TLS version like 1.2 into a comparable number like 12.
+fn parse_version(version: &str) -> Result<u8, String> {
+    match version.trim() {
+        "1.0" | "1.1" => Err(format!("TLS {} is not supported: only TLS 1.2 and TLS 1.3 are supported", version.trim())),
+        "1.2" => Ok(12),
+        "1.3" => Ok(13),
+        _ => Err(format!("Unknown TLS version {}", version)),
+    }
+}
+
+/// Returns the Mozilla root certificates, which are trusted by the default agent, too.
+fn root_certificates() -> RootCertStore {
+    let mut root_certificates = RootCertStore::empty();
+    root_certificates.add_server_trust_anchors(
+        webpki_roots::TLS_SERVER_ROOTS
+            .0
+            .iter()
+            .map(|trust_anchor| OwnedTrustAnchor::from_subject_spki_name_constraints(trust_anchor.subject, trust_anchor.spki, trust_anchor.name_constraints)),
+    );
+    root_certificates
+}
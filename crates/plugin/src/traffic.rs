@@ -0,0 +1,104 @@
+use std::io;
+use std::io::Read;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use log::error;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::audit::subscribe_requests;
+use crate::audit::AuditEntry;
+use crate::config::get_config;
+use crate::server::register_route;
+use crate::server::unregister_route;
+use crate::server::OutgoingResponse;
+use crate::server::Route;
+use crate::server::RouteHandler;
+
+/// The interval of the comments which keep idle connections open and detect clients which have gone away.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+lazy_static! {
+    static ref TRAFFIC_STREAM_ROUTE_ID: Uuid = Uuid::new_v4();
+}
+
+/// Starts the endpoint which streams the summaries of the requests as server-sent events, if a port is configured.
+pub fn start_traffic_stream() {
+    let config = &get_config().traffic_stream;
+    if config.port == 0 {
+        return;
+    }
+    let route = Route {
+        id: *TRAFFIC_STREAM_ROUTE_ID,
+        method: Some(String::from("GET")),
+        path: config.path.clone(),
+        handler: RouteHandler::Buffered(Arc::new(|_| {
+            let headers = vec![
+                (String::from("Content-Type"), String::from("text/event-stream")),
+                (String::from("Cache-Control"), String::from("no-cache")),
+                // The inspector is served by the runtime from another origin
+                (String::from("Access-Control-Allow-Origin"), String::from("*")),
+            ];
+            OutgoingResponse::stream(200, headers, Box::new(EventStream::new(subscribe_requests())))
+        })),
+        cors: None,
+    };
+    if let Err(e) = register_route(config.port, route) {
+        error!("Failed to start the traffic stream: {}", e);
+    }
+}
+
+pub fn stop_traffic_stream() {
+    unregister_route(*TRAFFIC_STREAM_ROUTE_ID);
+}
+
+/// Reads the requests as server-sent events.
+struct EventStream {
+    receiver: Receiver<AuditEntry>,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl EventStream {
+    fn new(receiver: Receiver<AuditEntry>) -> Self {
+        EventStream {
+            receiver,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl Read for EventStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.buffer.len() {
+            let event = match self.receiver.recv_timeout(KEEP_ALIVE_INTERVAL) {
+                Ok(entry) => {
+                    let summary = json!({
+                        "id": entry.id,
+                        "behaviour": entry.behaviour,
+                        "timestamp": entry.timestamp,
+                        "method": entry.method,
+                        "url": entry.url,
+                        "status": entry.status,
+                        "duration_ms": entry.duration_ms,
+                        "error": entry.error
+                    });
+                    format!("event: request\ndata: {}\n\n", summary)
+                }
+                Err(RecvTimeoutError::Timeout) => String::from(": keep-alive\n\n"),
+                Err(RecvTimeoutError::Disconnected) => return Ok(0),
+            };
+            self.buffer = event.into_bytes();
+            self.position = 0;
+        }
+        let length = buf.len().min(self.buffer.len() - self.position);
+        buf[..length].copy_from_slice(&self.buffer[self.position..self.position + length]);
+        self.position += length;
+        Ok(length)
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use http::header::CONTENT_TYPE;
+use http::Request;
+use http::Response;
+use http::Result;
+use http::StatusCode;
+use rust_embed::RustEmbed;
+use serde_json::json;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::audit::query_audit_log;
+use crate::audit::AuditEntry;
+use crate::audit::AuditQuery;
+use crate::di::*;
+use crate::plugins::HttpBody;
+use crate::plugins::WebResourceProvider;
+
+/// The inspector lists the instances which have sent requests with their last requests, statuses and latencies.
+#[derive(RustEmbed)]
+#[folder = "../../assets/web/inspector"]
+struct InspectorAssets;
+
+pub trait HttpWebResourceProvider: WebResourceProvider + Send + Sync {}
+
+#[derive(Clone)]
+pub struct HttpWebResourceProviderImpl {}
+
+#[component]
+impl HttpWebResourceProviderImpl {
+    #[provides]
+    fn new() -> Self {
+        Self {}
+    }
+}
+
+interfaces!(HttpWebResourceProviderImpl: dyn WebResourceProvider);
+
+#[provides]
+impl HttpWebResourceProvider for HttpWebResourceProviderImpl {}
+
+impl WebResourceProvider for HttpWebResourceProviderImpl {
+    fn get_base_path(&self) -> String {
+        String::from("http")
+    }
+
+    fn handle_web_resource(&self, _base_path: String, path: String, request: Request<HttpBody>) -> Result<Response<HttpBody>> {
+        let query = request.uri().query().map(parse_query).unwrap_or_default();
+        match path.trim_start_matches('/') {
+            "api/instances" => json_response(get_instances()),
+            "api/requests" => {
+                let audit_query = AuditQuery {
+                    id: query.get("id").and_then(|id| Uuid::parse_str(id).ok()),
+                    failed: query.get("failed").map(|failed| failed == "true").unwrap_or(false),
+                    limit: query.get("limit").and_then(|limit| limit.parse().ok()).unwrap_or(100),
+                    ..AuditQuery::default()
+                };
+                json_response(json!(query_audit_log(&audit_query)))
+            }
+            "" | "inspector" => asset_response("index.html"),
+            path => asset_response(path.trim_start_matches("inspector/")),
+        }
+    }
+}
+
+/// Summarizes the audit log by instance.
+fn get_instances() -> Value {
+    let entries = query_audit_log(&AuditQuery::default());
+    let mut instances: Vec<Value> = Vec::new();
+    let mut by_id: HashMap<Uuid, Vec<&AuditEntry>> = HashMap::new();
+    for entry in entries.iter() {
+        by_id.entry(entry.id).or_default().push(entry);
+    }
+    for (id, entries) in by_id.iter() {
+        // The entries are ordered by recency
+        let last = entries[0];
+        let failures = entries.iter().filter(|entry| entry.error.is_some()).count();
+        let average_duration_ms = entries.iter().map(|entry| entry.duration_ms).sum::<u64>() / entries.len() as u64;
+        instances.push(json!({
+            "id": id,
+            "behaviour": last.behaviour,
+            "method": last.method,
+            "url": last.url,
+            "status": last.status,
+            "error": last.error,
+            "timestamp": last.timestamp,
+            "duration_ms": last.duration_ms,
+            "requests": entries.len(),
+            "failures": failures,
+            "average_duration_ms": average_duration_ms
+        }));
+    }
+    instances.sort_by(|a, b| b["timestamp"].as_str().cmp(&a["timestamp"].as_str()));
+    json!(instances)
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    url::form_urlencoded::parse(query.as_bytes()).into_owned().collect()
+}
+
+fn json_response(value: Value) -> Result<Response<HttpBody>> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/json")
+        .body(HttpBody::Json(value))
+}
+
+fn asset_response(path: &str) -> Result<Response<HttpBody>> {
+    match InspectorAssets::get(path) {
+        Some(asset) => {
+            let content_type = match path.rsplit('.').next() {
+                Some("html") => "text/html",
+                Some("js") => "application/javascript",
+                Some("css") => "text/css",
+                _ => "application/octet-stream",
+            };
+            Response::builder()
+                .status(StatusCode::OK)
+                .header(CONTENT_TYPE, content_type)
+                .body(HttpBody::Binary(asset.data.into_owned()))
+        }
+        None => Response::builder().status(StatusCode::NOT_FOUND).body(HttpBody::None),
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use serde_json::Map;
+use serde_json::Value;
+use tungstenite::client::IntoClientRequest;
+use tungstenite::http::HeaderName;
+use tungstenite::http::HeaderValue;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::Message;
+use tungstenite::WebSocket;
+use url::Url;
+use uuid::Uuid;
+
+use crate::dns::connect_checked;
+use crate::placeholder::resolve_env;
+use crate::secrets::resolve_secrets_for_url;
+use crate::ssrf::UrlPolicy;
+
+/// The interval in which a worker which waits for messages looks whether it has been stopped or has to send messages.
+pub const READ_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// The timeout of connecting to an address of the server and of the opening handshake.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub type WebSocketStream = WebSocket<MaybeTlsStream<TcpStream>>;
+
+lazy_static! {
+    /// The messages which are waiting to be sent by the worker of a behaviour and instance.
+    static ref OUTBOXES: Mutex<HashMap<(Uuid, String), Vec<Message>>> = Mutex::new(HashMap::new());
+}
+
+/// Converts an http or https url into the WebSocket url. Other urls are returned unchanged.
+pub fn to_websocket_url(url: &str) -> String {
+    if let Some(rest) = url.strip_prefix("https://") {
+        return format!("wss://{}", rest);
+    }
+    if let Some(rest) = url.strip_prefix("http://") {
+        return format!("ws://{}", rest);
+    }
+    url.to_string()
+}
+
+/// Opens a WebSocket connection with the request headers and the subprotocol.
+///
+/// The connection is made to the addresses which have been checked against the url policy. The handshake blocks until it
+/// is complete or the connect timeout has passed. Afterwards reads time out after the read timeout, so that the worker
+/// stays responsive while the connection is idle.
+pub fn connect_websocket(
+    url: &str,
+    request_headers: &Map<String, Value>,
+    subprotocol: Option<&str>,
+    url_policy: &UrlPolicy,
+) -> Result<WebSocketStream, String> {
+    let url = Url::parse(&to_websocket_url(url)).map_err(|e| format!("Invalid url {}: {}", url, e))?;
+    let mut request = url.as_str().into_client_request().map_err(|e| e.to_string())?;
+    for (name, value) in request_headers.iter() {
+        let Some(value) = value.as_str() else {
+            continue;
+        };
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| format!("Invalid header {}: {}", name, e))?;
+        let value = HeaderValue::from_str(&resolve_secrets_for_url(&resolve_env(value), url.as_str())?)
+            .map_err(|e| format!("Invalid value of header {}: {}", name, e))?;
+        request.headers_mut().insert(name, value);
+    }
+    if let Some(subprotocol) = subprotocol {
+        request
+            .headers_mut()
+            .insert("Sec-WebSocket-Protocol", HeaderValue::from_str(subprotocol).map_err(|e| e.to_string())?);
+    }
+    let host = url.host_str().ok_or_else(|| format!("The url {} has no host", url))?;
+    let port = url.port_or_known_default().unwrap_or(80);
+    let stream = connect_checked(host, port, url_policy, CONNECT_TIMEOUT)?;
+    stream.set_read_timeout(Some(CONNECT_TIMEOUT)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(CONNECT_TIMEOUT)).map_err(|e| e.to_string())?;
+    let (socket, _) = tungstenite::client_tls(request, stream).map_err(|e| format!("Failed to connect to {}: {}", url, e))?;
+    let stream = match socket.get_ref() {
+        MaybeTlsStream::Plain(stream) => stream,
+        MaybeTlsStream::Rustls(stream) => &stream.sock,
+        _ => return Err(format!("Unsupported stream of the connection to {}", url)),
+    };
+    stream.set_read_timeout(Some(READ_TIMEOUT)).map_err(|e| e.to_string())?;
+    Ok(socket)
+}
+
+/// Reads the next message. Returns none, if no message has been received within the read timeout.
+///
+/// Pings are answered by the WebSocket implementation and are not returned.
+pub fn read_websocket_message<S: Read + Write>(socket: &mut WebSocket<S>) -> Result<Option<Message>, String> {
+    match socket.read_message() {
+        Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_)) => Ok(None),
+        Ok(Message::Close(frame)) => Err(match frame {
+            Some(frame) => format!("Connection closed: {} {}", frame.code, frame.reason),
+            None => String::from("Connection closed"),
+        }),
+        Ok(message) => Ok(Some(message)),
+        Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Queues a message which is sent by the worker of the behaviour of the given instance.
+pub fn queue_websocket_message(id: Uuid, behaviour_name: &str, message: Message) {
+    OUTBOXES.lock().unwrap().entry((id, behaviour_name.to_string())).or_default().push(message);
+}
+
+/// Sends the queued messages of the behaviour of the given instance.
+pub fn send_queued_websocket_messages<S: Read + Write>(socket: &mut WebSocket<S>, id: Uuid, behaviour_name: &str) -> Result<(), String> {
+    let messages = OUTBOXES.lock().unwrap().remove(&(id, behaviour_name.to_string())).unwrap_or_default();
+    for message in messages {
+        socket.write_message(message).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Discards the queued messages, for example because the behaviour has been disconnected.
+pub fn clear_queued_websocket_messages(id: Uuid, behaviour_name: &str) {
+    OUTBOXES.lock().unwrap().remove(&(id, behaviour_name.to_string()));
+}
+
+/// Returns the number of messages which are waiting to be sent by all workers.
+pub fn count_queued_websocket_messages() -> usize {
+    OUTBOXES.lock().unwrap().values().map(Vec::len).sum()
+}
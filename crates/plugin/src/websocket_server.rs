@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+use chrono::Utc;
+use lazy_static::lazy_static;
+use log::debug;
+use log::error;
+use log::info;
+use rustls::Certificate;
+use rustls::PrivateKey;
+use rustls::ServerConnection;
+use rustls::StreamOwned;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use tungstenite::handshake::server::ErrorResponse;
+use tungstenite::handshake::server::Request;
+use tungstenite::handshake::server::Response;
+use tungstenite::http::StatusCode;
+use tungstenite::WebSocket;
+use url::form_urlencoded;
+use uuid::Uuid;
+use x509_parser::pem::Pem;
+
+use crate::access_log::record_access;
+use crate::access_log::AccessLogEntry;
+use crate::config::get_config;
+use crate::server::match_path;
+use crate::server::IncomingRequest;
+use crate::server::OutgoingResponse;
+use crate::server::ServerStatus;
+use crate::websocket::READ_TIMEOUT;
+
+/// Decides whether a client may connect. The response is sent instead of completing the handshake.
+pub type WebSocketAcceptor = Arc<dyn Fn(&IncomingRequest) -> Result<(), OutgoingResponse> + Send + Sync>;
+
+/// Handles a connected client. The handler runs on the thread of the client until the connection is closed.
+///
+/// Reads of the socket time out after the read timeout, so that the handler can send messages while the client is idle.
+pub type WebSocketHandler = Arc<dyn Fn(IncomingRequest, WebSocketServerStream) + Send + Sync>;
+
+pub type WebSocketServerStream = WebSocket<ServerStream>;
+
+/// The connection of a client, which is encrypted, if the WebSocket server has a certificate.
+pub enum ServerStream {
+    Plain(TcpStream),
+    Tls(Box<StreamOwned<ServerConnection, TcpStream>>),
+}
+
+impl ServerStream {
+    fn tcp_stream(&self) -> &TcpStream {
+        match self {
+            ServerStream::Plain(stream) => stream,
+            ServerStream::Tls(stream) => &stream.sock,
+        }
+    }
+}
+
+impl Read for ServerStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            ServerStream::Plain(stream) => stream.read(buf),
+            ServerStream::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for ServerStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            ServerStream::Plain(stream) => stream.write(buf),
+            ServerStream::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            ServerStream::Plain(stream) => stream.flush(),
+            ServerStream::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Binds WebSocket connections to the given path to a handler.
+#[derive(Clone)]
+pub struct WebSocketRoute {
+    pub id: Uuid,
+    /// The path or a path pattern like the path of the routes of the embedded server.
+    pub path: String,
+    pub acceptor: WebSocketAcceptor,
+    pub handler: WebSocketHandler,
+}
+
+/// An embedded WebSocket server listening on a port. The server is started with the first route and stopped with the
+/// last route.
+///
+/// The WebSocket servers are separate from the embedded HTTP servers, so they can't share a port. A server accepts
+/// only encrypted connections, if a certificate is configured for its port like for an embedded HTTP server. The
+/// certificate is loaded when the server is started.
+struct WebSocketServer {
+    routes: Arc<Mutex<Vec<WebSocketRoute>>>,
+    stopped: Arc<AtomicBool>,
+    tls: Option<Arc<rustls::ServerConfig>>,
+}
+
+lazy_static! {
+    static ref SERVERS: Mutex<HashMap<u16, WebSocketServer>> = Mutex::new(HashMap::new());
+}
+
+/// Registers the route on the WebSocket server listening on the given port. The server is started, if necessary.
+pub fn register_websocket_route(port: u16, route: WebSocketRoute) -> Result<(), String> {
+    let mut servers = SERVERS.lock().unwrap();
+    if let Some(server) = servers.get(&port) {
+        server.routes.lock().unwrap().push(route);
+        return Ok(());
+    }
+    let tls = load_tls_config(port)?;
+    let listener =
+        TcpListener::bind((get_config().get_bind_address(port), port)).map_err(|e| format!("Failed to start the WebSocket server on port {}: {}", port, e))?;
+    if tls.is_some() {
+        info!("Started the WebSocket server on port {} with TLS", port);
+    } else {
+        info!("Started the WebSocket server on port {}", port);
+    }
+    let routes = Arc::new(Mutex::new(vec![route]));
+    let stopped = Arc::new(AtomicBool::new(false));
+    servers.insert(
+        port,
+        WebSocketServer {
+            routes: routes.clone(),
+            stopped: stopped.clone(),
+            tls: tls.clone(),
+        },
+    );
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if stopped.load(Ordering::SeqCst) {
+                return;
+            }
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let routes = routes.clone();
+            let tls = tls.clone();
+            thread::spawn(move || serve(port, stream, tls, routes));
+        }
+    });
+    Ok(())
+}
+
+/// Removes the route. A WebSocket server is stopped after its last route has been removed. Connected clients of the
+/// route are closed by their handlers.
+pub fn unregister_websocket_route(id: Uuid) {
+    let mut servers = SERVERS.lock().unwrap();
+    let ports: Vec<u16> = servers
+        .iter()
+        .filter_map(|(port, server)| {
+            let mut routes = server.routes.lock().unwrap();
+            routes.retain(|route| route.id != id);
+            routes.is_empty().then_some(*port)
+        })
+        .collect();
+    for port in ports {
+        if let Some(server) = servers.remove(&port) {
+            server.stopped.store(true, Ordering::SeqCst);
+            // Wakes up the listener, which is blocked in accept
+            let _ = TcpStream::connect(("127.0.0.1", port));
+            info!("Stopped the WebSocket server on port {}", port);
+        }
+    }
+}
+
+/// Returns the status of the running WebSocket servers ordered by port.
+pub fn get_websocket_server_status() -> Vec<ServerStatus> {
+    let mut status: Vec<ServerStatus> = SERVERS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(port, server)| ServerStatus {
+            port: *port,
+            tls: server.tls.is_some(),
+            routes: server.routes.lock().unwrap().len(),
+        })
+        .collect();
+    status.sort_by_key(|status| status.port);
+    status
+}
+
+/// Loads the certificate which is configured for the port, if any.
+fn load_tls_config(port: u16) -> Result<Option<Arc<rustls::ServerConfig>>, String> {
+    let Some(tls) = get_config().servers.iter().find(|server| server.port == port && server.has_tls()) else {
+        return Ok(None);
+    };
+    let read = |path: &str| fs::read(path).map_err(|e| format!("Failed to read {} of the WebSocket server on port {}: {}", path, port, e));
+    let certificate_chain: Vec<Certificate> = Pem::iter_from_buffer(&read(&tls.certificate)?)
+        .filter_map(Result::ok)
+        .filter(|pem| pem.label == "CERTIFICATE")
+        .map(|pem| Certificate(pem.contents))
+        .collect();
+    let private_key = Pem::iter_from_buffer(&read(&tls.private_key)?)
+        .filter_map(Result::ok)
+        .find(|pem| pem.label.ends_with("PRIVATE KEY"))
+        .map(|pem| PrivateKey(pem.contents))
+        .ok_or_else(|| format!("No private key in {} of the WebSocket server on port {}", tls.private_key, port))?;
+    let config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certificate_chain, private_key)
+        .map_err(|e| format!("Invalid certificate of the WebSocket server on port {}: {}", port, e))?;
+    Ok(Some(Arc::new(config)))
+}
+
+fn serve(port: u16, stream: TcpStream, tls: Option<Arc<rustls::ServerConfig>>, routes: Arc<Mutex<Vec<WebSocketRoute>>>) {
+    let started_at = Instant::now();
+    let remote_addr = stream.peer_addr().map(|remote_addr| remote_addr.to_string()).unwrap_or_default();
+    // The TLS handshake is completed by the first read of the WebSocket handshake
+    let stream = match tls.map(ServerConnection::new) {
+        Some(Ok(connection)) => ServerStream::Tls(Box::new(StreamOwned::new(connection, stream))),
+        Some(Err(e)) => {
+            error!("Failed to accept the TLS connection of {}: {}", remote_addr, e);
+            return;
+        }
+        None => ServerStream::Plain(stream),
+    };
+    let mut accepted = None;
+    let mut status = 101;
+    let mut path = String::new();
+    let callback = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        path = request.uri().path().to_string();
+        let route = routes
+            .lock()
+            .unwrap()
+            .iter()
+            .find_map(|route| Some((route.clone(), match_path(&route.path, &path)?)));
+        let Some((route, params)) = route else {
+            status = 404;
+            return Err(error_response(OutgoingResponse::status(404)));
+        };
+        let incoming_request = IncomingRequest {
+            method: request.method().to_string(),
+            path: path.clone(),
+            params,
+            query: request
+                .uri()
+                .query()
+                .map(|query| {
+                    form_urlencoded::parse(query.as_bytes())
+                        .map(|(name, value)| (name.to_string(), json!(value)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            headers: request
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.to_string(), json!(String::from_utf8_lossy(value.as_bytes()))))
+                .collect::<Map<String, Value>>(),
+            body: Vec::new(),
+            remote_addr: remote_addr.clone(),
+        };
+        if let Err(response) = (route.acceptor)(&incoming_request) {
+            status = response.status;
+            return Err(error_response(response));
+        }
+        accepted = Some((route, incoming_request));
+        Ok(response)
+    };
+    // The error contains the callback, which borrows the state of the handshake
+    let result = tungstenite::accept_hdr(stream, callback).map_err(|e| e.to_string());
+    let route_id = accepted.as_ref().map(|(route, _)| route.id);
+    match (result, accepted) {
+        (Ok(socket), Some((route, incoming_request))) => {
+            if let Err(e) = socket.get_ref().tcp_stream().set_read_timeout(Some(READ_TIMEOUT)) {
+                error!("Failed to configure the WebSocket connection of {}: {}", remote_addr, e);
+                return;
+            }
+            (route.handler)(incoming_request, socket);
+        }
+        (Ok(_), None) => {}
+        (Err(e), _) => {
+            debug!("Failed the WebSocket handshake of {}: {}", remote_addr, e);
+            if status == 101 {
+                status = 400;
+            }
+        }
+    }
+    record_access(AccessLogEntry {
+        timestamp: Utc::now(),
+        port,
+        route: route_id,
+        method: String::from("GET"),
+        path,
+        status,
+        duration_ms: started_at.elapsed().as_millis() as u64,
+        remote_addr,
+    });
+}
+
+fn error_response(response: OutgoingResponse) -> ErrorResponse {
+    let mut error_response = ErrorResponse::new(Some(String::from_utf8_lossy(&response.body).to_string()));
+    *error_response.status_mut() = StatusCode::from_u16(response.status).unwrap_or(StatusCode::BAD_REQUEST);
+    for (name, value) in response.headers.iter() {
+        if let (Ok(name), Ok(value)) = (name.parse::<tungstenite::http::HeaderName>(), value.parse()) {
+            error_response.headers_mut().insert(name, value);
+        }
+    }
+    error_response
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::Sender;
+use std::sync::mpsc::TryRecvError;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use uuid::Uuid;
+
+lazy_static! {
+    /// The running workers by instance id and behaviour name. Dropping the sender stops the worker.
+    static ref WORKERS: Mutex<HashMap<(Uuid, String), Sender<()>>> = Mutex::new(HashMap::new());
+}
+
+/// Tells a worker thread whether it has been stopped.
+pub struct WorkerSignal(Receiver<()>);
+
+impl WorkerSignal {
+    /// Returns true, if the worker has been stopped.
+    pub fn is_stopped(&self) -> bool {
+        matches!(self.0.try_recv(), Err(TryRecvError::Disconnected))
+    }
+
+    /// Sleeps for the given duration or until the worker has been stopped.
+    ///
+    /// Returns false, if the worker has been stopped.
+    pub fn sleep(&self, duration: Duration) -> bool {
+        !matches!(self.0.recv_timeout(duration), Err(RecvTimeoutError::Disconnected))
+    }
+}
+
+/// Spawns a worker thread for the behaviour of the given instance. A previously started worker of the same
+/// behaviour and instance is stopped.
+pub fn start_worker<F>(id: Uuid, behaviour_name: &str, f: F)
+where
+    F: FnOnce(WorkerSignal) + Send + 'static,
+{
+    let (sender, receiver) = channel();
+    WORKERS.lock().unwrap().insert((id, behaviour_name.to_string()), sender);
+    thread::spawn(move || f(WorkerSignal(receiver)));
+}
+
+/// Stops the worker thread of the behaviour of the given instance.
+pub fn stop_worker(id: Uuid, behaviour_name: &str) {
+    WORKERS.lock().unwrap().remove(&(id, behaviour_name.to_string()));
+}
+
+/// Returns the number of running workers by behaviour name.
+pub fn count_workers() -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for (_, behaviour_name) in WORKERS.lock().unwrap().keys() {
+        *counts.entry(behaviour_name.clone()).or_insert(0) += 1;
+    }
+    counts
+}